@@ -0,0 +1,114 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use arrow::array::{Array, Float64Array};
+use arrow::chunk::Chunk;
+use arrow::datatypes::{Field, Schema};
+use arrow::error::Result;
+
+use strawboat::read::batch_read::batch_read_array;
+use strawboat::read::reader::read_meta;
+use strawboat::{to_parquet_schema, write, CommonCompression, Compression};
+
+/// The encodings of doubles that can be chosen besides the compared ones.
+const DOUBLE_ENCODINGS: [Compression; 7] = [
+    Compression::OneValue,
+    Compression::Freq,
+    Compression::Dict,
+    Compression::Patas,
+    Compression::Gorilla,
+    Compression::Chimp,
+    Compression::Rle,
+];
+
+/// Readings of a sensor, with two decimals.
+fn sample_column(size: usize) -> Float64Array {
+    Float64Array::from_vec(
+        (0..size)
+            .map(|i| {
+                let reading = 20.0 + (i as f64 * 0.01).sin() * 5.0 + (i % 7) as f64 * 0.03;
+                (reading * 100.0).round() / 100.0
+            })
+            .collect(),
+    )
+}
+
+/// Writes the column with `compression`, an encoding of doubles or the default
+/// compression.
+fn write(array: &Float64Array, compression: Compression) -> Result<Vec<u8>> {
+    let schema = Schema::from(vec![Field::new("c1", array.data_type().clone(), false)]);
+    let columns = Chunk::new(vec![array.clone().boxed()]);
+
+    let options = match CommonCompression::try_from(&compression) {
+        Ok(c) => write::WriteOptions {
+            default_compression: c,
+            default_compress_ratio: None,
+            ..Default::default()
+        },
+        Err(_) => write::WriteOptions {
+            default_compression: CommonCompression::None,
+            default_compress_ratio: Some(0.0),
+            forbidden_compressions: DOUBLE_ENCODINGS
+                .into_iter()
+                .filter(|c| *c != compression)
+                .collect(),
+            ..Default::default()
+        },
+    };
+    let options = write::WriteOptions {
+        max_page_size: Some(8192),
+        ..options
+    };
+
+    let mut writer = write::NativeWriter::new(vec![], schema, options);
+    writer.start()?;
+    writer.write(&columns)?;
+    writer.finish()?;
+    Ok(writer.into_inner())
+}
+
+fn read(bytes: &[u8]) -> Result<Box<dyn Array>> {
+    let field = Field::new("c1", arrow::datatypes::DataType::Float64, false);
+    let leaves = to_parquet_schema(&Schema::from(vec![field.clone()]))?
+        .columns()
+        .to_vec();
+    let meta = read_meta(&mut std::io::Cursor::new(bytes))?.remove(0);
+    let reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+    batch_read_array(vec![reader], leaves, field, false, vec![meta.pages])
+}
+
+fn add_benchmark(c: &mut Criterion) {
+    let array = sample_column(1024 * 64);
+    for compression in [Compression::Chimp, Compression::Gorilla, Compression::Zstd] {
+        let bytes = write(&array, compression).unwrap();
+        println!(
+            "{compression:?}: {} bytes for {} bytes of doubles",
+            bytes.len(),
+            array.len() * 8
+        );
+
+        let name = format!("write double {compression:?}");
+        c.bench_function(&name, |b| b.iter(|| write(&array, compression).unwrap()));
+        let name = format!("read double {compression:?}");
+        c.bench_function(&name, |b| b.iter(|| read(&bytes).unwrap()));
+    }
+}
+
+criterion_group!(benches, add_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,78 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use arrow::array::{Array, Int64Array};
+use arrow::chunk::Chunk;
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::Result;
+
+use strawboat::read::batch_read::batch_read_array;
+use strawboat::read::reader::read_meta;
+use strawboat::{to_parquet_schema, write, CommonCompression, Compression};
+
+/// Order ids of a few hundred customers, so that every page is dictionary encoded.
+fn sample_column(size: usize) -> Int64Array {
+    Int64Array::from_vec(
+        (0..size)
+            .map(|i| 1_000_000 + ((i * 7919) % 512) as i64)
+            .collect(),
+    )
+}
+
+fn write(array: &Int64Array) -> Result<Vec<u8>> {
+    let schema = Schema::from(vec![Field::new("c1", array.data_type().clone(), false)]);
+    let columns = Chunk::new(vec![array.clone().boxed()]);
+
+    let options = write::WriteOptions {
+        default_compression: CommonCompression::None,
+        default_compress_ratio: Some(0.0),
+        forbidden_compressions: Compression::ALL
+            .into_iter()
+            .filter(|c| *c != Compression::Dict)
+            .collect(),
+        ..Default::default()
+    };
+
+    let mut writer = write::NativeWriter::new(vec![], schema, options);
+    writer.start()?;
+    writer.write(&columns)?;
+    writer.finish()?;
+    Ok(writer.into_inner())
+}
+
+fn read(bytes: &[u8]) -> Result<Box<dyn Array>> {
+    let field = Field::new("c1", DataType::Int64, false);
+    let leaves = to_parquet_schema(&Schema::from(vec![field.clone()]))?
+        .columns()
+        .to_vec();
+    let meta = read_meta(&mut std::io::Cursor::new(bytes))?.remove(0);
+    let reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+    batch_read_array(vec![reader], leaves, field, false, vec![meta.pages])
+}
+
+fn add_benchmark(c: &mut Criterion) {
+    let array = sample_column(1024 * 1024);
+    let bytes = write(&array).unwrap();
+    assert_eq!(read(&bytes).unwrap().as_ref(), &array as &dyn Array);
+
+    c.bench_function("read dict i64 2^20", |b| b.iter(|| read(&bytes).unwrap()));
+}
+
+criterion_group!(benches, add_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,84 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use arrow::array::{Array, Int64Array};
+use arrow::chunk::Chunk;
+use arrow::datatypes::{Field, Schema};
+use arrow::error::Result;
+
+use strawboat::read::batch_read::{read_simple, read_simple_parallel};
+use strawboat::read::reader::read_meta;
+use strawboat::read::ReadOptions;
+use strawboat::{write, ColumnMeta};
+
+const PAGE_SIZE: usize = 64 * 1024;
+const NUM_PAGES: usize = 100;
+
+fn write(array: &Int64Array) -> Result<Vec<u8>> {
+    let schema = Schema::from(vec![Field::new("c1", array.data_type().clone(), false)]);
+    let columns = Chunk::new(vec![array.clone().boxed()]);
+
+    let options = write::WriteOptions {
+        max_page_size: Some(PAGE_SIZE),
+        ..Default::default()
+    };
+
+    let mut writer = write::NativeWriter::new(vec![], schema, options);
+    writer.start()?;
+    writer.write(&columns)?;
+    writer.finish()?;
+    Ok(writer.into_inner())
+}
+
+fn read_serial(bytes: &[u8], field: &Field, meta: &ColumnMeta) -> Result<Box<dyn Array>> {
+    let mut reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+    read_simple(&mut reader, field.clone(), meta.pages.clone())
+}
+
+fn read_parallel(bytes: &[u8], field: &Field, meta: &ColumnMeta) -> Result<Box<dyn Array>> {
+    let reader = std::io::Cursor::new(bytes);
+    read_simple_parallel(&reader, field.clone(), meta, ReadOptions::default())
+}
+
+fn add_benchmark(c: &mut Criterion) {
+    let array = Int64Array::from_vec(
+        (0..PAGE_SIZE * NUM_PAGES)
+            .map(|i| (i as i64 * 7919) % 1_000_003)
+            .collect(),
+    );
+    let bytes = write(&array).unwrap();
+    let field = Field::new("c1", array.data_type().clone(), false);
+    let meta = read_meta(&mut std::io::Cursor::new(&bytes))
+        .unwrap()
+        .remove(0);
+    assert_eq!(meta.pages.len(), NUM_PAGES);
+    let serial = read_serial(&bytes, &field, &meta).unwrap();
+    assert_eq!(serial.as_ref(), &array as &dyn Array);
+    assert_eq!(read_parallel(&bytes, &field, &meta).unwrap(), serial);
+
+    c.bench_function("read i64 100 pages serial", |b| {
+        b.iter(|| read_serial(&bytes, &field, &meta).unwrap())
+    });
+    c.bench_function("read i64 100 pages parallel", |b| {
+        b.iter(|| read_parallel(&bytes, &field, &meta).unwrap())
+    });
+}
+
+criterion_group!(benches, add_benchmark);
+criterion_main!(benches);
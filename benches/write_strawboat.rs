@@ -35,7 +35,7 @@ fn write(array: &dyn Array) -> Result<()> {
         default_compression: CommonCompression::Lz4,
         max_page_size: Some(8192),
         default_compress_ratio: None,
-        forbidden_compressions: vec![],
+        ..Default::default()
     };
 
     let file = vec![];
@@ -22,17 +22,14 @@ use std::time::Instant;
 use arrow::{
     chunk::Chunk,
     error::Result,
-    io::parquet::{
-        read::{n_columns, ColumnDescriptor},
-        write::to_parquet_schema,
-    },
+    io::parquet::read::{n_columns, ColumnDescriptor},
 };
 use strawboat::{
     read::{
         batch_read::batch_read_array,
         reader::{infer_schema, is_primitive, read_meta},
     },
-    ColumnMeta, PageMeta,
+    to_parquet_schema, ColumnMeta, PageMeta,
 };
 
 /// Simplest way: read all record batches from the file. This can be used e.g. for random access.
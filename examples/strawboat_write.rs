@@ -31,18 +31,11 @@ fn write_batches(path: &str, schema: Schema, chunks: &[Chunk<Box<dyn Array>>]) -
         default_compression: CommonCompression::Lz4,
         default_compress_ratio: None,
         max_page_size: Some(8192),
-        forbidden_compressions: vec![],
+        ..Default::default()
     };
-    let mut writer = write::NativeWriter::new(file, schema, options);
+    let (metas, _) = write::write_file(file, schema, chunks, options)?;
 
-    writer.start()?;
-    for chunk in chunks {
-        writer.write(chunk)?
-    }
-
-    writer.finish()?;
-
-    let metas = serde_json::to_vec(&writer.metas).unwrap();
+    let metas = serde_json::to_vec(&metas).unwrap();
     let mut meta_file = File::options()
         .create(true)
         .write(true)
@@ -25,6 +25,11 @@ pub enum CommonCompression {
     Lz4,
     Zstd,
     Snappy,
+    /// Requires the `brotli` feature
+    Brotli,
+    /// Snappy in the framing format, which unlike the raw `Snappy` is understood by
+    /// the snappy stream decoders of other tools.
+    SnappyFramed,
 }
 
 impl Default for CommonCompression {
@@ -42,6 +47,8 @@ impl TryFrom<&Compression> for CommonCompression {
             Compression::Lz4 => Ok(CommonCompression::Lz4),
             Compression::Zstd => Ok(CommonCompression::Zstd),
             Compression::Snappy => Ok(CommonCompression::Snappy),
+            Compression::Brotli => Ok(CommonCompression::Brotli),
+            Compression::SnappyFramed => Ok(CommonCompression::SnappyFramed),
             other => Err(Error::OutOfSpec(format!(
                 "Unknown compression codec {other:?}",
             ))),
@@ -56,6 +63,8 @@ impl CommonCompression {
             Self::Lz4 => Compression::Lz4,
             Self::Zstd => Compression::Zstd,
             Self::Snappy => Compression::Snappy,
+            Self::Brotli => Compression::Brotli,
+            Self::SnappyFramed => Compression::SnappyFramed,
         }
     }
 
@@ -64,6 +73,8 @@ impl CommonCompression {
             Self::Lz4 => decompress_lz4(input, out_slice),
             Self::Zstd => decompress_zstd(input, out_slice),
             Self::Snappy => decompress_snappy(input, out_slice),
+            Self::Brotli => decompress_brotli(input, out_slice),
+            Self::SnappyFramed => decompress_snappy_framed(input, out_slice),
             Self::None => {
                 out_slice.copy_from_slice(input);
                 Ok(())
@@ -72,10 +83,23 @@ impl CommonCompression {
     }
 
     pub fn compress(&self, input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
+        self.compress_with_level(input_buf, output_buf, None)
+    }
+
+    /// Like [`Self::compress`] but with a codec specific level, only zstd and brotli
+    /// have levels and `None` uses their default level.
+    pub fn compress_with_level(
+        &self,
+        input_buf: &[u8],
+        output_buf: &mut Vec<u8>,
+        level: Option<i32>,
+    ) -> Result<usize> {
         match self {
             Self::Lz4 => compress_lz4(input_buf, output_buf),
-            Self::Zstd => compress_zstd(input_buf, output_buf),
+            Self::Zstd => compress_zstd(input_buf, output_buf, level.unwrap_or(0)),
             Self::Snappy => compress_snappy(input_buf, output_buf),
+            Self::Brotli => compress_brotli(input_buf, output_buf, level.unwrap_or(1)),
+            Self::SnappyFramed => compress_snappy_framed(input_buf, output_buf),
             Self::None => {
                 output_buf.extend_from_slice(input_buf);
                 Ok(input_buf.len())
@@ -96,6 +120,33 @@ pub fn decompress_zstd(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
         .map_err(|e| e.into())
 }
 
+/// A zstd dictionary prepared once to decompress all the pages of its column.
+pub struct ZstdDecoderDictionary(zstd::dict::DecoderDictionary<'static>);
+
+impl ZstdDecoderDictionary {
+    pub fn new(dictionary: &[u8]) -> Self {
+        Self(zstd::dict::DecoderDictionary::copy(dictionary))
+    }
+}
+
+impl std::fmt::Debug for ZstdDecoderDictionary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ZstdDecoderDictionary")
+            .finish_non_exhaustive()
+    }
+}
+
+pub fn decompress_zstd_with_dict(
+    input_buf: &[u8],
+    output_buf: &mut [u8],
+    dictionary: &ZstdDecoderDictionary,
+) -> Result<()> {
+    zstd::bulk::Decompressor::with_prepared_dictionary(&dictionary.0)?
+        .decompress_to_buffer(input_buf, output_buf)
+        .map(|_| {})
+        .map_err(|e| e.into())
+}
+
 pub fn decompress_snappy(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
     snap::raw::Decoder::new()
         .decompress(input_buf, output_buf)
@@ -105,6 +156,13 @@ pub fn decompress_snappy(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()>
         })
 }
 
+pub fn decompress_snappy_framed(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+    use std::io::Read;
+    snap::read::FrameDecoder::new(input_buf)
+        .read_exact(output_buf)
+        .map_err(|e| e.into())
+}
+
 pub fn compress_lz4(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
     let bound = lz4::block::compress_bound(input_buf.len())?;
     let len = output_buf.len();
@@ -119,14 +177,14 @@ pub fn compress_lz4(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize>
     Ok(size)
 }
 
-pub fn compress_zstd(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
+pub fn compress_zstd(input_buf: &[u8], output_buf: &mut Vec<u8>, level: i32) -> Result<usize> {
     let bound = zstd::zstd_safe::compress_bound(input_buf.len());
     let len = output_buf.len();
     output_buf.reserve(bound);
 
     let s = unsafe { core::slice::from_raw_parts_mut(output_buf.as_mut_ptr().add(len), bound) };
 
-    let size = zstd::bulk::compress_to_buffer(input_buf, s, 0).map_err(|e| {
+    let size = zstd::bulk::compress_to_buffer(input_buf, s, level).map_err(|e| {
         arrow::error::Error::External("Compress zstd faild".to_owned(), Box::new(e))
     })?;
 
@@ -134,6 +192,34 @@ pub fn compress_zstd(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize
     Ok(size)
 }
 
+pub fn compress_zstd_with_dict(
+    input_buf: &[u8],
+    output_buf: &mut Vec<u8>,
+    level: i32,
+    dictionary: &[u8],
+) -> Result<usize> {
+    let bound = zstd::zstd_safe::compress_bound(input_buf.len());
+    let len = output_buf.len();
+    output_buf.reserve(bound);
+
+    let s = unsafe { core::slice::from_raw_parts_mut(output_buf.as_mut_ptr().add(len), bound) };
+
+    let size = zstd::bulk::Compressor::with_dictionary(level, dictionary)
+        .and_then(|mut compressor| compressor.compress_to_buffer(input_buf, s))
+        .map_err(|e| {
+            arrow::error::Error::External("Compress zstd faild".to_owned(), Box::new(e))
+        })?;
+
+    unsafe { output_buf.set_len(size + len) };
+    Ok(size)
+}
+
+/// Trains a zstd dictionary of at most `max_size` bytes from `samples`,
+/// `None` if there is not enough data to train one.
+pub fn train_zstd_dictionary<S: AsRef<[u8]>>(samples: &[S], max_size: usize) -> Option<Vec<u8>> {
+    zstd::dict::from_samples(samples, max_size).ok()
+}
+
 pub fn compress_snappy(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
     let bound = snap::raw::max_compress_len(input_buf.len());
     let len = output_buf.len();
@@ -150,3 +236,48 @@ pub fn compress_snappy(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usi
     unsafe { output_buf.set_len(size + len) };
     Ok(size)
 }
+
+pub fn compress_snappy_framed(input_buf: &[u8], output_buf: &mut Vec<u8>) -> Result<usize> {
+    use std::io::Write;
+    let len = output_buf.len();
+    let mut encoder = snap::write::FrameEncoder::new(&mut *output_buf);
+    encoder.write_all(input_buf)?;
+    encoder.flush()?;
+    drop(encoder);
+    Ok(output_buf.len() - len)
+}
+
+#[cfg(feature = "brotli")]
+pub fn decompress_brotli(input_buf: &[u8], output_buf: &mut [u8]) -> Result<()> {
+    use std::io::Read;
+    brotli::Decompressor::new(input_buf, 4096)
+        .read_exact(output_buf)
+        .map_err(|e| e.into())
+}
+
+#[cfg(not(feature = "brotli"))]
+pub fn decompress_brotli(_input_buf: &[u8], _output_buf: &mut [u8]) -> Result<()> {
+    Err(Error::InvalidArgumentError(
+        "The brotli codec requires the `brotli` feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "brotli")]
+pub fn compress_brotli(input_buf: &[u8], output_buf: &mut Vec<u8>, level: i32) -> Result<usize> {
+    use std::io::Write;
+    let len = output_buf.len();
+    {
+        let mut encoder = brotli::CompressorWriter::new(&mut *output_buf, 4096, level as u32, 22);
+        encoder.write_all(input_buf)?;
+        encoder.flush()?;
+    }
+    Ok(output_buf.len() - len)
+}
+
+#[cfg(not(feature = "brotli"))]
+#[allow(clippy::ptr_arg)]
+pub fn compress_brotli(_input_buf: &[u8], _output_buf: &mut Vec<u8>, _level: i32) -> Result<usize> {
+    Err(Error::InvalidArgumentError(
+        "The brotli codec requires the `brotli` feature".to_string(),
+    ))
+}
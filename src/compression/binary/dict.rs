@@ -25,12 +25,15 @@ use arrow::error::Result;
 use arrow::types::Offset;
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use crate::compression::integer::compress_integer;
-use crate::compression::integer::{decompress_integer, Dict, DictEncoder};
+use super::check_decode_size;
+use crate::compression::integer::{
+    compress_indices, decompress_indices, shift_indices, Dict, DictEncoder, SORTED_DICT_FLAG,
+};
 use crate::compression::{get_bits_needed, is_valid, Compression};
 use crate::general_err;
 use crate::util::AsBytes;
 use crate::write::WriteOptions;
+use crate::SINGLE_ROW_GROUP_VERSION;
 
 use super::BinaryCompression;
 use super::BinaryStats;
@@ -41,11 +44,6 @@ impl<O: Offset> BinaryCompression<O> for Dict {
     }
 
     fn compress_ratio(&self, stats: &super::BinaryStats<O>) -> f64 {
-        const MIN_DICT_RATIO: usize = 3;
-        if stats.unique_count * MIN_DICT_RATIO >= stats.tuple_count {
-            return 0.0f64;
-        }
-
         let mut after_size = stats.total_unique_size
             + stats.tuple_count * (get_bits_needed(stats.unique_count as u64) / 8) as usize;
         after_size += (stats.tuple_count) * 2 / 128;
@@ -74,19 +72,36 @@ impl<O: Offset> BinaryCompression<O> for Dict {
             }
         }
 
+        if write_options.sorted_dictionary {
+            encoder.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        }
         let indices = encoder.take_indices();
         // dict data use custom encoding
         let mut write_options = write_options.clone();
         write_options.forbidden_compressions.push(Compression::Dict);
-        compress_integer(&indices, write_options, output_buf)?;
-
-        // data page use plain encoding
         let sets = encoder.get_sets();
-        output_buf.extend_from_slice(&(sets.len() as u32).to_le_bytes());
-        for val in sets.iter() {
-            let bs = val.as_bytes();
-            output_buf.extend_from_slice(&(bs.len() as u64).to_le_bytes());
-            output_buf.extend_from_slice(bs.as_ref());
+        compress_indices(&indices, sets.len(), write_options.clone(), output_buf)?;
+
+        if write_options.sorted_dictionary {
+            // sorted data page use prefix encoding
+            output_buf.extend_from_slice(&(sets.len() as u32 | SORTED_DICT_FLAG).to_le_bytes());
+            let mut last: &[u8] = &[];
+            for val in sets.iter() {
+                let bs = val.as_bytes();
+                let prefix = last.iter().zip(bs).take_while(|(a, b)| a == b).count();
+                output_buf.extend_from_slice(&(prefix as u32).to_le_bytes());
+                output_buf.extend_from_slice(&((bs.len() - prefix) as u32).to_le_bytes());
+                output_buf.extend_from_slice(&bs[prefix..]);
+                last = bs;
+            }
+        } else {
+            // data page use plain encoding
+            output_buf.extend_from_slice(&(sets.len() as u32).to_le_bytes());
+            for val in sets.iter() {
+                let bs = val.as_bytes();
+                output_buf.extend_from_slice(&(bs.len() as u64).to_le_bytes());
+                output_buf.extend_from_slice(bs.as_ref());
+            }
         }
 
         Ok(output_buf.len() - start)
@@ -94,20 +109,145 @@ impl<O: Offset> BinaryCompression<O> for Dict {
 
     fn decompress(
         &self,
-        mut input: &[u8],
+        input: &[u8],
         length: usize,
         offsets: &mut Vec<O>,
         values: &mut Vec<u8>,
     ) -> Result<()> {
-        let mut indices: Vec<u32> = Vec::new();
-        decompress_integer(&mut input, length, &mut indices, &mut vec![])?;
+        self.decompress_with_version(input, length, offsets, values, SINGLE_ROW_GROUP_VERSION)
+    }
 
-        let mut data: Vec<u8> = vec![];
-        let mut data_offsets = vec![0];
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+        format_version: u16,
+    ) -> Result<()> {
+        decompress_dict(input, length, offsets, values, None, format_version)
+    }
+}
+
+/// Decodes a `Dict` page, erroring before the dictionary or the gathered values grow
+/// past `max_decode_bytes`. The page is of a file of the header `format_version`.
+pub(crate) fn decompress_dict<O: Offset>(
+    mut input: &[u8],
+    length: usize,
+    offsets: &mut Vec<O>,
+    values: &mut Vec<u8>,
+    max_decode_bytes: Option<usize>,
+    format_version: u16,
+) -> Result<()> {
+    let mut indices: Vec<u32> = Vec::new();
+    decompress_indices(
+        &mut input,
+        length,
+        &mut indices,
+        &mut vec![],
+        format_version,
+    )?;
+
+    let mut data: Vec<u8> = vec![];
+    let mut data_offsets = vec![0];
+    decompress_dictionary(&mut input, &mut data_offsets, &mut data, max_decode_bytes)?;
+
+    let mut size = 0usize;
+    for i in indices.iter() {
+        if *i as usize + 1 >= data_offsets.len() {
+            return Err(general_err!("dictionary index {} out of range", i));
+        }
+        size += data_offsets[*i as usize + 1] - data_offsets[*i as usize];
+    }
+    check_decode_size(size, max_decode_bytes)?;
+
+    let mut last_offset = if offsets.is_empty() {
+        offsets.push(O::default());
+        0
+    } else {
+        offsets.last().unwrap().to_usize()
+    };
 
-        let mut last_offset = 0;
+    offsets.reserve(indices.len());
+    values.reserve(size);
 
-        let data_size = input.read_u32::<LittleEndian>()? as usize;
+    for i in indices.iter() {
+        let off = data_offsets[*i as usize];
+        let end = data_offsets[(*i + 1) as usize];
+
+        values.extend_from_slice(&data[off..end]);
+
+        last_offset += end - off;
+        offsets.push(O::from_usize(last_offset).unwrap());
+    }
+    Ok(())
+}
+
+/// Decodes the indices and the dictionary of a `Dict` page without gathering its values:
+/// the dictionary is appended to `offsets` and `values` and the indices, shifted past the
+/// values already in it, to `indices`. The page is of a file of the header `format_version`.
+pub fn decompress_dict_page<O: Offset>(
+    mut input: &[u8],
+    length: usize,
+    indices: &mut Vec<u32>,
+    offsets: &mut Vec<O>,
+    values: &mut Vec<u8>,
+    format_version: u16,
+) -> Result<()> {
+    let start = indices.len();
+    decompress_indices(&mut input, length, indices, &mut vec![], format_version)?;
+
+    let mut data_offsets = vec![0];
+    let values_start = values.len();
+    decompress_dictionary(&mut input, &mut data_offsets, values, None)?;
+    if offsets.is_empty() {
+        offsets.push(O::default());
+    }
+    let base = offsets.len() - 1;
+    let last = offsets.last().unwrap().to_usize();
+    if last != values_start {
+        return Err(general_err!(
+            "the dictionary offsets do not match its values"
+        ));
+    }
+    for offset in &data_offsets[1..] {
+        let offset = O::from_usize(last + offset)
+            .ok_or_else(|| general_err!("dictionary values overflow the offsets"))?;
+        offsets.push(offset);
+    }
+    shift_indices(&mut indices[start..], base, offsets.len() - 1)
+}
+
+/// Appends the dictionary of a `Dict` page, which follows its indices, to `data`,
+/// and the offsets of its values in `data`, from the end of `data`, to `data_offsets`.
+/// Errors if the dictionary grows past `max_decode_bytes`.
+fn decompress_dictionary(
+    input: &mut &[u8],
+    data_offsets: &mut Vec<usize>,
+    data: &mut Vec<u8>,
+    max_decode_bytes: Option<usize>,
+) -> Result<()> {
+    let data_start = data.len();
+    let mut last_offset = 0;
+    let data_size = input.read_u32::<LittleEndian>()?;
+    if data_size & SORTED_DICT_FLAG != 0 {
+        let mut last_start = 0;
+        for _ in 0..data_size & !SORTED_DICT_FLAG {
+            let prefix = input.read_u32::<LittleEndian>()? as usize;
+            let len = input.read_u32::<LittleEndian>()? as usize;
+            if input.len() < len || prefix > last_offset - last_start {
+                return Err(general_err!("invalid sorted dictionary value"));
+            }
+            // the shared prefixes expand a sorted dictionary past the size of its page
+            check_decode_size(last_offset + prefix + len, max_decode_bytes)?;
+            data.extend_from_within(data_start + last_start..data_start + last_start + prefix);
+            data.extend_from_slice(&input[..len]);
+            input.consume(len);
+            last_start = last_offset;
+            last_offset += prefix + len;
+            data_offsets.push(last_offset);
+        }
+    } else {
         for _ in 0..data_size {
             let len = input.read_u64::<LittleEndian>()? as usize;
             if input.len() < len {
@@ -118,25 +258,84 @@ impl<O: Offset> BinaryCompression<O> for Dict {
             data.extend_from_slice(&input[..len]);
             input.consume(len);
         }
+    }
+    Ok(())
+}
 
-        last_offset = if offsets.is_empty() {
-            offsets.push(O::default());
-            0
-        } else {
-            offsets.last().unwrap().to_usize()
-        };
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::super::gen_stats;
+    use super::BinaryCompression;
+    use crate::compression::integer::Dict;
+    use crate::write::WriteOptions;
+    use crate::CommonCompression;
 
-        offsets.reserve(indices.len());
+    #[test]
+    fn test_sorted_dict() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let array = BinaryArray::<i32>::from_iter_values(
+            (0..10000).map(|_| format!("https://example.com/item/{}", rng.gen_range(0..1000))),
+        );
+        let stats = gen_stats(&array);
 
-        for i in indices.iter() {
-            let off = data_offsets[*i as usize];
-            let end = data_offsets[(*i + 1) as usize];
+        let mut unsorted = vec![];
+        let options = WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            ..Default::default()
+        };
+        Dict {}
+            .compress(&array, &stats, &options, &mut unsorted)
+            .unwrap();
 
-            values.extend_from_slice(&data[off..end]);
+        let mut sorted = vec![];
+        let options = WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            sorted_dictionary: true,
+            ..Default::default()
+        };
+        Dict {}
+            .compress(&array, &stats, &options, &mut sorted)
+            .unwrap();
+        assert!(sorted.len() < unsorted.len());
 
-            last_offset += end - off;
-            offsets.push(O::from_usize(last_offset).unwrap());
+        for buf in [unsorted, sorted] {
+            let mut offsets = vec![];
+            let mut values = vec![];
+            BinaryCompression::<i32>::decompress(
+                &Dict {},
+                &buf,
+                array.len(),
+                &mut offsets,
+                &mut values,
+            )
+            .unwrap();
+            let result = BinaryArray::<i32>::new(
+                array.data_type().clone(),
+                offsets.try_into().unwrap(),
+                values.into(),
+                None,
+            );
+            assert_eq!(result, array);
         }
-        Ok(())
+    }
+
+    #[test]
+    fn test_empty_dict() {
+        let array = BinaryArray::<i64>::from_iter_values(std::iter::empty::<&[u8]>());
+        let stats = gen_stats(&array);
+
+        let mut buf = vec![];
+        Dict {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut offsets = vec![];
+        let mut values = vec![];
+        BinaryCompression::<i64>::decompress(&Dict {}, &buf, 0, &mut offsets, &mut values).unwrap();
+        assert_eq!(offsets, vec![0]);
+        assert!(values.is_empty());
     }
 }
@@ -30,11 +30,17 @@ use roaring::RoaringBitmap;
 use crate::compression::integer::Freq;
 use crate::compression::Compression;
 use crate::general_err;
+use crate::SINGLE_ROW_GROUP_VERSION;
 
 use crate::write::WriteOptions;
 
 use super::BinaryCompression;
 use super::BinaryStats;
+use super::{compress_binary, decompress_binary};
+
+/// Set in the exceptions bitmap size when the exceptions are a nested binary page,
+/// they are written one after another with their `u64` length otherwise.
+pub(crate) const NESTED_EXCEPTIONS_FLAG: u32 = 1 << 31;
 
 impl<O: Offset> BinaryCompression<O> for Freq {
     fn to_compression(&self) -> Compression {
@@ -83,7 +89,8 @@ impl<O: Offset> BinaryCompression<O> for Freq {
         output.extend_from_slice(top_value);
 
         // Write exceptions bitmap
-        output.extend_from_slice(&(exceptions_bitmap.serialized_size() as u32).to_le_bytes());
+        let bitmap_size = exceptions_bitmap.serialized_size() as u32;
+        output.extend_from_slice(&(bitmap_size | NESTED_EXCEPTIONS_FLAG).to_le_bytes());
 
         exceptions_bitmap.serialize_into(&mut (*output))?;
 
@@ -91,20 +98,28 @@ impl<O: Offset> BinaryCompression<O> for Freq {
         let mut write_options = write_options.clone();
         write_options.forbidden_compressions.push(Compression::Freq);
 
-        // Plain encoding
-        for exception in exceptions {
-            output.extend_from_slice(&(exception.len() as u64).to_le_bytes());
-            output.extend_from_slice(exception);
-        }
+        let exceptions = BinaryArray::<O>::from_slice(exceptions);
+        compress_binary(&exceptions, output, write_options)?;
         Ok(output.len() - size)
     }
 
     fn decompress(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        self.decompress_with_version(input, length, offsets, values, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
         &self,
         mut input: &[u8],
         length: usize,
         offsets: &mut Vec<O>,
         values: &mut Vec<u8>,
+        format_version: u16,
     ) -> Result<()> {
         let len = input.read_u64::<LittleEndian>()? as usize;
         if input.len() < len {
@@ -115,9 +130,39 @@ impl<O: Offset> BinaryCompression<O> for Freq {
 
         // read exceptions bitmap
         let exceptions_bitmap_size = input.read_u32::<LittleEndian>()?;
-        let exceptions_bitmap =
-            RoaringBitmap::deserialize_from(&input[..exceptions_bitmap_size as usize])?;
-        input.consume(exceptions_bitmap_size as usize);
+        let nested_exceptions = exceptions_bitmap_size & NESTED_EXCEPTIONS_FLAG != 0;
+        let exceptions_bitmap_size = (exceptions_bitmap_size & !NESTED_EXCEPTIONS_FLAG) as usize;
+        if input.len() < exceptions_bitmap_size {
+            return Err(general_err!(
+                "data size is less than {}",
+                exceptions_bitmap_size
+            ));
+        }
+        let exceptions_bitmap = RoaringBitmap::deserialize_from(&input[..exceptions_bitmap_size])?;
+        input.consume(exceptions_bitmap_size);
+
+        let mut exceptions_offsets: Vec<O> = Vec::new();
+        let mut exceptions_values = Vec::new();
+        if nested_exceptions {
+            decompress_binary(
+                &mut input,
+                exceptions_bitmap.len() as usize,
+                &mut exceptions_offsets,
+                &mut exceptions_values,
+                &mut vec![],
+                format_version,
+            )?;
+            if exceptions_offsets.len() != exceptions_bitmap.len() as usize + 1 {
+                return Err(general_err!(
+                    "expected {} freq exceptions, got {}",
+                    exceptions_bitmap.len(),
+                    exceptions_offsets.len().saturating_sub(1)
+                ));
+            }
+        }
+        let mut exceptions = exceptions_offsets
+            .windows(2)
+            .map(|w| &exceptions_values[w[0].to_usize()..w[1].to_usize()]);
 
         if offsets.is_empty() {
             offsets.push(O::default());
@@ -126,12 +171,17 @@ impl<O: Offset> BinaryCompression<O> for Freq {
         offsets.reserve(length);
         for i in 0..length {
             if exceptions_bitmap.contains(i as u32) {
-                let len = input.read_u64::<LittleEndian>()? as usize;
-                if input.len() < len {
-                    return Err(general_err!("data size is less than {}", len));
-                }
-                let val = &input[..len];
-                input.consume(len);
+                let val = if nested_exceptions {
+                    exceptions.next().unwrap()
+                } else {
+                    let len = input.read_u64::<LittleEndian>()? as usize;
+                    if input.len() < len {
+                        return Err(general_err!("data size is less than {}", len));
+                    }
+                    let val = &input[..len];
+                    input.consume(len);
+                    val
+                };
 
                 values.extend_from_slice(val);
                 offsets.push(O::from_usize(values.len()).unwrap());
@@ -168,3 +218,57 @@ impl<O: Offset> BinaryCompression<O> for Freq {
         0.0f64
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::super::gen_stats;
+    use super::BinaryCompression;
+    use crate::compression::integer::Freq;
+    use crate::write::WriteOptions;
+
+    fn roundtrip(array: BinaryArray<i32>) {
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        Freq {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut offsets = vec![];
+        let mut values = vec![];
+        BinaryCompression::<i32>::decompress(
+            &Freq {},
+            &buf,
+            array.len(),
+            &mut offsets,
+            &mut values,
+        )
+        .unwrap();
+        let result = BinaryArray::<i32>::new(
+            array.data_type().clone(),
+            offsets.try_into().unwrap(),
+            values.into(),
+            array.validity().cloned(),
+        );
+        assert_eq!(result, array);
+    }
+
+    #[test]
+    fn test_freq() {
+        roundtrip(BinaryArray::<i32>::from_iter((0..10000).map(|i| {
+            (i % 37 != 0).then(|| match i % 100 {
+                0 => format!("error {i}"),
+                _ => "ok".to_string(),
+            })
+        })));
+        // without exceptions
+        roundtrip(BinaryArray::<i32>::from_iter(
+            (0..1000).map(|i| (i % 3 != 0).then_some("ok")),
+        ));
+        // mostly nulls, every value is an exception
+        roundtrip(BinaryArray::<i32>::from_iter(
+            (0..1000).map(|i| (i % 20 == 0).then(|| format!("{i}"))),
+        ));
+    }
+}
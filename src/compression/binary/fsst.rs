@@ -0,0 +1,372 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use arrow::array::{BinaryArray, PrimitiveArray};
+use arrow::error::Error;
+use arrow::error::Result;
+use arrow::types::Offset;
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::compression::integer::{compress_integer, decompress_integer};
+use crate::compression::Compression;
+use crate::general_err;
+use crate::write::WriteOptions;
+use crate::SINGLE_ROW_GROUP_VERSION;
+
+use super::BinaryCompression;
+use super::BinaryStats;
+
+const MAX_SYMBOLS: usize = 255;
+const MAX_SYMBOL_LEN: usize = 8;
+// code of a byte which isn't covered by any symbol, the byte itself follows
+const ESCAPE: u8 = 255;
+const GENERATIONS: usize = 5;
+// number of value bytes the symbol table of a page is trained on
+const SAMPLE_BYTES: usize = 16 * 1024;
+const SAMPLE_RUNS: usize = 16;
+
+/// Fast Static Symbol Table compression.
+/// Each page trains a table of up to 255 symbols of 1-8 bytes, and every value
+/// is replaced by the codes of its longest matching symbols.
+///
+/// Page format: the symbol table, the code length of every value encoded as
+/// integers and the codes of all values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fsst {}
+
+impl<O: Offset> BinaryCompression<O> for Fsst {
+    fn to_compression(&self) -> Compression {
+        Compression::Fsst
+    }
+
+    fn compress_ratio(&self, stats: &BinaryStats<O>) -> f64 {
+        let sample = sample_values(&stats.src, SAMPLE_BYTES / 4);
+        let sample_bytes = sample.iter().map(|v| v.len()).sum::<usize>();
+        if sample_bytes == 0 {
+            return 0.0f64;
+        }
+
+        let table = SymbolTable::train(&sample);
+        let mut codes = Vec::with_capacity(sample_bytes);
+        for value in sample.iter() {
+            table.encode(value, &mut codes);
+        }
+
+        let values_size = stats.src.offsets().range().to_usize();
+        // every length is expected to be packed into about one byte
+        let after_size =
+            values_size * codes.len() / sample_bytes + table.serialized_size() + stats.tuple_count;
+        stats.total_bytes as f64 / after_size as f64
+    }
+
+    fn compress(
+        &self,
+        array: &BinaryArray<O>,
+        _stats: &BinaryStats<O>,
+        write_options: &WriteOptions,
+        output_buf: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output_buf.len();
+
+        let table = SymbolTable::train(&sample_values(array, SAMPLE_BYTES));
+        table.serialize(output_buf);
+
+        let mut codes = Vec::with_capacity(array.offsets().range().to_usize());
+        let mut lengths = Vec::with_capacity(array.len());
+        for value in array.values_iter() {
+            let len = codes.len();
+            table.encode(value, &mut codes);
+            lengths.push((codes.len() - len) as u32);
+        }
+
+        let mut write_options = write_options.clone();
+        write_options.forbidden_compressions.push(Compression::Dict);
+        compress_integer(
+            &PrimitiveArray::<u32>::from_vec(lengths),
+            write_options,
+            output_buf,
+        )?;
+
+        output_buf.extend_from_slice(&(codes.len() as u32).to_le_bytes());
+        output_buf.extend_from_slice(&codes);
+
+        Ok(output_buf.len() - start)
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        self.decompress_with_version(input, length, offsets, values, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+        format_version: u16,
+    ) -> Result<()> {
+        let table = SymbolTable::deserialize(&mut input)?;
+
+        let mut lengths: Vec<u32> = Vec::new();
+        decompress_integer(
+            &mut input,
+            length,
+            &mut lengths,
+            &mut vec![],
+            format_version,
+        )?;
+
+        let codes_len = input.read_u32::<LittleEndian>()? as usize;
+        if input.len() < codes_len {
+            return Err(general_err!(
+                "Invalid codes size: {} less than {}",
+                input.len(),
+                codes_len
+            ));
+        }
+        let mut codes = &input[..codes_len];
+
+        let mut last_offset = if offsets.is_empty() {
+            offsets.push(O::default());
+            0
+        } else {
+            offsets.last().unwrap().to_usize()
+        };
+        offsets.reserve(length);
+
+        for len in lengths.iter() {
+            let len = *len as usize;
+            if codes.len() < len {
+                return Err(general_err!("codes size is less than {}", len));
+            }
+            let start = values.len();
+            table.decode(&codes[..len], values)?;
+            codes.consume(len);
+
+            last_offset += values.len() - start;
+            offsets.push(O::from_usize(last_offset).unwrap());
+        }
+        Ok(())
+    }
+}
+
+/// Takes runs of consecutive values evenly spread over the array,
+/// about `sample_bytes` in total.
+fn sample_values<O: Offset>(array: &BinaryArray<O>, sample_bytes: usize) -> Vec<&[u8]> {
+    if array.offsets().range().to_usize() <= sample_bytes {
+        return array.values_iter().collect();
+    }
+    let run_step = array.len() / SAMPLE_RUNS + 1;
+    let mut sample = vec![];
+    for run_start in (0..array.len()).step_by(run_step) {
+        let mut run_bytes = 0;
+        for i in run_start..(run_start + run_step).min(array.len()) {
+            let value = array.value(i);
+            sample.push(value);
+            run_bytes += value.len();
+            if run_bytes >= sample_bytes / SAMPLE_RUNS {
+                break;
+            }
+        }
+    }
+    sample
+}
+
+#[derive(Debug, Default)]
+struct SymbolTable {
+    symbols: Vec<Vec<u8>>,
+    // codes of the symbols starting with each byte, longest symbol first
+    index: HashMap<u8, Vec<u8>>,
+}
+
+impl SymbolTable {
+    fn new(symbols: Vec<Vec<u8>>) -> Self {
+        let mut index: HashMap<u8, Vec<u8>> = HashMap::new();
+        for (code, symbol) in symbols.iter().enumerate() {
+            index.entry(symbol[0]).or_default().push(code as u8);
+        }
+        for codes in index.values_mut() {
+            codes.sort_by_key(|code| std::cmp::Reverse(symbols[*code as usize].len()));
+        }
+        Self { symbols, index }
+    }
+
+    fn train(sample: &[&[u8]]) -> Self {
+        let mut table = Self::default();
+        for _ in 0..GENERATIONS {
+            // count the symbols used by the current table, and the concatenations
+            // of adjacent symbols as the candidates of the next table
+            let mut counts: HashMap<&[u8], usize, ahash::RandomState> = HashMap::default();
+            for value in sample {
+                let mut pos = 0;
+                let mut prev_start = None;
+                while pos < value.len() {
+                    let len = table.find(&value[pos..]).map(|(_, len)| len).unwrap_or(1);
+                    *counts.entry(&value[pos..pos + len]).or_default() += 1;
+                    if let Some(prev_start) = prev_start {
+                        if pos + len - prev_start <= MAX_SYMBOL_LEN {
+                            *counts.entry(&value[prev_start..pos + len]).or_default() += 1;
+                        }
+                    }
+                    prev_start = Some(pos);
+                    pos += len;
+                }
+            }
+
+            let mut candidates: Vec<(usize, &[u8])> = counts
+                .into_iter()
+                .map(|(symbol, count)| (count * symbol.len(), symbol))
+                .collect();
+            candidates.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+            table = Self::new(
+                candidates
+                    .into_iter()
+                    .take(MAX_SYMBOLS)
+                    .map(|(_, symbol)| symbol.to_vec())
+                    .collect(),
+            );
+        }
+        table
+    }
+
+    /// Returns the code and length of the longest symbol `input` starts with.
+    fn find(&self, input: &[u8]) -> Option<(u8, usize)> {
+        self.index.get(&input[0]).and_then(|codes| {
+            codes.iter().find_map(|code| {
+                let symbol = &self.symbols[*code as usize];
+                input.starts_with(symbol).then_some((*code, symbol.len()))
+            })
+        })
+    }
+
+    fn encode(&self, mut input: &[u8], output: &mut Vec<u8>) {
+        while !input.is_empty() {
+            match self.find(input) {
+                Some((code, len)) => {
+                    output.push(code);
+                    input = &input[len..];
+                }
+                None => {
+                    output.push(ESCAPE);
+                    output.push(input[0]);
+                    input = &input[1..];
+                }
+            }
+        }
+    }
+
+    fn decode(&self, mut codes: &[u8], output: &mut Vec<u8>) -> Result<()> {
+        while let Some((code, rest)) = codes.split_first() {
+            if *code == ESCAPE {
+                let (byte, rest) = rest
+                    .split_first()
+                    .ok_or_else(|| general_err!("missing escaped byte"))?;
+                output.push(*byte);
+                codes = rest;
+            } else {
+                let symbol = self
+                    .symbols
+                    .get(*code as usize)
+                    .ok_or_else(|| general_err!("Invalid symbol code {}", code))?;
+                output.extend_from_slice(symbol);
+                codes = rest;
+            }
+        }
+        Ok(())
+    }
+
+    fn serialized_size(&self) -> usize {
+        1 + self.symbols.iter().map(|s| s.len() + 1).sum::<usize>()
+    }
+
+    fn serialize(&self, output: &mut Vec<u8>) {
+        output.push(self.symbols.len() as u8);
+        for symbol in self.symbols.iter() {
+            output.push(symbol.len() as u8);
+            output.extend_from_slice(symbol);
+        }
+    }
+
+    fn deserialize(input: &mut &[u8]) -> Result<Self> {
+        let num_symbols = input.read_u8()? as usize;
+        let mut symbols = Vec::with_capacity(num_symbols);
+        for _ in 0..num_symbols {
+            let len = input.read_u8()? as usize;
+            if len == 0 || len > MAX_SYMBOL_LEN || input.len() < len {
+                return Err(general_err!("Invalid symbol length {}", len));
+            }
+            symbols.push(input[..len].to_vec());
+            input.consume(len);
+        }
+        Ok(Self::new(symbols))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::super::gen_stats;
+    use super::{BinaryCompression, Fsst};
+    use crate::write::WriteOptions;
+
+    #[test]
+    fn test_fsst() {
+        let array = BinaryArray::<i32>::from_iter_values((0..10000).map(|i| {
+            format!(
+                "https://example.com/{}/item?id={}",
+                ["users", "orders", "items"][i % 3],
+                i
+            )
+        }));
+        let stats = gen_stats(&array);
+        assert!(BinaryCompression::<i32>::compress_ratio(&Fsst {}, &stats) > 2.0);
+
+        let mut buf = vec![];
+        let size = Fsst {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+        assert!(size * 2 < array.values().len());
+
+        let mut offsets = vec![];
+        let mut values = vec![];
+        BinaryCompression::<i32>::decompress(
+            &Fsst {},
+            &buf,
+            array.len(),
+            &mut offsets,
+            &mut values,
+        )
+        .unwrap();
+        let result = BinaryArray::<i32>::new(
+            array.data_type().clone(),
+            offsets.try_into().unwrap(),
+            values.into(),
+            None,
+        );
+        assert_eq!(result, array);
+    }
+}
@@ -0,0 +1,246 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::BufRead;
+
+use arrow::array::BinaryArray;
+use arrow::error::Error;
+use arrow::error::Result;
+use arrow::types::Offset;
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::compression::{get_bits_needed, Compression, SAMPLE_COUNT};
+use crate::general_err;
+use crate::util::bit_pack::{need_bytes, pack32, unpack32};
+use crate::write::WriteOptions;
+
+use super::{compress_sample_ratio, BinaryCompression, BinaryStats};
+
+const BLOCK_LEN: usize = 256;
+
+/// Front coding for sorted binary columns.
+/// Each value is stored as the length of the prefix it shares with the previous value
+/// plus the remaining suffix bytes.
+///
+/// Page format: the prefix lengths and the suffix lengths, both bit-packed in blocks
+/// of 256 values, followed by the size of all suffixes and the suffix bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IncrementalBinary {}
+
+impl<O: Offset> BinaryCompression<O> for IncrementalBinary {
+    fn to_compression(&self) -> Compression {
+        Compression::IncrementalBinary
+    }
+
+    fn compress_ratio(&self, stats: &BinaryStats<O>) -> f64 {
+        let array = &stats.src;
+        if array.len() == 0 {
+            return 0.0f64;
+        }
+        // the shared prefixes are local, so the ratio of sorted pages is sampled
+        if array.value(0) > array.value(array.len() - 1) {
+            return 0.0f64;
+        }
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
+    }
+
+    fn compress(
+        &self,
+        array: &BinaryArray<O>,
+        _stats: &BinaryStats<O>,
+        _write_options: &WriteOptions,
+        output_buf: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output_buf.len();
+
+        let mut prefix_lens = Vec::with_capacity(array.len());
+        let mut suffix_lens = Vec::with_capacity(array.len());
+        let mut suffixes = Vec::with_capacity(array.offsets().range().to_usize());
+        let mut last: &[u8] = &[];
+        for value in array.values_iter() {
+            let prefix = common_prefix_len(last, value);
+            prefix_lens.push(prefix as u32);
+            suffix_lens.push((value.len() - prefix) as u32);
+            suffixes.extend_from_slice(&value[prefix..]);
+            last = value;
+        }
+
+        pack_lengths(&prefix_lens, output_buf);
+        pack_lengths(&suffix_lens, output_buf);
+        output_buf.extend_from_slice(&(suffixes.len() as u32).to_le_bytes());
+        output_buf.extend_from_slice(&suffixes);
+
+        Ok(output_buf.len() - start)
+    }
+
+    fn decompress(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        let prefix_lens = unpack_lengths(&mut input, length)?;
+        let suffix_lens = unpack_lengths(&mut input, length)?;
+
+        let suffixes_size = input.read_u32::<LittleEndian>()? as usize;
+        if input.len() < suffixes_size {
+            return Err(general_err!(
+                "Invalid suffixes size: {} less than {}",
+                input.len(),
+                suffixes_size
+            ));
+        }
+        let mut suffixes = &input[..suffixes_size];
+
+        if offsets.is_empty() {
+            offsets.push(O::zero());
+        }
+        offsets.reserve(length);
+
+        // start of the previous value of this page in `values`
+        let mut last_start = values.len();
+        for (prefix, suffix) in prefix_lens.iter().zip(suffix_lens.iter()) {
+            let (prefix, suffix) = (*prefix as usize, *suffix as usize);
+            let start = values.len();
+            if prefix > start - last_start || suffixes.len() < suffix {
+                return Err(general_err!(
+                    "Invalid prefix {} or suffix {}",
+                    prefix,
+                    suffix
+                ));
+            }
+            values.extend_from_within(last_start..last_start + prefix);
+            values.extend_from_slice(&suffixes[..suffix]);
+            suffixes.consume(suffix);
+
+            last_start = start;
+            offsets.push(O::from_usize(values.len()).unwrap());
+        }
+        Ok(())
+    }
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Bit-packs `lengths` in blocks of [`BLOCK_LEN`], each block led by its bit width.
+fn pack_lengths(lengths: &[u32], output: &mut Vec<u8>) {
+    let mut block = [0u32; BLOCK_LEN];
+    let mut packed = vec![0u32; BLOCK_LEN];
+    for chunk in lengths.chunks(BLOCK_LEN) {
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()..].fill(0);
+
+        let width = chunk
+            .iter()
+            .map(|len| get_bits_needed(*len as u64))
+            .max()
+            .unwrap_or_default() as usize;
+        let size = need_bytes(BLOCK_LEN, width as u8);
+
+        packed.fill(0);
+        pack32(&block, bytemuck::cast_slice_mut(&mut packed), width);
+        output.push(width as u8);
+        output.extend_from_slice(&bytemuck::cast_slice(&packed)[..size]);
+    }
+}
+
+fn unpack_lengths(input: &mut &[u8], length: usize) -> Result<Vec<u32>> {
+    let mut lengths = vec![0u32; (length + BLOCK_LEN - 1) / BLOCK_LEN * BLOCK_LEN];
+    let mut packed = vec![0u32; BLOCK_LEN];
+    for block in lengths.chunks_mut(BLOCK_LEN) {
+        let width = input.read_u8()? as usize;
+        let size = need_bytes(BLOCK_LEN, width as u8);
+        if width > 32 || input.len() < size {
+            return Err(general_err!("Invalid bit width {}", width));
+        }
+        // copy to an aligned buffer for the unpacking
+        bytemuck::cast_slice_mut(&mut packed)[..size].copy_from_slice(&input[..size]);
+        unpack32(bytemuck::cast_slice(&packed), block, width);
+        input.consume(size);
+    }
+    lengths.truncate(length);
+    Ok(lengths)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::super::gen_stats;
+    use super::{BinaryCompression, IncrementalBinary};
+    use crate::write::WriteOptions;
+
+    #[test]
+    fn test_incremental_binary() {
+        let array = BinaryArray::<i64>::from_iter_values(
+            (0..1000).map(|i| format!("user/{:04}/profile/{}", i / 10, i % 10)),
+        );
+        let stats = gen_stats(&array);
+        assert!(BinaryCompression::<i64>::compress_ratio(&IncrementalBinary {}, &stats) > 1.5);
+
+        let mut buf = vec![];
+        let size = IncrementalBinary {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+        assert!(size * 2 < array.values().len());
+
+        // decode twice in the same buffers to check the offsets carried across pages
+        let mut offsets = vec![];
+        let mut values = vec![];
+        for _ in 0..2 {
+            BinaryCompression::<i64>::decompress(
+                &IncrementalBinary {},
+                &buf,
+                array.len(),
+                &mut offsets,
+                &mut values,
+            )
+            .unwrap();
+        }
+        let result = BinaryArray::<i64>::new(
+            array.data_type().clone(),
+            offsets.try_into().unwrap(),
+            values.into(),
+            None,
+        );
+        let expected = arrow::compute::concatenate::concatenate(&[&array, &array]).unwrap();
+        assert_eq!(&result as &dyn arrow::array::Array, expected.as_ref());
+    }
+
+    #[test]
+    fn test_incremental_binary_sampled_ratio() {
+        // the first and the last values share no prefix, the neighbouring ones do
+        let array = BinaryArray::<i64>::from_iter_values(
+            (0..10000).map(|i| format!("{}/segment/{:06}", (b'a' + (i / 1000) as u8) as char, i)),
+        );
+        let mut stats = gen_stats(&array);
+        let ratio = BinaryCompression::<i64>::compress_ratio(&IncrementalBinary {}, &stats);
+        assert!(ratio > 2.0);
+
+        // samples covering the whole array give the exact ratio
+        stats.sample_size = array.len();
+        let mut buf = vec![];
+        let size = IncrementalBinary {}
+            .compress(&array, &stats, &WriteOptions::uncompressed(), &mut buf)
+            .unwrap();
+        let ratio = BinaryCompression::<i64>::compress_ratio(&IncrementalBinary {}, &stats);
+        assert_eq!(ratio, stats.total_bytes as f64 / size as f64);
+    }
+}
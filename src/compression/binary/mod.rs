@@ -1,26 +1,46 @@
 mod dict;
 mod freq;
+mod fsst;
+mod incremental;
 mod one_value;
+mod restart;
+mod rle;
 
-use std::{collections::HashMap, hash::Hash, marker::PhantomData};
+use std::{borrow::Cow, collections::HashMap, hash::Hash, marker::PhantomData};
 
 use arrow::{
-    array::BinaryArray,
+    array::{BinaryArray, MutableBinaryArray},
     buffer::Buffer,
     error::{Error, Result},
     types::Offset,
 };
 
+use rand::{thread_rng, Rng};
+
 use crate::{
     read::{read_basic::read_compress_header, NativeReadBuf},
-    util::env::{check_dict_env, check_freq_env},
+    util::{
+        env::{check_dict_env, check_freq_env},
+        HyperLogLog, HYPER_LOG_LOG_ERROR,
+    },
     write::WriteOptions,
 };
 
+use self::dict::decompress_dict;
+pub use self::dict::decompress_dict_page;
+pub(crate) use self::freq::NESTED_EXCEPTIONS_FLAG;
+pub use self::fsst::Fsst;
+pub use self::incremental::IncrementalBinary;
+pub(crate) use self::restart::value_at;
+pub use self::restart::RestartPoints;
+
 use super::{
     basic::CommonCompression,
-    integer::{Dict, Freq, OneValue},
-    Compression,
+    compress_encoding, decompress_encoding,
+    integer::{Dict, Freq, OneValue, RLE},
+    is_valid,
+    registry::{registered_binary_compression, registered_binary_compressions},
+    split_codec, Compression, SAMPLE_SIZE,
 };
 
 pub fn compress_binary<O: Offset>(
@@ -28,9 +48,18 @@ pub fn compress_binary<O: Offset>(
     buf: &mut Vec<u8>,
     write_options: WriteOptions,
 ) -> Result<()> {
-    // choose compressor
-    let stats = gen_stats(array);
-    let compressor = choose_compressor(array, &stats, &write_options);
+    // choose compressor, the stats are only generated if an encoding is evaluated
+    let stats =
+        (write_options.evaluates_encodings() || write_options.binary_restart_points).then(|| {
+            let mut stats =
+                estimate_stats(array, &write_options).unwrap_or_else(|| gen_stats(array));
+            stats.sample_size = write_options.sample_size.unwrap_or(SAMPLE_SIZE);
+            stats
+        });
+    let compressor = match &stats {
+        Some(stats) => choose_compressor(array, stats, &write_options),
+        None => BinaryCompressor::Basic(write_options.default_compression),
+    };
 
     log::info!(
         "choose binary compression : {:?}",
@@ -43,26 +72,37 @@ pub fn compress_binary<O: Offset>(
         BinaryCompressor::Basic(c) => {
             //offsets
             let offsets = array.offsets();
-            let offsets = if offsets.first().is_zero() {
-                offsets.buffer().clone()
+            let first = *offsets.first();
+            let narrow_offsets;
+            let zero_offsets;
+            let input_buf: &[u8] = if write_options.narrow_large_offsets
+                && std::mem::size_of::<O>() == 8
+                && offsets.range().to_usize() <= u32::MAX as usize
+            {
+                narrow_offsets = offsets
+                    .iter()
+                    .map(|offset| (*offset - first).to_usize() as u32)
+                    .collect::<Vec<_>>();
+                bytemuck::cast_slice(&narrow_offsets)
+            } else if first.is_zero() {
+                bytemuck::cast_slice(offsets.buffer())
             } else {
-                let first = offsets.first();
-                let mut zero_offsets = Vec::with_capacity(offsets.len());
-                for offset in offsets.iter() {
-                    zero_offsets.push(*offset - *first);
-                }
-                zero_offsets.into()
+                zero_offsets = offsets
+                    .iter()
+                    .map(|offset| *offset - first)
+                    .collect::<Vec<_>>();
+                bytemuck::cast_slice(&zero_offsets)
             };
 
-            let input_buf = bytemuck::cast_slice(&offsets);
             buf.extend_from_slice(&codec.to_le_bytes());
             let pos = buf.len();
-            buf.extend_from_slice(&[0u8; 8]);
+            buf.extend_from_slice(&[0u8; 16]);
 
-            let compressed_size = c.compress(input_buf, buf)?;
+            let compressed_size =
+                c.compress_with_level(input_buf, buf, write_options.compression_level)?;
 
-            buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-            buf[pos + 4..pos + 8].copy_from_slice(&(input_buf.len() as u32).to_le_bytes());
+            buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+            buf[pos + 8..pos + 16].copy_from_slice(&(input_buf.len() as u64).to_le_bytes());
 
             // values
             let mut values = array.values().clone();
@@ -73,19 +113,28 @@ pub fn compress_binary<O: Offset>(
             let input_buf = bytemuck::cast_slice(&values);
             buf.extend_from_slice(&codec.to_le_bytes());
             let pos = buf.len();
-            buf.extend_from_slice(&[0u8; 8]);
+            buf.extend_from_slice(&[0u8; 16]);
 
-            let compressed_size = c.compress(input_buf, buf)?;
-            buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-            buf[pos + 4..pos + 8].copy_from_slice(&(input_buf.len() as u32).to_le_bytes());
+            let compressed_size =
+                c.compress_with_level(input_buf, buf, write_options.compression_level)?;
+            buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+            buf[pos + 8..pos + 16].copy_from_slice(&(input_buf.len() as u64).to_le_bytes());
         }
         BinaryCompressor::Extend(c) => {
+            let stats = stats.as_ref().unwrap();
             buf.extend_from_slice(&codec.to_le_bytes());
             let pos = buf.len();
-            buf.extend_from_slice(&[0u8; 8]);
-            let compressed_size = c.compress(array, &stats, &write_options, buf)?;
-            buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-            buf[pos + 4..pos + 8].copy_from_slice(&(array.values().len() as u32).to_le_bytes());
+            buf.extend_from_slice(&[0u8; 16]);
+            let compressed_size = compress_encoding(
+                c.to_compression(),
+                &write_options,
+                buf,
+                pos - 1,
+                |write_options, buf| c.compress(array, stats, write_options, buf),
+            )?;
+            buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+            buf[pos + 8..pos + 16]
+                .copy_from_slice(&(array.offsets().range().to_usize() as u64).to_le_bytes());
         }
     }
 
@@ -98,9 +147,46 @@ pub fn decompress_binary<O: Offset, R: NativeReadBuf>(
     offsets: &mut Vec<O>,
     values: &mut Vec<u8>,
     scratch: &mut Vec<u8>,
+    format_version: u16,
 ) -> Result<()> {
-    let (codec, compressed_size, _uncompressed_size) = read_compress_header(reader)?;
-    let compression = Compression::from_codec(codec)?;
+    decompress_binary_with_limit(
+        reader,
+        length,
+        offsets,
+        values,
+        scratch,
+        None,
+        format_version,
+    )
+}
+
+/// Decompresses a page like [`decompress_binary`], returning an error instead of decoding
+/// more than `max_decode_bytes` bytes of values, see `ReadOptions::max_decode_bytes`.
+/// The size of the values is checked before it is allocated, from the page header and,
+/// for the `Dict` pages, from the lengths of the values the indices gather.
+pub(crate) fn decompress_binary_with_limit<O: Offset, R: NativeReadBuf>(
+    reader: &mut R,
+    length: usize,
+    offsets: &mut Vec<O>,
+    values: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    max_decode_bytes: Option<usize>,
+    format_version: u16,
+) -> Result<()> {
+    let (codec, compressed_size, uncompressed_size) = read_compress_header(reader, format_version)?;
+    let (compression, compressed_encoding) = split_codec(codec)?;
+    if compression == Compression::AllNull {
+        // every value is empty
+        let last = match offsets.last() {
+            Some(last) => *last,
+            None => {
+                offsets.push(O::default());
+                O::default()
+            }
+        };
+        offsets.resize(offsets.len() + length, last);
+        return Ok(());
+    }
 
     // already fit in buffer
     let mut use_inner = false;
@@ -120,14 +206,28 @@ pub fn decompress_binary<O: Offset, R: NativeReadBuf>(
         BinaryCompressor::Basic(c) => {
             let last = offsets.last().cloned();
             offsets.reserve(length + 1);
-            let out_slice = unsafe {
-                core::slice::from_raw_parts_mut(
-                    offsets.as_mut_ptr().add(offsets.len()) as *mut u8,
-                    (length + 1) * std::mem::size_of::<O>(),
-                )
-            };
-            c.decompress(&input[..compressed_size], out_slice)?;
-            unsafe { offsets.set_len(offsets.len() + length + 1) };
+            if std::mem::size_of::<O>() == 8 && uncompressed_size == (length + 1) * 4 {
+                // the offsets are stored as u32, see `WriteOptions::narrow_large_offsets`
+                let mut narrow_offsets = vec![0u32; length + 1];
+                c.decompress(
+                    &input[..compressed_size],
+                    bytemuck::cast_slice_mut(&mut narrow_offsets),
+                )?;
+                offsets.extend(
+                    narrow_offsets
+                        .into_iter()
+                        .map(|offset| O::from_usize(offset as usize).unwrap()),
+                );
+            } else {
+                let out_slice = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        offsets.as_mut_ptr().add(offsets.len()) as *mut u8,
+                        (length + 1) * std::mem::size_of::<O>(),
+                    )
+                };
+                c.decompress(&input[..compressed_size], out_slice)?;
+                unsafe { offsets.set_len(offsets.len() + length + 1) };
+            }
 
             if use_inner {
                 reader.consume(compressed_size);
@@ -145,7 +245,9 @@ pub fn decompress_binary<O: Offset, R: NativeReadBuf>(
 
             // values
 
-            let (_, compressed_size, uncompressed_size) = read_compress_header(reader)?;
+            let (_, compressed_size, uncompressed_size) =
+                read_compress_header(reader, format_version)?;
+            check_decode_size(uncompressed_size, max_decode_bytes)?;
             use_inner = false;
             reader.fill_buf()?;
             let input = if reader.buffer_bytes().len() >= compressed_size {
@@ -172,7 +274,28 @@ pub fn decompress_binary<O: Offset, R: NativeReadBuf>(
             }
         }
         BinaryCompressor::Extend(c) => {
-            c.decompress(input, length, offsets, values)?;
+            // the uncompressed size of an encoded page is the size of its values
+            check_decode_size(uncompressed_size, max_decode_bytes)?;
+            values.reserve(uncompressed_size);
+            let start = values.len();
+            let encoded = if compressed_encoding {
+                Cow::Owned(decompress_encoding(&input[..compressed_size])?)
+            } else {
+                Cow::Borrowed(input)
+            };
+            if compression == Compression::Dict {
+                decompress_dict(
+                    &encoded,
+                    length,
+                    offsets,
+                    values,
+                    max_decode_bytes,
+                    format_version,
+                )?;
+            } else {
+                c.decompress_with_version(&encoded, length, offsets, values, format_version)?;
+            }
+            check_decode_size(values.len() - start, max_decode_bytes)?;
             if use_inner {
                 reader.consume(compressed_size);
             }
@@ -182,6 +305,16 @@ pub fn decompress_binary<O: Offset, R: NativeReadBuf>(
     Ok(())
 }
 
+/// Errors if `size` bytes of values exceed `max_decode_bytes`.
+pub(crate) fn check_decode_size(size: usize, max_decode_bytes: Option<usize>) -> Result<()> {
+    match max_decode_bytes {
+        Some(max) if size > max => Err(Error::OutOfSpec(format!(
+            "The values of a page decode to {size} bytes, over the limit of {max} bytes"
+        ))),
+        _ => Ok(()),
+    }
+}
+
 pub trait BinaryCompression<O: Offset> {
     fn compress(
         &self,
@@ -199,6 +332,21 @@ pub trait BinaryCompression<O: Offset> {
         values: &mut Vec<u8>,
     ) -> Result<()>;
 
+    /// Decompresses `input` like `decompress` for a page of a file of the header
+    /// `format_version`, which the layout of the buffers nested in the bytes of the
+    /// encodings depends on, see `ReadOptions::format_version`. `decompress` reads
+    /// the layout written by `compress`.
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+        _format_version: u16,
+    ) -> Result<()> {
+        self.decompress(input, length, offsets, values)
+    }
+
     fn compress_ratio(&self, stats: &BinaryStats<O>) -> f64;
     fn to_compression(&self) -> Compression;
 }
@@ -224,6 +372,16 @@ impl<O: Offset> BinaryCompressor<O> {
             Compression::OneValue => Ok(Self::Extend(Box::new(OneValue {}))),
             Compression::Freq => Ok(Self::Extend(Box::new(Freq {}))),
             Compression::Dict => Ok(Self::Extend(Box::new(Dict {}))),
+            Compression::Rle => Ok(Self::Extend(Box::new(RLE {}))),
+            Compression::Fsst => Ok(Self::Extend(Box::new(Fsst {}))),
+            Compression::IncrementalBinary => Ok(Self::Extend(Box::new(IncrementalBinary {}))),
+            Compression::RestartPoints => Ok(Self::Extend(Box::new(RestartPoints {}))),
+            Compression::Custom(codec) => match registered_binary_compression(codec) {
+                Some(c) => Ok(Self::Extend(Box::new(c))),
+                None => Err(Error::OutOfSpec(format!(
+                    "No binary encoding is registered with the codec {codec}"
+                ))),
+            },
             other => Err(Error::OutOfSpec(format!(
                 "Unknown compression codec {other:?}",
             ))),
@@ -252,28 +410,88 @@ impl std::ops::Deref for U8Buffer {
 
 #[allow(dead_code)]
 #[derive(Debug)]
-pub struct BinaryStats<O> {
-    tuple_count: usize,
-    total_bytes: usize,
-    unique_count: usize,
-    total_unique_size: usize,
-    null_count: usize,
-    distinct_values: HashMap<U8Buffer, usize>,
+pub struct BinaryStats<O: Offset> {
+    pub src: BinaryArray<O>,
+    pub tuple_count: usize,
+    pub total_bytes: usize,
+    pub unique_count: usize,
+    pub total_unique_size: usize,
+    pub null_count: usize,
+    /// Empty when the unique values of a high cardinality page are only counted.
+    pub distinct_values: HashMap<U8Buffer, usize>,
+    /// Run size of the samples that the encodings estimate their ratio on.
+    pub sample_size: usize,
     _data: PhantomData<O>,
 }
 
-fn gen_stats<O: Offset>(array: &BinaryArray<O>) -> BinaryStats<O> {
-    let mut stats = BinaryStats {
+/// The stats of `array` without its unique values.
+fn new_stats<O: Offset>(array: &BinaryArray<O>) -> BinaryStats<O> {
+    BinaryStats {
+        src: array.clone(),
         tuple_count: array.len(),
         total_bytes: array.values().len() + (array.len() + 1) * std::mem::size_of::<O>(),
         unique_count: 0,
         total_unique_size: 0,
         null_count: array.validity().map(|v| v.unset_bits()).unwrap_or_default(),
         distinct_values: HashMap::new(),
+        sample_size: SAMPLE_SIZE,
         _data: PhantomData,
-    };
+    }
+}
 
-    for o in array.offsets().windows(2) {
+/// Pages with fewer values always collect their unique values.
+const ESTIMATE_MIN_TUPLES: usize = 4096;
+/// `Freq` needs a value in 90% of the rows, so a page with more unique values
+/// than this ratio never uses it.
+const FREQ_MAX_UNIQUE_RATIO: f64 = 0.1;
+
+/// The stats of a high cardinality page, whose unique values are counted by a
+/// [`HyperLogLog`] instead of being collected in `distinct_values`, which stays empty.
+/// `None` if the estimate is within a few standard errors of the ratios of unique
+/// values that `Dict` and `Freq` are used under, which need the exact stats.
+fn estimate_stats<O: Offset>(
+    array: &BinaryArray<O>,
+    write_options: &WriteOptions,
+) -> Option<BinaryStats<O>> {
+    if array.len() < ESTIMATE_MIN_TUPLES {
+        return None;
+    }
+    let mut hll = HyperLogLog::new();
+    let mut valid_count = 0;
+    let mut valid_bytes = 0;
+    let validity = array.validity();
+    for (i, o) in array.offsets().windows(2).enumerate() {
+        if !is_valid(&validity, i) {
+            continue;
+        }
+        let value = &array.values()[o[0].to_usize()..o[1].to_usize()];
+        hll.add(value);
+        valid_count += 1;
+        valid_bytes += value.len();
+    }
+
+    let estimate = hll.estimate();
+    let max_unique_ratio = write_options
+        .dict_unique_ratio_limit()
+        .max(FREQ_MAX_UNIQUE_RATIO);
+    if estimate < max_unique_ratio * array.len() as f64 * (1.0 + 6.0 * HYPER_LOG_LOG_ERROR) {
+        return None;
+    }
+
+    let mut stats = new_stats(array);
+    stats.unique_count = (estimate.round() as usize).min(valid_count);
+    stats.total_unique_size = stats.unique_count * (valid_bytes / valid_count.max(1) + 8);
+    Some(stats)
+}
+
+fn gen_stats<O: Offset>(array: &BinaryArray<O>) -> BinaryStats<O> {
+    let mut stats = new_stats(array);
+
+    let validity = array.validity();
+    for (i, o) in array.offsets().windows(2).enumerate() {
+        if !is_valid(&validity, i) {
+            continue;
+        }
         let mut values = array.values().clone();
         values.slice(o[0].to_usize(), o[1].to_usize() - o[0].to_usize());
 
@@ -312,17 +530,34 @@ fn choose_compressor<O: Offset>(
             return BinaryCompressor::Extend(Box::new(Dict {}));
         }
     }
+    if write_options.binary_restart_points
+        && !write_options
+            .forbidden_compressions
+            .contains(&Compression::RestartPoints)
+    {
+        return BinaryCompressor::Extend(Box::new(RestartPoints {}));
+    }
     // todo
     let basic = BinaryCompressor::Basic(write_options.default_compression);
-    if let Some(ratio) = write_options.default_compress_ratio {
+    if let Some((ratio, sorted_only)) = write_options.compress_ratio() {
         let mut max_ratio = ratio;
         let mut result = basic;
 
-        let compressors: Vec<Box<dyn BinaryCompression<O>>> = vec![
+        let mut compressors: Vec<Box<dyn BinaryCompression<O>>> = vec![
             Box::new(OneValue {}) as _,
             Box::new(Freq {}) as _,
             Box::new(Dict {}) as _,
+            Box::new(RLE {}) as _,
+            Box::new(Fsst {}) as _,
+            Box::new(IncrementalBinary {}) as _,
         ];
+        for c in registered_binary_compressions::<O>() {
+            compressors.push(Box::new(c));
+        }
+        // front coding is the encoding of the declared sorted columns
+        if sorted_only {
+            compressors.retain(|c| c.to_compression() == Compression::IncrementalBinary);
+        }
 
         for encoder in compressors {
             if write_options
@@ -331,6 +566,11 @@ fn choose_compressor<O: Offset>(
             {
                 continue;
             }
+            if encoder.to_compression() == Compression::Dict
+                && !write_options.dict_candidate(stats.unique_count, stats.tuple_count)
+            {
+                continue;
+            }
             let r = encoder.compress_ratio(stats);
             if r > max_ratio {
                 max_ratio = r;
@@ -346,3 +586,172 @@ fn choose_compressor<O: Offset>(
         basic
     }
 }
+
+/// Estimates the ratio of `c` by compressing `sample_count` runs of `sample_size`
+/// consecutive values at random positions, or the whole array if it is smaller.
+fn compress_sample_ratio<O: Offset, C: BinaryCompression<O>>(
+    c: &C,
+    stats: &BinaryStats<O>,
+    sample_count: usize,
+    sample_size: usize,
+) -> f64 {
+    let mut rng = thread_rng();
+    let sample_size = sample_size.max(1);
+
+    let sample_stats;
+    let stats = if stats.src.len() / sample_count <= sample_size {
+        stats
+    } else {
+        let array = &stats.src;
+        let separator = array.len() / sample_count;
+        let remainder = array.len() % sample_count;
+        let mut builder = MutableBinaryArray::<O>::with_capacity(sample_count * sample_size);
+        for sample_i in 0..sample_count {
+            let range_end = if sample_i == sample_count - 1 {
+                separator + remainder
+            } else {
+                separator
+            } - sample_size;
+
+            let partition_begin = sample_i * separator + rng.gen_range(0..range_end);
+            builder.extend(array.clone().sliced(partition_begin, sample_size).iter());
+        }
+        let sample_array: BinaryArray<O> = builder.into();
+        sample_stats = gen_stats(&sample_array);
+        &sample_stats
+    };
+
+    let size = c
+        .compress(
+            &stats.src,
+            stats,
+            &WriteOptions::uncompressed(),
+            &mut vec![],
+        )
+        .unwrap_or(stats.total_bytes);
+
+    stats.total_bytes as f64 / size as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::{compress_binary, decompress_binary, estimate_stats, gen_stats};
+    use crate::{
+        compression::Compression, write::WriteOptions, CommonCompression, SINGLE_ROW_GROUP_VERSION,
+    };
+
+    #[test]
+    fn test_estimate_stats() {
+        let write_options = WriteOptions {
+            default_compress_ratio: Some(1.5),
+            ..Default::default()
+        };
+        let new_array = |num_values: usize, num_unique: usize| {
+            BinaryArray::<i32>::from_iter_values(
+                (0..num_values).map(|i| format!("value {}", i % num_unique)),
+            )
+        };
+
+        // the unique values of a high cardinality page are estimated
+        let array = new_array(20_000, 15_000);
+        let stats = estimate_stats(&array, &write_options).unwrap();
+        assert!(stats.distinct_values.is_empty());
+        assert!(stats.unique_count.abs_diff(15_000) < 15_000 / 10);
+        assert!(!write_options.dict_candidate(stats.unique_count, stats.tuple_count));
+        let exact = gen_stats(&array);
+        assert_eq!(exact.unique_count, 15_000);
+        assert!(
+            stats.total_unique_size.abs_diff(exact.total_unique_size)
+                < exact.total_unique_size / 10
+        );
+
+        // the pages near the threshold of `Dict` and the low cardinality ones are counted
+        for num_unique in [7_000, 100] {
+            assert!(estimate_stats(&new_array(20_000, num_unique), &write_options).is_none());
+        }
+        let options = WriteOptions {
+            max_dict_unique_ratio: Some(0.9),
+            ..write_options.clone()
+        };
+        assert!(estimate_stats(&array, &options).is_none());
+        // small pages are always counted
+        assert!(estimate_stats(&new_array(1000, 1000), &write_options).is_none());
+
+        let mut buf = vec![];
+        compress_binary(&array, &mut buf, write_options).unwrap();
+        let mut offsets = vec![];
+        let mut values = vec![];
+        decompress_binary(
+            &mut buf.as_slice(),
+            array.len(),
+            &mut offsets,
+            &mut values,
+            &mut vec![],
+            SINGLE_ROW_GROUP_VERSION,
+        )
+        .unwrap();
+        let result = BinaryArray::<i32>::new(
+            array.data_type().clone(),
+            offsets.try_into().unwrap(),
+            values.into(),
+            None,
+        );
+        assert_eq!(result, array);
+    }
+
+    #[test]
+    fn test_decompress_reserves_uncompressed_size() {
+        let array = BinaryArray::<i32>::from_iter_values(
+            (0..3000).map(|i| format!("a long value of the dictionary {}", i % 10)),
+        );
+        // the page of a slice only holds the values of the slice
+        let array = array.sliced(1000, 1000);
+        let values_len = array.offsets().range() as usize;
+
+        for compression in [Compression::Dict, Compression::Lz4] {
+            let write_options = match CommonCompression::try_from(&compression) {
+                Ok(c) => WriteOptions {
+                    default_compression: c,
+                    default_compress_ratio: None,
+                    ..Default::default()
+                },
+                Err(_) => WriteOptions {
+                    default_compression: CommonCompression::None,
+                    default_compress_ratio: Some(0.0),
+                    forbidden_compressions: Compression::ALL
+                        .into_iter()
+                        .filter(|c| *c != compression)
+                        .collect(),
+                    ..Default::default()
+                },
+            };
+            let mut buf = vec![];
+            compress_binary(&array, &mut buf, write_options).unwrap();
+            assert_eq!(buf[0], u8::from(compression));
+
+            let mut offsets = vec![];
+            let mut values = vec![];
+            decompress_binary(
+                &mut buf.as_slice(),
+                array.len(),
+                &mut offsets,
+                &mut values,
+                &mut vec![],
+                SINGLE_ROW_GROUP_VERSION,
+            )
+            .unwrap();
+            assert_eq!(values.len(), values_len);
+            assert_eq!(values.capacity(), values_len);
+
+            let result = BinaryArray::<i32>::new(
+                array.data_type().clone(),
+                offsets.try_into().unwrap(),
+                values.into(),
+                None,
+            );
+            assert_eq!(result, array);
+        }
+    }
+}
@@ -0,0 +1,250 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::BinaryArray;
+use arrow::error::Error;
+use arrow::error::Result;
+use arrow::types::Offset;
+use bitpacking::{BitPacker, BitPacker4x};
+
+use crate::compression::Compression;
+use crate::general_err;
+use crate::write::WriteOptions;
+
+use super::BinaryCompression;
+use super::BinaryStats;
+
+const BLOCK_LEN: usize = BitPacker4x::BLOCK_LEN;
+
+/// Binary layout with a restart point every 128 values,
+/// so a single value can be read by decoding the lengths of its block only.
+///
+/// Page format: the number of blocks, a restart point per block (the offset of its
+/// first value and the position of its lengths), the size of the bit-packed lengths,
+/// the lengths of every block led by its bit width, and the raw value bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RestartPoints {}
+
+impl<O: Offset> BinaryCompression<O> for RestartPoints {
+    fn to_compression(&self) -> Compression {
+        Compression::RestartPoints
+    }
+
+    fn compress_ratio(&self, _stats: &BinaryStats<O>) -> f64 {
+        // only used through `WriteOptions::binary_restart_points`
+        0.0f64
+    }
+
+    fn compress(
+        &self,
+        array: &BinaryArray<O>,
+        _stats: &BinaryStats<O>,
+        _write_options: &WriteOptions,
+        output_buf: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output_buf.len();
+        let bitpacker = BitPacker4x::new();
+        let offsets = array.offsets().buffer();
+        let first = offsets[0].to_usize();
+
+        let mut restarts = Vec::with_capacity((array.len() + BLOCK_LEN - 1) / BLOCK_LEN * 2);
+        let mut lengths = vec![];
+        let mut block = [0u32; BLOCK_LEN];
+        let mut packed = [0u8; BLOCK_LEN * 4];
+        for chunk in offsets.windows(2).collect::<Vec<_>>().chunks(BLOCK_LEN) {
+            restarts.push((chunk[0][0].to_usize() - first) as u32);
+            restarts.push(lengths.len() as u32);
+            for (len, window) in block.iter_mut().zip(chunk) {
+                *len = (window[1].to_usize() - window[0].to_usize()) as u32;
+            }
+            block[chunk.len()..].fill(0);
+
+            let num_bits = bitpacker.num_bits(&block);
+            let size = bitpacker.compress(&block, &mut packed, num_bits);
+            lengths.push(num_bits);
+            lengths.extend_from_slice(&packed[..size]);
+        }
+
+        output_buf.extend_from_slice(&((restarts.len() / 2) as u32).to_le_bytes());
+        for restart in restarts {
+            output_buf.extend_from_slice(&restart.to_le_bytes());
+        }
+        output_buf.extend_from_slice(&(lengths.len() as u32).to_le_bytes());
+        output_buf.extend_from_slice(&lengths);
+        output_buf.extend_from_slice(&array.values()[first..offsets[offsets.len() - 1].to_usize()]);
+
+        Ok(output_buf.len() - start)
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        let page = Page::try_new(input, length)?;
+
+        if offsets.is_empty() {
+            offsets.push(O::zero());
+        }
+        offsets.reserve(length);
+
+        let mut block = [0u32; BLOCK_LEN];
+        let mut end = values.len();
+        for b in 0..page.block_count {
+            let len = page.unpack_block(b, &mut block)?;
+            for value_len in &block[..len] {
+                end += *value_len as usize;
+                offsets.push(O::from_usize(end).unwrap());
+            }
+        }
+        if end - values.len() != page.values.len() {
+            return Err(general_err!("Invalid lengths of the restart points page"));
+        }
+        values.extend_from_slice(page.values);
+        Ok(())
+    }
+}
+
+/// Returns the value at `index` of a `RestartPoints` page of `length` values.
+pub(crate) fn value_at(input: &[u8], length: usize, index: usize) -> Result<&[u8]> {
+    if index >= length {
+        return Err(general_err!(
+            "Index {} out of bounds of a page of {} values",
+            index,
+            length
+        ));
+    }
+    let page = Page::try_new(input, length)?;
+    let (offset, _) = page.restart(index / BLOCK_LEN);
+
+    let mut block = [0u32; BLOCK_LEN];
+    page.unpack_block(index / BLOCK_LEN, &mut block)?;
+    let lengths = &block[..=index % BLOCK_LEN];
+    let start = offset + lengths[..lengths.len() - 1].iter().sum::<u32>() as usize;
+    let end = start + lengths[lengths.len() - 1] as usize;
+    page.values
+        .get(start..end)
+        .ok_or_else(|| general_err!("Invalid restart point of block {}", index / BLOCK_LEN))
+}
+
+struct Page<'a> {
+    length: usize,
+    block_count: usize,
+    restarts: &'a [u8],
+    lengths: &'a [u8],
+    values: &'a [u8],
+}
+
+impl<'a> Page<'a> {
+    fn try_new(input: &'a [u8], length: usize) -> Result<Self> {
+        let read_u32 = |pos: usize| {
+            input
+                .get(pos..pos + 4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()) as usize)
+                .ok_or_else(|| general_err!("Unexpected end of restart points page"))
+        };
+        let block_count = read_u32(0)?;
+        if block_count != (length + BLOCK_LEN - 1) / BLOCK_LEN {
+            return Err(general_err!(
+                "Invalid block count {} of a page of {} values",
+                block_count,
+                length
+            ));
+        }
+        let lengths_start = 4 + block_count * 8 + 4;
+        let lengths_size = read_u32(lengths_start - 4)?;
+        if input.len() < lengths_start + lengths_size {
+            return Err(general_err!("Unexpected end of restart points page"));
+        }
+        Ok(Self {
+            length,
+            block_count,
+            restarts: &input[4..lengths_start - 4],
+            lengths: &input[lengths_start..lengths_start + lengths_size],
+            values: &input[lengths_start + lengths_size..],
+        })
+    }
+
+    /// The offset of the first value and the position of the lengths of block `b`.
+    fn restart(&self, b: usize) -> (usize, usize) {
+        let restart = &self.restarts[b * 8..b * 8 + 8];
+        (
+            u32::from_le_bytes(restart[..4].try_into().unwrap()) as usize,
+            u32::from_le_bytes(restart[4..].try_into().unwrap()) as usize,
+        )
+    }
+
+    /// Unpacks the lengths of block `b`, returns the number of values in it.
+    fn unpack_block(&self, b: usize, block: &mut [u32; BLOCK_LEN]) -> Result<usize> {
+        let (_, pos) = self.restart(b);
+        let num_bits = *self
+            .lengths
+            .get(pos)
+            .ok_or_else(|| general_err!("Invalid restart point of block {}", b))?;
+        if num_bits > 32
+            || self.lengths.len() < pos + 1 + BitPacker4x::compressed_block_size(num_bits)
+        {
+            return Err(general_err!("Invalid bit width {}", num_bits));
+        }
+        BitPacker4x::new().decompress(&self.lengths[pos + 1..], block, num_bits);
+        Ok(BLOCK_LEN.min(self.length - b * BLOCK_LEN))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::super::gen_stats;
+    use super::{value_at, BinaryCompression, RestartPoints};
+    use crate::write::WriteOptions;
+
+    #[test]
+    fn test_restart_points() {
+        let array = BinaryArray::<i32>::from_iter_values(
+            (0..1000).map(|i| format!("value-{}", "x".repeat(i % 37))),
+        );
+        let mut sliced = array.clone();
+        sliced.slice(10, 900);
+        for array in [array, sliced] {
+            let stats = gen_stats(&array);
+            let mut buf = vec![];
+            RestartPoints {}
+                .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+                .unwrap();
+
+            let (mut offsets, mut values) = (vec![], vec![]);
+            RestartPoints {}
+                .decompress(&buf, array.len(), &mut offsets, &mut values)
+                .unwrap();
+            let output = BinaryArray::<i32>::new(
+                array.data_type().clone(),
+                offsets.try_into().unwrap(),
+                values.into(),
+                None,
+            );
+            assert_eq!(output, array);
+
+            for i in [0, 127, 128, 500, array.len() - 1] {
+                assert_eq!(value_at(&buf, array.len(), i).unwrap(), array.value(i));
+            }
+            assert!(value_at(&buf, array.len(), array.len()).is_err());
+        }
+    }
+}
@@ -0,0 +1,192 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::BufRead;
+
+use arrow::array::BinaryArray;
+use arrow::error::Error;
+use arrow::error::Result;
+use arrow::types::Offset;
+use byteorder::{LittleEndian, ReadBytesExt};
+
+use crate::compression::integer::RLE;
+use crate::compression::{is_valid, Compression, SAMPLE_COUNT};
+use crate::general_err;
+use crate::write::WriteOptions;
+
+use super::BinaryCompression;
+use super::BinaryStats;
+
+impl<O: Offset> BinaryCompression<O> for RLE {
+    fn to_compression(&self) -> Compression {
+        Compression::Rle
+    }
+
+    fn compress_ratio(&self, stats: &BinaryStats<O>) -> f64 {
+        let array = &stats.src;
+        if array.len() == 0 {
+            return 0.0f64;
+        }
+        let average_run_length = sample_run_length(array, stats.sample_size);
+        let average_value_size = array.offsets().range().to_usize() as f64 / array.len() as f64;
+        // every run stores its length, the value length and the value
+        let after_size = array.len() as f64 / average_run_length * (8.0 + average_value_size);
+        stats.total_bytes as f64 / after_size
+    }
+
+    fn compress(
+        &self,
+        array: &BinaryArray<O>,
+        _stats: &BinaryStats<O>,
+        _write_options: &WriteOptions,
+        output_buf: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output_buf.len();
+        let validity = array.validity();
+        let mut seen_count: u32 = 0;
+        let mut last_value: &[u8] = &[];
+        let mut all_null = true;
+
+        for (i, value) in array.values_iter().enumerate() {
+            if is_valid(&validity, i) {
+                if all_null {
+                    all_null = false;
+                    last_value = value;
+                    seen_count += 1;
+                } else if last_value != value {
+                    write_run(output_buf, seen_count, last_value);
+                    last_value = value;
+                    seen_count = 1;
+                } else {
+                    seen_count += 1;
+                }
+            } else {
+                // NULL value: we merely increment the seen_count
+                seen_count += 1;
+            }
+        }
+
+        if seen_count != 0 {
+            write_run(output_buf, seen_count, last_value);
+        }
+        Ok(output_buf.len() - start)
+    }
+
+    fn decompress(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        if offsets.is_empty() {
+            offsets.push(O::zero());
+        }
+        offsets.reserve(length);
+
+        let mut num_values = 0;
+        while num_values < length {
+            let run_length = input.read_u32::<LittleEndian>()? as usize;
+            let len = input.read_u32::<LittleEndian>()? as usize;
+            if input.len() < len {
+                return Err(general_err!("data size is less than {}", len));
+            }
+            let val = &input[..len];
+            for _ in 0..run_length.min(length - num_values) {
+                values.extend_from_slice(val);
+                offsets.push(O::from_usize(values.len()).unwrap());
+            }
+            input.consume(len);
+            num_values += run_length;
+        }
+        Ok(())
+    }
+}
+
+fn write_run(output_buf: &mut Vec<u8>, run_length: u32, value: &[u8]) {
+    output_buf.extend_from_slice(&run_length.to_le_bytes());
+    output_buf.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    output_buf.extend_from_slice(value);
+}
+
+/// The average run length of `SAMPLE_COUNT` evenly spaced windows
+/// of `sample_size` values, nulls are ignored.
+fn sample_run_length<O: Offset>(array: &BinaryArray<O>, sample_size: usize) -> f64 {
+    let window = sample_size.max(1).min(array.len());
+    let step = (array.len() / SAMPLE_COUNT).max(window);
+    let validity = array.validity();
+
+    let mut sampled = 0;
+    let mut runs = 0;
+    for start in (0..=array.len() - window).step_by(step) {
+        let mut last: Option<&[u8]> = None;
+        for i in start..start + window {
+            if !is_valid(&validity, i) {
+                continue;
+            }
+            let value = array.value(i);
+            if last != Some(value) {
+                runs += 1;
+                last = Some(value);
+            }
+        }
+        sampled += window;
+    }
+    sampled as f64 / runs.max(1) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::BinaryArray;
+
+    use super::super::gen_stats;
+    use super::BinaryCompression;
+    use crate::compression::integer::RLE;
+    use crate::write::WriteOptions;
+
+    #[test]
+    fn test_rle() {
+        let levels = ["DEBUG", "INFO", "WARN", "ERROR"];
+        let array = BinaryArray::<i64>::from_iter(
+            (0..10000).map(|i| (i % 37 != 0).then(|| levels[i / 1000 % 4])),
+        );
+        let stats = gen_stats(&array);
+        assert!(BinaryCompression::<i64>::compress_ratio(&RLE {}, &stats) > 10.0);
+
+        let mut buf = vec![];
+        RLE {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+        // a run for every thousand values
+        assert_eq!(
+            buf.len(),
+            10 * 8 + (0..10).map(|i| levels[i % 4].len()).sum::<usize>()
+        );
+
+        let mut offsets = vec![];
+        let mut values = vec![];
+        BinaryCompression::<i64>::decompress(&RLE {}, &buf, array.len(), &mut offsets, &mut values)
+            .unwrap();
+        let result = BinaryArray::<i64>::new(
+            array.data_type().clone(),
+            offsets.try_into().unwrap(),
+            values.into(),
+            array.validity().cloned(),
+        );
+        assert_eq!(result, array);
+    }
+}
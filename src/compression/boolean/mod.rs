@@ -16,8 +16,9 @@ use crate::{
 
 use super::{
     basic::CommonCompression,
+    compress_encoding, decompress_encoding,
     integer::{OneValue, RLE},
-    Compression,
+    split_codec, Compression, SAMPLE_SIZE,
 };
 
 pub fn compress_boolean(
@@ -25,9 +26,14 @@ pub fn compress_boolean(
     buf: &mut Vec<u8>,
     write_options: WriteOptions,
 ) -> Result<()> {
-    // choose compressor
-    let stats = gen_stats(array);
-    let compressor = choose_compressor(array, &stats, &write_options);
+    // choose compressor, the stats are only generated if an encoding is evaluated
+    let compressor = if write_options.evaluates_encodings() {
+        let mut stats = gen_stats(array);
+        stats.sample_size = write_options.sample_size.unwrap_or(SAMPLE_SIZE);
+        choose_compressor(array, &stats, &write_options)
+    } else {
+        BooleanCompressor::Basic(write_options.default_compression)
+    };
 
     log::info!(
         "choose boolean compression : {:?}",
@@ -37,7 +43,7 @@ pub fn compress_boolean(
     let codec = u8::from(compressor.to_compression());
     buf.extend_from_slice(&codec.to_le_bytes());
     let pos = buf.len();
-    buf.extend_from_slice(&[0u8; 8]);
+    buf.extend_from_slice(&[0u8; 16]);
 
     let compressed_size = match compressor {
         BooleanCompressor::Basic(c) => {
@@ -51,12 +57,18 @@ pub fn compress_boolean(
                 bitmap.clone()
             };
             let (slice, _, _) = bitmap.as_slice();
-            c.compress(slice, buf)
+            c.compress_with_level(slice, buf, write_options.compression_level)
         }
-        BooleanCompressor::Extend(c) => c.compress(array, buf),
+        BooleanCompressor::Extend(c) => compress_encoding(
+            c.to_compression(),
+            &write_options,
+            buf,
+            pos - 1,
+            |_, buf| c.compress(array, buf),
+        ),
     }?;
-    buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-    buf[pos + 4..pos + 8].copy_from_slice(&(array.len() as u32).to_le_bytes());
+    buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+    buf[pos + 8..pos + 16].copy_from_slice(&(array.len() as u64).to_le_bytes());
     Ok(())
 }
 
@@ -65,9 +77,15 @@ pub fn decompress_boolean<R: NativeReadBuf>(
     length: usize,
     output: &mut MutableBitmap,
     scratch: &mut Vec<u8>,
+    format_version: u16,
 ) -> Result<()> {
-    let (codec, compressed_size, _uncompressed_size) = read_compress_header(reader)?;
-    let compression = Compression::from_codec(codec)?;
+    let (codec, compressed_size, _uncompressed_size) =
+        read_compress_header(reader, format_version)?;
+    let (compression, compressed_encoding) = split_codec(codec)?;
+    if compression == Compression::AllNull {
+        output.extend_constant(length, false);
+        return Ok(());
+    }
 
     // already fit in buffer
     let mut use_inner = false;
@@ -91,7 +109,12 @@ pub fn decompress_boolean<R: NativeReadBuf>(
             output.extend_from_slice(buffer.as_slice(), 0, length);
         }
         BooleanCompressor::Extend(c) => {
-            c.decompress(input, length, output)?;
+            if compressed_encoding {
+                let encoded = decompress_encoding(&input[..compressed_size])?;
+                c.decompress(&encoded, length, output)?;
+            } else {
+                c.decompress(input, length, output)?;
+            }
         }
     }
 
@@ -146,6 +169,8 @@ pub struct BooleanStats {
     pub false_count: usize,
     pub true_count: usize,
     pub average_run_length: f64,
+    /// Run size of the samples that the encodings estimate their ratio on.
+    pub sample_size: usize,
 }
 
 fn gen_stats(array: &BooleanArray) -> BooleanStats {
@@ -153,16 +178,10 @@ fn gen_stats(array: &BooleanArray) -> BooleanStats {
     let mut false_count = 0;
     let mut true_count = 0;
 
-    let mut is_init_value_initialized = false;
     let mut last_value = false;
     let mut run_count = 0;
 
     for v in array.iter() {
-        if !is_init_value_initialized {
-            is_init_value_initialized = true;
-            last_value = v.unwrap_or_default();
-        }
-
         match v {
             Some(v) => {
                 if v {
@@ -171,7 +190,7 @@ fn gen_stats(array: &BooleanArray) -> BooleanStats {
                     false_count += 1;
                 }
 
-                if last_value != v {
+                if run_count == 0 || last_value != v {
                     run_count += 1;
                     last_value = v;
                 }
@@ -187,7 +206,9 @@ fn gen_stats(array: &BooleanArray) -> BooleanStats {
         null_count,
         false_count,
         true_count,
-        average_run_length: array.len() as f64 / 8.0f64 / run_count as f64,
+        // an all-null array is a single run
+        average_run_length: array.len() as f64 / 8.0f64 / run_count.max(1) as f64,
+        sample_size: SAMPLE_SIZE,
     }
 }
 
@@ -245,6 +266,7 @@ fn compress_sample_ratio<C: BooleanCompression>(
     sample_size: usize,
 ) -> f64 {
     let mut rng = thread_rng();
+    let sample_size = sample_size.max(1);
 
     let stats = if stats.src.len() / sample_count <= sample_size {
         stats.clone()
@@ -22,7 +22,7 @@ use arrow::error::Result;
 
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use crate::compression::{integer::RLE, SAMPLE_SIZE};
+use crate::compression::integer::RLE;
 use crate::compression::{Compression, SAMPLE_COUNT};
 
 use super::{compress_sample_ratio, BooleanCompression, BooleanStats};
@@ -59,6 +59,6 @@ impl BooleanCompression for RLE {
     }
 
     fn compress_ratio(&self, stats: &BooleanStats) -> f64 {
-        compress_sample_ratio(self, stats, SAMPLE_COUNT, SAMPLE_SIZE)
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
     }
 }
@@ -0,0 +1,286 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::PrimitiveArray;
+use arrow::error::Error;
+use arrow::error::Result;
+
+use crate::compression::{Compression, SAMPLE_COUNT};
+use crate::general_err;
+use crate::write::WriteOptions;
+
+use super::gorilla::{from_u64, to_u64, BitReader, BitWriter};
+use super::{compress_sample_ratio, DoubleCompression, DoubleStats, DoubleType};
+
+/// Chimp128 XOR encoding, see "Chimp: Efficient Lossless Floating Point Compression
+/// for Time Series Databases". Each value is XORed with the one of the previous 128
+/// values that shares its lowest bits, if the XOR has enough trailing zeros, or else
+/// with the previous value. After a 2 bits flag, the XOR is written as:
+/// - `00`: equal to the reference, only the distance to it.
+/// - `01`: the distance, the leading zeros, the number of significant bits and the bits
+///   between the leading and trailing zeros.
+/// - `10`: the bits after the leading zeros, which are the same as the last `11` XOR.
+/// - `11`: the leading zeros and the bits after them.
+///
+/// The leading zeros are rounded down to one of [`LEADING_ZEROS`], stored as its index.
+pub(crate) struct Chimp {}
+
+/// Values are referenced up to `WINDOW_SIZE - 1` positions back.
+const WINDOW_SIZE: usize = 128;
+const DISTANCE_BITS: u32 = 7;
+const LEADING_ZEROS: [u32; 8] = [0, 8, 12, 16, 18, 20, 22, 24];
+
+/// Index in [`LEADING_ZEROS`] of the rounded down leading zeros.
+fn leading_zeros_index(leading: u32) -> usize {
+    LEADING_ZEROS
+        .iter()
+        .rposition(|zeros| *zeros <= leading)
+        .unwrap()
+}
+
+impl<T: DoubleType> DoubleCompression<T> for Chimp {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        _stats: &DoubleStats<T>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output.len();
+        let bits = (std::mem::size_of::<T>() * 8) as u32;
+        // the XOR with a reference of the same lowest bits needs more trailing zeros
+        let threshold = bits.trailing_zeros() + DISTANCE_BITS;
+        let key_mask = (1u64 << (threshold + 1)) - 1;
+
+        let values: Vec<u64> = array.values().iter().map(to_u64).collect();
+        if values.is_empty() {
+            return Ok(0);
+        }
+        let mut writer = BitWriter::new(output);
+        writer.write(values[0], bits);
+
+        // the latest index of the values by their lowest bits
+        let mut indices = vec![usize::MAX; 1 << (threshold + 1)];
+        indices[(values[0] & key_mask) as usize] = 0;
+        // the leading zeros of the last `11` value, `bits + 1` means none
+        let mut stored_leading = bits + 1;
+        for i in 1..values.len() {
+            let val = values[i];
+            let key = (val & key_mask) as usize;
+            let candidate = indices[key];
+            let (reference, xor) = match candidate {
+                c if c != usize::MAX
+                    && i - c < WINDOW_SIZE
+                    && (val ^ values[c]).trailing_zeros() > threshold =>
+                {
+                    (c, val ^ values[c])
+                }
+                _ => (i - 1, val ^ values[i - 1]),
+            };
+            indices[key] = i;
+
+            if xor == 0 {
+                writer.write(0b00, 2);
+                writer.write((i - reference) as u64, DISTANCE_BITS);
+                stored_leading = bits + 1;
+                continue;
+            }
+
+            let index = leading_zeros_index(xor.leading_zeros() - (64 - bits));
+            let leading = LEADING_ZEROS[index];
+            let trailing = xor.trailing_zeros();
+            if trailing > threshold {
+                let significant = bits - leading - trailing;
+                writer.write(0b01, 2);
+                writer.write((i - reference) as u64, DISTANCE_BITS);
+                writer.write(index as u64, 3);
+                writer.write(significant as u64, 6);
+                writer.write(xor >> trailing, significant);
+                stored_leading = bits + 1;
+            } else if leading == stored_leading {
+                writer.write(0b10, 2);
+                writer.write(xor, bits - leading);
+            } else {
+                writer.write(0b11, 2);
+                writer.write(index as u64, 3);
+                writer.write(xor, bits - leading);
+                stored_leading = leading;
+            }
+        }
+        writer.finish();
+
+        Ok(output.len() - start)
+    }
+
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        if length == 0 {
+            return Ok(());
+        }
+        let bits = (std::mem::size_of::<T>() * 8) as u32;
+        let mut reader = BitReader::new(input);
+
+        output.reserve(length);
+        // the values of the page, to find the references
+        let mut values = Vec::with_capacity(length);
+        values.push(reader.read(bits)?);
+
+        let mut stored_leading = bits + 1;
+        for i in 1..length {
+            let reference = |distance: u64| {
+                let distance = distance as usize;
+                if distance == 0 || distance > i {
+                    return Err(general_err!(
+                        "Invalid chimp reference distance {}",
+                        distance
+                    ));
+                }
+                Ok(values[i - distance])
+            };
+            let val = match reader.read(2)? {
+                0b00 => reference(reader.read(DISTANCE_BITS)?)?,
+                0b01 => {
+                    let reference = reference(reader.read(DISTANCE_BITS)?)?;
+                    let leading = LEADING_ZEROS[reader.read(3)? as usize];
+                    let significant = reader.read(6)? as u32;
+                    if significant == 0 || leading + significant > bits {
+                        return Err(general_err!("Invalid chimp significant bits"));
+                    }
+                    let trailing = bits - leading - significant;
+                    stored_leading = bits + 1;
+                    reference ^ (reader.read(significant)? << trailing)
+                }
+                0b10 => {
+                    if stored_leading > bits {
+                        return Err(general_err!("Missing chimp leading zeros"));
+                    }
+                    values[i - 1] ^ reader.read(bits - stored_leading)?
+                }
+                _ => {
+                    stored_leading = LEADING_ZEROS[reader.read(3)? as usize];
+                    values[i - 1] ^ reader.read(bits - stored_leading)?
+                }
+            };
+            values.push(val);
+        }
+        output.extend(values.into_iter().map(from_u64::<T>));
+        Ok(())
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::Chimp
+    }
+
+    fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64 {
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::PrimitiveArray;
+
+    use super::super::{gen_stats, gorilla::Gorilla, DoubleType};
+    use super::{Chimp, DoubleCompression};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: DoubleType>(values: Vec<T>) -> usize {
+        let array = PrimitiveArray::<T>::from_vec(values);
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        let size = Chimp {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+        assert_eq!(size, buf.len());
+
+        let mut output: Vec<T> = vec![];
+        Chimp {}.decompress(&buf, array.len(), &mut output).unwrap();
+        assert_eq!(output.len(), array.len());
+        for (a, b) in output.iter().zip(array.values().iter()) {
+            assert_eq!(a.as_bits(), b.as_bits());
+        }
+        size
+    }
+
+    #[test]
+    fn test_chimp() {
+        let readings: Vec<f64> = (0..10000)
+            .map(|i| 20.0 + ((i / 10) as f64 * 0.25).sin().round())
+            .collect();
+        let size = roundtrip(readings);
+        assert!(size * 4 < 10000 * 8);
+
+        roundtrip((0..1000).map(|i| i as f32 * 0.5).collect::<Vec<_>>());
+        roundtrip(
+            (0..1000)
+                .map(|i| (i as f64 * 0.01).sin())
+                .collect::<Vec<_>>(),
+        );
+        roundtrip(
+            (0..1000)
+                .map(|i| (i as f32 * 0.01).sin())
+                .collect::<Vec<_>>(),
+        );
+        roundtrip(vec![
+            1.5f64,
+            f64::NAN,
+            -f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            -0.0,
+            0.0,
+            -0.0,
+            f64::MIN_POSITIVE,
+            f64::MIN_POSITIVE / 3.0,
+            -f64::from_bits(1),
+            f64::MAX,
+            f64::from_bits(1),
+        ]);
+        roundtrip(vec![
+            f32::NAN,
+            f32::INFINITY,
+            -0.0f32,
+            0.0,
+            f32::MAX,
+            f32::MIN_POSITIVE / 3.0,
+            f32::from_bits(1),
+        ]);
+        roundtrip(vec![3.0f64]);
+        roundtrip(Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_chimp_references() {
+        // values of a few series interleaved, which Gorilla only compares to the previous one
+        let series = [
+            std::f64::consts::PI,
+            std::f64::consts::E,
+            std::f64::consts::SQRT_2,
+            std::f64::consts::LN_10,
+        ];
+        let values: Vec<f64> = (0..4096)
+            .map(|i| series[i % 4] * (i / 64 + 1) as f64)
+            .collect();
+        let size = roundtrip(values.clone());
+
+        let array = PrimitiveArray::<f64>::from_vec(values);
+        let stats = gen_stats(&array);
+        let gorilla_size = Gorilla {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut vec![])
+            .unwrap();
+        assert!(size * 4 < gorilla_size);
+    }
+}
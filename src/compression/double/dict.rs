@@ -22,14 +22,14 @@ use arrow::error::Result;
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use crate::compression::get_bits_needed;
-use crate::compression::integer::compress_integer;
-use crate::compression::integer::decompress_integer;
 use crate::compression::integer::Dict;
 use crate::compression::integer::DictEncoder;
 use crate::compression::integer::RawNative;
+use crate::compression::integer::{compress_indices, decompress_indices, gather};
 use crate::compression::Compression;
 use crate::general_err;
 use crate::write::WriteOptions;
+use crate::SINGLE_ROW_GROUP_VERSION;
 
 use super::traits::DoubleType;
 use super::DoubleCompression;
@@ -64,9 +64,9 @@ impl<T: DoubleType> DoubleCompression<T> for Dict {
         // dict data use custom encoding
         let mut write_options = write_options.clone();
         write_options.forbidden_compressions.push(Compression::Dict);
-        compress_integer(&indices, write_options, output_buf)?;
-
         let sets = encoder.get_sets();
+        compress_indices(&indices, sets.len(), write_options, output_buf)?;
+
         output_buf.extend_from_slice(&(sets.len() as u32).to_le_bytes());
         // data page use plain encoding
         for val in sets.iter() {
@@ -77,9 +77,25 @@ impl<T: DoubleType> DoubleCompression<T> for Dict {
         Ok(output_buf.len() - start)
     }
 
-    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.decompress_with_version(input, length, output, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
         let mut indices: Vec<u32> = Vec::new();
-        decompress_integer(&mut input, length, &mut indices, &mut vec![])?;
+        decompress_indices(
+            &mut input,
+            length,
+            &mut indices,
+            &mut vec![],
+            format_version,
+        )?;
 
         let data_size = input.read_u32::<LittleEndian>()? as usize * std::mem::size_of::<T>();
         if input.len() < data_size {
@@ -99,11 +115,7 @@ impl<T: DoubleType> DoubleCompression<T> for Dict {
             })
             .collect();
 
-        output.reserve(length);
-        for i in indices.iter() {
-            output.push(data[*i as usize]);
-        }
-        Ok(())
+        gather(&data, &indices, output)
     }
 
     fn to_compression(&self) -> Compression {
@@ -111,11 +123,6 @@ impl<T: DoubleType> DoubleCompression<T> for Dict {
     }
 
     fn compress_ratio(&self, stats: &super::DoubleStats<T>) -> f64 {
-        const MIN_DICT_RATIO: usize = 3;
-        if stats.unique_count * MIN_DICT_RATIO >= stats.tuple_count {
-            return 0.0f64;
-        }
-
         let mut after_size = stats.unique_count * std::mem::size_of::<T>()
             + stats.tuple_count * (get_bits_needed(stats.unique_count as u64) / 8) as usize;
         after_size += (stats.tuple_count) * 2 / 128;
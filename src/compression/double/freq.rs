@@ -26,6 +26,7 @@ use roaring::RoaringBitmap;
 use crate::{
     compression::{double::decompress_double, integer::Freq, Compression},
     write::WriteOptions,
+    SINGLE_ROW_GROUP_VERSION,
 };
 
 use super::{compress_double, DoubleCompression, DoubleStats, DoubleType};
@@ -41,7 +42,7 @@ impl<T: DoubleType> DoubleCompression<T> for Freq {
         let size = output.len();
 
         let mut top_value_is_null = false;
-        let mut top_value = T::default().as_order();
+        let mut top_value = T::default().as_bits();
         let mut max_count = 0;
 
         if stats.null_count as f64 / stats.tuple_count as f64 >= 0.9 {
@@ -60,7 +61,7 @@ impl<T: DoubleType> DoubleCompression<T> for Freq {
 
         for (i, val) in array.iter().enumerate() {
             if let Some(val) = val {
-                if top_value_is_null || val.as_order() != top_value {
+                if top_value_is_null || val.as_bits() != top_value {
                     exceptions_bitmap.insert(i as u32);
                     exceptions.push(*val);
                 }
@@ -68,7 +69,7 @@ impl<T: DoubleType> DoubleCompression<T> for Freq {
         }
 
         // Write TopValue
-        output.extend_from_slice(T::from_order(top_value).to_le_bytes().as_ref());
+        output.extend_from_slice(T::from_bits_val(top_value).to_le_bytes().as_ref());
 
         // Write exceptions bitmap
         output.extend_from_slice(&(exceptions_bitmap.serialized_size() as u32).to_le_bytes());
@@ -87,7 +88,17 @@ impl<T: DoubleType> DoubleCompression<T> for Freq {
         Ok(output.len() - size)
     }
 
-    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.decompress_with_version(input, length, output, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
         let begin = output.len();
 
         let mut bs = vec![0u8; std::mem::size_of::<T>()];
@@ -111,6 +122,7 @@ impl<T: DoubleType> DoubleCompression<T> for Freq {
             exceptions_bitmap.len() as usize,
             &mut exceptions,
             &mut vec![],
+            format_version,
         )?;
 
         assert_eq!(exceptions_bitmap.len() as usize, exceptions.len());
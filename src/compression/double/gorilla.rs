@@ -0,0 +1,261 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::PrimitiveArray;
+use arrow::error::Error;
+use arrow::error::Result;
+
+use crate::compression::{Compression, SAMPLE_COUNT};
+use crate::general_err;
+use crate::write::WriteOptions;
+
+use super::{compress_sample_ratio, DoubleCompression, DoubleStats, DoubleType};
+
+/// Gorilla XOR encoding, see "Gorilla: A Fast, Scalable, In-Memory Time Series Database".
+/// The first value is stored verbatim, each following value is XORed with the previous
+/// one and only the meaningful bits of the result are written, either inside the
+/// leading/trailing zeros window of the previous value or with a new window.
+pub(crate) struct Gorilla {}
+
+// the number of leading zeros is stored in 5 bits
+const MAX_LEADING_ZEROS: u32 = 31;
+
+impl<T: DoubleType> DoubleCompression<T> for Gorilla {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        _stats: &DoubleStats<T>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start = output.len();
+        let bits = (std::mem::size_of::<T>() * 8) as u32;
+        let mut writer = BitWriter::new(output);
+
+        let mut values = array.values().iter().map(to_u64);
+        let mut prev = match values.next() {
+            Some(first) => first,
+            None => return Ok(0),
+        };
+        writer.write(prev, bits);
+
+        // leading and trailing zeros of the current window, `bits` means no window yet
+        let mut window = (bits, bits);
+        for val in values {
+            let xor = val ^ prev;
+            prev = val;
+            if xor == 0 {
+                writer.write(0, 1);
+                continue;
+            }
+            writer.write(1, 1);
+
+            let leading = (xor.leading_zeros() - (64 - bits)).min(MAX_LEADING_ZEROS);
+            let trailing = xor.trailing_zeros();
+            if window.0 + window.1 < bits && leading >= window.0 && trailing >= window.1 {
+                writer.write(0, 1);
+                writer.write(xor >> window.1, bits - window.0 - window.1);
+            } else {
+                let meaningful = bits - leading - trailing;
+                writer.write(1, 1);
+                writer.write(leading as u64, 5);
+                writer.write((meaningful - 1) as u64, 6);
+                writer.write(xor >> trailing, meaningful);
+                window = (leading, trailing);
+            }
+        }
+        writer.finish();
+
+        Ok(output.len() - start)
+    }
+
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        if length == 0 {
+            return Ok(());
+        }
+        let bits = (std::mem::size_of::<T>() * 8) as u32;
+        let mut reader = BitReader::new(input);
+
+        output.reserve(length);
+        let mut prev = reader.read(bits)?;
+        output.push(from_u64(prev));
+
+        let mut window = (bits, bits);
+        for _ in 1..length {
+            if reader.read(1)? == 1 {
+                if reader.read(1)? == 1 {
+                    let leading = reader.read(5)? as u32;
+                    let meaningful = reader.read(6)? as u32 + 1;
+                    if leading + meaningful > bits {
+                        return Err(general_err!("Invalid gorilla window"));
+                    }
+                    window = (leading, bits - leading - meaningful);
+                } else if window.0 + window.1 >= bits {
+                    return Err(general_err!("Missing gorilla window"));
+                }
+                let xor = reader.read(bits - window.0 - window.1)? << window.1;
+                prev ^= xor;
+            }
+            output.push(from_u64(prev));
+        }
+        Ok(())
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::Gorilla
+    }
+
+    fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64 {
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
+    }
+}
+
+pub(super) fn to_u64<T: DoubleType>(val: &T) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes[..std::mem::size_of::<T>()].copy_from_slice(val.to_le_bytes().as_ref());
+    u64::from_le_bytes(bytes)
+}
+
+pub(super) fn from_u64<T: DoubleType>(val: u64) -> T {
+    let bytes = val.to_le_bytes();
+    bytemuck::pod_read_unaligned(&bytes[..std::mem::size_of::<T>()])
+}
+
+/// Writes the lowest bits of values into bytes, least significant bit first.
+pub(super) struct BitWriter<'a> {
+    output: &'a mut Vec<u8>,
+    buffer: u128,
+    len: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    pub(super) fn new(output: &'a mut Vec<u8>) -> Self {
+        Self {
+            output,
+            buffer: 0,
+            len: 0,
+        }
+    }
+
+    pub(super) fn write(&mut self, value: u64, bits: u32) {
+        let mask = (1u128 << bits) - 1;
+        self.buffer |= (value as u128 & mask) << self.len;
+        self.len += bits;
+        while self.len >= 8 {
+            self.output.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.len -= 8;
+        }
+    }
+
+    pub(super) fn finish(self) {
+        if self.len > 0 {
+            self.output.push(self.buffer as u8);
+        }
+    }
+}
+
+pub(super) struct BitReader<'a> {
+    input: &'a [u8],
+    buffer: u128,
+    len: u32,
+}
+
+impl<'a> BitReader<'a> {
+    pub(super) fn new(input: &'a [u8]) -> Self {
+        Self {
+            input,
+            buffer: 0,
+            len: 0,
+        }
+    }
+
+    pub(super) fn read(&mut self, bits: u32) -> Result<u64> {
+        while self.len < bits {
+            let (byte, rest) = self
+                .input
+                .split_first()
+                .ok_or_else(|| general_err!("Unexpected end of bit stream"))?;
+            self.buffer |= (*byte as u128) << self.len;
+            self.input = rest;
+            self.len += 8;
+        }
+        let value = (self.buffer & ((1u128 << bits) - 1)) as u64;
+        self.buffer >>= bits;
+        self.len -= bits;
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::PrimitiveArray;
+
+    use super::super::{gen_stats, DoubleType};
+    use super::{DoubleCompression, Gorilla};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: DoubleType>(values: Vec<T>) -> usize {
+        let array = PrimitiveArray::<T>::from_vec(values);
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        let size = Gorilla {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut output: Vec<T> = vec![];
+        Gorilla {}
+            .decompress(&buf, array.len(), &mut output)
+            .unwrap();
+        assert_eq!(output.len(), array.len());
+        for (a, b) in output.iter().zip(array.values().iter()) {
+            assert_eq!(a.as_bits(), b.as_bits());
+        }
+        size
+    }
+
+    #[test]
+    fn test_gorilla() {
+        let readings: Vec<f64> = (0..10000)
+            .map(|i| 20.0 + ((i / 10) as f64 * 0.25).sin().round())
+            .collect();
+        let size = roundtrip(readings);
+        assert!(size * 4 < 10000 * 8);
+
+        roundtrip((0..1000).map(|i| i as f32 * 0.5).collect::<Vec<_>>());
+        roundtrip(vec![
+            1.5f64,
+            f64::NAN,
+            -f64::NAN,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            -0.0,
+            0.0,
+            f64::MIN_POSITIVE,
+            f64::MAX,
+            f64::from_bits(1),
+        ]);
+        roundtrip(vec![
+            f32::NAN,
+            f32::INFINITY,
+            -0.0f32,
+            f32::MAX,
+            f32::from_bits(1),
+        ]);
+        roundtrip(vec![3.0f64]);
+    }
+}
@@ -1,5 +1,7 @@
+mod chimp;
 mod dict;
 mod freq;
+mod gorilla;
 mod one_value;
 mod patas;
 mod rle;
@@ -19,14 +21,19 @@ use crate::{
     write::WriteOptions,
 };
 
+use self::chimp::Chimp;
+use self::gorilla::Gorilla;
 pub use self::one_value::OneValue;
 use self::patas::Patas;
 pub use self::traits::DoubleType;
 
 use super::{
     basic::CommonCompression,
+    compress_encoding, decompress_encoding,
     integer::{Dict, Freq, RLE},
-    is_valid, Compression,
+    is_valid,
+    registry::{registered_double_compression, registered_double_compressions},
+    split_codec, Compression, SAMPLE_SIZE,
 };
 
 pub fn compress_double<T: DoubleType>(
@@ -34,9 +41,24 @@ pub fn compress_double<T: DoubleType>(
     write_options: WriteOptions,
     buf: &mut Vec<u8>,
 ) -> Result<()> {
-    // choose compressor
-    let stats = gen_stats(array);
-    let compressor = choose_compressor(array, &stats, &write_options);
+    let canonical;
+    let array = if write_options.canonicalize_floats {
+        canonical = canonicalize(array);
+        &canonical
+    } else {
+        array
+    };
+
+    // choose compressor, the stats are only generated if an encoding is evaluated
+    let stats = write_options.evaluates_encodings().then(|| {
+        let mut stats = gen_stats_with_order(array, write_options.sorted);
+        stats.sample_size = write_options.sample_size.unwrap_or(SAMPLE_SIZE);
+        stats
+    });
+    let compressor = match &stats {
+        Some(stats) => choose_compressor(array, stats, &write_options),
+        None => DoubleCompressor::Basic(write_options.default_compression),
+    };
 
     log::info!(
         "choose double compression : {:?}",
@@ -46,34 +68,71 @@ pub fn compress_double<T: DoubleType>(
     let codec = u8::from(compressor.to_compression());
     buf.extend_from_slice(&codec.to_le_bytes());
     let pos = buf.len();
-    buf.extend_from_slice(&[0u8; 8]);
+    buf.extend_from_slice(&[0u8; 16]);
 
     let compressed_size = match compressor {
         DoubleCompressor::Basic(c) => {
             let input_buf = bytemuck::cast_slice(array.values());
-            c.compress(input_buf, buf)
+            c.compress_with_level(input_buf, buf, write_options.compression_level)
+        }
+        DoubleCompressor::Extend(c) => {
+            let stats = stats.as_ref().unwrap();
+            compress_encoding(
+                c.to_compression(),
+                &write_options,
+                buf,
+                pos - 1,
+                |write_options, buf| c.compress(array, stats, write_options, buf),
+            )
         }
-        DoubleCompressor::Extend(c) => c.compress(array, &stats, &write_options, buf),
     }?;
-    buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-    buf[pos + 4..pos + 8]
-        .copy_from_slice(&((array.len() * std::mem::size_of::<T>()) as u32).to_le_bytes());
+    let total_bytes = array.len() * std::mem::size_of::<T>();
+    buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+    buf[pos + 8..pos + 16].copy_from_slice(&(total_bytes as u64).to_le_bytes());
 
     log::debug!(
         "double compress ratio {}",
-        stats.total_bytes as f64 / compressed_size as f64
+        total_bytes as f64 / compressed_size as f64
     );
     Ok(())
 }
 
+/// Maps -0.0 to 0.0 and every NaN to the canonical NaN.
+fn canonicalize<T: DoubleType>(array: &PrimitiveArray<T>) -> PrimitiveArray<T> {
+    let values: Vec<T> = array
+        .values()
+        .iter()
+        .map(|v| {
+            if v.is_nan() {
+                T::nan()
+            } else if *v == T::zero() {
+                T::zero()
+            } else {
+                *v
+            }
+        })
+        .collect();
+    PrimitiveArray::new(
+        array.data_type().clone(),
+        values.into(),
+        array.validity().cloned(),
+    )
+}
+
 pub fn decompress_double<T: DoubleType, R: NativeReadBuf>(
     reader: &mut R,
     length: usize,
     output: &mut Vec<T>,
     scratch: &mut Vec<u8>,
+    format_version: u16,
 ) -> Result<()> {
-    let (codec, compressed_size, _uncompressed_size) = read_compress_header(reader)?;
-    let compression = Compression::from_codec(codec)?;
+    let (codec, compressed_size, _uncompressed_size) =
+        read_compress_header(reader, format_version)?;
+    let (compression, compressed_encoding) = split_codec(codec)?;
+    if compression == Compression::AllNull {
+        output.resize(output.len() + length, T::default());
+        return Ok(());
+    }
 
     // already fit in buffer
     let mut use_inner = false;
@@ -103,7 +162,12 @@ pub fn decompress_double<T: DoubleType, R: NativeReadBuf>(
             unsafe { output.set_len(output.len() + length) };
         }
         DoubleCompressor::Extend(c) => {
-            c.decompress(input, length, output)?;
+            if compressed_encoding {
+                let encoded = decompress_encoding(&input[..compressed_size])?;
+                c.decompress_with_version(&encoded, length, output, format_version)?;
+            } else {
+                c.decompress_with_version(input, length, output, format_version)?;
+            }
         }
     }
 
@@ -123,6 +187,20 @@ pub trait DoubleCompression<T: DoubleType> {
     ) -> Result<usize>;
     fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()>;
 
+    /// Decompresses `input` like `decompress` for a page of a file of the header
+    /// `format_version`, which the layout of the buffers nested in the bytes of the
+    /// encodings depends on, see `ReadOptions::format_version`. `decompress` reads
+    /// the layout written by `compress`.
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        _format_version: u16,
+    ) -> Result<()> {
+        self.decompress(input, length, output)
+    }
+
     fn to_compression(&self) -> Compression;
     fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64;
 }
@@ -150,6 +228,14 @@ impl<T: DoubleType> DoubleCompressor<T> {
             Compression::Freq => Ok(Self::Extend(Box::new(Freq {}))),
             Compression::Rle => Ok(Self::Extend(Box::new(RLE {}))),
             Compression::Patas => Ok(Self::Extend(Box::new(Patas {}))),
+            Compression::Gorilla => Ok(Self::Extend(Box::new(Gorilla {}))),
+            Compression::Chimp => Ok(Self::Extend(Box::new(Chimp {}))),
+            Compression::Custom(codec) => match registered_double_compression(codec) {
+                Some(c) => Ok(Self::Extend(Box::new(c))),
+                None => Err(Error::OutOfSpec(format!(
+                    "No float encoding is registered with the codec {codec}"
+                ))),
+            },
 
             other => Err(Error::OutOfSpec(format!(
                 "Unknown compression codec {other:?}",
@@ -170,12 +256,22 @@ pub struct DoubleStats<T: DoubleType> {
     pub max: T::OrderType,
 
     pub average_run_length: f64,
-    pub distinct_values: HashMap<T::OrderType, usize>,
+    /// The count of each value by bit pattern, so -0.0 is not 0.0 and every
+    /// NaN payload is a value of its own, like in the runs of `average_run_length`.
+    pub distinct_values: HashMap<T::BitType, usize>,
     pub unique_count: usize,
     pub set_count: usize,
+    /// Run size of the samples that the encodings estimate their ratio on.
+    pub sample_size: usize,
 }
 
+#[cfg(test)]
 fn gen_stats<T: DoubleType>(array: &PrimitiveArray<T>) -> DoubleStats<T> {
+    gen_stats_with_order(array, false)
+}
+
+/// The stats of `array`, whose order is not checked if it is declared `sorted`.
+fn gen_stats_with_order<T: DoubleType>(array: &PrimitiveArray<T>, sorted: bool) -> DoubleStats<T> {
     let mut stats = DoubleStats::<T> {
         src: array.clone(),
         tuple_count: array.len(),
@@ -188,24 +284,30 @@ fn gen_stats<T: DoubleType>(array: &PrimitiveArray<T>) -> DoubleStats<T> {
         distinct_values: HashMap::new(),
         unique_count: 0,
         set_count: array.len() - array.null_count(),
+        sample_size: SAMPLE_SIZE,
     };
 
     let mut is_init_value_initialized = false;
     let mut last_value = T::default().as_order();
+    let mut last_bits = T::default().as_bits();
     let mut run_count = 0;
 
     let validity = array.validity();
     for (i, current_value) in array.values().iter().cloned().enumerate() {
+        let current_bits = current_value.as_bits();
         let current_value = current_value.as_order();
         if is_valid(&validity, i) {
-            if current_value < last_value {
+            if !sorted && current_value < last_value {
                 stats.is_sorted = false;
             }
+            last_value = current_value;
 
-            if last_value != current_value {
+            if run_count == 0 || last_bits != current_bits {
                 run_count += 1;
-                last_value = current_value;
+                last_bits = current_bits;
             }
+
+            *stats.distinct_values.entry(current_bits).or_insert(0) += 1;
         }
 
         if !is_init_value_initialized {
@@ -219,11 +321,10 @@ fn gen_stats<T: DoubleType>(array: &PrimitiveArray<T>) -> DoubleStats<T> {
         } else if current_value < stats.min {
             stats.min = current_value;
         }
-
-        *stats.distinct_values.entry(current_value).or_insert(0) += 1;
     }
     stats.unique_count = stats.distinct_values.len();
-    stats.average_run_length = array.len() as f64 / run_count as f64;
+    // an all-null array is a single run
+    stats.average_run_length = array.len() as f64 / run_count.max(1) as f64;
 
     stats
 }
@@ -268,13 +369,18 @@ fn choose_compressor<T: DoubleType>(
     if let Some(ratio) = write_options.default_compress_ratio {
         let mut max_ratio = ratio;
         let mut result = basic;
-        let compressors: Vec<Box<dyn DoubleCompression<T>>> = vec![
+        let mut compressors: Vec<Box<dyn DoubleCompression<T>>> = vec![
             Box::new(OneValue {}) as _,
             Box::new(Freq {}) as _,
             Box::new(Dict {}) as _,
             Box::new(Patas {}) as _,
+            Box::new(Gorilla {}) as _,
+            Box::new(Chimp {}) as _,
             Box::new(RLE {}) as _,
         ];
+        for c in registered_double_compressions::<T>() {
+            compressors.push(Box::new(c));
+        }
         for c in compressors {
             if write_options
                 .forbidden_compressions
@@ -282,6 +388,11 @@ fn choose_compressor<T: DoubleType>(
             {
                 continue;
             }
+            if c.to_compression() == Compression::Dict
+                && !write_options.dict_candidate(stats.unique_count, stats.tuple_count)
+            {
+                continue;
+            }
             let r = c.compress_ratio(stats);
 
             log::debug!(
@@ -313,6 +424,7 @@ fn compress_sample_ratio<T: DoubleType, C: DoubleCompression<T>>(
     sample_size: usize,
 ) -> f64 {
     let mut rng = thread_rng();
+    let sample_size = sample_size.max(1);
 
     let stats = if stats.src.len() / sample_count <= sample_size {
         stats.clone()
@@ -336,12 +448,125 @@ fn compress_sample_ratio<T: DoubleType, C: DoubleCompression<T>>(
             builder.extend_trusted_len(s.into_iter());
         }
         let sample_array: PrimitiveArray<T> = builder.into();
-        gen_stats(&sample_array)
+        gen_stats_with_order(&sample_array, stats.is_sorted)
     };
 
     let size = c
-        .compress(&stats.src, &stats, &WriteOptions::default(), &mut vec![])
+        .compress(
+            &stats.src,
+            &stats,
+            &WriteOptions::uncompressed(),
+            &mut vec![],
+        )
         .unwrap_or(stats.total_bytes);
 
     stats.total_bytes as f64 / size as f64
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::PrimitiveArray;
+
+    use super::{compress_double, decompress_double, gen_stats};
+    use crate::{
+        compression::Compression, write::WriteOptions, CommonCompression, SINGLE_ROW_GROUP_VERSION,
+    };
+
+    /// Mostly zeros, with some -0.0 and NaNs of different bits.
+    fn zeros_and_nans() -> PrimitiveArray<f64> {
+        let payload_nan = f64::from_bits(0x7ff8_0000_0000_0001);
+        PrimitiveArray::from_vec(
+            (0..2000)
+                .map(|i| match i {
+                    i if i % 50 == 0 => -0.0,
+                    i if i % 70 == 0 => f64::NAN,
+                    i if i % 90 == 0 => payload_nan,
+                    i if i % 110 == 0 => -f64::NAN,
+                    _ => 0.0,
+                })
+                .collect(),
+        )
+    }
+
+    fn write_read(array: &PrimitiveArray<f64>, write_options: WriteOptions) -> (u8, Vec<f64>) {
+        let mut buf = vec![];
+        compress_double(array, write_options, &mut buf).unwrap();
+        let mut output = vec![];
+        decompress_double(
+            &mut buf.as_slice(),
+            array.len(),
+            &mut output,
+            &mut vec![],
+            SINGLE_ROW_GROUP_VERSION,
+        )
+        .unwrap();
+        (buf[0], output)
+    }
+
+    fn bits(values: &[f64]) -> Vec<u64> {
+        values.iter().map(|v| v.to_bits()).collect()
+    }
+
+    #[test]
+    fn test_zeros_and_nans_round_trip() {
+        let array = zeros_and_nans();
+        let stats = gen_stats(&array);
+        assert_eq!(stats.unique_count, 5);
+
+        for compression in [
+            Compression::None,
+            Compression::Dict,
+            Compression::Freq,
+            Compression::Rle,
+            Compression::Patas,
+            Compression::Gorilla,
+            Compression::Chimp,
+        ] {
+            let write_options = WriteOptions {
+                default_compression: CommonCompression::None,
+                default_compress_ratio: Some(0.0),
+                forbidden_compressions: Compression::ALL
+                    .into_iter()
+                    .filter(|c| *c != compression)
+                    .collect(),
+                ..Default::default()
+            };
+            let (codec, output) = write_read(&array, write_options.clone());
+            assert_eq!(codec, u8::from(compression));
+            assert_eq!(bits(&output), bits(array.values()), "{compression:?}");
+
+            let (_, output) = write_read(
+                &array,
+                WriteOptions {
+                    canonicalize_floats: true,
+                    ..write_options
+                },
+            );
+            let expected: Vec<f64> = array
+                .values()
+                .iter()
+                .map(|v| if v.is_nan() { f64::NAN } else { 0.0 })
+                .collect();
+            assert_eq!(bits(&output), bits(&expected), "{compression:?}");
+        }
+
+        // a page of 0.0 and -0.0 has two values
+        let array = PrimitiveArray::from_vec(vec![0.0, -0.0, 0.0]);
+        let write_options = WriteOptions {
+            default_compress_ratio: Some(1.0),
+            ..Default::default()
+        };
+        let (codec, output) = write_read(&array, write_options.clone());
+        assert_ne!(codec, u8::from(Compression::OneValue));
+        assert_eq!(bits(&output), bits(array.values()));
+        let (codec, output) = write_read(
+            &array,
+            WriteOptions {
+                canonicalize_floats: true,
+                ..write_options
+            },
+        );
+        assert_eq!(codec, u8::from(Compression::OneValue));
+        assert_eq!(bits(&output), bits(&[0.0; 3]));
+    }
+}
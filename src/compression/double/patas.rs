@@ -21,16 +21,26 @@ use std::io::{BufRead, Read};
 use arrow::array::PrimitiveArray;
 use arrow::types::NativeType;
 
-use arrow::error::Result;
+use arrow::error::{Error, Result};
 use byteorder::{LittleEndian, ReadBytesExt};
 
-use ringbuffer::{AllocRingBuffer, RingBuffer};
-
-use crate::compression::{SAMPLE_COUNT, SAMPLE_SIZE};
+use crate::compression::SAMPLE_COUNT;
 use crate::{compression::Compression, util::ByteWriter, write::WriteOptions};
 
 use super::{compress_sample_ratio, DoubleCompression, DoubleStats, DoubleType};
 
+/// Values are referenced up to `BLOCK_SIZE - 1` positions back,
+/// the distance is packed in 7 bits.
+const BLOCK_SIZE: usize = 128;
+/// Trailing zeros packed with no significant bytes, marks a value equal to its reference.
+const EQUAL_TRAILING_ZEROS: u8 = 63;
+
+/// Page format: the first value, then for every other value a packed `u16` of the
+/// distance to the reference value, the number of significant bytes and the trailing
+/// zeros of their XOR, followed by the significant bytes of the XOR.
+///
+/// The reference is the latest equal value of the last `BLOCK_SIZE` values, or the
+/// previous value.
 pub(crate) struct Patas {}
 
 impl<T: DoubleType> DoubleCompression<T> for Patas {
@@ -41,62 +51,45 @@ impl<T: DoubleType> DoubleCompression<T> for Patas {
         _write_options: &WriteOptions,
         output: &mut Vec<u8>,
     ) -> Result<usize> {
-        let mut is_first = false;
-        const BLOCK_SIZE: usize = 128;
-        let mut ring = AllocRingBuffer::<T::BitType>::new(BLOCK_SIZE);
+        let values = array.values();
         let mut indices = HashMap::with_capacity(BLOCK_SIZE);
 
         let mut byte_writer =
-            ByteWriter::<false>::with_capacity(array.len() * (std::mem::size_of::<T>() + 2));
+            ByteWriter::<false>::with_capacity(values.len() * (std::mem::size_of::<T>() + 2));
 
-        for (i, val) in array.values().iter().enumerate() {
+        for (i, val) in values.iter().enumerate() {
             let val = val.as_bits();
 
-            if !is_first {
-                is_first = true;
+            if i == 0 {
                 byte_writer.write_value(val);
             } else {
-                let mut reference_index = indices.get(&val).cloned().unwrap_or(0);
-                let exceeds_highest_index = reference_index > i;
-                let difference_too_big = (i - reference_index) >= BLOCK_SIZE;
-
-                if exceeds_highest_index || difference_too_big {
-                    reference_index = i - 1;
+                let reference_index = match indices.get(&val) {
+                    Some(&index) if i - index < BLOCK_SIZE => index,
+                    _ => i - 1,
                 };
-
                 let reference_diff = i - reference_index;
-
-                let refer_value = ring.get(-(reference_diff as isize)).unwrap();
-                let xor_result = val ^ *refer_value;
+                let xor_result = val ^ values[reference_index].as_bits();
 
                 let trailing_zeros = T::trailing_zeros(&xor_result) as u8;
-                let leading_zeros = T::leading_zeros(&xor_result) as u8;
-
-                let is_equal = u8::from(trailing_zeros == std::mem::size_of::<T>() as u8 * 8);
-                let significant_bits = if is_equal == 1 {
-                    0
+                if trailing_zeros as usize == std::mem::size_of::<T>() * 8 {
+                    byte_writer.write_value(pack(reference_diff as u8, 0, EQUAL_TRAILING_ZEROS));
                 } else {
-                    std::mem::size_of::<T>() as u8 * 8 - trailing_zeros - leading_zeros
-                };
-
-                let significant_bytes = (((significant_bits as usize) >> 3)
-                    + usize::from((significant_bits & 7) != 0))
-                    as u8;
-
-                let pack_data = pack(
-                    reference_diff as u8,
-                    significant_bytes,
-                    trailing_zeros - is_equal,
-                );
-
-                byte_writer.write_value(pack_data);
-                byte_writer.write_value_bytes(
-                    xor_result >> (trailing_zeros - is_equal) as usize,
-                    significant_bytes as usize,
-                );
+                    let leading_zeros = T::leading_zeros(&xor_result) as u8;
+                    let significant_bits =
+                        std::mem::size_of::<T>() as u8 * 8 - trailing_zeros - leading_zeros;
+                    let significant_bytes = (significant_bits + 7) >> 3;
+
+                    byte_writer.write_value(pack(
+                        reference_diff as u8,
+                        significant_bytes,
+                        trailing_zeros,
+                    ));
+                    byte_writer.write_value_bytes(
+                        xor_result >> trailing_zeros as usize,
+                        significant_bytes as usize,
+                    );
+                }
             }
-
-            ring.push(val);
             indices.insert(val, i);
         }
 
@@ -105,6 +98,9 @@ impl<T: DoubleType> DoubleCompression<T> for Patas {
     }
 
     fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        if length == 0 {
+            return Ok(());
+        }
         let mut bs = vec![0u8; std::mem::size_of::<T>()];
         input.read_exact(&mut bs)?;
         let a: T::Bytes = match bs.as_slice().try_into() {
@@ -114,18 +110,38 @@ impl<T: DoubleType> DoubleCompression<T> for Patas {
         let first = T::from_le_bytes(a);
 
         output.reserve(length);
+        let start = output.len();
         output.push(first);
 
         for _ in 0..length - 1 {
             let packed_data = input.read_u16::<LittleEndian>()?;
             let (reference_diff, significant_bytes, trailing_zeros) = unpack(packed_data);
 
-            let val: T::BitType = read_value_custom(input, significant_bytes, trailing_zeros);
-            input.consume(significant_bytes as usize);
+            let reference_diff = reference_diff as usize;
+            if reference_diff == 0 || reference_diff > output.len() - start {
+                return Err(Error::OutOfSpec(format!(
+                    "Invalid patas reference distance {reference_diff}"
+                )));
+            }
+            let previous = output[output.len() - reference_diff];
 
-            let previous = output[output.len() - (reference_diff as usize)].as_bits();
-            let xor_result = (val << (trailing_zeros as usize)) ^ previous;
+            if significant_bytes == 0 {
+                output.push(previous);
+                continue;
+            }
+            if significant_bytes as usize > std::mem::size_of::<T>()
+                || trailing_zeros as usize >= std::mem::size_of::<T>() * 8
+                || input.len() < significant_bytes as usize
+            {
+                return Err(Error::OutOfSpec(format!(
+                    "Invalid patas value of {significant_bytes} significant bytes and {trailing_zeros} trailing zeros"
+                )));
+            }
 
+            let val: T::BitType = read_value_custom(input, significant_bytes);
+            input.consume(significant_bytes as usize);
+
+            let xor_result = (val << (trailing_zeros as usize)) ^ previous.as_bits();
             output.push(T::from_bits_val(xor_result));
         }
         Ok(())
@@ -136,7 +152,7 @@ impl<T: DoubleType> DoubleCompression<T> for Patas {
     }
 
     fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64 {
-        compress_sample_ratio(self, stats, SAMPLE_COUNT, SAMPLE_SIZE)
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
     }
 }
 
@@ -155,30 +171,17 @@ pub fn unpack(packed_data: u16) -> (u8, u8, u8) {
     let mut significant_bytes = ((packed_data >> 6) & 0b0000_0111) as u8;
     let trailing_zeros = (packed_data & 0b0011_1111) as u8;
 
-    if trailing_zeros < 63 && significant_bytes == 0 {
+    if trailing_zeros < EQUAL_TRAILING_ZEROS && significant_bytes == 0 {
         significant_bytes = 8;
     }
     (reference_index, significant_bytes, trailing_zeros)
 }
 
+/// Reads the `bytes` low bytes of a value, `bytes` must not exceed the size of `T`.
 #[inline]
-pub fn read_value_custom<T: NativeType>(input: &[u8], mut bytes: u8, trailing_zero: u8) -> T {
-    if (bytes > 8) && trailing_zero < 8 {
-        bytes = 8;
-    }
-
-    if bytes > 8 {
-        return T::default();
-    }
-
+pub fn read_value_custom<T: NativeType>(input: &[u8], bytes: u8) -> T {
     let mut bs = vec![0u8; std::mem::size_of::<T>()];
-    unsafe {
-        std::ptr::copy(
-            input.as_ptr() as *const u8,
-            bs.as_mut_ptr() as *mut u8,
-            bytes as usize,
-        )
-    }
+    bs[..bytes as usize].copy_from_slice(&input[..bytes as usize]);
 
     let a: T::Bytes = match bs.as_slice().try_into() {
         Ok(a) => a,
@@ -200,3 +203,43 @@ fn test_unpack() {
         );
     }
 }
+
+#[test]
+fn test_patas() {
+    fn roundtrip<T: DoubleType>(values: Vec<T>) {
+        let array = PrimitiveArray::<T>::from_vec(values.clone());
+        let stats = super::gen_stats(&array);
+        let mut buf = vec![];
+        let size = Patas {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+        assert_eq!(size, buf.len());
+
+        let mut output = vec![T::default()];
+        Patas {}
+            .decompress(&buf, values.len(), &mut output)
+            .unwrap();
+        let bits = |values: &[T]| values.iter().map(|v| v.as_bits()).collect::<Vec<_>>();
+        assert_eq!(bits(&output[1..]), bits(&values));
+    }
+
+    roundtrip::<f32>(vec![]);
+    roundtrip::<f64>(vec![]);
+    roundtrip::<f32>(vec![1.5]);
+    let f32s = vec![
+        1.5,
+        1.5,
+        2.25,
+        2.25,
+        3.0,
+        1.5,
+        0.0,
+        -7.125,
+        f32::MAX,
+        f32::NAN,
+    ];
+    roundtrip::<f32>(f32s.clone());
+    roundtrip::<f64>(f32s.iter().map(|v| *v as f64).collect());
+    roundtrip::<f64>((0..1000).map(|i| ((i % 200) as f64 * 0.1).sin()).collect());
+    roundtrip::<f32>((0..1000).map(|i| ((i % 200) as f32 * 0.1).sin()).collect());
+}
@@ -24,7 +24,7 @@ use arrow::error::Result;
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use crate::{
-    compression::{integer::RLE, is_valid, Compression, SAMPLE_COUNT, SAMPLE_SIZE},
+    compression::{integer::RLE, is_valid, Compression, SAMPLE_COUNT},
     write::WriteOptions,
 };
 
@@ -53,7 +53,7 @@ impl<T: DoubleType> DoubleCompression<T> for RLE {
     }
 
     fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64 {
-        compress_sample_ratio(self, stats, SAMPLE_COUNT, SAMPLE_SIZE)
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
     }
 }
 
@@ -66,11 +66,12 @@ impl RLE {
     ) -> Result<()> {
         // help me generate RLE encode algorithm
         let mut seen_count: u32 = 0;
-        let mut last_value = T::default().as_order();
+        let mut last_value = T::default().as_bits();
         let mut all_null = true;
 
+        // the runs are of equal bits, so -0.0 and the NaN payloads are kept
         for (i, item) in values.into_iter().enumerate() {
-            let item = item.as_order();
+            let item = item.as_bits();
 
             if is_valid(&validity, i) {
                 if all_null {
@@ -81,7 +82,7 @@ impl RLE {
                 } else if last_value != item {
                     // flush  u32 cnt , value
                     w.write_all(&seen_count.to_le_bytes())?;
-                    w.write_all(T::from_order(last_value).to_le_bytes().as_ref())?;
+                    w.write_all(T::from_bits_val(last_value).to_le_bytes().as_ref())?;
 
                     last_value = item;
                     seen_count = 1;
@@ -96,7 +97,7 @@ impl RLE {
 
         if seen_count != 0 {
             w.write_all(&seen_count.to_le_bytes())?;
-            w.write_all(T::from_order(last_value).to_le_bytes().as_ref())?;
+            w.write_all(T::from_bits_val(last_value).to_le_bytes().as_ref())?;
         }
 
         Ok(())
@@ -0,0 +1,97 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use arrow::array::FixedSizeBinaryArray;
+use arrow::error::{Error, Result};
+
+use crate::{
+    read::{read_basic::read_compress_header, NativeReadBuf},
+    write::WriteOptions,
+};
+
+use super::{basic::CommonCompression, Compression};
+
+/// The values of a fixed size binary array are stored as one flat buffer,
+/// compressed with the default compression.
+pub fn compress_fixed_binary(
+    array: &FixedSizeBinaryArray,
+    buf: &mut Vec<u8>,
+    write_options: WriteOptions,
+) -> Result<()> {
+    let input_buf = array.values().as_slice();
+    let c = if input_buf.is_empty() {
+        CommonCompression::None
+    } else {
+        write_options.default_compression
+    };
+    let codec = u8::from(c.to_compression());
+
+    buf.extend_from_slice(&codec.to_le_bytes());
+    let pos = buf.len();
+    buf.extend_from_slice(&[0u8; 16]);
+
+    let compressed_size = c.compress_with_level(input_buf, buf, write_options.compression_level)?;
+    buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+    buf[pos + 8..pos + 16].copy_from_slice(&(input_buf.len() as u64).to_le_bytes());
+    Ok(())
+}
+
+pub fn decompress_fixed_binary<R: NativeReadBuf>(
+    reader: &mut R,
+    length: usize,
+    size: usize,
+    values: &mut Vec<u8>,
+    scratch: &mut Vec<u8>,
+    format_version: u16,
+) -> Result<()> {
+    let (codec, compressed_size, uncompressed_size) = read_compress_header(reader, format_version)?;
+    let compression = Compression::from_codec(codec)?;
+    if compression == Compression::AllNull {
+        values.resize(values.len() + length * size, 0);
+        return Ok(());
+    }
+    let c = CommonCompression::try_from(&compression)?;
+    if uncompressed_size != length * size {
+        return Err(Error::OutOfSpec(format!(
+            "Fixed size binary page has {uncompressed_size} bytes, expected {length} values of size {size}",
+        )));
+    }
+
+    // already fit in buffer
+    let mut use_inner = false;
+    reader.fill_buf()?;
+    let input = if reader.buffer_bytes().len() >= compressed_size {
+        use_inner = true;
+        reader.buffer_bytes()
+    } else {
+        scratch.resize(compressed_size, 0);
+        reader.read_exact(scratch.as_mut_slice())?;
+        scratch.as_slice()
+    };
+
+    values.reserve(uncompressed_size);
+    let out_slice = unsafe {
+        core::slice::from_raw_parts_mut(values.as_mut_ptr().add(values.len()), uncompressed_size)
+    };
+    c.decompress(&input[..compressed_size], out_slice)?;
+    unsafe { values.set_len(values.len() + uncompressed_size) };
+
+    if use_inner {
+        reader.consume(compressed_size);
+    }
+    Ok(())
+}
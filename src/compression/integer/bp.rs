@@ -20,19 +20,25 @@ use std::io::BufRead;
 use arrow::array::PrimitiveArray;
 use bitpacking::{BitPacker, BitPacker4x};
 
-use arrow::error::Result;
+use arrow::error::{Error, Result};
 use byteorder::ReadBytesExt;
 
 use crate::{
-    compression::{Compression, SAMPLE_COUNT, SAMPLE_SIZE},
+    compression::{Compression, SAMPLE_COUNT},
     write::WriteOptions,
 };
 
 use super::{compress_sample_ratio, IntegerCompression, IntegerStats, IntegerType};
 
+/// Packs the values in blocks of `BitPacker4x::BLOCK_LEN` `u32`, 1 and 2 bytes values
+/// are zero-extended to `u32` before packing. The values of a block with negative
+/// values are zigzag encoded, which is flagged in the bit width of the block.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Bitpacking {}
 
+/// Set in the bit width of a block whose values are zigzag encoded.
+const ZIGZAG_BLOCK_FLAG: u8 = 1 << 7;
+
 impl<T: IntegerType> IntegerCompression<T> for Bitpacking {
     fn compress(
         &self,
@@ -43,11 +49,33 @@ impl<T: IntegerType> IntegerCompression<T> for Bitpacking {
     ) -> Result<usize> {
         let start: usize = output.len();
         let bitpacker = BitPacker4x::new();
-        let my_data = bytemuck::cast_slice(array.values().as_slice());
+        let mut block = [0u32; BitPacker4x::BLOCK_LEN];
 
-        for chunk in my_data.chunks(BitPacker4x::BLOCK_LEN) {
+        for values in array.values().chunks(BitPacker4x::BLOCK_LEN) {
+            let zigzag = values.iter().any(|value| value.as_i64() < 0);
+            let chunk: &[u32] = if std::mem::size_of::<T>() == 4
+                && !zigzag
+                && values.len() == BitPacker4x::BLOCK_LEN
+            {
+                bytemuck::cast_slice(values)
+            } else {
+                // a partial block is padded with zeros
+                block.fill(0);
+                for (dst, value) in block.iter_mut().zip(values) {
+                    *dst = if zigzag {
+                        zigzag_encode(value)
+                    } else {
+                        to_u32(value)
+                    };
+                }
+                &block
+            };
             let num_bits: u8 = bitpacker.num_bits(chunk);
-            output.push(num_bits);
+            output.push(if zigzag {
+                num_bits | ZIGZAG_BLOCK_FLAG
+            } else {
+                num_bits
+            });
             output.reserve(BitPacker4x::BLOCK_LEN * 4);
 
             let out_slice = unsafe {
@@ -66,21 +94,41 @@ impl<T: IntegerType> IntegerCompression<T> for Bitpacking {
 
     fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
         let bitpacker = BitPacker4x::new();
+        let mut block = [0u32; BitPacker4x::BLOCK_LEN];
 
-        output.reserve(BitPacker4x::BLOCK_LEN * length);
+        output.reserve(length);
 
-        for _ in (0..length).step_by(BitPacker4x::BLOCK_LEN) {
+        for begin in (0..length).step_by(BitPacker4x::BLOCK_LEN) {
             let num_bits = input.read_u8()?;
-            let out_slice = unsafe {
-                core::slice::from_raw_parts_mut(
-                    output.as_mut_ptr().add(output.len()) as *mut u32,
-                    BitPacker4x::BLOCK_LEN,
-                )
-            };
-            let size = bitpacker.decompress(input, out_slice, num_bits);
-            input.consume(size);
+            let zigzag = num_bits & ZIGZAG_BLOCK_FLAG != 0;
+            let num_bits = num_bits & !ZIGZAG_BLOCK_FLAG;
+            if num_bits > 32 || input.len() < BitPacker4x::compressed_block_size(num_bits) {
+                return Err(Error::OutOfSpec(format!(
+                    "Invalid bitpacking block of {num_bits} bits"
+                )));
+            }
+            let end = (length - begin).min(BitPacker4x::BLOCK_LEN);
+            if std::mem::size_of::<T>() == 4 && !zigzag && end == BitPacker4x::BLOCK_LEN {
+                output.reserve(BitPacker4x::BLOCK_LEN);
+                let out_slice = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        output.as_mut_ptr().add(output.len()) as *mut u32,
+                        BitPacker4x::BLOCK_LEN,
+                    )
+                };
+                let size = bitpacker.decompress(input, out_slice, num_bits);
+                input.consume(size);
 
-            unsafe { output.set_len(output.len() + BitPacker4x::BLOCK_LEN) };
+                unsafe { output.set_len(output.len() + BitPacker4x::BLOCK_LEN) };
+            } else {
+                let size = bitpacker.decompress(input, &mut block, num_bits);
+                input.consume(size);
+                if zigzag {
+                    output.extend(block[..end].iter().map(|value| zigzag_decode::<T>(*value)));
+                } else {
+                    output.extend(block[..end].iter().map(|value| from_u32::<T>(*value)));
+                }
+            }
         }
         Ok(())
     }
@@ -90,12 +138,169 @@ impl<T: IntegerType> IntegerCompression<T> for Bitpacking {
     }
 
     fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
-        if stats.min.as_i64() < 0
-            || std::mem::size_of::<T>() != 4
-            || stats.src.len() % BitPacker4x::BLOCK_LEN != 0
-        {
+        if std::mem::size_of::<T>() > 4 {
             return 0.0f64;
         }
-        compress_sample_ratio(self, stats, SAMPLE_COUNT, SAMPLE_SIZE)
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
+    }
+}
+
+/// Zero-extends the bits of a value of at most 4 bytes.
+fn to_u32<T: IntegerType>(value: &T) -> u32 {
+    let mut bytes = [0u8; 4];
+    bytes[..std::mem::size_of::<T>()].copy_from_slice(value.to_le_bytes().as_ref());
+    u32::from_le_bytes(bytes)
+}
+
+fn zigzag_encode<T: IntegerType>(value: &T) -> u32 {
+    let value = value.as_i64();
+    ((value << 1) ^ (value >> 63)) as u32
+}
+
+fn zigzag_decode<T: IntegerType>(value: u32) -> T {
+    let value = (value >> 1) as i64 ^ -((value & 1) as i64);
+    from_u32(value as u32)
+}
+
+/// Truncates the bits of a value to a value of at most 4 bytes.
+fn from_u32<T: IntegerType>(value: u32) -> T {
+    bytemuck::pod_read_unaligned(&value.to_le_bytes()[..std::mem::size_of::<T>()])
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::PrimitiveArray;
+    use bitpacking::{BitPacker, BitPacker4x};
+
+    use super::super::{gen_stats, IntegerType};
+    use super::{Bitpacking, IntegerCompression, ZIGZAG_BLOCK_FLAG};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: IntegerType>(array: PrimitiveArray<T>) -> usize {
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        let size = Bitpacking {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut output: Vec<T> = vec![];
+        Bitpacking {}
+            .decompress(&buf, array.len(), &mut output)
+            .unwrap();
+        assert_eq!(output.as_slice(), array.values().as_slice());
+        size
+    }
+
+    #[test]
+    fn test_bitpacking_narrow() {
+        let len = BitPacker4x::BLOCK_LEN * 4;
+        // 3 bits per value
+        let size = roundtrip(PrimitiveArray::<u8>::from_vec(
+            (0..len).map(|i| (i % 8) as u8).collect(),
+        ));
+        assert_eq!(size, 4 * (1 + BitPacker4x::BLOCK_LEN * 3 / 8));
+        roundtrip(PrimitiveArray::<u16>::from_vec(
+            (0..len).map(|i| (i * 31) as u16).collect(),
+        ));
+        roundtrip(PrimitiveArray::<i8>::from_vec(
+            (0..len).map(|i| (i % 100) as i8 - 50).collect(),
+        ));
+        roundtrip(PrimitiveArray::<i16>::from_vec(
+            (0..len).map(|i| i as i16 * -3).collect(),
+        ));
+        // a partial block
+        roundtrip(PrimitiveArray::<u8>::from_vec((0..100).collect()));
+
+        let stats = gen_stats(&PrimitiveArray::<u16>::from_vec(vec![7; len]));
+        assert!(IntegerCompression::<u16>::compress_ratio(&Bitpacking {}, &stats) > 1.0);
+    }
+
+    #[test]
+    fn test_bitpacking_block_boundaries() {
+        let block = BitPacker4x::BLOCK_LEN;
+        for len in [1, block - 1, block, block + 1, 2 * block + 3] {
+            let blocks = (len + block - 1) / block;
+            // 5 bits per value, each block takes its width and 5 * 128 bits
+            let size = roundtrip(PrimitiveArray::<u32>::from_vec(
+                (0..len as u32).map(|i| (i % 32) | 16).collect(),
+            ));
+            assert_eq!(size, blocks * (1 + block * 5 / 8), "{len} values");
+            roundtrip(PrimitiveArray::<u8>::from_vec(
+                (0..len).map(|i| i as u8).collect(),
+            ));
+            roundtrip(PrimitiveArray::<u16>::from_vec(
+                (0..len).map(|i| (i * 257) as u16).collect(),
+            ));
+            roundtrip(PrimitiveArray::<i32>::from_vec(
+                (0..len as i32).map(|i| i * 7 - 300).collect(),
+            ));
+
+            // the values are appended after the existing ones and the input of a
+            // missing block is an error
+            let array = PrimitiveArray::<u32>::from_vec((0..len as u32).collect());
+            let mut buf = vec![];
+            Bitpacking {}
+                .compress(
+                    &array,
+                    &gen_stats(&array),
+                    &WriteOptions::default(),
+                    &mut buf,
+                )
+                .unwrap();
+            let mut output = vec![u32::MAX];
+            Bitpacking {}.decompress(&buf, len, &mut output).unwrap();
+            assert_eq!(&output[1..], array.values().as_slice());
+            assert!(Bitpacking {}
+                .decompress(&buf, len + block, &mut Vec::<u32>::new())
+                .is_err());
+            assert!(Bitpacking {}
+                .decompress(&buf[..buf.len() - 1], len, &mut Vec::<u32>::new())
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_bitpacking_signed() {
+        let array = PrimitiveArray::<i32>::from_vec(vec![-3, -1, -2, 0, 1]);
+        let mut buf = vec![];
+        Bitpacking {}
+            .compress(
+                &array,
+                &gen_stats(&array),
+                &WriteOptions::default(),
+                &mut buf,
+            )
+            .unwrap();
+        // zigzag encoded in 3 bits
+        assert_eq!(buf[0], 3 | ZIGZAG_BLOCK_FLAG);
+        roundtrip(array);
+
+        let len = BitPacker4x::BLOCK_LEN * 4;
+        roundtrip(PrimitiveArray::<i32>::from_vec(
+            (0..len as i32).map(|i| i - 200).collect(),
+        ));
+        roundtrip(PrimitiveArray::<i32>::from_vec(vec![
+            i32::MIN,
+            i32::MAX,
+            -1,
+            0,
+        ]));
+        roundtrip(PrimitiveArray::<i8>::from_vec(vec![
+            i8::MIN,
+            i8::MAX,
+            -1,
+            0,
+        ]));
+        roundtrip(PrimitiveArray::<i16>::from_vec(vec![
+            i16::MIN,
+            i16::MAX,
+            -1,
+            0,
+        ]));
+
+        let stats = gen_stats(&PrimitiveArray::<i32>::from_vec(
+            (0..len).map(|i| (i % 7) as i32 - 3).collect(),
+        ));
+        assert!(IntegerCompression::<i32>::compress_ratio(&Bitpacking {}, &stats) > 1.0);
     }
 }
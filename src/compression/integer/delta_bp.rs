@@ -20,11 +20,12 @@ use std::io::BufRead;
 use arrow::array::PrimitiveArray;
 use bitpacking::{BitPacker, BitPacker4x};
 
-use arrow::error::Result;
+use arrow::error::{Error, Result};
+use arrow::types::PrimitiveType;
 use byteorder::ReadBytesExt;
 
 use crate::{
-    compression::{Compression, SAMPLE_COUNT, SAMPLE_SIZE},
+    compression::{Compression, SAMPLE_COUNT},
     write::WriteOptions,
 };
 
@@ -43,10 +44,19 @@ impl<T: IntegerType> IntegerCompression<T> for DeltaBitpacking {
     ) -> Result<usize> {
         let start: usize = output.len();
         let bitpacker = BitPacker4x::new();
-        let my_data = bytemuck::cast_slice(array.values().as_slice());
+        let my_data: &[u32] = bytemuck::cast_slice(array.values().as_slice());
+        let mut block = [0u32; BitPacker4x::BLOCK_LEN];
 
         let mut initial = 0;
         for chunk in my_data.chunks(BitPacker4x::BLOCK_LEN) {
+            // a partial block is padded with its last value, which keeps it sorted
+            let chunk = if chunk.len() < BitPacker4x::BLOCK_LEN {
+                block[..chunk.len()].copy_from_slice(chunk);
+                block[chunk.len()..].fill(*chunk.last().unwrap());
+                &block
+            } else {
+                chunk
+            };
             let num_bits: u8 = bitpacker.num_bits(chunk);
             output.push(num_bits);
             output.reserve(BitPacker4x::BLOCK_LEN * 4);
@@ -69,23 +79,37 @@ impl<T: IntegerType> IntegerCompression<T> for DeltaBitpacking {
     fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
         log::debug!("DeltaBitpacking::decompress {}", input.len());
         let bitpacker = BitPacker4x::new();
+        let mut block = [0u32; BitPacker4x::BLOCK_LEN];
 
         let mut initial = 0;
 
-        output.reserve(BitPacker4x::BLOCK_LEN * length);
-        for _ in (0..length).step_by(BitPacker4x::BLOCK_LEN) {
+        // the full blocks are decompressed in place, within the `length` values
+        output.reserve(length);
+        for begin in (0..length).step_by(BitPacker4x::BLOCK_LEN) {
             let num_bits = input.read_u8()?;
-            let out_slice = unsafe {
-                core::slice::from_raw_parts_mut(
-                    output.as_mut_ptr().add(output.len()) as *mut u32,
-                    BitPacker4x::BLOCK_LEN,
-                )
-            };
-            let size = bitpacker.decompress_sorted(initial, input, out_slice, num_bits);
-            input.consume(size);
-
-            initial = *out_slice.last().unwrap();
-            unsafe { output.set_len(output.len() + BitPacker4x::BLOCK_LEN) };
+            if num_bits > 32 || input.len() < BitPacker4x::compressed_block_size(num_bits) {
+                return Err(Error::OutOfSpec(format!(
+                    "Invalid delta bitpacking block of {num_bits} bits"
+                )));
+            }
+            let end = (length - begin).min(BitPacker4x::BLOCK_LEN);
+            if end == BitPacker4x::BLOCK_LEN {
+                let out_slice = unsafe {
+                    core::slice::from_raw_parts_mut(
+                        output.as_mut_ptr().add(output.len()) as *mut u32,
+                        BitPacker4x::BLOCK_LEN,
+                    )
+                };
+                let size = bitpacker.decompress_sorted(initial, input, out_slice, num_bits);
+                input.consume(size);
+                initial = *out_slice.last().unwrap();
+                unsafe { output.set_len(output.len() + BitPacker4x::BLOCK_LEN) };
+            } else {
+                // the padding of the last block is dropped
+                let size = bitpacker.decompress_sorted(initial, input, &mut block, num_bits);
+                input.consume(size);
+                output.extend_from_slice(bytemuck::cast_slice(&block[..end]));
+            }
         }
         Ok(())
     }
@@ -95,8 +119,9 @@ impl<T: IntegerType> IntegerCompression<T> for DeltaBitpacking {
     }
 
     fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
+        // the values are bitpacked as `u32`
         if stats.min.as_i64() < 0
-            || std::mem::size_of::<T>() != 4
+            || !matches!(T::PRIMITIVE, PrimitiveType::Int32 | PrimitiveType::UInt32)
             || stats.src.len() % BitPacker4x::BLOCK_LEN != 0
             || !stats.is_sorted
             || stats.null_count > 0
@@ -105,6 +130,42 @@ impl<T: IntegerType> IntegerCompression<T> for DeltaBitpacking {
         }
 
         let bpk = super::bp::Bitpacking {};
-        compress_sample_ratio(&bpk, stats, SAMPLE_COUNT, SAMPLE_SIZE) * 1.50f64
+        compress_sample_ratio(&bpk, stats, SAMPLE_COUNT, stats.sample_size) * 1.50f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::PrimitiveArray;
+    use bitpacking::{BitPacker, BitPacker4x};
+
+    use super::super::gen_stats;
+    use super::{DeltaBitpacking, IntegerCompression};
+    use crate::write::WriteOptions;
+
+    #[test]
+    fn test_delta_bitpacking_block_boundaries() {
+        let block = BitPacker4x::BLOCK_LEN;
+        for len in [1, block - 1, block, block + 1, 2 * block + 3] {
+            let array = PrimitiveArray::<u32>::from_vec((0..len as u32).map(|i| i * 3).collect());
+            let mut buf = vec![];
+            DeltaBitpacking {}
+                .compress(
+                    &array,
+                    &gen_stats(&array),
+                    &WriteOptions::default(),
+                    &mut buf,
+                )
+                .unwrap();
+            let mut output: Vec<u32> = vec![];
+            DeltaBitpacking {}
+                .decompress(&buf, len, &mut output)
+                .unwrap();
+            assert_eq!(output.as_slice(), array.values().as_slice(), "{len} values");
+
+            assert!(DeltaBitpacking {}
+                .decompress(&buf[..buf.len() - 1], len, &mut Vec::<u32>::new())
+                .is_err());
+        }
     }
 }
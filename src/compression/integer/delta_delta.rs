@@ -0,0 +1,264 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::BufRead;
+
+use arrow::array::PrimitiveArray;
+use arrow::error::{Error, Result};
+use bitpacking::{BitPacker, BitPacker4x};
+
+use crate::{
+    compression::{get_bits_needed, is_valid, Compression},
+    general_err,
+    write::WriteOptions,
+};
+
+use super::for_bp::{pack_blocks, unpack_blocks};
+use super::zigzag_delta::{unzigzag, zigzag};
+use super::{IntegerCompression, IntegerStats, IntegerType};
+
+/// Delta-of-delta encoding, for near-regularly spaced columns such as timestamps
+/// whose deltas are large but repetitive. The first value and the first delta are
+/// stored verbatim, followed by the zigzag encoded differences of the consecutive
+/// deltas in blocks of `BitPacker4x::BLOCK_LEN`. The differences are computed in
+/// the width of `T` and must fit in 32 bits once zigzag encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DeltaDelta {}
+
+impl<T: IntegerType> IntegerCompression<T> for DeltaDelta {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        _stats: &IntegerStats<T>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start: usize = output.len();
+        let (first, first_delta, deltas) = delta_deltas(array);
+        // `i256::to_le_bytes` swaps its words, so the memory layout is written instead
+        output.extend_from_slice(bytemuck::bytes_of(&first));
+        output.extend_from_slice(bytemuck::bytes_of(&first_delta));
+
+        let deltas = deltas
+            .map(|delta| {
+                delta
+                    .and_then(|delta| u32::try_from(delta).ok())
+                    .ok_or_else(|| {
+                        general_err!("Delta of delta doesn't fit in 32 bits once zigzag encoded")
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        pack_blocks(&deltas, output);
+
+        Ok(output.len() - start)
+    }
+
+    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        if length == 0 {
+            return Ok(());
+        }
+        let size = std::mem::size_of::<T>();
+        if input.len() < size * 2 {
+            return Err(general_err!(
+                "Missing the first value or delta of the DeltaDelta page"
+            ));
+        }
+        let mut last: T = bytemuck::pod_read_unaligned(&input[..size]);
+        let mut delta: T = bytemuck::pod_read_unaligned(&input[size..size * 2]);
+        input.consume(size * 2);
+
+        output.reserve(length);
+        output.push(last);
+        if length == 1 {
+            return Ok(());
+        }
+        last = last.wrapping_add(&delta);
+        output.push(last);
+        unpack_blocks(&mut input, length - 2, |delta_delta| {
+            delta = delta.wrapping_add(&from_i64(unzigzag(delta_delta as u64)));
+            last = last.wrapping_add(&delta);
+            output.push(last);
+        })
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::DeltaDelta
+    }
+
+    fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
+        // only worth it for monotonic columns, whose deltas vary little
+        if !stats.is_sorted || stats.tuple_count < 3 {
+            return 0.0f64;
+        }
+        // the deltas are cheap to compute, so the size is counted exactly
+        let (_, _, deltas) = delta_deltas(&stats.src);
+        let count = stats.tuple_count - 2;
+        let mut size = std::mem::size_of::<T>() * 2;
+        let mut block_bits = 0;
+        for (i, delta) in deltas.enumerate() {
+            match delta {
+                Some(delta) => block_bits = block_bits.max(get_bits_needed(delta)),
+                None => return 0.0f64,
+            }
+            if (i + 1) % BitPacker4x::BLOCK_LEN == 0 || i + 1 == count {
+                if block_bits > 32 {
+                    return 0.0f64;
+                }
+                size += 1 + BitPacker4x::compressed_block_size(block_bits as u8);
+                block_bits = 0;
+            }
+        }
+        stats.total_bytes as f64 / size as f64
+    }
+}
+
+/// The first value and the first delta of `array`, and the zigzag encoded deltas of
+/// the following deltas, `None` if one doesn't fit in 64 bits. Null slots continue the
+/// previous delta.
+fn delta_deltas<T: IntegerType>(
+    array: &PrimitiveArray<T>,
+) -> (T, T, impl Iterator<Item = Option<u64>> + '_) {
+    let bits = std::mem::size_of::<T>() as u32 * 8;
+    let validity = array.validity();
+    let values = array.values();
+    let first = values.first().cloned().unwrap_or_default();
+    let first_delta = match values.get(1) {
+        Some(second) if is_valid(&validity, 1) => second.wrapping_sub(&first),
+        _ => T::default(),
+    };
+
+    let mut last = first.wrapping_add(&first_delta);
+    let mut last_delta = first_delta;
+    let deltas = values.iter().enumerate().skip(2).map(move |(i, value)| {
+        if !is_valid(&validity, i) {
+            last = last.wrapping_add(&last_delta);
+            return Some(0);
+        }
+        let delta = value.wrapping_sub(&last);
+        let delta_delta = delta.wrapping_sub(&last_delta);
+        last = *value;
+        last_delta = delta;
+
+        let delta_delta = if bits < 64 {
+            // sign extend the difference from the width of `T`
+            (delta_delta.as_i64() << (64 - bits)) >> (64 - bits)
+        } else {
+            let truncated = delta_delta.as_i64();
+            if from_i64::<T>(truncated) != delta_delta {
+                return None;
+            }
+            truncated
+        };
+        Some(zigzag(delta_delta))
+    });
+    (first, first_delta, deltas)
+}
+
+/// `val` sign extended or truncated to the width of `T`.
+fn from_i64<T: IntegerType>(val: i64) -> T {
+    let mut bytes = if val < 0 { [u8::MAX; 32] } else { [0; 32] };
+    bytes[..8].copy_from_slice(&val.to_le_bytes());
+    bytemuck::pod_read_unaligned(&bytes[..std::mem::size_of::<T>()])
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, PrimitiveArray};
+    use arrow::types::i256;
+
+    use super::super::{gen_stats, zigzag_delta::ZigzagDelta, IntegerType};
+    use super::{from_i64, DeltaDelta, IntegerCompression};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: IntegerType>(array: PrimitiveArray<T>) -> f64 {
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        DeltaDelta {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut output: Vec<T> = vec![];
+        DeltaDelta {}
+            .decompress(&buf, array.len(), &mut output)
+            .unwrap();
+        assert_eq!(output.len(), array.len());
+        for (i, (a, b)) in output.iter().zip(array.values().iter()).enumerate() {
+            if array.is_valid(i) {
+                assert_eq!(a, b);
+            }
+        }
+        DeltaDelta {}.compress_ratio(&stats)
+    }
+
+    #[test]
+    fn test_delta_delta() {
+        assert_eq!(from_i64::<i8>(-3), -3);
+        assert_eq!(from_i64::<u16>(-1), u16::MAX);
+        assert_eq!(from_i64::<i128>(-5), -5);
+        assert_eq!(from_i64::<i128>(i64::MAX), i64::MAX as i128);
+
+        // nanosecond timestamps taken every second, with a few microseconds of jitter
+        let timestamps: Vec<i64> = (0..10000)
+            .map(|i| 1_600_000_000_000_000_000 + i * 1_000_000_000 + (i * 7919 % 13) * 1000)
+            .collect();
+        let ratio = roundtrip(PrimitiveArray::<i64>::from_vec(timestamps));
+        assert!(ratio > 2.0);
+        // regular timestamps only cost the widths of the blocks
+        let regular = PrimitiveArray::<i64>::from_vec(
+            (0..10000)
+                .map(|i| 1_600_000_000_000_000_000 + i * 1_000_000_000)
+                .collect(),
+        );
+        let stats = gen_stats(&regular);
+        assert!(DeltaDelta {}.compress_ratio(&stats) > ZigzagDelta {}.compress_ratio(&stats));
+        let ratio = roundtrip(regular);
+        assert!(ratio > 100.0);
+
+        roundtrip(PrimitiveArray::<i128>::from_vec(
+            (0..1000)
+                .map(|i| (1i128 << 100) + i * 1_000_000_000 + i % 3)
+                .collect(),
+        ));
+        roundtrip(PrimitiveArray::<i256>::from_vec(
+            (0..300)
+                .map(|i| i256::from_words(-1, i * 1000 + i % 2))
+                .collect(),
+        ));
+        roundtrip(PrimitiveArray::<u8>::from_vec(vec![250, 251, 253, 255, 1]));
+        roundtrip(PrimitiveArray::<u64>::from_vec(vec![0, u64::MAX, 5, 7]));
+        roundtrip(PrimitiveArray::<i32>::from(vec![
+            Some(10i32),
+            None,
+            Some(30),
+            None,
+            None,
+            Some(61),
+        ]));
+        roundtrip(PrimitiveArray::<i64>::from_vec(vec![42]));
+        roundtrip(PrimitiveArray::<i64>::from_vec(vec![42, 43]));
+        roundtrip(PrimitiveArray::<i64>::from_vec(vec![]));
+
+        // the differences of the deltas don't fit in 32 bits
+        let stats = gen_stats(&PrimitiveArray::<i64>::from_vec(vec![0, 1, 1 << 40]));
+        assert_eq!(DeltaDelta {}.compress_ratio(&stats), 0.0);
+        let stats = gen_stats(&PrimitiveArray::<i128>::from_vec(vec![0, 1, 1 << 80]));
+        assert_eq!(DeltaDelta {}.compress_ratio(&stats), 0.0);
+        // not sorted
+        let stats = gen_stats(&PrimitiveArray::<i64>::from_vec(vec![3, 2, 1]));
+        assert_eq!(DeltaDelta {}.compress_ratio(&stats), 0.0);
+    }
+}
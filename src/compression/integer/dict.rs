@@ -54,73 +54,308 @@ impl<T: IntegerType> IntegerCompression<T> for Dict {
                 }
             };
         }
+        if write_options.sorted_dictionary {
+            encoder.sort_by(|a, b| a.inner.partial_cmp(&b.inner).unwrap());
+        }
         let indices = encoder.take_indices();
 
         // dict data use custom encoding
         let mut write_options = write_options.clone();
         write_options.forbidden_compressions.push(Compression::Dict);
-        compress_integer(&indices, write_options, output_buf)?;
-
         let sets = encoder.get_sets();
-        output_buf.extend_from_slice(&(sets.len() as u32).to_le_bytes());
-        // data page use plain encoding
-        for val in sets.iter() {
-            let bs = val.inner.to_le_bytes();
-            output_buf.extend_from_slice(bs.as_ref());
+        compress_indices(&indices, sets.len(), write_options.clone(), output_buf)?;
+
+        if write_options.sorted_dictionary {
+            output_buf.extend_from_slice(&(sets.len() as u32 | SORTED_DICT_FLAG).to_le_bytes());
+            // sorted data page use delta encoding
+            let mut last = T::default();
+            let deltas: Vec<T> = sets
+                .iter()
+                .map(|val| {
+                    let delta = val.inner.wrapping_sub(&last);
+                    last = val.inner;
+                    delta
+                })
+                .collect();
+            compress_integer(&PrimitiveArray::from_vec(deltas), write_options, output_buf)?;
+        } else {
+            output_buf.extend_from_slice(&(sets.len() as u32).to_le_bytes());
+            // data page use plain encoding
+            for val in sets.iter() {
+                let bs = val.inner.to_le_bytes();
+                output_buf.extend_from_slice(bs.as_ref());
+            }
         }
 
         Ok(output_buf.len() - start)
     }
 
-    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.decompress_with_version(input, length, output, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
         let mut indices: Vec<u32> = Vec::new();
-        decompress_integer(&mut input, length, &mut indices, &mut vec![])?;
+        decompress_indices(
+            &mut input,
+            length,
+            &mut indices,
+            &mut vec![],
+            format_version,
+        )?;
+
+        let mut data: Vec<T> = vec![];
+        decompress_dictionary(&mut input, &mut data, format_version)?;
+        gather(&data, &indices, output)
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::Dict
+    }
 
-        let data_size = input.read_u32::<LittleEndian>()? as usize * std::mem::size_of::<T>();
-        if input.len() < data_size {
-            return Err(general_err!(
-                "Invalid data size: {} less than {}",
-                input.len(),
-                data_size
-            ));
+    fn compress_ratio(&self, stats: &super::IntegerStats<T>) -> f64 {
+        let mut after_size = stats.unique_count * std::mem::size_of::<T>()
+            + stats.tuple_count * (get_bits_needed(stats.unique_count as u64) / 8) as usize;
+        // after_size += std::mem::size_of::<DynamicDictionaryStructure>() + 5;
+        after_size += (stats.tuple_count) * 2 / 128;
+        stats.total_bytes as f64 / after_size as f64
+    }
+}
+
+/// Decodes the indices and the dictionary of a `Dict` page without gathering its values:
+/// the dictionary is appended to `dictionary` and the indices, shifted past the values
+/// already in it, to `indices`. The page is of a file of the header `format_version`.
+pub fn decompress_dict_page<T: IntegerType>(
+    mut input: &[u8],
+    length: usize,
+    indices: &mut Vec<u32>,
+    dictionary: &mut Vec<T>,
+    format_version: u16,
+) -> Result<()> {
+    let start = indices.len();
+    decompress_indices(&mut input, length, indices, &mut vec![], format_version)?;
+    let base = dictionary.len();
+    decompress_dictionary(&mut input, dictionary, format_version)?;
+    shift_indices(&mut indices[start..], base, dictionary.len())
+}
+
+/// Shifts the indices of a page dictionary appended at `base` to a dictionary of
+/// `len` values, the indices out of the page dictionary are an error.
+pub(crate) fn shift_indices(indices: &mut [u32], base: usize, len: usize) -> Result<()> {
+    let page_len = len - base;
+    let base = u32::try_from(base)
+        .ok()
+        .filter(|_| u32::try_from(len).is_ok())
+        .ok_or_else(|| general_err!("dictionary of {} values exceeds u32", len))?;
+    for index in indices.iter_mut() {
+        if *index as usize >= page_len {
+            return Err(general_err!("dictionary index {} out of range", index));
+        }
+        *index += base;
+    }
+    Ok(())
+}
+
+/// Appends the dictionary of a `Dict` page, which follows its indices, to `output`.
+fn decompress_dictionary<T: IntegerType>(
+    input: &mut &[u8],
+    output: &mut Vec<T>,
+    format_version: u16,
+) -> Result<()> {
+    let data_len = input.read_u32::<LittleEndian>()?;
+    if data_len & SORTED_DICT_FLAG != 0 {
+        let data_len = (data_len & !SORTED_DICT_FLAG) as usize;
+        let start = output.len();
+        decompress_integer(input, data_len, output, &mut vec![], format_version)?;
+        let mut last = T::default();
+        for val in output[start..].iter_mut() {
+            last = last.wrapping_add(val);
+            *val = last;
         }
+        return Ok(());
+    }
+
+    let data_size = data_len as usize * std::mem::size_of::<T>();
+    if input.len() < data_size {
+        return Err(general_err!(
+            "Invalid data size: {} less than {}",
+            input.len(),
+            data_size
+        ));
+    }
 
-        let data: Vec<T> = input[0..data_size]
-            .chunks(std::mem::size_of::<T>())
-            .map(|chunk| match <T::Bytes>::try_from(chunk) {
+    output.extend(
+        input[0..data_size].chunks(std::mem::size_of::<T>()).map(
+            |chunk| match <T::Bytes>::try_from(chunk) {
                 Ok(bs) => T::from_le_bytes(bs),
                 Err(_e) => {
                     unreachable!()
                 }
-            })
-            .collect();
+            },
+        ),
+    );
+    *input = &input[data_size..];
+    Ok(())
+}
 
-        output.reserve(length);
-        for i in indices.iter() {
-            output.push(data[*i as usize]);
+/// Dictionary encoder.
+/// Compresses the dictionary indices of a page with the narrowest unsigned integer
+/// type that can index `unique_count` values, the width in bytes is written first.
+pub fn compress_indices(
+    indices: &PrimitiveArray<u32>,
+    unique_count: usize,
+    write_options: WriteOptions,
+    output_buf: &mut Vec<u8>,
+) -> Result<()> {
+    let width = index_width(unique_count);
+    output_buf.push(width);
+    match width {
+        1 => {
+            let indices = indices.values().iter().map(|i| *i as u8).collect();
+            compress_integer(
+                &PrimitiveArray::<u8>::from_vec(indices),
+                write_options,
+                output_buf,
+            )
         }
-        Ok(())
+        2 => {
+            let indices = indices.values().iter().map(|i| *i as u16).collect();
+            compress_integer(
+                &PrimitiveArray::<u16>::from_vec(indices),
+                write_options,
+                output_buf,
+            )
+        }
+        _ => compress_integer(indices, write_options, output_buf),
     }
+}
 
-    fn to_compression(&self) -> Compression {
-        Compression::Dict
+/// Decompresses the dictionary indices written by [`compress_indices`], or as `u32`
+/// by the files of the header `format_version` 0.
+pub fn decompress_indices<R: NativeReadBuf>(
+    reader: &mut R,
+    length: usize,
+    output: &mut Vec<u32>,
+    scratch: &mut Vec<u8>,
+    format_version: u16,
+) -> Result<()> {
+    // the indices of the files of version 0 are always u32, without their width
+    if format_version == 0 {
+        return decompress_integer(reader, length, output, scratch, format_version);
     }
+    let mut width = [0u8; 1];
+    reader.read_exact(&mut width)?;
+    match width[0] {
+        1 => {
+            let mut indices: Vec<u8> = Vec::with_capacity(length);
+            decompress_integer(reader, length, &mut indices, scratch, format_version)?;
+            output.extend(indices.iter().map(|i| *i as u32));
+        }
+        2 => {
+            let mut indices: Vec<u16> = Vec::with_capacity(length);
+            decompress_integer(reader, length, &mut indices, scratch, format_version)?;
+            output.extend(indices.iter().map(|i| *i as u32));
+        }
+        4 => decompress_integer(reader, length, output, scratch, format_version)?,
+        width => return Err(general_err!("invalid dictionary index width {}", width)),
+    }
+    Ok(())
+}
 
-    fn compress_ratio(&self, stats: &super::IntegerStats<T>) -> f64 {
-        const MIN_DICT_RATIO: usize = 3;
-        if stats.unique_count * MIN_DICT_RATIO >= stats.tuple_count {
-            return 0.0f64;
+/// Appends the values of `data` at `indices` to `output`, the indices
+/// out of `data` are an error.
+pub(crate) fn gather<T: NativeType>(
+    data: &[T],
+    indices: &[u32],
+    output: &mut Vec<T>,
+) -> Result<()> {
+    #[cfg(feature = "simd")]
+    match std::mem::size_of::<T>() {
+        4 => return simd::gather::<T, u32>(data, indices, output),
+        8 => return simd::gather::<T, u64>(data, indices, output),
+        _ => {}
+    }
+
+    output.reserve(indices.len());
+    for i in indices {
+        let value = data
+            .get(*i as usize)
+            .ok_or_else(|| general_err!("Dictionary index {} out of {} values", i, data.len()))?;
+        output.push(*value);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "simd")]
+mod simd {
+    use std::simd::{Simd, SimdElement, SimdPartialOrd};
+
+    use arrow::error::{Error, Result};
+    use arrow::types::NativeType;
+
+    use crate::general_err;
+
+    const LANES: usize = 8;
+
+    /// Gathers the values as the unsigned integers `U` of the same size as `T`,
+    /// a block of `LANES` indices at a time.
+    pub(super) fn gather<T: NativeType, U: SimdElement + bytemuck::Pod + Default>(
+        data: &[T],
+        indices: &[u32],
+        output: &mut Vec<T>,
+    ) -> Result<()> {
+        let values: &[U] = bytemuck::cast_slice(data);
+        let start = output.len();
+        output.resize(start + indices.len(), T::default());
+        let out: &mut [U] = bytemuck::cast_slice_mut(&mut output[start..]);
+
+        let len = Simd::splat(values.len());
+        let mut blocks = indices.chunks_exact(LANES);
+        let mut out_blocks = out.chunks_exact_mut(LANES);
+        for (block, out_block) in blocks.by_ref().zip(out_blocks.by_ref()) {
+            let idxs = Simd::<u32, LANES>::from_slice(block).cast::<usize>();
+            if !idxs.simd_lt(len).all() {
+                output.truncate(start);
+                return Err(out_of_bounds(block, values.len()));
+            }
+            let gathered = Simd::<U, LANES>::gather_or_default(values, idxs);
+            out_block.copy_from_slice(gathered.as_array());
+        }
+        for (i, o) in blocks.remainder().iter().zip(out_blocks.into_remainder()) {
+            match values.get(*i as usize) {
+                Some(value) => *o = *value,
+                None => {
+                    output.truncate(start);
+                    return Err(out_of_bounds(&[*i], values.len()));
+                }
+            }
         }
+        Ok(())
+    }
 
-        let mut after_size = stats.unique_count * std::mem::size_of::<T>()
-            + stats.tuple_count * (get_bits_needed(stats.unique_count as u64) / 8) as usize;
-        // after_size += std::mem::size_of::<DynamicDictionaryStructure>() + 5;
-        after_size += (stats.tuple_count) * 2 / 128;
-        stats.total_bytes as f64 / after_size as f64
+    fn out_of_bounds(indices: &[u32], len: usize) -> Error {
+        let i = indices.iter().find(|i| **i as usize >= len).unwrap();
+        general_err!("Dictionary index {} out of {} values", i, len)
+    }
+}
+
+/// The width in bytes of the indices of a dictionary with `unique_count` values.
+pub(crate) fn index_width(unique_count: usize) -> u8 {
+    if unique_count <= u8::MAX as usize + 1 {
+        1
+    } else if unique_count <= u16::MAX as usize + 1 {
+        2
+    } else {
+        4
     }
 }
 
-/// Dictionary encoder.
 /// The dictionary encoding builds a dictionary of values encountered in a given column.
 /// The dictionary page is written first, before the data pages of the column chunk.
 ///
@@ -163,6 +398,29 @@ where
         &self.interner.sets
     }
 
+    /// Sorts the dictionary and remaps the pushed indices to the sorted order.
+    /// No value can be pushed after sorting.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&T, &T) -> std::cmp::Ordering,
+    {
+        let sets = &self.interner.sets;
+        let mut order: Vec<u32> = (0..sets.len() as u32).collect();
+        order.sort_by(|a, b| compare(&sets[*a as usize], &sets[*b as usize]));
+
+        let mut ranks = vec![0u32; order.len()];
+        for (rank, index) in order.iter().enumerate() {
+            ranks[*index as usize] = rank as u32;
+        }
+        for index in self.indices.iter_mut() {
+            *index = ranks[*index as usize];
+        }
+        self.interner.sets = order
+            .iter()
+            .map(|index| sets[*index as usize].clone())
+            .collect();
+    }
+
     pub fn take_indices(&mut self) -> PrimitiveArray<u32> {
         let indices = std::mem::take(&mut self.indices);
         PrimitiveArray::<u32>::from_vec(indices)
@@ -175,11 +433,17 @@ use hashbrown::HashMap;
 use crate::compression::{get_bits_needed, Compression};
 
 use crate::general_err;
+use crate::read::NativeReadBuf;
 use crate::util::AsBytes;
 use crate::write::WriteOptions;
+use crate::SINGLE_ROW_GROUP_VERSION;
 
 const DEFAULT_DEDUP_CAPACITY: usize = 4096;
 
+/// Set in the dictionary length of a page whose dictionary is sorted
+/// and stored delta (integer) or prefix (binary) encoded.
+pub(crate) const SORTED_DICT_FLAG: u32 = 1 << 31;
+
 #[derive(Debug, Default)]
 pub struct DictMap<T: AsBytes> {
     state: ahash::RandomState,
@@ -240,4 +504,103 @@ impl<T: NativeType> AsBytes for RawNative<T> {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use arrow::array::PrimitiveArray;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::super::{gen_stats, IntegerCompression};
+    use super::{compress_indices, decompress_indices, gather, Dict};
+    use crate::write::WriteOptions;
+    use crate::CommonCompression;
+    use crate::SINGLE_ROW_GROUP_VERSION;
+
+    #[test]
+    fn test_sorted_dict() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let array = PrimitiveArray::<i64>::from_vec(
+            (0..10000)
+                .map(|_| 1_000_000_000 + rng.gen_range(0..1000) * 7)
+                .collect(),
+        );
+        let stats = gen_stats(&array);
+
+        let mut unsorted = vec![];
+        let options = WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            ..Default::default()
+        };
+        Dict {}
+            .compress(&array, &stats, &options, &mut unsorted)
+            .unwrap();
+
+        let mut sorted = vec![];
+        let options = WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            sorted_dictionary: true,
+            ..Default::default()
+        };
+        Dict {}
+            .compress(&array, &stats, &options, &mut sorted)
+            .unwrap();
+        assert!(sorted.len() < unsorted.len());
+
+        for buf in [unsorted, sorted] {
+            let mut output = vec![];
+            IntegerCompression::<i64>::decompress(&Dict {}, &buf, array.len(), &mut output)
+                .unwrap();
+            assert_eq!(output.as_slice(), array.values().as_slice());
+        }
+    }
+
+    #[test]
+    fn test_index_width() {
+        for (unique_count, width) in [(3, 1), (256, 1), (257, 2), (65536, 2), (65537, 4)] {
+            let indices = PrimitiveArray::<u32>::from_vec(
+                (0..100_000).map(|i| i % unique_count as u32).collect(),
+            );
+            let mut buf = vec![];
+            compress_indices(&indices, unique_count, WriteOptions::default(), &mut buf).unwrap();
+            assert_eq!(buf[0], width);
+
+            let mut output = vec![];
+            decompress_indices(
+                &mut buf.as_slice(),
+                indices.len(),
+                &mut output,
+                &mut vec![],
+                SINGLE_ROW_GROUP_VERSION,
+            )
+            .unwrap();
+            assert_eq!(output.as_slice(), indices.values().as_slice());
+        }
+    }
+
+    #[test]
+    fn test_gather() {
+        let mut rng = StdRng::seed_from_u64(42);
+        // a remainder of indices after the blocks of lanes
+        let indices: Vec<u32> = (0..1003).map(|_| rng.gen_range(0..100)).collect();
+
+        fn check<T: arrow::types::NativeType>(data: &[T], indices: &[u32]) {
+            let mut output = vec![data[0]];
+            gather(data, indices, &mut output).unwrap();
+            let expected: Vec<T> = std::iter::once(data[0])
+                .chain(indices.iter().map(|i| data[*i as usize]))
+                .collect();
+            assert_eq!(output, expected);
+
+            let mut invalid = indices.to_vec();
+            invalid[500] = data.len() as u32;
+            let mut output = vec![];
+            assert!(gather(data, &invalid, &mut output).is_err());
+        }
+        check(&(0..100i64).map(|i| i * 1000).collect::<Vec<_>>(), &indices);
+        check(&(0..100i32).collect::<Vec<_>>(), &indices);
+        check(&(0..100u8).collect::<Vec<_>>(), &indices);
+        check(
+            &(0..100).map(|i| i as f64 / 3.0).collect::<Vec<_>>(),
+            &indices,
+        );
+        check(&(0..100i128).collect::<Vec<_>>(), &indices);
+    }
+}
@@ -0,0 +1,215 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::BufRead;
+
+use arrow::array::PrimitiveArray;
+use arrow::error::{Error, Result};
+use bitpacking::{BitPacker, BitPacker4x};
+use byteorder::ReadBytesExt;
+
+use crate::{
+    compression::{get_bits_needed, is_valid, Compression},
+    general_err,
+    write::WriteOptions,
+};
+
+use super::{IntegerCompression, IntegerStats, IntegerType};
+
+/// Frame of reference: `min` is stored once as the reference and the values
+/// are bitpacked as their distance to it, in blocks of `BitPacker4x::BLOCK_LEN`.
+/// Only types up to 64 bits whose range fits in 32 bits are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct For {}
+
+impl<T: IntegerType> IntegerCompression<T> for For {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        stats: &IntegerStats<T>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start: usize = output.len();
+        output.extend_from_slice(stats.min.to_le_bytes().as_ref());
+
+        let validity = array.validity();
+        let distances: Vec<u32> = array
+            .values()
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                // null slots are not guaranteed to be in [min, max]
+                if is_valid(&validity, i) {
+                    distance(value, &stats.min) as u32
+                } else {
+                    0
+                }
+            })
+            .collect();
+        pack_blocks(&distances, output);
+
+        Ok(output.len() - start)
+    }
+
+    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        let size = std::mem::size_of::<T>();
+        if input.len() < size {
+            return Err(general_err!("Missing the reference of the FOR page"));
+        }
+        let reference: T = bytemuck::pod_read_unaligned(&input[..size]);
+        input.consume(size);
+
+        output.reserve(length);
+        unpack_blocks(&mut input, length, |distance| {
+            output.push(from_u64::<T>(distance as u64).wrapping_add(&reference))
+        })
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::For
+    }
+
+    fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
+        if std::mem::size_of::<T>() > 8 || stats.tuple_count == 0 {
+            return 0.0f64;
+        }
+        let width = get_bits_needed(distance(&stats.max, &stats.min)) as usize;
+        if width > 32 {
+            return 0.0f64;
+        }
+        let blocks = (stats.tuple_count + BitPacker4x::BLOCK_LEN - 1) / BitPacker4x::BLOCK_LEN;
+        let size = std::mem::size_of::<T>() + blocks * (1 + BitPacker4x::BLOCK_LEN * width / 8);
+        stats.total_bytes as f64 / size as f64
+    }
+}
+
+/// `value - reference` of types up to 64 bits, as an unsigned number.
+fn distance<T: IntegerType>(value: &T, reference: &T) -> u64 {
+    let bits = std::mem::size_of::<T>() * 8;
+    let mask = if bits == 64 {
+        u64::MAX
+    } else {
+        (1u64 << bits) - 1
+    };
+    value.wrapping_sub(reference).as_i64() as u64 & mask
+}
+
+/// Bitpacks `values` in blocks of `BitPacker4x::BLOCK_LEN`, each block led by its bit width.
+pub(super) fn pack_blocks(values: &[u32], output: &mut Vec<u8>) {
+    let bitpacker = BitPacker4x::new();
+    let mut block = [0u32; BitPacker4x::BLOCK_LEN];
+    for chunk in values.chunks(BitPacker4x::BLOCK_LEN) {
+        block[..chunk.len()].copy_from_slice(chunk);
+        block[chunk.len()..].fill(0);
+
+        let num_bits: u8 = bitpacker.num_bits(&block);
+        output.push(num_bits);
+        output.reserve(BitPacker4x::BLOCK_LEN * 4);
+
+        let out_slice = unsafe {
+            core::slice::from_raw_parts_mut(
+                output.as_mut_ptr().add(output.len()),
+                BitPacker4x::BLOCK_LEN * 4,
+            )
+        };
+
+        let size = bitpacker.compress(&block, out_slice, num_bits);
+        unsafe { output.set_len(output.len() + size) };
+    }
+}
+
+/// Unpacks `length` values packed by [`pack_blocks`], calling `f` on each of them.
+pub(super) fn unpack_blocks<F: FnMut(u32)>(
+    input: &mut &[u8],
+    length: usize,
+    mut f: F,
+) -> Result<()> {
+    let bitpacker = BitPacker4x::new();
+    let mut block = [0u32; BitPacker4x::BLOCK_LEN];
+    for start in (0..length).step_by(BitPacker4x::BLOCK_LEN) {
+        let num_bits = input.read_u8()?;
+        if num_bits > 32 || input.len() < BitPacker4x::compressed_block_size(num_bits) {
+            return Err(general_err!("Invalid bit width {}", num_bits));
+        }
+        let size = bitpacker.decompress(input, &mut block, num_bits);
+        input.consume(size);
+
+        let len = BitPacker4x::BLOCK_LEN.min(length - start);
+        block[..len].iter().for_each(|value| f(*value));
+    }
+    Ok(())
+}
+
+pub(super) fn from_u64<T: IntegerType>(val: u64) -> T {
+    let bytes = val.to_le_bytes();
+    bytemuck::pod_read_unaligned(&bytes[..std::mem::size_of::<T>()])
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, PrimitiveArray};
+
+    use super::super::{gen_stats, IntegerType};
+    use super::{For, IntegerCompression};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: IntegerType>(array: PrimitiveArray<T>) -> f64 {
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        For {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut output: Vec<T> = vec![];
+        For {}.decompress(&buf, array.len(), &mut output).unwrap();
+        assert_eq!(output.len(), array.len());
+        for (i, (a, b)) in output.iter().zip(array.values().iter()).enumerate() {
+            if array.is_valid(i) {
+                assert_eq!(a, b);
+            }
+        }
+        For {}.compress_ratio(&stats)
+    }
+
+    #[test]
+    fn test_for() {
+        let ratio = roundtrip(PrimitiveArray::<u64>::from_vec(
+            (0..1000).map(|i| 1_000_000 + i % 7).collect(),
+        ));
+        assert!(ratio > 16.0);
+
+        roundtrip(PrimitiveArray::<i32>::from_vec(
+            (0..300).map(|i| i * 13 - 2000).collect(),
+        ));
+        roundtrip(PrimitiveArray::<i8>::from_vec(vec![-128, 127, 0, -1, 1]));
+        roundtrip(PrimitiveArray::<i64>::from_vec(vec![
+            i64::MIN,
+            i64::MIN + 5,
+        ]));
+        roundtrip(PrimitiveArray::<u16>::from(vec![
+            Some(60000u16),
+            None,
+            Some(60100),
+            Some(60050),
+        ]));
+
+        // the range doesn't fit in 32 bits
+        let stats = gen_stats(&PrimitiveArray::<i64>::from_vec(vec![0, 1 << 40]));
+        assert_eq!(For {}.compress_ratio(&stats), 0.0);
+    }
+}
@@ -23,7 +23,7 @@ use arrow::error::Result;
 use byteorder::{LittleEndian, ReadBytesExt};
 use roaring::RoaringBitmap;
 
-use crate::{compression::Compression, write::WriteOptions};
+use crate::{compression::Compression, write::WriteOptions, SINGLE_ROW_GROUP_VERSION};
 
 use super::{compress_integer, decompress_integer, IntegerCompression, IntegerStats, IntegerType};
 
@@ -85,7 +85,17 @@ impl<T: IntegerType> IntegerCompression<T> for Freq {
         Ok(output.len() - size)
     }
 
-    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.decompress_with_version(input, length, output, SINGLE_ROW_GROUP_VERSION)
+    }
+
+    fn decompress_with_version(
+        &self,
+        mut input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
         let begin = output.len();
 
         let mut bs = vec![0u8; std::mem::size_of::<T>()];
@@ -111,6 +121,7 @@ impl<T: IntegerType> IntegerCompression<T> for Freq {
             exceptions_bitmap.len() as usize,
             &mut exceptions,
             &mut vec![],
+            format_version,
         )?;
 
         assert_eq!(exceptions_bitmap.len() as usize, exceptions.len());
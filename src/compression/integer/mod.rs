@@ -1,10 +1,13 @@
 mod bp;
 mod delta_bp;
+mod delta_delta;
 mod dict;
+mod for_bp;
 mod freq;
 mod one_value;
 mod rle;
 mod traits;
+mod zigzag_delta;
 
 use std::collections::HashMap;
 
@@ -22,24 +25,50 @@ use crate::{
 
 use self::bp::Bitpacking;
 use self::delta_bp::DeltaBitpacking;
+use self::delta_delta::DeltaDelta;
+pub(crate) use self::dict::gather;
+pub(crate) use self::dict::shift_indices;
 pub use self::dict::Dict;
 pub use self::dict::DictEncoder;
 pub use self::dict::RawNative;
+pub(crate) use self::dict::SORTED_DICT_FLAG;
+pub use self::dict::{compress_indices, decompress_dict_page, decompress_indices};
+use self::for_bp::For;
 pub use self::freq::Freq;
 pub use self::one_value::OneValue;
 pub use self::rle::RLE;
 pub use self::traits::IntegerType;
+use self::zigzag_delta::ZigzagDelta;
 
-use super::{basic::CommonCompression, is_valid, Compression};
+use super::{
+    basic::CommonCompression,
+    compress_encoding, decompress_encoding, is_valid,
+    registry::{registered_integer_compression, registered_integer_compressions},
+    split_codec, Compression, SAMPLE_SIZE,
+};
+
+/// The encodings evaluated on the declared sorted columns, see `WriteOptions::sorted`.
+const SORTED_COMPRESSIONS: [Compression; 3] = [
+    Compression::DeltaBitpacking,
+    Compression::ZigzagDelta,
+    Compression::DeltaDelta,
+];
 
 pub fn compress_integer<T: IntegerType>(
     array: &PrimitiveArray<T>,
     write_options: WriteOptions,
     buf: &mut Vec<u8>,
 ) -> Result<()> {
-    // choose compressor
-    let stats = gen_stats(array);
-    let compressor = choose_compressor(array, &stats, &write_options);
+    // choose compressor, the stats are only generated if an encoding is evaluated
+    let stats = write_options.evaluates_encodings().then(|| {
+        let mut stats = gen_stats_with_order(array, write_options.sorted);
+        stats.sample_size = write_options.sample_size.unwrap_or(SAMPLE_SIZE);
+        stats
+    });
+    let compressor = match &stats {
+        Some(stats) => choose_compressor(array, stats, &write_options),
+        None => IntCompressor::Basic(write_options.default_compression),
+    };
 
     log::info!(
         "choose integer compression : {:?}",
@@ -49,22 +78,31 @@ pub fn compress_integer<T: IntegerType>(
     let codec = u8::from(compressor.to_compression());
     buf.extend_from_slice(&codec.to_le_bytes());
     let pos = buf.len();
-    buf.extend_from_slice(&[0u8; 8]);
+    buf.extend_from_slice(&[0u8; 16]);
 
     let compressed_size = match compressor {
         IntCompressor::Basic(c) => {
             let input_buf = bytemuck::cast_slice(array.values());
-            c.compress(input_buf, buf)
+            c.compress_with_level(input_buf, buf, write_options.compression_level)
+        }
+        IntCompressor::Extend(c) => {
+            let stats = stats.as_ref().unwrap();
+            compress_encoding(
+                c.to_compression(),
+                &write_options,
+                buf,
+                pos - 1,
+                |write_options, buf| c.compress(array, stats, write_options, buf),
+            )
         }
-        IntCompressor::Extend(c) => c.compress(array, &stats, &write_options, buf),
     }?;
-    buf[pos..pos + 4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
-    buf[pos + 4..pos + 8]
-        .copy_from_slice(&((array.len() * std::mem::size_of::<T>()) as u32).to_le_bytes());
+    let total_bytes = array.len() * std::mem::size_of::<T>();
+    buf[pos..pos + 8].copy_from_slice(&(compressed_size as u64).to_le_bytes());
+    buf[pos + 8..pos + 16].copy_from_slice(&(total_bytes as u64).to_le_bytes());
 
     log::debug!(
         "integer compress ratio {}",
-        stats.total_bytes as f64 / compressed_size as f64
+        total_bytes as f64 / compressed_size as f64
     );
     Ok(())
 }
@@ -74,9 +112,15 @@ pub fn decompress_integer<T: IntegerType, R: NativeReadBuf>(
     length: usize,
     output: &mut Vec<T>,
     scratch: &mut Vec<u8>,
+    format_version: u16,
 ) -> Result<()> {
-    let (codec, compressed_size, _uncompressed_size) = read_compress_header(reader)?;
-    let compression = Compression::from_codec(codec)?;
+    let (codec, compressed_size, _uncompressed_size) =
+        read_compress_header(reader, format_version)?;
+    let (compression, compressed_encoding) = split_codec(codec)?;
+    if compression == Compression::AllNull {
+        output.resize(output.len() + length, T::default());
+        return Ok(());
+    }
 
     // already fit in buffer
     let mut use_inner = false;
@@ -106,7 +150,12 @@ pub fn decompress_integer<T: IntegerType, R: NativeReadBuf>(
             unsafe { output.set_len(output.len() + length) };
         }
         IntCompressor::Extend(c) => {
-            c.decompress(input, length, output)?;
+            if compressed_encoding {
+                let encoded = decompress_encoding(&input[..compressed_size])?;
+                c.decompress_with_version(&encoded, length, output, format_version)?;
+            } else {
+                c.decompress_with_version(input, length, output, format_version)?;
+            }
         }
     }
 
@@ -126,6 +175,20 @@ pub trait IntegerCompression<T: IntegerType> {
     ) -> Result<usize>;
     fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()>;
 
+    /// Decompresses `input` like `decompress` for a page of a file of the header
+    /// `format_version`, which the layout of the buffers nested in the bytes of the
+    /// encodings depends on, see `ReadOptions::format_version`. `decompress` reads
+    /// the layout written by `compress`.
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        _format_version: u16,
+    ) -> Result<()> {
+        self.decompress(input, length, output)
+    }
+
     fn to_compression(&self) -> Compression;
     fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64;
 }
@@ -154,6 +217,15 @@ impl<T: IntegerType> IntCompressor<T> {
             Compression::Freq => Ok(Self::Extend(Box::new(Freq {}))),
             Compression::Bitpacking => Ok(Self::Extend(Box::new(Bitpacking {}))),
             Compression::DeltaBitpacking => Ok(Self::Extend(Box::new(DeltaBitpacking {}))),
+            Compression::For => Ok(Self::Extend(Box::new(For {}))),
+            Compression::ZigzagDelta => Ok(Self::Extend(Box::new(ZigzagDelta {}))),
+            Compression::DeltaDelta => Ok(Self::Extend(Box::new(DeltaDelta {}))),
+            Compression::Custom(codec) => match registered_integer_compression(codec) {
+                Some(c) => Ok(Self::Extend(Box::new(c))),
+                None => Err(Error::OutOfSpec(format!(
+                    "No integer encoding is registered with the codec {codec}"
+                ))),
+            },
             other => Err(Error::OutOfSpec(format!(
                 "Unknown compression codec {other:?}",
             ))),
@@ -174,9 +246,20 @@ pub struct IntegerStats<T: IntegerType> {
     pub distinct_values: HashMap<T, usize>,
     pub unique_count: usize,
     pub set_count: usize,
+    /// Run size of the samples that the encodings estimate their ratio on.
+    pub sample_size: usize,
 }
 
+#[cfg(test)]
 fn gen_stats<T: IntegerType>(array: &PrimitiveArray<T>) -> IntegerStats<T> {
+    gen_stats_with_order(array, false)
+}
+
+/// The stats of `array`, whose order is not checked if it is declared `sorted`.
+fn gen_stats_with_order<T: IntegerType>(
+    array: &PrimitiveArray<T>,
+    sorted: bool,
+) -> IntegerStats<T> {
     let mut stats = IntegerStats::<T> {
         src: array.clone(),
         tuple_count: array.len(),
@@ -189,6 +272,7 @@ fn gen_stats<T: IntegerType>(array: &PrimitiveArray<T>) -> IntegerStats<T> {
         distinct_values: HashMap::new(),
         unique_count: 0,
         set_count: array.len() - array.null_count(),
+        sample_size: SAMPLE_SIZE,
     };
 
     let mut is_init_value_initialized = false;
@@ -198,32 +282,34 @@ fn gen_stats<T: IntegerType>(array: &PrimitiveArray<T>) -> IntegerStats<T> {
     let validity = array.validity();
     for (i, current_value) in array.values().iter().cloned().enumerate() {
         if is_valid(&validity, i) {
-            if current_value < last_value {
+            if !sorted && current_value < last_value {
                 stats.is_sorted = false;
             }
 
-            if last_value != current_value {
+            if run_count == 0 || last_value != current_value {
                 run_count += 1;
                 last_value = current_value;
             }
-        }
-
-        *stats.distinct_values.entry(current_value).or_insert(0) += 1;
 
-        if !is_init_value_initialized {
-            is_init_value_initialized = true;
-            stats.min = current_value;
-            stats.max = current_value;
-        }
+            *stats.distinct_values.entry(current_value).or_insert(0) += 1;
 
-        if current_value > stats.max {
-            stats.max = current_value;
-        } else if current_value < stats.min {
-            stats.min = current_value;
+            // the values of null slots are arbitrary, min and max only cover the valid values
+            if !is_init_value_initialized {
+                is_init_value_initialized = true;
+                stats.min = current_value;
+                stats.max = current_value;
+            }
+            if current_value > stats.max {
+                stats.max = current_value;
+            }
+            if current_value < stats.min {
+                stats.min = current_value;
+            }
         }
     }
     stats.unique_count = stats.distinct_values.len();
-    stats.average_run_length = array.len() as f64 / run_count as f64;
+    // an all-null array is a single run
+    stats.average_run_length = array.len() as f64 / run_count.max(1) as f64;
 
     stats
 }
@@ -265,17 +351,26 @@ fn choose_compressor<T: IntegerType>(
         }
     }
     let basic = IntCompressor::Basic(write_options.default_compression);
-    if let Some(ratio) = write_options.default_compress_ratio {
+    if let Some((ratio, sorted_only)) = write_options.compress_ratio() {
         let mut max_ratio = ratio;
         let mut result = basic;
-        let compressors: Vec<Box<dyn IntegerCompression<T>>> = vec![
+        let mut compressors: Vec<Box<dyn IntegerCompression<T>>> = vec![
             Box::new(OneValue {}) as _,
             Box::new(Freq {}) as _,
             Box::new(Dict {}) as _,
             Box::new(RLE {}) as _,
             Box::new(Bitpacking {}) as _,
             Box::new(DeltaBitpacking {}) as _,
+            Box::new(For {}) as _,
+            Box::new(ZigzagDelta {}) as _,
+            Box::new(DeltaDelta {}) as _,
         ];
+        for c in registered_integer_compressions::<T>() {
+            compressors.push(Box::new(c));
+        }
+        if sorted_only {
+            compressors.retain(|c| SORTED_COMPRESSIONS.contains(&c.to_compression()));
+        }
         for c in compressors {
             if write_options
                 .forbidden_compressions
@@ -283,6 +378,11 @@ fn choose_compressor<T: IntegerType>(
             {
                 continue;
             }
+            if c.to_compression() == Compression::Dict
+                && !write_options.dict_candidate(stats.unique_count, stats.tuple_count)
+            {
+                continue;
+            }
             let r = c.compress_ratio(stats);
 
             log::debug!(
@@ -314,6 +414,7 @@ fn compress_sample_ratio<T: IntegerType, C: IntegerCompression<T>>(
     sample_size: usize,
 ) -> f64 {
     let mut rng = thread_rng();
+    let sample_size = sample_size.max(1);
 
     let stats = if stats.src.len() / sample_count <= sample_size {
         stats.clone()
@@ -336,12 +437,55 @@ fn compress_sample_ratio<T: IntegerType, C: IntegerCompression<T>>(
             builder.extend_trusted_len(s.into_iter());
         }
         let sample_array: PrimitiveArray<T> = builder.into();
-        gen_stats(&sample_array)
+        // the samples are taken in order, so the samples of sorted values are sorted
+        gen_stats_with_order(&sample_array, stats.is_sorted)
     };
 
     let size = c
-        .compress(&stats.src, &stats, &WriteOptions::default(), &mut vec![])
+        .compress(
+            &stats.src,
+            &stats,
+            &WriteOptions::uncompressed(),
+            &mut vec![],
+        )
         .unwrap_or(stats.total_bytes);
 
     stats.total_bytes as f64 / size as f64
 }
+
+#[cfg(test)]
+mod tests {
+    use arrow::{array::PrimitiveArray, bitmap::Bitmap, datatypes::DataType};
+
+    use super::gen_stats;
+
+    #[test]
+    fn test_gen_stats_min_max() {
+        let stats = gen_stats(&PrimitiveArray::<u32>::from_vec(vec![1000, 1001, 1002]));
+        assert_eq!((stats.min, stats.max), (1000, 1002));
+
+        let stats = gen_stats(&PrimitiveArray::<i64>::from_vec(vec![5, -3, 7, 2]));
+        assert_eq!((stats.min, stats.max), (-3, 7));
+
+        // the value behind a null slot does not count
+        let array = PrimitiveArray::<i32>::new(
+            DataType::Int32,
+            vec![-1, 1002, 1000, 5000].into(),
+            Some(Bitmap::from([false, true, true, false])),
+        );
+        let stats = gen_stats(&array);
+        assert_eq!((stats.min, stats.max), (1000, 1002));
+    }
+
+    #[test]
+    fn test_gen_stats_average_run_length() {
+        let stats = gen_stats(&PrimitiveArray::<i32>::from_vec(vec![0; 1000]));
+        assert_eq!(stats.average_run_length, 1000.0);
+
+        let stats = gen_stats(&PrimitiveArray::<i32>::new_null(DataType::Int32, 1000));
+        assert_eq!(stats.average_run_length, 1000.0);
+
+        let stats = gen_stats(&PrimitiveArray::<i32>::from_vec(vec![0, 0, 1, 1]));
+        assert_eq!(stats.average_run_length, 2.0);
+    }
+}
@@ -25,6 +25,8 @@ use crate::{compression::Compression, write::WriteOptions};
 
 use super::{IntegerCompression, IntegerStats, IntegerType};
 
+/// Stores the single valid value of a constant page once, e.g. of a partition
+/// key column. The values of the null slots are not kept.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct OneValue {}
 
@@ -70,7 +72,7 @@ impl OneValue {
             Some(Some(v)) => *v,
             _ => T::default(),
         };
-        let _ = w.write(val.to_le_bytes().as_ref())?;
+        w.write_all(val.to_le_bytes().as_ref())?;
         Ok(())
     }
 
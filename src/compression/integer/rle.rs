@@ -24,12 +24,13 @@ use arrow::error::Result;
 use byteorder::{LittleEndian, ReadBytesExt};
 
 use crate::{
-    compression::{is_valid, Compression, SAMPLE_COUNT, SAMPLE_SIZE},
+    compression::{is_valid, Compression, SAMPLE_COUNT},
     write::WriteOptions,
 };
 
 use super::{compress_sample_ratio, IntegerCompression, IntegerStats, IntegerType};
 
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RLE {}
 
@@ -56,7 +57,7 @@ impl<T: IntegerType> IntegerCompression<T> for RLE {
     }
 
     fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
-        compress_sample_ratio(self, stats, SAMPLE_COUNT, SAMPLE_SIZE)
+        compress_sample_ratio(self, stats, SAMPLE_COUNT, stats.sample_size)
     }
 }
 
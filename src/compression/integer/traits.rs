@@ -4,6 +4,10 @@ use std::hash::Hash;
 
 pub trait IntegerType: NativeType + PartialOrd + Hash + Eq {
     fn as_i64(&self) -> i64;
+
+    fn wrapping_add(&self, other: &Self) -> Self;
+
+    fn wrapping_sub(&self, other: &Self) -> Self;
 }
 
 macro_rules! integer_type {
@@ -12,6 +16,14 @@ macro_rules! integer_type {
             fn as_i64(&self) -> i64 {
                 *self as i64
             }
+
+            fn wrapping_add(&self, other: &Self) -> Self {
+                <$type>::wrapping_add(*self, *other)
+            }
+
+            fn wrapping_sub(&self, other: &Self) -> Self {
+                <$type>::wrapping_sub(*self, *other)
+            }
         }
     };
 }
@@ -24,16 +36,20 @@ integer_type!(i8);
 integer_type!(i16);
 integer_type!(i32);
 integer_type!(i64);
+integer_type!(i128);
 // integer_type!(days_ms);
 // integer_type!(months_days_ns);
 
-impl IntegerType for i128 {
-    fn as_i64(&self) -> i64 {
-        *self as i64
-    }
-}
 impl IntegerType for i256 {
     fn as_i64(&self) -> i64 {
         self.0.as_i64()
     }
+
+    fn wrapping_add(&self, other: &Self) -> Self {
+        i256(self.0.wrapping_add(other.0))
+    }
+
+    fn wrapping_sub(&self, other: &Self) -> Self {
+        i256(self.0.wrapping_sub(other.0))
+    }
 }
@@ -0,0 +1,196 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::BufRead;
+
+use arrow::array::PrimitiveArray;
+use arrow::error::{Error, Result};
+use arrow::types::PrimitiveType;
+use bitpacking::{BitPacker, BitPacker4x};
+
+use crate::{
+    compression::{get_bits_needed, is_valid, Compression},
+    general_err,
+    write::WriteOptions,
+};
+
+use super::for_bp::{from_u64, pack_blocks, unpack_blocks};
+use super::{IntegerCompression, IntegerStats, IntegerType};
+
+/// Delta encoding whose deltas are zigzag encoded before being bitpacked,
+/// so descending or oscillating columns pack as well as ascending ones.
+/// The first value is stored verbatim, followed by the deltas in blocks of
+/// `BitPacker4x::BLOCK_LEN`. Only types up to 64 bits whose zigzag deltas fit
+/// in 32 bits are supported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ZigzagDelta {}
+
+impl<T: IntegerType> IntegerCompression<T> for ZigzagDelta {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        _stats: &IntegerStats<T>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        let start: usize = output.len();
+        let first = array.values().first().cloned().unwrap_or_default();
+        output.extend_from_slice(first.to_le_bytes().as_ref());
+
+        let deltas = zigzag_deltas(array)
+            .map(|delta| {
+                u32::try_from(delta)
+                    .map_err(|_| general_err!("Zigzag delta {} doesn't fit in 32 bits", delta))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        pack_blocks(&deltas, output);
+
+        Ok(output.len() - start)
+    }
+
+    fn decompress(&self, mut input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        let size = std::mem::size_of::<T>();
+        if input.len() < size {
+            return Err(general_err!(
+                "Missing the first value of the ZigzagDelta page"
+            ));
+        }
+        let mut last: T = bytemuck::pod_read_unaligned(&input[..size]);
+        input.consume(size);
+
+        output.reserve(length);
+        unpack_blocks(&mut input, length, |delta| {
+            last = last.wrapping_add(&from_u64(unzigzag(delta as u64) as u64));
+            output.push(last);
+        })
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::ZigzagDelta
+    }
+
+    fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
+        // the deltas are computed in 64 bits, which the 128 and 256 bits types don't fit in
+        if matches!(T::PRIMITIVE, PrimitiveType::Int128 | PrimitiveType::Int256)
+            || stats.tuple_count == 0
+        {
+            return 0.0f64;
+        }
+        // the deltas are cheap to compute, so the size is counted exactly
+        let mut size = std::mem::size_of::<T>();
+        let mut block_bits = 0;
+        for (i, delta) in zigzag_deltas(&stats.src).enumerate() {
+            block_bits = block_bits.max(get_bits_needed(delta));
+            if (i + 1) % BitPacker4x::BLOCK_LEN == 0 || i + 1 == stats.tuple_count {
+                if block_bits > 32 {
+                    return 0.0f64;
+                }
+                size += 1 + BitPacker4x::compressed_block_size(block_bits as u8);
+                block_bits = 0;
+            }
+        }
+        stats.total_bytes as f64 / size as f64
+    }
+}
+
+/// The zigzag encoded deltas of `array`, starting with the delta of the first value
+/// to itself. Null slots repeat the previous value.
+fn zigzag_deltas<T: IntegerType>(array: &PrimitiveArray<T>) -> impl Iterator<Item = u64> + '_ {
+    let bits = std::mem::size_of::<T>() as u32 * 8;
+    let validity = array.validity();
+    let mut last = array.values().first().cloned().unwrap_or_default();
+    array.values().iter().enumerate().map(move |(i, value)| {
+        if !is_valid(&validity, i) {
+            return 0;
+        }
+        let delta = value.wrapping_sub(&last);
+        last = *value;
+        // sign extend the delta from the width of `T`
+        let delta = (delta.as_i64() << (64 - bits)) >> (64 - bits);
+        zigzag(delta)
+    })
+}
+
+pub(super) fn zigzag(n: i64) -> u64 {
+    ((n << 1) ^ (n >> 63)) as u64
+}
+
+pub(super) fn unzigzag(n: u64) -> i64 {
+    (n >> 1) as i64 ^ -((n & 1) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, PrimitiveArray};
+
+    use super::super::{gen_stats, IntegerType};
+    use super::{unzigzag, zigzag, IntegerCompression, ZigzagDelta};
+    use crate::write::WriteOptions;
+
+    fn roundtrip<T: IntegerType>(array: PrimitiveArray<T>) -> f64 {
+        let stats = gen_stats(&array);
+        let mut buf = vec![];
+        ZigzagDelta {}
+            .compress(&array, &stats, &WriteOptions::default(), &mut buf)
+            .unwrap();
+
+        let mut output: Vec<T> = vec![];
+        ZigzagDelta {}
+            .decompress(&buf, array.len(), &mut output)
+            .unwrap();
+        assert_eq!(output.len(), array.len());
+        for (i, (a, b)) in output.iter().zip(array.values().iter()).enumerate() {
+            if array.is_valid(i) {
+                assert_eq!(a, b);
+            }
+        }
+        ZigzagDelta {}.compress_ratio(&stats)
+    }
+
+    #[test]
+    fn test_zigzag_delta() {
+        for n in [0, 1, -1, 63, -64, i64::MAX, i64::MIN] {
+            assert_eq!(unzigzag(zigzag(n)), n);
+        }
+
+        // descending
+        let ratio = roundtrip(PrimitiveArray::<i64>::from_vec(
+            (0..1000).map(|i| 1_000_000_000 - i * 3).collect(),
+        ));
+        assert!(ratio > 16.0);
+        // oscillating
+        roundtrip(PrimitiveArray::<i32>::from_vec(
+            (0..300).map(|i| if i % 2 == 0 { i } else { -i }).collect(),
+        ));
+        roundtrip(PrimitiveArray::<u8>::from_vec(vec![255, 0, 255, 3, 2, 1]));
+        roundtrip(PrimitiveArray::<u64>::from_vec(vec![
+            u64::MAX,
+            u64::MAX - 7,
+            5,
+        ]));
+        roundtrip(PrimitiveArray::<i16>::from(vec![
+            Some(100i16),
+            None,
+            Some(90),
+            Some(80),
+        ]));
+
+        // the deltas don't fit in 32 bits
+        let stats = gen_stats(&PrimitiveArray::<i64>::from_vec(vec![0, 1 << 40]));
+        assert_eq!(ZigzagDelta {}.compress_ratio(&stats), 0.0);
+    }
+}
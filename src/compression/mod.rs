@@ -20,11 +20,26 @@ mod basic;
 pub mod binary;
 pub mod boolean;
 pub mod double;
+pub mod fixed_binary;
 pub mod integer;
+pub mod registry;
 
-use arrow::{bitmap::Bitmap, error::Result};
+use arrow::{
+    bitmap::Bitmap,
+    error::{Error, Result},
+};
 
 pub use basic::CommonCompression;
+pub(crate) use basic::{
+    compress_zstd_with_dict, decompress_zstd_with_dict, train_zstd_dictionary,
+    ZstdDecoderDictionary,
+};
+
+use crate::write::WriteOptions;
+
+/// Set in the codec byte of a page whose encoded bytes are compressed again
+/// with a common codec, see [`WriteOptions::compress_encodings`].
+pub(crate) const COMPRESSED_ENCODING_FLAG: u8 = 1 << 7;
 
 // number of samples to take
 pub static SAMPLE_COUNT: usize = 10;
@@ -32,22 +47,44 @@ pub static SAMPLE_COUNT: usize = 10;
 // run size of each sample
 pub static SAMPLE_SIZE: usize = 64;
 
-/// Compression codec
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// Compression codec, the discriminant of a codec is its byte in the page headers.
+/// The encodings registered at runtime are [`Compression::Custom`], see [`registry`].
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+#[repr(u8)]
 pub enum Compression {
-    None,
-    Lz4,
-    Zstd,
-    Snappy,
+    None = 0,
+    Lz4 = 1,
+    Zstd = 2,
+    Snappy = 3,
+    Brotli = 4,
+    SnappyFramed = 5,
 
     // start from 10 for none common compression
-    Rle,
-    Dict,
-    OneValue,
-    Freq,
-    Bitpacking,
-    DeltaBitpacking,
-    Patas,
+    Rle = 10,
+    Dict = 11,
+    OneValue = 12,
+    Freq = 13,
+    Bitpacking = 14,
+    DeltaBitpacking = 15,
+    Patas = 16,
+    Fsst = 17,
+    IncrementalBinary = 18,
+    Gorilla = 19,
+    For = 20,
+    RestartPoints = 21,
+    ZigzagDelta = 22,
+    /// Marks a page whose values are all null, the values buffer is empty.
+    AllNull = 23,
+    Chimp = 24,
+    DeltaDelta = 25,
+    /// Marks the validity of a page stored as the positions of its valid values,
+    /// see [`WriteOptions::sparse_validity_null_ratio`].
+    SparseValidity = 26,
+
+    /// An encoding registered under this byte of [`registry::CUSTOM_CODECS`].
+    Custom(u8) = 64,
 }
 
 impl Default for Compression {
@@ -57,34 +94,62 @@ impl Default for Compression {
 }
 
 impl Compression {
+    /// Every built-in codec, by codec byte.
+    pub const ALL: [Compression; 23] = [
+        Compression::None,
+        Compression::Lz4,
+        Compression::Zstd,
+        Compression::Snappy,
+        Compression::Brotli,
+        Compression::SnappyFramed,
+        Compression::Rle,
+        Compression::Dict,
+        Compression::OneValue,
+        Compression::Freq,
+        Compression::Bitpacking,
+        Compression::DeltaBitpacking,
+        Compression::Patas,
+        Compression::Fsst,
+        Compression::IncrementalBinary,
+        Compression::Gorilla,
+        Compression::For,
+        Compression::RestartPoints,
+        Compression::ZigzagDelta,
+        Compression::AllNull,
+        Compression::Chimp,
+        Compression::DeltaDelta,
+        Compression::SparseValidity,
+    ];
+
     pub fn is_none(&self) -> bool {
         matches!(self, Compression::None)
     }
 
     pub fn from_codec(t: u8) -> Result<Self> {
-        match t {
-            0 => Ok(Compression::None),
-            1 => Ok(Compression::Lz4),
-            2 => Ok(Compression::Zstd),
-            3 => Ok(Compression::Snappy),
-            10 => Ok(Compression::Rle),
-            11 => Ok(Compression::Dict),
-            12 => Ok(Compression::OneValue),
-            13 => Ok(Compression::Freq),
-            14 => Ok(Compression::Bitpacking),
-            15 => Ok(Compression::DeltaBitpacking),
-            16 => Ok(Compression::Patas),
-
-            other => Err(arrow::error::Error::OutOfSpec(format!(
-                "Unknown compression codec {other}",
-            ))),
+        if registry::CUSTOM_CODECS.contains(&t) {
+            return Ok(Compression::Custom(t));
         }
+        Self::ALL
+            .into_iter()
+            .find(|c| u8::from(*c) == t)
+            .ok_or_else(|| Error::OutOfSpec(format!("unsupported codec {t}, upgrade strawboat")))
+    }
+
+    /// Whether the output of this encoding is compressed again with the default
+    /// compression when [`WriteOptions::compress_encodings`] is set.
+    pub fn compressible_encoding(&self) -> bool {
+        matches!(self, Compression::Rle | Compression::Dict)
     }
 
     pub fn raw_mode(&self) -> bool {
         matches!(
             self,
-            Compression::None | Compression::Lz4 | Compression::Zstd | Compression::Snappy
+            Compression::None
+                | Compression::Lz4
+                | Compression::Zstd
+                | Compression::Snappy
+                | Compression::Brotli
+                | Compression::SnappyFramed
         )
     }
 }
@@ -92,21 +157,68 @@ impl Compression {
 impl From<Compression> for u8 {
     fn from(value: Compression) -> Self {
         match value {
-            Compression::None => 0,
-            Compression::Lz4 => 1,
-            Compression::Zstd => 2,
-            Compression::Snappy => 3,
-            Compression::Rle => 10,
-            Compression::Dict => 11,
-            Compression::OneValue => 12,
-            Compression::Freq => 13,
-            Compression::Bitpacking => 14,
-            Compression::DeltaBitpacking => 15,
-            Compression::Patas => 16,
+            Compression::Custom(codec) => codec,
+            // SAFETY: the first byte of a `repr(u8)` enum is its discriminant
+            _ => unsafe { *(&value as *const Compression as *const u8) },
         }
     }
 }
 
+/// Splits the codec byte of a page header into the encoding of the page
+/// and whether its encoded bytes are compressed again.
+pub(crate) fn split_codec(codec: u8) -> Result<(Compression, bool)> {
+    let compression = Compression::from_codec(codec & !COMPRESSED_ENCODING_FLAG)?;
+    Ok((compression, codec & COMPRESSED_ENCODING_FLAG != 0))
+}
+
+/// Encodes a page into `buf` with `encode`. When `compress_encodings` is set and the
+/// encoding is compressible, the encoded bytes are compressed again with the default
+/// compression, after its codec and the encoded size, and the flag is set in the codec
+/// byte of the page header at `codec_pos`.
+pub(crate) fn compress_encoding(
+    compression: Compression,
+    write_options: &WriteOptions,
+    buf: &mut Vec<u8>,
+    codec_pos: usize,
+    encode: impl FnOnce(&WriteOptions, &mut Vec<u8>) -> Result<usize>,
+) -> Result<usize> {
+    let c = write_options.default_compression;
+    if !write_options.compress_encodings
+        || !compression.compressible_encoding()
+        || c == CommonCompression::None
+    {
+        return encode(write_options, buf);
+    }
+    // the nested encodings, like the dictionary indices, are compressed once with the page
+    let inner_options = WriteOptions {
+        compress_encodings: false,
+        ..write_options.clone()
+    };
+    let mut encoded = vec![];
+    encode(&inner_options, &mut encoded)?;
+
+    buf[codec_pos] |= COMPRESSED_ENCODING_FLAG;
+    let start = buf.len();
+    buf.push(u8::from(c.to_compression()));
+    buf.extend_from_slice(&(encoded.len() as u64).to_le_bytes());
+    c.compress_with_level(&encoded, buf, write_options.compression_level)?;
+    Ok(buf.len() - start)
+}
+
+/// Decompresses the encoded bytes of a page written by [`compress_encoding`].
+pub(crate) fn decompress_encoding(input: &[u8]) -> Result<Vec<u8>> {
+    if input.len() < 9 {
+        return Err(Error::OutOfSpec(
+            "Missing the header of the compressed encoding".to_string(),
+        ));
+    }
+    let c = CommonCompression::try_from(&Compression::from_codec(input[0])?)?;
+    let size = u64::from_le_bytes(input[1..9].try_into().unwrap()) as usize;
+    let mut encoded = vec![0u8; size];
+    c.decompress(&input[9..], &mut encoded)?;
+    Ok(encoded)
+}
+
 #[inline]
 pub(crate) fn is_valid(validity: &Option<&Bitmap>, i: usize) -> bool {
     match validity {
@@ -121,4 +233,68 @@ pub(crate) fn get_bits_needed(input: u64) -> u32 {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codec_bytes() {
+        let mut codecs = vec![];
+        for byte in 0..=u8::MAX {
+            if let Ok(c) = Compression::from_codec(byte) {
+                assert_eq!(u8::from(c), byte);
+                codecs.push(c);
+            }
+        }
+        let (custom, codecs): (Vec<_>, Vec<_>) = codecs
+            .into_iter()
+            .partition(|c| matches!(c, Compression::Custom(_)));
+        assert_eq!(codecs, Compression::ALL);
+        assert_eq!(
+            custom,
+            registry::CUSTOM_CODECS
+                .map(Compression::Custom)
+                .collect::<Vec<_>>()
+        );
+        // the codec bytes are below the flag of the compressed encodings
+        assert!(codecs
+            .iter()
+            .chain(custom.iter())
+            .all(|c| u8::from(*c) & COMPRESSED_ENCODING_FLAG == 0));
+        for c in Compression::ALL {
+            if let Ok(common) = CommonCompression::try_from(&c) {
+                assert_eq!(common.to_compression(), c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_snappy_framed() {
+        let input: Vec<u8> = (0..100_000u32)
+            .flat_map(|i| (i % 1000).to_le_bytes())
+            .collect();
+        let mut buf = vec![1, 2, 3];
+        let size = CommonCompression::SnappyFramed
+            .compress(&input, &mut buf)
+            .unwrap();
+        assert_eq!(size, buf.len() - 3);
+        assert!(size < input.len());
+
+        // the buffer is a snappy stream, starting with the stream identifier
+        let compressed = &buf[3..];
+        assert_eq!(&compressed[..10], b"\xff\x06\x00\x00sNaPpY");
+        let mut output = vec![];
+        std::io::Read::read_to_end(&mut snap::read::FrameDecoder::new(compressed), &mut output)
+            .unwrap();
+        assert_eq!(output, input);
+
+        let mut output = vec![0u8; input.len()];
+        CommonCompression::SnappyFramed
+            .decompress(compressed, &mut output)
+            .unwrap();
+        assert_eq!(output, input);
+        // the raw decoder does not read the framing format
+        assert!(CommonCompression::Snappy
+            .decompress(compressed, &mut output)
+            .is_err());
+    }
+}
@@ -0,0 +1,284 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Encodings implemented outside of strawboat, registered at runtime under a codec byte
+//! of [`CUSTOM_CODECS`].
+//!
+//! A registered encoding is evaluated by the writer like the built-in ones, on the
+//! columns of its type when [`crate::write::WriteOptions::default_compress_ratio`] is
+//! set, and decodes the pages written with its codec byte. The pages of a custom
+//! codec can only be read by a process that registered the same encoding.
+
+use std::any::{Any, TypeId};
+use std::ops::RangeInclusive;
+use std::sync::{Arc, RwLock};
+
+use arrow::array::{BinaryArray, PrimitiveArray};
+use arrow::error::{Error, Result};
+use arrow::types::Offset;
+
+use crate::write::WriteOptions;
+
+use super::binary::{BinaryCompression, BinaryStats};
+use super::double::{DoubleCompression, DoubleStats, DoubleType};
+use super::integer::{IntegerCompression, IntegerStats, IntegerType};
+use super::Compression;
+
+/// The codec bytes reserved to the registered encodings, [`Compression::Custom`].
+/// They are below the flag of the compressed encodings, and the built-in codecs
+/// never use them.
+pub const CUSTOM_CODECS: RangeInclusive<u8> = 64..=127;
+
+/// A registered encoding, by the type of its trait object and its codec byte.
+struct Entry {
+    type_id: TypeId,
+    codec: u8,
+    encoding: Box<dyn Any + Send + Sync>,
+}
+
+static REGISTRY: RwLock<Vec<Entry>> = RwLock::new(Vec::new());
+
+fn register<C: ?Sized + Send + Sync + 'static>(
+    compression: Compression,
+    encoding: Arc<C>,
+) -> Result<()> {
+    let codec = match compression {
+        Compression::Custom(codec) if CUSTOM_CODECS.contains(&codec) => codec,
+        other => {
+            return Err(Error::InvalidArgumentError(format!(
+                "A registered encoding must use a custom codec in {CUSTOM_CODECS:?}, not {other:?}"
+            )))
+        }
+    };
+    let type_id = TypeId::of::<Arc<C>>();
+    let mut registry = REGISTRY.write().unwrap();
+    if registry
+        .iter()
+        .any(|entry| entry.type_id == type_id && entry.codec == codec)
+    {
+        return Err(Error::InvalidArgumentError(format!(
+            "An encoding of this type is already registered with the codec {codec}"
+        )));
+    }
+    registry.push(Entry {
+        type_id,
+        codec,
+        encoding: Box::new(encoding),
+    });
+    Ok(())
+}
+
+/// The encodings of the type `C` registered so far, by codec byte.
+fn registered<C: ?Sized + Send + Sync + 'static>() -> Vec<Arc<C>> {
+    let type_id = TypeId::of::<Arc<C>>();
+    let registry = REGISTRY.read().unwrap();
+    let mut entries: Vec<&Entry> = registry
+        .iter()
+        .filter(|entry| entry.type_id == type_id)
+        .collect();
+    entries.sort_by_key(|entry| entry.codec);
+    entries
+        .into_iter()
+        .filter_map(|entry| entry.encoding.downcast_ref::<Arc<C>>().cloned())
+        .collect()
+}
+
+fn registered_codec<C: ?Sized + Send + Sync + 'static>(codec: u8) -> Option<Arc<C>> {
+    let type_id = TypeId::of::<Arc<C>>();
+    REGISTRY
+        .read()
+        .unwrap()
+        .iter()
+        .find(|entry| entry.type_id == type_id && entry.codec == codec)
+        .and_then(|entry| entry.encoding.downcast_ref::<Arc<C>>().cloned())
+}
+
+pub(crate) type SharedIntegerCompression<T> = Arc<dyn IntegerCompression<T> + Send + Sync>;
+pub(crate) type SharedBinaryCompression<O> = Arc<dyn BinaryCompression<O> + Send + Sync>;
+pub(crate) type SharedDoubleCompression<T> = Arc<dyn DoubleCompression<T> + Send + Sync>;
+
+/// Registers an encoding of the integer columns of type `T` under the codec byte of
+/// its [`IntegerCompression::to_compression`], which must be a [`Compression::Custom`]
+/// in [`CUSTOM_CODECS`].
+/// # Errors
+/// Errors if the codec is not a custom one or is already registered for `T`.
+pub fn register_integer_compression<T: IntegerType>(
+    encoding: impl IntegerCompression<T> + Send + Sync + 'static,
+) -> Result<()> {
+    let encoding: SharedIntegerCompression<T> = Arc::new(encoding);
+    register(encoding.to_compression(), encoding)
+}
+
+/// Registers an encoding of the binary and utf8 columns with offsets of type `O`,
+/// see [`register_integer_compression`].
+pub fn register_binary_compression<O: Offset>(
+    encoding: impl BinaryCompression<O> + Send + Sync + 'static,
+) -> Result<()> {
+    let encoding: SharedBinaryCompression<O> = Arc::new(encoding);
+    register(encoding.to_compression(), encoding)
+}
+
+/// Registers an encoding of the float columns of type `T`, see [`register_integer_compression`].
+pub fn register_double_compression<T: DoubleType>(
+    encoding: impl DoubleCompression<T> + Send + Sync + 'static,
+) -> Result<()> {
+    let encoding: SharedDoubleCompression<T> = Arc::new(encoding);
+    register(encoding.to_compression(), encoding)
+}
+
+pub(crate) fn registered_integer_compressions<T: IntegerType>() -> Vec<SharedIntegerCompression<T>>
+{
+    registered()
+}
+
+pub(crate) fn registered_integer_compression<T: IntegerType>(
+    codec: u8,
+) -> Option<SharedIntegerCompression<T>> {
+    registered_codec(codec)
+}
+
+pub(crate) fn registered_binary_compressions<O: Offset>() -> Vec<SharedBinaryCompression<O>> {
+    registered()
+}
+
+pub(crate) fn registered_binary_compression<O: Offset>(
+    codec: u8,
+) -> Option<SharedBinaryCompression<O>> {
+    registered_codec(codec)
+}
+
+pub(crate) fn registered_double_compressions<T: DoubleType>() -> Vec<SharedDoubleCompression<T>> {
+    registered()
+}
+
+pub(crate) fn registered_double_compression<T: DoubleType>(
+    codec: u8,
+) -> Option<SharedDoubleCompression<T>> {
+    registered_codec(codec)
+}
+
+impl<T: IntegerType> IntegerCompression<T> for SharedIntegerCompression<T> {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        stats: &IntegerStats<T>,
+        write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        self.as_ref().compress(array, stats, write_options, output)
+    }
+
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.as_ref().decompress(input, length, output)
+    }
+
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
+        self.as_ref()
+            .decompress_with_version(input, length, output, format_version)
+    }
+
+    fn to_compression(&self) -> Compression {
+        self.as_ref().to_compression()
+    }
+
+    fn compress_ratio(&self, stats: &IntegerStats<T>) -> f64 {
+        self.as_ref().compress_ratio(stats)
+    }
+}
+
+impl<O: Offset> BinaryCompression<O> for SharedBinaryCompression<O> {
+    fn compress(
+        &self,
+        array: &BinaryArray<O>,
+        stats: &BinaryStats<O>,
+        write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        self.as_ref().compress(array, stats, write_options, output)
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+    ) -> Result<()> {
+        self.as_ref().decompress(input, length, offsets, values)
+    }
+
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        offsets: &mut Vec<O>,
+        values: &mut Vec<u8>,
+        format_version: u16,
+    ) -> Result<()> {
+        self.as_ref()
+            .decompress_with_version(input, length, offsets, values, format_version)
+    }
+
+    fn to_compression(&self) -> Compression {
+        self.as_ref().to_compression()
+    }
+
+    fn compress_ratio(&self, stats: &BinaryStats<O>) -> f64 {
+        self.as_ref().compress_ratio(stats)
+    }
+}
+
+impl<T: DoubleType> DoubleCompression<T> for SharedDoubleCompression<T> {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<T>,
+        stats: &DoubleStats<T>,
+        write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> Result<usize> {
+        self.as_ref().compress(array, stats, write_options, output)
+    }
+
+    fn decompress(&self, input: &[u8], length: usize, output: &mut Vec<T>) -> Result<()> {
+        self.as_ref().decompress(input, length, output)
+    }
+
+    fn decompress_with_version(
+        &self,
+        input: &[u8],
+        length: usize,
+        output: &mut Vec<T>,
+        format_version: u16,
+    ) -> Result<()> {
+        self.as_ref()
+            .decompress_with_version(input, length, output, format_version)
+    }
+
+    fn to_compression(&self) -> Compression {
+        self.as_ref().to_compression()
+    }
+
+    fn compress_ratio(&self, stats: &DoubleStats<T>) -> f64 {
+        self.as_ref().compress_ratio(stats)
+    }
+}
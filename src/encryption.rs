@@ -0,0 +1,212 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Encryption of the pages of the selected columns with AES-256-GCM, behind the
+//! `encryption` feature.
+//!
+//! The writer asks its `KeyProvider` for the key of every leaf column and
+//! encrypts the pages of the columns it has a key for, after they are compressed.
+//! An encrypted page is the random nonce of the page followed by the ciphertext and
+//! its tag, and is flagged by [`crate::PageMeta::encrypted`]. The other pages, and
+//! the files without encrypted columns, are written as before.
+//! Every page is authenticated with the index of its leaf column and its index in
+//! the column as associated data, so that a page moved to another place of the
+//! file fails to decrypt. The values page of a dictionary column has the index
+//! after its key pages.
+//!
+//! The readers decrypt the pages with the key of their column, see
+//! `NativeReader::with_decryption_key`, and reject the plaintext pages of the
+//! columns they have a key for. The functions of `read::batch_read`, which
+//! read the stored bytes of the pages, return an error for the encrypted columns.
+//! The footer, the statistics and the bloom filters are not encrypted.
+
+#[cfg(feature = "encryption")]
+use std::sync::Arc;
+
+use arrow::error::{Error, Result};
+
+/// The AES-256 key of a column.
+pub type EncryptionKey = [u8; 32];
+
+/// Size of the nonce that starts an encrypted page.
+pub const NONCE_SIZE: usize = 12;
+
+/// Size of the authentication tag that ends an encrypted page.
+pub const TAG_SIZE: usize = 16;
+
+/// The key of a leaf column and the index of the column, which the pages of the
+/// column are authenticated with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ColumnKey {
+    pub key: EncryptionKey,
+    pub column: usize,
+}
+
+/// The associated data of the page at `index` of the leaf column at `column`.
+#[cfg(feature = "encryption")]
+fn page_aad(column: usize, index: usize) -> [u8; 16] {
+    let mut aad = [0u8; 16];
+    aad[..8].copy_from_slice(&(column as u64).to_le_bytes());
+    aad[8..].copy_from_slice(&(index as u64).to_le_bytes());
+    aad
+}
+
+/// Gives the keys of the columns to encrypt, e.g. from a key management service.
+#[cfg(feature = "encryption")]
+pub trait KeyProvider: Send + Sync {
+    /// The key of the leaf column at `column`, `None` to write it in plaintext.
+    fn column_key(&self, column: usize) -> Result<Option<EncryptionKey>>;
+}
+
+/// A [`KeyProvider`] shared by the clones of the write options. Two of them are
+/// equal if they are the same provider.
+#[cfg(feature = "encryption")]
+#[derive(Clone)]
+pub struct SharedKeyProvider(pub Arc<dyn KeyProvider>);
+
+#[cfg(feature = "encryption")]
+impl SharedKeyProvider {
+    pub fn new(provider: impl KeyProvider + 'static) -> Self {
+        Self(Arc::new(provider))
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl std::fmt::Debug for SharedKeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SharedKeyProvider")
+    }
+}
+
+#[cfg(feature = "encryption")]
+impl PartialEq for SharedKeyProvider {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(
+            Arc::as_ptr(&self.0) as *const u8,
+            Arc::as_ptr(&other.0) as *const u8,
+        )
+    }
+}
+
+/// Encrypts the bytes of the page at `index` of the column of `key` with a random
+/// nonce, which starts the output.
+#[cfg(feature = "encryption")]
+pub(crate) fn encrypt_page(key: &ColumnKey, index: usize, page: &[u8]) -> Result<Vec<u8>> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+    use rand::RngCore;
+
+    let mut nonce = [0u8; NONCE_SIZE];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    let payload = Payload {
+        msg: page,
+        aad: &page_aad(key.column, index),
+    };
+    let ciphertext = Aes256Gcm::new((&key.key).into())
+        .encrypt(Nonce::from_slice(&nonce), payload)
+        .map_err(|_| Error::InvalidArgumentError("Failed to encrypt a page".to_string()))?;
+    let mut output = Vec::with_capacity(NONCE_SIZE + ciphertext.len());
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn encrypt_page(_key: &ColumnKey, _index: usize, _page: &[u8]) -> Result<Vec<u8>> {
+    Err(Error::InvalidArgumentError(
+        "Encrypting a page requires the encryption feature".to_string(),
+    ))
+}
+
+/// Decrypts the encrypted page at `index` of its column with the key of the column,
+/// erroring without a key or if the page was not encrypted with it at this place.
+#[cfg(feature = "encryption")]
+pub(crate) fn decrypt_page(key: Option<&ColumnKey>, index: usize, page: &[u8]) -> Result<Vec<u8>> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Nonce};
+
+    let key = key.ok_or_else(|| {
+        Error::InvalidArgumentError(format!(
+            "Page {index} is encrypted, its column must be read with a decryption key"
+        ))
+    })?;
+    if page.len() < NONCE_SIZE + TAG_SIZE {
+        return Err(Error::OutOfSpec(format!(
+            "Encrypted page {index} of {} bytes is shorter than its nonce and tag",
+            page.len()
+        )));
+    }
+    let (nonce, ciphertext) = page.split_at(NONCE_SIZE);
+    let payload = Payload {
+        msg: ciphertext,
+        aad: &page_aad(key.column, index),
+    };
+    Aes256Gcm::new((&key.key).into())
+        .decrypt(Nonce::from_slice(nonce), payload)
+        .map_err(|_| {
+            Error::OutOfSpec(format!(
+                "Failed to decrypt page {index} of column {}, the key is wrong or the page is corrupted or moved",
+                key.column
+            ))
+        })
+}
+
+#[cfg(not(feature = "encryption"))]
+pub(crate) fn decrypt_page(
+    _key: Option<&ColumnKey>,
+    index: usize,
+    _page: &[u8],
+) -> Result<Vec<u8>> {
+    Err(Error::NotYetImplemented(format!(
+        "Page {index} is encrypted, reading it requires the encryption feature"
+    )))
+}
+
+#[cfg(all(test, feature = "encryption"))]
+mod tests {
+    use super::{decrypt_page, encrypt_page, ColumnKey, NONCE_SIZE, TAG_SIZE};
+
+    #[test]
+    fn test_encrypt_page() {
+        let key = ColumnKey {
+            key: [7u8; 32],
+            column: 1,
+        };
+        let page = b"the bytes of a compressed page".to_vec();
+        let encrypted = encrypt_page(&key, 2, &page).unwrap();
+        assert_eq!(encrypted.len(), NONCE_SIZE + page.len() + TAG_SIZE);
+        // every page has its own nonce
+        assert_ne!(encrypt_page(&key, 2, &page).unwrap(), encrypted);
+        assert_eq!(decrypt_page(Some(&key), 2, &encrypted).unwrap(), page);
+
+        assert!(decrypt_page(None, 2, &encrypted).is_err());
+        let wrong_key = ColumnKey {
+            key: [8u8; 32],
+            ..key
+        };
+        assert!(decrypt_page(Some(&wrong_key), 2, &encrypted).is_err());
+        let mut corrupted = encrypted.clone();
+        corrupted[NONCE_SIZE] ^= 1;
+        assert!(decrypt_page(Some(&key), 2, &corrupted).is_err());
+        assert!(decrypt_page(Some(&key), 2, &encrypted[..NONCE_SIZE]).is_err());
+
+        // the page is bound to its place in the file
+        assert!(decrypt_page(Some(&key), 3, &encrypted).is_err());
+        let other_column = ColumnKey { column: 0, ..key };
+        assert!(decrypt_page(Some(&other_column), 2, &encrypted).is_err());
+    }
+}
@@ -0,0 +1,127 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Conversions between strawboat files and other Arrow formats.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
+use std::path::Path;
+
+use arrow::array::new_empty_array;
+use arrow::chunk::Chunk;
+use arrow::error::Result;
+use arrow::io::ipc::write::{FileWriter as IpcWriter, WriteOptions as IpcWriteOptions};
+use arrow::io::parquet::read as parquet_read;
+use parquet2::compression::Compression as ParquetCompression;
+
+use crate::read::FileReader;
+use crate::write::{writer::concatenate_chunks, NativeWriter, WriteOptions};
+use crate::{ColumnMeta, CommonCompression};
+
+/// The number of rows of the chunks read from the row groups of a parquet file.
+const PARQUET_CHUNK_SIZE: usize = 65536;
+
+/// Exports the columns of a strawboat file to an Arrow IPC file written to `w`.
+///
+/// The columns are decoded by `reader`, so only its projected fields are exported,
+/// and every page of the strawboat file becomes a record batch of the IPC file.
+/// The IPC schema is the schema of `reader`, including its nested types.
+pub fn to_ipc<R: Read + Seek, W: Write>(reader: FileReader<R>, w: W) -> Result<()> {
+    let schema = reader.schema().clone();
+    let mut writer = IpcWriter::try_new(w, schema, None, IpcWriteOptions { compression: None })?;
+    for chunk in reader {
+        writer.write(&chunk?, None)?;
+    }
+    writer.finish()
+}
+
+/// Converts the parquet file at `parquet_path` to a strawboat file at `out_path`,
+/// with the schema inferred from the parquet metadata.
+///
+/// The row groups are read in chunks that are concatenated into the one chunk given
+/// to [`NativeWriter::write`], so the pages of a column span the row groups. If `options` is `None`, the
+/// [`WriteOptions::default`] are used with the compression of the parquet columns
+/// when strawboat supports it. Returns the metas of the written columns.
+pub fn parquet_to_strawboat<P: AsRef<Path>, Q: AsRef<Path>>(
+    parquet_path: P,
+    out_path: Q,
+    options: Option<WriteOptions>,
+) -> Result<Vec<ColumnMeta>> {
+    let mut reader = BufReader::new(File::open(parquet_path)?);
+    let metadata = parquet_read::read_metadata(&mut reader)?;
+    let schema = parquet_read::infer_schema(&metadata)?;
+
+    let options = options.unwrap_or_else(|| {
+        let compression = metadata
+            .row_groups
+            .iter()
+            .flat_map(|row_group| row_group.columns())
+            .map(|column| column.compression())
+            .next();
+        match compression.and_then(to_common_compression) {
+            Some(compression) => WriteOptions {
+                default_compression: compression,
+                ..Default::default()
+            },
+            None => WriteOptions::default(),
+        }
+    });
+
+    let chunks = parquet_read::FileReader::new(
+        reader,
+        metadata.row_groups,
+        schema.clone(),
+        Some(PARQUET_CHUNK_SIZE),
+        None,
+        None,
+    );
+
+    let chunks = chunks.collect::<Result<Vec<_>>>()?;
+    let chunk = match chunks.len() {
+        // a file without rows still has its (empty) columns
+        0 => Chunk::new(
+            schema
+                .fields
+                .iter()
+                .map(|field| new_empty_array(field.data_type().clone()))
+                .collect(),
+        ),
+        1 => chunks.into_iter().next().unwrap(),
+        _ => concatenate_chunks(&chunks)?,
+    };
+
+    let mut writer = NativeWriter::new(BufWriter::new(File::create(out_path)?), schema, options);
+    writer.start()?;
+    writer.write(&chunk)?;
+    writer.finish()?;
+    let metas = std::mem::take(&mut writer.metas);
+    writer.into_inner().flush()?;
+    Ok(metas)
+}
+
+/// The [`CommonCompression`] of a parquet compression codec, if strawboat supports it.
+fn to_common_compression(compression: ParquetCompression) -> Option<CommonCompression> {
+    match compression {
+        ParquetCompression::Uncompressed => Some(CommonCompression::None),
+        ParquetCompression::Snappy => Some(CommonCompression::Snappy),
+        ParquetCompression::Lz4 | ParquetCompression::Lz4Raw => Some(CommonCompression::Lz4),
+        ParquetCompression::Zstd => Some(CommonCompression::Zstd),
+        #[cfg(feature = "brotli")]
+        ParquetCompression::Brotli => Some(CommonCompression::Brotli),
+        _ => None,
+    }
+}
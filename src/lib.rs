@@ -16,14 +16,17 @@
 // under the License.
 
 #![feature(iter_advance_by)]
+#![cfg_attr(feature = "simd", feature(portable_simd))]
 
-mod compression;
+pub mod compression;
 
 #[macro_use]
 mod errors;
 
-pub use compression::CommonCompression;
+pub use compression::{CommonCompression, Compression};
 
+pub mod encryption;
+pub mod interop;
 pub mod read;
 pub mod stat;
 pub mod write;
@@ -31,8 +34,30 @@ pub mod write;
 #[macro_use]
 mod util;
 
+pub use util::to_parquet_schema;
+
 const ARROW_MAGIC: [u8; 6] = [b'A', b'R', b'R', b'O', b'W', b'2'];
+/// Version of the file format, written after the magic in the file header.
+/// Files written before the version was recorded have version 0.
+/// The footer of a file with several row groups has version 2, the header of
+/// every file and the footer of the other files [`SINGLE_ROW_GROUP_VERSION`],
+/// so that the readers of version 1 still read them.
+pub(crate) const FORMAT_VERSION: u16 = 2;
+pub(crate) const SINGLE_ROW_GROUP_VERSION: u16 = 1;
+/// Size of the header of a compressed buffer, the codec byte
+/// followed by the compressed and uncompressed sizes as `u64`.
+pub(crate) const COMPRESS_HEADER_SIZE: usize = 17;
+/// Size of the header of a compressed buffer of the files of version 0,
+/// whose sizes are `u32`.
+pub(crate) const COMPRESS_HEADER_SIZE_V0: usize = 9;
 pub(crate) const CONTINUATION_MARKER: [u8; 4] = [0xff; 4];
+/// Set in the meta size of the tail when the column metas are LEB128 encoded.
+pub(crate) const VARINT_META_FLAG: u32 = 1 << 31;
+/// Set in the meta size of the tail when the column metas end with the
+/// format version and the magic.
+pub(crate) const VERSIONED_META_FLAG: u32 = 1 << 30;
+/// Size of the format version and the magic that end the column metas.
+pub(crate) const FOOTER_VERSION_SIZE: usize = 8;
 
 #[derive(
     Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize,
@@ -40,6 +65,54 @@ pub(crate) const CONTINUATION_MARKER: [u8; 4] = [0xff; 4];
 pub struct ColumnMeta {
     pub offset: u64,
     pub pages: Vec<PageMeta>,
+    /// The zstd dictionary every page of the column is compressed with,
+    /// see `WriteOptions::zstd_dictionary_size`.
+    #[serde(default)]
+    pub zstd_dictionary: Option<Vec<u8>>,
+    /// The column of the values of a `DictionaryArray` column, whose pages hold the keys.
+    /// It is written as a single page right after the key pages.
+    #[serde(default)]
+    pub dictionary: Option<Box<ColumnMeta>>,
+    /// The bloom filter of the valid values of the column, see `WriteOptions::bloom_filter`.
+    #[serde(default)]
+    pub bloom_filter: Option<BloomFilter>,
+    /// The number of null values of the leaf values of the column, `None` if
+    /// unknown like for the files written before it was recorded.
+    #[serde(default)]
+    pub null_count: Option<u64>,
+    /// The encoding of the values of most pages of the column.
+    #[serde(default)]
+    pub dominant_codec: Compression,
+    /// The number of pages of the column per encoding, in the order the encodings
+    /// were first used. Empty for the files written before it was recorded.
+    #[serde(default)]
+    pub codec_counts: Vec<(Compression, u64)>,
+    /// The min and max of the valid values of a non-nested column, see [`PageStatistics`].
+    /// The columns of merged files have them when every merged file has them.
+    #[serde(default)]
+    pub statistics: Option<PageStatistics>,
+    /// The values of the column were declared sorted in ascending order by the
+    /// writer, see `WriteOptions::sorted_columns`.
+    #[serde(default)]
+    pub sorted: bool,
+    /// An estimate of the number of distinct valid values of the column, with a
+    /// standard error of about 1.6%, see `WriteOptions::distinct_count`.
+    /// The columns of merged files have none.
+    #[serde(default)]
+    pub distinct_count: Option<u64>,
+}
+
+/// The metas of the columns of a row group, the chunk written between two
+/// calls of `NativeWriter::finish_row_group`.
+#[derive(
+    Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize,
+)]
+pub struct RowGroupMeta {
+    /// Number of rows of the row group, `None` for the files written before it
+    /// was recorded whose columns are all nested.
+    pub num_rows: Option<u64>,
+    /// The metas of the leaf columns of the row group.
+    pub columns: Vec<ColumnMeta>,
 }
 
 impl ColumnMeta {
@@ -56,17 +129,153 @@ impl ColumnMeta {
             .sum::<u64>()
             + self.offset;
         let pages = self.pages[start_page_index..end_page_index].to_vec();
+        let null_count = pages.iter().map(|page| page.null_count).sum();
+
+        Self {
+            offset,
+            pages,
+            zstd_dictionary: self.zstd_dictionary.clone(),
+            dictionary: self.dictionary.clone(),
+            bloom_filter: self.bloom_filter.clone(),
+            null_count,
+            dominant_codec: self.dominant_codec,
+            codec_counts: self.codec_counts.clone(),
+            statistics: self.statistics.clone(),
+            sorted: self.sorted,
+            distinct_count: self.distinct_count,
+        }
+    }
+
+    /// Selects the pages of a non-nested column overlapping the rows `[start, end)`,
+    /// where the `num_values` of a page is its number of rows.
+    /// Returns the number of skipped pages, the meta of the selected pages and the
+    /// row of the first selected page that `start` is at, the rows before it and
+    /// after `end` are to be trimmed from the decoded pages.
+    /// `end` is clamped to the number of rows, an empty range selects no page.
+    pub fn pages_for_row_range(&self, start: u64, end: u64) -> (usize, ColumnMeta, u64) {
+        let mut skip_pages = 0;
+        let mut page_start = 0;
+        for page in &self.pages {
+            if page_start + page.num_values > start {
+                break;
+            }
+            page_start += page.num_values;
+            skip_pages += 1;
+        }
 
-        Self { offset, pages }
+        let mut end_page = skip_pages;
+        let mut page_end = page_start;
+        while start < end && end_page < self.pages.len() && page_end < end {
+            page_end += self.pages[end_page].num_values;
+            end_page += 1;
+        }
+
+        if skip_pages == end_page {
+            let meta = Self {
+                offset: self.offset
+                    + self.pages[..skip_pages]
+                        .iter()
+                        .map(|m| m.length)
+                        .sum::<u64>(),
+                pages: vec![],
+                ..self.clone()
+            };
+            return (skip_pages, meta, 0);
+        }
+        (
+            skip_pages,
+            self.slice(skip_pages, end_page),
+            start - page_start,
+        )
+    }
+
+    /// The indices of the pages of a non-nested column that may hold a row matching
+    /// `predicate`, see [`read::predicate::Predicate::may_match`]. Only these pages
+    /// are to be decoded, e.g. with [`ColumnMeta::slice`], and their rows filtered
+    /// exactly unless [`read::predicate::Predicate::matches_all`].
+    pub fn select_pages(&self, predicate: &read::predicate::Predicate) -> Vec<usize> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| predicate.may_match(page))
+            .map(|(index, _)| index)
+            .collect()
     }
 
     pub fn skip_one_page(&self) -> Self {
-        self.slice(1, self.pages.len())
+        self.skip_n_pages(1)
+    }
+
+    /// The meta of the pages after the first `n` ones, which has no page and
+    /// starts at the end of the column if `n` is the number of pages.
+    pub fn skip_n_pages(&self, n: usize) -> Self {
+        assert!(n <= self.pages.len());
+        if n < self.pages.len() {
+            return self.slice(n, self.pages.len());
+        }
+        Self {
+            offset: self.offset + self.total_len(),
+            pages: vec![],
+            null_count: Some(0),
+            ..self.clone()
+        }
+    }
+
+    /// The statistics of the column, see [`ColumnStatistics`].
+    pub fn column_statistics(&self) -> ColumnStatistics {
+        let (min, max) = match &self.statistics {
+            Some(statistics) => (Some(statistics.min.clone()), Some(statistics.max.clone())),
+            None => (None, None),
+        };
+        ColumnStatistics {
+            min,
+            max,
+            null_count: self.null_count,
+            distinct_count_estimate: self.distinct_count,
+        }
+    }
+
+    /// The byte ranges of the pages in the file, from the offset of the column
+    /// and the lengths of its pages.
+    pub fn page_ranges(&self) -> Vec<std::ops::Range<u64>> {
+        let mut start = self.offset;
+        self.pages
+            .iter()
+            .map(|page| {
+                start += page.length;
+                start - page.length..start
+            })
+            .collect()
     }
 
     pub fn total_len(&self) -> u64 {
         self.pages.iter().map(|m| m.length).sum::<u64>()
     }
+
+    /// Whether the column may hold `value`, given as the little endian bytes of a
+    /// primitive value or the bytes of a binary value.
+    /// `false` means that the column does not hold it, columns without a loaded
+    /// bloom filter may hold any value.
+    pub fn bloom_contains(&self, value: &[u8]) -> bool {
+        match &self.bloom_filter {
+            Some(filter) if !filter.bitset.is_empty() => {
+                parquet2::bloom_filter::is_in_set(&filter.bitset, util::bloom_filter_hash(value))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// A split-block bloom filter, written right after the pages of its column.
+/// The footer only records where it is, `read_bloom_filters` loads the `bitset`.
+#[derive(
+    Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize,
+)]
+pub struct BloomFilter {
+    pub offset: u64,
+    pub length: u64,
+    #[serde(default)]
+    pub bitset: Vec<u8>,
 }
 
 #[derive(
@@ -77,4 +286,53 @@ pub struct PageMeta {
     pub length: u64,
     // num values(rows) of this page
     pub num_values: u64,
+    // num null values of the leaf values of this page, `None` if unknown like for
+    // the files written before it was recorded, whose validity is always decoded
+    #[serde(default)]
+    pub null_count: Option<u64>,
+    // min and max of the valid values of this page, if known
+    #[serde(default)]
+    pub statistics: Option<PageStatistics>,
+    // crc32 of the bytes of this page, see `WriteOptions::page_checksum`
+    #[serde(default)]
+    pub checksum: Option<u32>,
+    // whether the bytes of this page are encrypted, see `encryption`
+    #[serde(default)]
+    pub encrypted: bool,
+    // whether the bytes of this page are compressed with the zstd dictionary of
+    // its column, set for every page of the columns with one
+    #[serde(default)]
+    pub zstd_dictionary: bool,
+}
+
+/// The range of the valid values of a page, encoded like the values themselves:
+/// little endian for primitive types and the raw bytes for binary types.
+/// It is written for the integer, float and binary pages of non-nested columns
+/// with at least one valid value, and used to skip pages with a
+/// [`read::predicate::PagePredicate`].
+/// With `WriteOptions::statistics_truncate_length`, the binary min and max are
+/// a lower and an upper bound of the values rather than values themselves.
+#[derive(
+    Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, serde::Serialize, serde::Deserialize,
+)]
+pub struct PageStatistics {
+    pub min: Vec<u8>,
+    pub max: Vec<u8>,
+}
+
+/// The statistics of a column recorded in the footer, read with
+/// [`read::reader::read_statistics`] without reading any page.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ColumnStatistics {
+    /// The min of the valid values, encoded like [`PageStatistics::min`].
+    /// `None` for the nested columns, the types without statistics, the columns
+    /// without valid values and the columns of merged files of which one has none.
+    pub min: Option<Vec<u8>>,
+    /// The max of the valid values, `None` like `min`.
+    pub max: Option<Vec<u8>>,
+    /// The number of null values of the leaf values, `None` for the files
+    /// written before it was recorded.
+    pub null_count: Option<u64>,
+    /// See [`ColumnMeta::distinct_count`].
+    pub distinct_count_estimate: Option<u64>,
 }
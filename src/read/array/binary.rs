@@ -15,11 +15,11 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 use std::marker::PhantomData;
 
-use crate::compression::binary::decompress_binary;
-use crate::read::{read_basic::*, BufReader, NativeReadBuf, PageIterator};
+use crate::compression::binary::decompress_binary_with_limit;
+use crate::read::{read_basic::*, NativeReadBuf, PageIterator, ReadOptions};
 use crate::PageMeta;
 use arrow::array::{Array, BinaryArray, Utf8Array};
 use arrow::bitmap::{Bitmap, MutableBitmap};
@@ -66,11 +66,12 @@ where
     O: Offset,
 {
     fn deserialize(&mut self, num_values: u64, buffer: Vec<u8>) -> Result<Box<dyn Array>> {
+        let format_version = self.iter.read_options().page_format_version()?;
         let length = num_values as usize;
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let mut reader = Cursor::new(buffer);
         let validity = if self.is_nullable {
             let mut validity_builder = MutableBitmap::with_capacity(length);
-            read_validity(&mut reader, length, &mut validity_builder)?;
+            read_validity(&mut reader, length, &mut validity_builder, format_version)?;
             Some(std::mem::take(&mut validity_builder).into())
         } else {
             None
@@ -79,19 +80,22 @@ where
         let mut offsets: Vec<O> = Vec::with_capacity(length + 1);
         let mut values = Vec::with_capacity(0);
 
-        decompress_binary(
+        decompress_binary_with_limit(
             &mut reader,
             length,
             &mut offsets,
             &mut values,
             &mut self.scratch,
+            self.iter.read_options().max_decode_bytes,
+            format_version,
         )?;
 
         try_new_binary_array(
             self.data_type.clone(),
-            unsafe { OffsetsBuffer::new_unchecked(offsets.into()) },
+            offsets.into(),
             values.into(),
             validity,
+            self.iter.read_options(),
         )
     }
 }
@@ -166,31 +170,36 @@ where
         num_values: u64,
         buffer: Vec<u8>,
     ) -> Result<(NestedState, Box<dyn Array>)> {
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let format_version = self.iter.read_options().page_format_version()?;
+        let mut reader = Cursor::new(buffer);
         let (mut nested, validity) = read_validity_nested(
             &mut reader,
             num_values as usize,
             &self.leaf,
             self.init.clone(),
+            format_version,
         )?;
         let length = nested.nested.pop().unwrap().len();
 
         let mut offsets: Vec<O> = Vec::with_capacity(length + 1);
         let mut values = Vec::with_capacity(0);
 
-        decompress_binary(
+        decompress_binary_with_limit(
             &mut reader,
             length,
             &mut offsets,
             &mut values,
             &mut self.scratch,
+            self.iter.read_options().max_decode_bytes,
+            format_version,
         )?;
 
         let array = try_new_binary_array(
             self.data_type.clone(),
-            unsafe { OffsetsBuffer::new_unchecked(offsets.into()) },
+            offsets.into(),
             values.into(),
             validity,
+            self.iter.read_options(),
         )?;
         Ok((nested, array))
     }
@@ -225,43 +234,48 @@ pub fn read_binary<O: Offset, R: NativeReadBuf>(
     is_nullable: bool,
     data_type: DataType,
     page_metas: Vec<PageMeta>,
+    read_options: ReadOptions,
 ) -> Result<Box<dyn Array>> {
     let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
 
-    let total_length: usize = page_metas.iter().map(|p| p.length as usize).sum();
-
-    let mut validity_builder = if is_nullable {
+    let mut validity_builder = if has_nulls(is_nullable, &page_metas) {
         Some(MutableBitmap::with_capacity(num_values))
     } else {
         None
     };
     let mut scratch = vec![];
+    let format_version = read_options.page_format_version()?;
     let out_off_len = num_values + 2;
-    // don't know how much space is needed for the buffer,
-    // if not enough, it may need to be reallocated several times.
-    let out_buf_len = total_length * 4;
     let mut offsets: Vec<O> = Vec::with_capacity(out_off_len);
-    let mut values: Vec<u8> = Vec::with_capacity(out_buf_len);
+    // every page reserves the uncompressed size of its values
+    let mut values: Vec<u8> = Vec::new();
 
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let length = page_meta.num_values as usize;
         if let Some(ref mut validity_builder) = validity_builder {
-            read_validity(reader, length, validity_builder)?;
+            read_validity(reader, length, validity_builder, format_version)?;
+        } else if is_nullable {
+            skip_validity(reader, format_version)?;
         }
 
-        decompress_binary(reader, length, &mut offsets, &mut values, &mut scratch)?;
+        decompress_binary_with_limit(
+            reader,
+            length,
+            &mut offsets,
+            &mut values,
+            &mut scratch,
+            read_options.max_decode_bytes,
+            format_version,
+        )?;
     }
     let validity =
         validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
     let offsets: Buffer<O> = offsets.into();
     let values: Buffer<u8> = values.into();
 
-    try_new_binary_array(
-        data_type,
-        unsafe { OffsetsBuffer::new_unchecked(offsets) },
-        values,
-        validity,
-    )
+    try_new_binary_array(data_type, offsets, values, validity, read_options)
 }
 
 pub fn read_nested_binary<O: Offset, R: NativeReadBuf>(
@@ -270,38 +284,60 @@ pub fn read_nested_binary<O: Offset, R: NativeReadBuf>(
     leaf: ColumnDescriptor,
     init: Vec<InitNested>,
     page_metas: Vec<PageMeta>,
+    read_options: ReadOptions,
 ) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
     let mut scratch = vec![];
+    let format_version = read_options.page_format_version()?;
 
     let mut results = Vec::with_capacity(page_metas.len());
 
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let num_values = page_meta.num_values as usize;
-        let (mut nested, validity) = read_validity_nested(reader, num_values, &leaf, init.clone())?;
+        let (mut nested, validity) =
+            read_validity_nested(reader, num_values, &leaf, init.clone(), format_version)?;
         let length = nested.nested.pop().unwrap().len();
 
         let mut offsets: Vec<O> = Vec::with_capacity(length + 1);
         let mut values = Vec::with_capacity(0);
 
-        decompress_binary(reader, length, &mut offsets, &mut values, &mut scratch)?;
+        decompress_binary_with_limit(
+            reader,
+            length,
+            &mut offsets,
+            &mut values,
+            &mut scratch,
+            read_options.max_decode_bytes,
+            format_version,
+        )?;
 
         let array = try_new_binary_array(
             data_type.clone(),
-            unsafe { OffsetsBuffer::new_unchecked(offsets.into()) },
+            offsets.into(),
             values.into(),
             validity,
+            read_options,
         )?;
         results.push((nested, array));
     }
     Ok(results)
 }
 
+/// Creates the array of the decoded `offsets` and `values`,
+/// the offsets are only checked if `read_options` validate them.
 fn try_new_binary_array<O: Offset>(
     data_type: DataType,
-    offsets: OffsetsBuffer<O>,
+    offsets: Buffer<O>,
     values: Buffer<u8>,
     validity: Option<Bitmap>,
+    read_options: ReadOptions,
 ) -> Result<Box<dyn Array>> {
+    let offsets = if read_options.validate {
+        OffsetsBuffer::try_from(offsets)?
+    } else {
+        unsafe { OffsetsBuffer::new_unchecked(offsets) }
+    };
     if matches!(data_type, DataType::Utf8 | DataType::LargeUtf8) {
         let array = Utf8Array::<O>::try_new(data_type, offsets, values, validity)?;
         Ok(Box::new(array) as Box<dyn Array>)
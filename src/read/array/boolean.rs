@@ -15,10 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 
 use crate::compression::boolean::decompress_boolean;
-use crate::read::{read_basic::*, BufReader, NativeReadBuf, PageIterator};
+use crate::read::{read_basic::*, NativeReadBuf, PageIterator};
 use crate::PageMeta;
 use arrow::array::{Array, BooleanArray};
 use arrow::bitmap::MutableBitmap;
@@ -57,21 +57,28 @@ where
     I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
 {
     fn deserialize(&mut self, num_values: u64, buffer: Vec<u8>) -> Result<Box<dyn Array>> {
+        let format_version = self.iter.read_options().page_format_version()?;
         let length = num_values as usize;
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let mut reader = Cursor::new(buffer);
         let validity = if self.is_nullable {
             let mut validity_builder = MutableBitmap::with_capacity(length);
-            read_validity(&mut reader, length, &mut validity_builder)?;
+            read_validity(&mut reader, length, &mut validity_builder, format_version)?;
             Some(std::mem::take(&mut validity_builder).into())
         } else {
             None
         };
         let mut bitmap_builder = MutableBitmap::with_capacity(length);
 
-        decompress_boolean(&mut reader, length, &mut bitmap_builder, &mut self.scratch)?;
+        decompress_boolean(
+            &mut reader,
+            length,
+            &mut bitmap_builder,
+            &mut self.scratch,
+            format_version,
+        )?;
 
         let values = std::mem::take(&mut bitmap_builder).into();
-        let mut buffer = reader.into_inner().into_inner();
+        let mut buffer = reader.into_inner();
         self.iter.swap_buffer(&mut buffer);
 
         let array = BooleanArray::try_new(self.data_type.clone(), values, validity)?;
@@ -143,21 +150,29 @@ where
         num_values: u64,
         buffer: Vec<u8>,
     ) -> Result<(NestedState, Box<dyn Array>)> {
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let format_version = self.iter.read_options().page_format_version()?;
+        let mut reader = Cursor::new(buffer);
         let (mut nested, validity) = read_validity_nested(
             &mut reader,
             num_values as usize,
             &self.leaf,
             self.init.clone(),
+            format_version,
         )?;
         let length = nested.nested.pop().unwrap().len();
         let mut bitmap_builder = MutableBitmap::with_capacity(length);
 
-        decompress_boolean(&mut reader, length, &mut bitmap_builder, &mut self.scratch)?;
+        decompress_boolean(
+            &mut reader,
+            length,
+            &mut bitmap_builder,
+            &mut self.scratch,
+            format_version,
+        )?;
 
         let values = std::mem::take(&mut bitmap_builder).into();
 
-        let mut buffer = reader.into_inner().into_inner();
+        let mut buffer = reader.into_inner();
         self.iter.swap_buffer(&mut buffer);
 
         let array = BooleanArray::try_new(self.data_type.clone(), values, validity)?;
@@ -193,22 +208,33 @@ pub fn read_boolean<R: NativeReadBuf>(
     is_nullable: bool,
     data_type: DataType,
     page_metas: Vec<PageMeta>,
+    format_version: u16,
 ) -> Result<Box<dyn Array>> {
     let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
     let mut scratch = vec![];
-    let mut validity_builder = if is_nullable {
+    let mut validity_builder = if has_nulls(is_nullable, &page_metas) {
         Some(MutableBitmap::with_capacity(num_values))
     } else {
         None
     };
     let mut bitmap_builder = MutableBitmap::with_capacity(num_values);
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let length = page_meta.num_values as usize;
         if let Some(ref mut validity_builder) = validity_builder {
-            read_validity(reader, length, validity_builder)?;
+            read_validity(reader, length, validity_builder, format_version)?;
+        } else if is_nullable {
+            skip_validity(reader, format_version)?;
         }
 
-        decompress_boolean(reader, length, &mut bitmap_builder, &mut scratch)?;
+        decompress_boolean(
+            reader,
+            length,
+            &mut bitmap_builder,
+            &mut scratch,
+            format_version,
+        )?;
     }
     let validity =
         validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
@@ -224,17 +250,27 @@ pub fn read_nested_boolean<R: NativeReadBuf>(
     leaf: ColumnDescriptor,
     init: Vec<InitNested>,
     page_metas: Vec<PageMeta>,
+    format_version: u16,
 ) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
     let mut scratch = vec![];
 
     let mut results = Vec::with_capacity(page_metas.len());
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let num_values = page_meta.num_values as usize;
-        let (mut nested, validity) = read_validity_nested(reader, num_values, &leaf, init.clone())?;
+        let (mut nested, validity) =
+            read_validity_nested(reader, num_values, &leaf, init.clone(), format_version)?;
         let length = nested.nested.pop().unwrap().len();
         let mut bitmap_builder = MutableBitmap::with_capacity(length);
 
-        decompress_boolean(reader, length, &mut bitmap_builder, &mut scratch)?;
+        decompress_boolean(
+            reader,
+            length,
+            &mut bitmap_builder,
+            &mut scratch,
+            format_version,
+        )?;
 
         let values = std::mem::take(&mut bitmap_builder).into();
         let array = BooleanArray::try_new(data_type.clone(), values, validity)?;
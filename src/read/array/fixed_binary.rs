@@ -0,0 +1,289 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::{Cursor, Read};
+
+use crate::compression::fixed_binary::decompress_fixed_binary;
+use crate::read::{read_basic::*, NativeReadBuf, PageIterator};
+use crate::PageMeta;
+use arrow::array::{Array, FixedSizeBinaryArray};
+use arrow::bitmap::MutableBitmap;
+use arrow::datatypes::DataType;
+use arrow::error::{Error, Result};
+use arrow::io::parquet::read::{InitNested, NestedState};
+use parquet2::metadata::ColumnDescriptor;
+
+#[derive(Debug)]
+pub struct FixedSizeBinaryIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    iter: I,
+    is_nullable: bool,
+    data_type: DataType,
+    scratch: Vec<u8>,
+}
+
+impl<I> FixedSizeBinaryIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    pub fn new(iter: I, is_nullable: bool, data_type: DataType) -> Self {
+        Self {
+            iter,
+            is_nullable,
+            data_type,
+            scratch: vec![],
+        }
+    }
+}
+
+impl<I> FixedSizeBinaryIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    fn deserialize(&mut self, num_values: u64, buffer: Vec<u8>) -> Result<Box<dyn Array>> {
+        let format_version = self.iter.read_options().page_format_version()?;
+        let length = num_values as usize;
+        let size = fixed_size(&self.data_type)?;
+        let mut reader = Cursor::new(buffer);
+        let validity = if self.is_nullable {
+            let mut validity_builder = MutableBitmap::with_capacity(length);
+            read_validity(&mut reader, length, &mut validity_builder, format_version)?;
+            Some(std::mem::take(&mut validity_builder).into())
+        } else {
+            None
+        };
+
+        let mut values = Vec::with_capacity(length * size);
+        decompress_fixed_binary(
+            &mut reader,
+            length,
+            size,
+            &mut values,
+            &mut self.scratch,
+            format_version,
+        )?;
+
+        let array = FixedSizeBinaryArray::try_new(self.data_type.clone(), values.into(), validity)?;
+        Ok(Box::new(array) as Box<dyn Array>)
+    }
+}
+
+impl<I> Iterator for FixedSizeBinaryIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    type Item = Result<Box<dyn Array>>;
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match self.iter.nth(n) {
+            Some(Ok((num_values, buffer))) => Some(self.deserialize(num_values, buffer)),
+            Some(Err(err)) => Some(Result::Err(err)),
+            None => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(Ok((num_values, buffer))) => Some(self.deserialize(num_values, buffer)),
+            Some(Err(err)) => Some(Result::Err(err)),
+            None => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct FixedSizeBinaryNestedIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    iter: I,
+    data_type: DataType,
+    leaf: ColumnDescriptor,
+    init: Vec<InitNested>,
+    scratch: Vec<u8>,
+}
+
+impl<I> FixedSizeBinaryNestedIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    pub fn new(
+        iter: I,
+        data_type: DataType,
+        leaf: ColumnDescriptor,
+        init: Vec<InitNested>,
+    ) -> Self {
+        Self {
+            iter,
+            data_type,
+            leaf,
+            init,
+            scratch: vec![],
+        }
+    }
+}
+
+impl<I> FixedSizeBinaryNestedIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    fn deserialize(
+        &mut self,
+        num_values: u64,
+        buffer: Vec<u8>,
+    ) -> Result<(NestedState, Box<dyn Array>)> {
+        let format_version = self.iter.read_options().page_format_version()?;
+        let size = fixed_size(&self.data_type)?;
+        let mut reader = Cursor::new(buffer);
+        let (mut nested, validity) = read_validity_nested(
+            &mut reader,
+            num_values as usize,
+            &self.leaf,
+            self.init.clone(),
+            format_version,
+        )?;
+        let length = nested.nested.pop().unwrap().len();
+
+        let mut values = Vec::with_capacity(length * size);
+        decompress_fixed_binary(
+            &mut reader,
+            length,
+            size,
+            &mut values,
+            &mut self.scratch,
+            format_version,
+        )?;
+
+        let array = FixedSizeBinaryArray::try_new(self.data_type.clone(), values.into(), validity)?;
+        Ok((nested, Box::new(array) as Box<dyn Array>))
+    }
+}
+
+impl<I> Iterator for FixedSizeBinaryNestedIter<I>
+where
+    I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
+{
+    type Item = Result<(NestedState, Box<dyn Array>)>;
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match self.iter.nth(n) {
+            Some(Ok((num_values, buffer))) => Some(self.deserialize(num_values, buffer)),
+            Some(Err(err)) => Some(Result::Err(err)),
+            None => None,
+        }
+    }
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.iter.next() {
+            Some(Ok((num_values, buffer))) => Some(self.deserialize(num_values, buffer)),
+            Some(Err(err)) => Some(Result::Err(err)),
+            None => None,
+        }
+    }
+}
+
+pub fn read_fixed_binary<R: NativeReadBuf>(
+    reader: &mut R,
+    is_nullable: bool,
+    data_type: DataType,
+    page_metas: Vec<PageMeta>,
+    format_version: u16,
+) -> Result<Box<dyn Array>> {
+    let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
+    let size = fixed_size(&data_type)?;
+
+    let mut validity_builder = if has_nulls(is_nullable, &page_metas) {
+        Some(MutableBitmap::with_capacity(num_values))
+    } else {
+        None
+    };
+    let mut scratch = vec![];
+    let mut values: Vec<u8> = Vec::with_capacity(num_values * size);
+
+    for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
+        let length = page_meta.num_values as usize;
+        if let Some(ref mut validity_builder) = validity_builder {
+            read_validity(reader, length, validity_builder, format_version)?;
+        } else if is_nullable {
+            skip_validity(reader, format_version)?;
+        }
+
+        decompress_fixed_binary(
+            reader,
+            length,
+            size,
+            &mut values,
+            &mut scratch,
+            format_version,
+        )?;
+    }
+    let validity =
+        validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
+
+    let array = FixedSizeBinaryArray::try_new(data_type, values.into(), validity)?;
+    Ok(Box::new(array) as Box<dyn Array>)
+}
+
+pub fn read_nested_fixed_binary<R: NativeReadBuf>(
+    reader: &mut R,
+    data_type: DataType,
+    leaf: ColumnDescriptor,
+    init: Vec<InitNested>,
+    page_metas: Vec<PageMeta>,
+    format_version: u16,
+) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
+    let size = fixed_size(&data_type)?;
+    let mut scratch = vec![];
+
+    let mut results = Vec::with_capacity(page_metas.len());
+
+    for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
+        let num_values = page_meta.num_values as usize;
+        let (mut nested, validity) =
+            read_validity_nested(reader, num_values, &leaf, init.clone(), format_version)?;
+        let length = nested.nested.pop().unwrap().len();
+
+        let mut values = Vec::with_capacity(length * size);
+        decompress_fixed_binary(
+            reader,
+            length,
+            size,
+            &mut values,
+            &mut scratch,
+            format_version,
+        )?;
+
+        let array = FixedSizeBinaryArray::try_new(data_type.clone(), values.into(), validity)?;
+        results.push((nested, Box::new(array) as Box<dyn Array>));
+    }
+    Ok(results)
+}
+
+fn fixed_size(data_type: &DataType) -> Result<usize> {
+    match data_type.to_logical_type() {
+        DataType::FixedSizeBinary(size) if *size > 0 => Ok(*size),
+        other => Err(Error::OutOfSpec(format!(
+            "Can not read {other:?} as a fixed size binary array",
+        ))),
+    }
+}
@@ -15,11 +15,15 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::io::Cursor;
+use std::io::{Cursor, Read};
 use std::marker::PhantomData;
 
 use crate::compression::integer::{decompress_integer, IntegerType};
-use crate::read::{read_basic::*, BufReader, NativeReadBuf, PageIterator};
+use crate::read::{
+    mmap::{read_mapped_values, PageValues},
+    read_basic::*,
+    NativeReadBuf, PageIterator,
+};
 use crate::PageMeta;
 use arrow::array::Array;
 use arrow::array::PrimitiveArray;
@@ -66,21 +70,28 @@ where
     Vec<u8>: TryInto<T::Bytes>,
 {
     fn deserialize(&mut self, num_values: u64, buffer: Vec<u8>) -> Result<Box<dyn Array>> {
+        let format_version = self.iter.read_options().page_format_version()?;
         let length = num_values as usize;
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let mut reader = Cursor::new(buffer);
         let validity = if self.is_nullable {
             let mut validity_builder = MutableBitmap::with_capacity(length);
-            read_validity(&mut reader, length, &mut validity_builder)?;
+            read_validity(&mut reader, length, &mut validity_builder, format_version)?;
             Some(std::mem::take(&mut validity_builder).into())
         } else {
             None
         };
         let mut values: Vec<T> = Vec::with_capacity(length);
 
-        decompress_integer(&mut reader, length, &mut values, &mut self.scratch)?;
+        decompress_integer(
+            &mut reader,
+            length,
+            &mut values,
+            &mut self.scratch,
+            format_version,
+        )?;
         assert_eq!(values.len(), length);
 
-        let mut buffer = reader.into_inner().into_inner();
+        let mut buffer = reader.into_inner();
         self.iter.swap_buffer(&mut buffer);
 
         let array = PrimitiveArray::<T>::try_new(self.data_type.clone(), values.into(), validity)?;
@@ -160,20 +171,28 @@ where
         num_values: u64,
         buffer: Vec<u8>,
     ) -> Result<(NestedState, Box<dyn Array>)> {
-        let mut reader = BufReader::with_capacity(buffer.len(), Cursor::new(buffer));
+        let format_version = self.iter.read_options().page_format_version()?;
+        let mut reader = Cursor::new(buffer);
         let (mut nested, validity) = read_validity_nested(
             &mut reader,
             num_values as usize,
             &self.leaf,
             self.init.clone(),
+            format_version,
         )?;
         let length = nested.nested.pop().unwrap().len();
 
         let mut values = Vec::with_capacity(length);
-        decompress_integer(&mut reader, length, &mut values, &mut self.scratch)?;
+        decompress_integer(
+            &mut reader,
+            length,
+            &mut values,
+            &mut self.scratch,
+            format_version,
+        )?;
         assert_eq!(values.len(), length);
 
-        let mut buffer = reader.into_inner().into_inner();
+        let mut buffer = reader.into_inner();
         self.iter.swap_buffer(&mut buffer);
 
         let array = PrimitiveArray::<T>::try_new(self.data_type.clone(), values.into(), validity)?;
@@ -212,26 +231,40 @@ pub fn read_integer<T: IntegerType, R: NativeReadBuf>(
     is_nullable: bool,
     data_type: DataType,
     page_metas: Vec<PageMeta>,
+    format_version: u16,
 ) -> Result<Box<dyn Array>> {
     let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
 
     let mut scratch = vec![];
-    let mut validity_builder = if is_nullable {
+    let mut validity_builder = if has_nulls(is_nullable, &page_metas) {
         Some(MutableBitmap::with_capacity(num_values))
     } else {
         None
     };
-    let mut out_buffer: Vec<T> = Vec::with_capacity(num_values);
+    let mut out_buffer = PageValues::<T>::with_capacity(num_values);
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let length = page_meta.num_values as usize;
         if let Some(ref mut validity_builder) = validity_builder {
-            read_validity(reader, length, validity_builder)?;
+            read_validity(reader, length, validity_builder, format_version)?;
+        } else if is_nullable {
+            skip_validity(reader, format_version)?;
+        }
+        match read_mapped_values(reader, length, format_version)? {
+            Some(buffer) => out_buffer.push_buffer(buffer),
+            None => decompress_integer(
+                reader,
+                length,
+                out_buffer.values_mut(),
+                &mut scratch,
+                format_version,
+            )?,
         }
-        decompress_integer(reader, length, &mut out_buffer, &mut scratch)?;
     }
     let validity =
         validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
-    let values: Buffer<T> = std::mem::take(&mut out_buffer).into();
+    let values: Buffer<T> = out_buffer.finish();
 
     let array = PrimitiveArray::<T>::try_new(data_type, values, validity)?;
     Ok(Box::new(array) as Box<dyn Array>)
@@ -243,18 +276,28 @@ pub fn read_nested_integer<T: IntegerType, R: NativeReadBuf>(
     leaf: ColumnDescriptor,
     init: Vec<InitNested>,
     page_metas: Vec<PageMeta>,
+    format_version: u16,
 ) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
     let mut scratch = vec![];
     let mut results = Vec::with_capacity(page_metas.len());
     for page_meta in page_metas {
+        // the buffers of a page are bounded by its length
+        let reader = &mut Read::take(&mut *reader, page_meta.length);
         let num_values = page_meta.num_values as usize;
-        let (mut nested, validity) = read_validity_nested(reader, num_values, &leaf, init.clone())?;
+        let (mut nested, validity) =
+            read_validity_nested(reader, num_values, &leaf, init.clone(), format_version)?;
         let length = nested.nested.pop().unwrap().len();
 
-        let mut values = Vec::with_capacity(length);
-        decompress_integer(reader, length, &mut values, &mut scratch)?;
+        let values = match read_mapped_values(reader, length, format_version)? {
+            Some(buffer) => buffer,
+            None => {
+                let mut values = Vec::with_capacity(length);
+                decompress_integer(reader, length, &mut values, &mut scratch, format_version)?;
+                values.into()
+            }
+        };
 
-        let array = PrimitiveArray::<T>::try_new(data_type.clone(), values.into(), validity)?;
+        let array = PrimitiveArray::<T>::try_new(data_type.clone(), values, validity)?;
         results.push((nested, Box::new(array) as Box<dyn Array>));
     }
     Ok(results)
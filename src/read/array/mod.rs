@@ -24,6 +24,8 @@ mod boolean;
 pub use boolean::*;
 mod binary;
 pub use binary::*;
+mod fixed_binary;
+pub use fixed_binary::*;
 mod null;
 pub use null::*;
 mod struct_;
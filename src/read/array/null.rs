@@ -81,6 +81,8 @@ where
     }
 }
 
+/// Reads a `Null` column, whose pages are empty, as a [`NullArray`] of the number of
+/// values of its pages.
 pub fn read_null(data_type: DataType, page_metas: Vec<PageMeta>) -> Result<Box<dyn Array>> {
     let length = page_metas.iter().map(|p| p.num_values as usize).sum();
 
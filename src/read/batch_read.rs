@@ -15,28 +15,84 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use super::{array::*, NativeReadBuf};
-use crate::{with_match_integer_double_type, PageMeta};
+use std::borrow::Cow;
+use std::io::{Read, Seek, SeekFrom};
+
+use super::{
+    array::*,
+    read_basic::{has_nulls, read_compress_header, read_validity, skip_validity},
+    reader::{
+        check_plaintext, check_unencrypted, file_format_version, is_primitive, read_column_pages,
+    },
+    NativeReadBuf, ReadOptions,
+};
+use crate::compression::{
+    binary::{self, decompress_binary, value_at},
+    decompress_encoding,
+    integer::{decompress_dict_page, decompress_indices, decompress_integer},
+    split_codec, Compression,
+};
+use crate::util::bits_to_f16;
+use crate::{with_match_dictionary_key_type, with_match_integer_double_type, ColumnMeta, PageMeta};
 use arrow::array::*;
+use arrow::bitmap::{Bitmap, MutableBitmap};
 use arrow::compute::concatenate::concatenate;
-use arrow::datatypes::{DataType, Field, PhysicalType};
-use arrow::error::Result;
+use arrow::compute::take::take;
+use arrow::datatypes::{DataType, Field, IntegerType, PhysicalType, PrimitiveType};
+use arrow::error::{Error, Result};
+use arrow::ffi::{export_array_to_c, export_field_to_c, ArrowArray, ArrowSchema};
 use arrow::io::parquet::read::{create_list, create_map, n_columns, InitNested, NestedState};
+use arrow::offset::OffsetsBuffer;
+use arrow::types::Offset;
 use parquet2::metadata::ColumnDescriptor;
 
-pub fn read_simple<R: NativeReadBuf>(
+/// Reads all pages of a non-nested column at the position of `reader`, which seeks from
+/// the start of the file to read its [`super::reader::read_format_version`].
+pub fn read_simple<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    page_metas: Vec<PageMeta>,
+) -> Result<Box<dyn Array>> {
+    let read_options = file_read_options(reader)?;
+    read_simple_with_options(reader, field, page_metas, read_options)
+}
+
+/// The default [`ReadOptions`] of the pages of the file of `reader`, which seeks from
+/// the start of the file, with the version of its header.
+fn file_read_options<R: Read + Seek>(reader: &mut R) -> Result<ReadOptions> {
+    let format_version = file_format_version(reader, &ReadOptions::default())?;
+    Ok(ReadOptions {
+        format_version: Some(format_version),
+        ..Default::default()
+    })
+}
+
+fn read_simple_with_options<R: NativeReadBuf>(
     reader: &mut R,
     field: Field,
     page_metas: Vec<PageMeta>,
+    read_options: ReadOptions,
 ) -> Result<Box<dyn Array>> {
     use PhysicalType::*;
 
+    check_plaintext(&page_metas)?;
+    let format_version = read_options.page_format_version()?;
     let is_nullable = field.is_nullable;
     let data_type = field.data_type().clone();
 
     match data_type.to_physical_type() {
         Null => read_null(data_type, page_metas),
-        Boolean => read_boolean(reader, is_nullable, data_type, page_metas),
+        Boolean => read_boolean(reader, is_nullable, data_type, page_metas, format_version),
+        Primitive(PrimitiveType::Float16) => {
+            let array = read_integer::<u16, _>(
+                reader,
+                is_nullable,
+                DataType::UInt16,
+                page_metas,
+                format_version,
+            )?;
+            bits_to_f16(array, data_type)
+        }
         Primitive(primitive) => with_match_integer_double_type!(primitive,
         |$T| {
             read_integer::<$T, _>(
@@ -44,6 +100,7 @@ pub fn read_simple<R: NativeReadBuf>(
                 is_nullable,
                 data_type,
                 page_metas,
+                format_version,
             )
         },
         |$T| {
@@ -52,28 +109,63 @@ pub fn read_simple<R: NativeReadBuf>(
                 is_nullable,
                 data_type,
                 page_metas,
+                format_version,
             )
         }),
-        Binary | Utf8 => read_binary::<i32, _>(reader, is_nullable, data_type, page_metas),
+        Binary | Utf8 => {
+            read_binary::<i32, _>(reader, is_nullable, data_type, page_metas, read_options)
+        }
         LargeBinary | LargeUtf8 => {
-            read_binary::<i64, _>(reader, is_nullable, data_type, page_metas)
+            read_binary::<i64, _>(reader, is_nullable, data_type, page_metas, read_options)
+        }
+        FixedSizeBinary => {
+            read_fixed_binary(reader, is_nullable, data_type, page_metas, format_version)
         }
-        FixedSizeBinary => unimplemented!(),
+        Dictionary(_) => Err(Error::NotYetImplemented(format!(
+            "Dictionary column {} must be read with read_dictionary_column",
+            field.name
+        ))),
         _ => unreachable!(),
     }
 }
 
-pub fn read_nested<R: NativeReadBuf>(
+/// Reads all pages of a nested column at the positions of `readers`, which seek from
+/// the start of the file, like [`read_simple`].
+pub fn read_nested<R: NativeReadBuf + Seek>(
+    mut readers: Vec<R>,
+    field: Field,
+    leaves: Vec<ColumnDescriptor>,
+    init: Vec<InitNested>,
+    page_metas: Vec<Vec<PageMeta>>,
+) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
+    let read_options = match readers.first_mut() {
+        Some(reader) => file_read_options(reader)?,
+        None => ReadOptions::default(),
+    };
+    read_nested_with_options(readers, field, leaves, init, page_metas, read_options)
+}
+
+fn read_nested_with_options<R: NativeReadBuf>(
     mut readers: Vec<R>,
     field: Field,
     mut leaves: Vec<ColumnDescriptor>,
     mut init: Vec<InitNested>,
     mut page_metas: Vec<Vec<PageMeta>>,
+    read_options: ReadOptions,
 ) -> Result<Vec<(NestedState, Box<dyn Array>)>> {
     use PhysicalType::*;
 
+    for page_metas in &page_metas {
+        check_plaintext(page_metas)?;
+    }
+    let format_version = read_options.page_format_version()?;
     Ok(match field.data_type().to_physical_type() {
-        Null => unimplemented!(),
+        Null => {
+            return Err(Error::NotYetImplemented(format!(
+                "Reading the nested null column {}",
+                field.name
+            )))
+        }
         Boolean => {
             init.push(InitNested::Primitive(field.is_nullable));
             read_nested_boolean(
@@ -82,8 +174,23 @@ pub fn read_nested<R: NativeReadBuf>(
                 leaves.pop().unwrap(),
                 init,
                 page_metas.pop().unwrap(),
+                format_version,
             )?
         }
+        Primitive(PrimitiveType::Float16) => {
+            init.push(InitNested::Primitive(field.is_nullable));
+            read_nested_integer::<u16, _>(
+                &mut readers.pop().unwrap(),
+                DataType::UInt16,
+                leaves.pop().unwrap(),
+                init,
+                page_metas.pop().unwrap(),
+                format_version,
+            )?
+            .into_iter()
+            .map(|(nested, array)| Ok((nested, bits_to_f16(array, field.data_type().clone())?)))
+            .collect::<Result<Vec<_>>>()?
+        }
         Primitive(primitive) => with_match_integer_double_type!(primitive,
         |$T| {
             init.push(InitNested::Primitive(field.is_nullable));
@@ -93,6 +200,7 @@ pub fn read_nested<R: NativeReadBuf>(
                 leaves.pop().unwrap(),
                 init,
                 page_metas.pop().unwrap(),
+                format_version,
             )?
         },
         |$T| {
@@ -103,6 +211,7 @@ pub fn read_nested<R: NativeReadBuf>(
                 leaves.pop().unwrap(),
                 init,
                 page_metas.pop().unwrap(),
+                format_version,
             )?
         }
         ),
@@ -114,6 +223,7 @@ pub fn read_nested<R: NativeReadBuf>(
                 leaves.pop().unwrap(),
                 init,
                 page_metas.pop().unwrap(),
+                read_options,
             )?
         }
         LargeBinary | LargeUtf8 => {
@@ -124,17 +234,33 @@ pub fn read_nested<R: NativeReadBuf>(
                 leaves.pop().unwrap(),
                 init,
                 page_metas.pop().unwrap(),
+                read_options,
+            )?
+        }
+        FixedSizeBinary => {
+            init.push(InitNested::Primitive(field.is_nullable));
+            read_nested_fixed_binary(
+                &mut readers.pop().unwrap(),
+                field.data_type().clone(),
+                leaves.pop().unwrap(),
+                init,
+                page_metas.pop().unwrap(),
+                format_version,
             )?
         }
-
-        FixedSizeBinary => unimplemented!(),
         _ => match field.data_type().to_logical_type() {
             DataType::List(inner)
             | DataType::LargeList(inner)
             | DataType::FixedSizeList(inner, _) => {
                 init.push(InitNested::List(field.is_nullable));
-                let results =
-                    read_nested(readers, inner.as_ref().clone(), leaves, init, page_metas)?;
+                let results = read_nested_with_options(
+                    readers,
+                    inner.as_ref().clone(),
+                    leaves,
+                    init,
+                    page_metas,
+                    read_options,
+                )?;
                 let mut arrays = Vec::with_capacity(results.len());
                 for (mut nested, values) in results {
                     let array = create_list(field.data_type().clone(), &mut nested, values);
@@ -144,8 +270,14 @@ pub fn read_nested<R: NativeReadBuf>(
             }
             DataType::Map(inner, _) => {
                 init.push(InitNested::List(field.is_nullable));
-                let results =
-                    read_nested(readers, inner.as_ref().clone(), leaves, init, page_metas)?;
+                let results = read_nested_with_options(
+                    readers,
+                    inner.as_ref().clone(),
+                    leaves,
+                    init,
+                    page_metas,
+                    read_options,
+                )?;
                 let mut arrays = Vec::with_capacity(results.len());
                 for (mut nested, values) in results {
                     let array = create_map(field.data_type().clone(), &mut nested, values);
@@ -163,7 +295,14 @@ pub fn read_nested<R: NativeReadBuf>(
                         let readers = readers.drain(..n).collect();
                         let leaves = leaves.drain(..n).collect();
                         let page_metas = page_metas.drain(..n).collect();
-                        read_nested(readers, f.clone(), leaves, init, page_metas)
+                        read_nested_with_options(
+                            readers,
+                            f.clone(),
+                            leaves,
+                            init,
+                            page_metas,
+                            read_options,
+                        )
                     })
                     .collect::<Result<Vec<_>>>()?;
                 let mut arrays = Vec::with_capacity(results[0].len());
@@ -186,24 +325,579 @@ pub fn read_nested<R: NativeReadBuf>(
     })
 }
 
-/// Read all pages of column at once.
-pub fn batch_read_array<R: NativeReadBuf>(
+/// Read all pages of column at once, `readers` seek from the start of the file
+/// like for [`read_simple`].
+pub fn batch_read_array<R: NativeReadBuf + Seek>(
+    mut readers: Vec<R>,
+    leaves: Vec<ColumnDescriptor>,
+    field: Field,
+    is_nested: bool,
+    page_metas: Vec<Vec<PageMeta>>,
+) -> Result<Box<dyn Array>> {
+    let read_options = match readers.first_mut() {
+        Some(reader) => file_read_options(reader)?,
+        None => ReadOptions::default(),
+    };
+    batch_read_array_with_options(readers, leaves, field, is_nested, page_metas, read_options)
+}
+
+/// Read all pages of column at once with `read_options`, see [`ReadOptions::validate`].
+pub fn batch_read_array_with_options<R: NativeReadBuf>(
     mut readers: Vec<R>,
     leaves: Vec<ColumnDescriptor>,
     field: Field,
     is_nested: bool,
     mut page_metas: Vec<Vec<PageMeta>>,
+    read_options: ReadOptions,
 ) -> Result<Box<dyn Array>> {
     if is_nested {
-        let results = read_nested(readers, field, leaves, vec![], page_metas)?;
+        let results =
+            read_nested_with_options(readers, field, leaves, vec![], page_metas, read_options)?;
         let arrays: Vec<&dyn Array> = results.iter().map(|(_, v)| v.as_ref()).collect();
         let array = concatenate(&arrays).unwrap();
         Ok(array)
     } else {
-        read_simple(
+        read_simple_with_options(
             &mut readers.pop().unwrap(),
             field,
             page_metas.pop().unwrap(),
+            read_options,
         )
     }
 }
+
+/// Read all pages of a non-nested column and export the array through the
+/// Arrow C Data Interface, so it can be handed to a non-Rust consumer without copying.
+pub fn read_column_ffi<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    page_metas: Vec<PageMeta>,
+) -> Result<(ArrowArray, ArrowSchema)> {
+    let schema = export_field_to_c(&field);
+    let array = read_simple(reader, field, page_metas)?;
+    Ok((export_array_to_c(array), schema))
+}
+
+/// Read all pages of a `Dict` encoded column, gathering the values of its indices
+/// from `external_values` instead of the dictionaries stored in the pages.
+/// The indices of a page refer to its own dictionary, so the caller must make sure that
+/// every page dictionary is laid out like `external_values`. `reader` seeks from the
+/// start of the file like for [`read_simple`].
+pub fn read_dict_column_with_external_dict<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    page_metas: Vec<PageMeta>,
+    external_values: &dyn Array,
+) -> Result<Box<dyn Array>> {
+    let format_version = file_format_version(reader, &ReadOptions::default())?;
+    let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
+
+    let mut scratch = vec![];
+    let mut buffer = vec![];
+    let mut validity_builder = if has_nulls(field.is_nullable, &page_metas) {
+        Some(MutableBitmap::with_capacity(num_values))
+    } else {
+        None
+    };
+    let mut indices: Vec<u32> = Vec::with_capacity(num_values);
+    for page_meta in page_metas {
+        let length = page_meta.num_values as usize;
+        buffer.resize(page_meta.length as usize, 0);
+        reader.read_exact(&mut buffer)?;
+
+        let mut page = buffer.as_slice();
+        if let Some(ref mut validity_builder) = validity_builder {
+            read_validity(&mut page, length, validity_builder, format_version)?;
+        } else if field.is_nullable {
+            skip_validity(&mut page, format_version)?;
+        }
+        let (codec, compressed_size, _) = read_compress_header(&mut page, format_version)?;
+        let (compression, compressed_encoding) = split_codec(codec)?;
+        if compression != Compression::Dict {
+            return Err(Error::OutOfSpec(format!(
+                "Column {} is not dictionary encoded, found {compression:?} page",
+                field.name
+            )));
+        }
+        // the indices are followed by the page dictionary, which is ignored
+        if compressed_encoding {
+            let encoded = decompress_encoding(&page[..compressed_size])?;
+            decompress_indices(
+                &mut encoded.as_slice(),
+                length,
+                &mut indices,
+                &mut scratch,
+                format_version,
+            )?;
+        } else {
+            decompress_indices(
+                &mut page,
+                length,
+                &mut indices,
+                &mut scratch,
+                format_version,
+            )?;
+        }
+    }
+    let validity =
+        validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
+    let indices = PrimitiveArray::<u32>::try_new(DataType::UInt32, indices.into(), validity)?;
+    take(external_values, &indices)
+}
+
+/// Reads all pages of a non-nested integer, binary or utf8 column as a `DictionaryArray<u32>`
+/// without gathering the values of its `Dict` pages: their indices are the keys and their
+/// dictionaries are concatenated into the values. The values of the pages of other
+/// encodings are appended to the values as they are, each with its own key, so the
+/// values of the array may repeat. `reader` seeks from the start of the file like for
+/// [`read_simple`].
+pub fn read_as_dictionary<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    page_metas: Vec<PageMeta>,
+) -> Result<Box<dyn Array>> {
+    use PhysicalType::*;
+
+    check_plaintext(&page_metas)?;
+    let format_version = file_format_version(reader, &ReadOptions::default())?;
+    let mut scratch = vec![];
+    let (keys, values) = match field.data_type().to_physical_type() {
+        Primitive(
+            PrimitiveType::Float16
+            | PrimitiveType::Float32
+            | PrimitiveType::Float64
+            | PrimitiveType::DaysMs
+            | PrimitiveType::MonthDayNano,
+        ) => return Err(not_dictionary_readable(&field)),
+        Primitive(primitive) => with_match_integer_double_type!(primitive,
+        |$T| {
+            let mut values: Vec<$T> = vec![];
+            let keys = read_keys(reader, &field, page_metas, format_version, |page, length, keys| {
+                match dict_page(page, format_version)? {
+                    Some(encoded) => {
+                        decompress_dict_page(&encoded, length, keys, &mut values, format_version)
+                    }
+                    None => {
+                        let start = values.len();
+                        decompress_integer(
+                            &mut &page[..],
+                            length,
+                            &mut values,
+                            &mut scratch,
+                            format_version,
+                        )?;
+                        keys.extend(start as u32..values.len() as u32);
+                        Ok(())
+                    }
+                }
+            })?;
+            let values = PrimitiveArray::<$T>::from_vec(values).to(field.data_type().clone());
+            (keys, values.boxed())
+        },
+        |$T| {
+            unreachable!()
+        }),
+        Binary => read_binary_keys::<i32, _>(reader, &field, page_metas, format_version)?,
+        LargeBinary => read_binary_keys::<i64, _>(reader, &field, page_metas, format_version)?,
+        Utf8 => read_binary_keys::<i32, _>(reader, &field, page_metas, format_version)?,
+        LargeUtf8 => read_binary_keys::<i64, _>(reader, &field, page_metas, format_version)?,
+        _ => return Err(not_dictionary_readable(&field)),
+    };
+    let data_type = DataType::Dictionary(
+        IntegerType::UInt32,
+        Box::new(field.data_type().clone()),
+        false,
+    );
+    let array = DictionaryArray::<u32>::try_new(data_type, keys, values)?;
+    Ok(Box::new(array))
+}
+
+fn not_dictionary_readable(field: &Field) -> Error {
+    Error::NotYetImplemented(format!(
+        "Reading column {} of type {:?} as a dictionary",
+        field.name,
+        field.data_type()
+    ))
+}
+
+/// Reads the keys of a column read by [`read_as_dictionary`], with `decode_page` appending
+/// the keys of the values buffer of each page and its values to the dictionary.
+fn read_keys<R: NativeReadBuf>(
+    reader: &mut R,
+    field: &Field,
+    page_metas: Vec<PageMeta>,
+    format_version: u16,
+    mut decode_page: impl FnMut(&[u8], usize, &mut Vec<u32>) -> Result<()>,
+) -> Result<PrimitiveArray<u32>> {
+    let num_values = page_metas.iter().map(|p| p.num_values as usize).sum();
+
+    let mut buffer = vec![];
+    let mut validity_builder = if has_nulls(field.is_nullable, &page_metas) {
+        Some(MutableBitmap::with_capacity(num_values))
+    } else {
+        None
+    };
+    let mut keys: Vec<u32> = Vec::with_capacity(num_values);
+    for page_meta in page_metas {
+        let length = page_meta.num_values as usize;
+        buffer.resize(page_meta.length as usize, 0);
+        reader.read_exact(&mut buffer)?;
+
+        let mut page = buffer.as_slice();
+        if let Some(ref mut validity_builder) = validity_builder {
+            read_validity(&mut page, length, validity_builder, format_version)?;
+        } else if field.is_nullable {
+            skip_validity(&mut page, format_version)?;
+        }
+        decode_page(page, length, &mut keys)?;
+    }
+    let validity =
+        validity_builder.map(|mut validity_builder| std::mem::take(&mut validity_builder).into());
+    PrimitiveArray::<u32>::try_new(DataType::UInt32, keys.into(), validity)
+}
+
+/// The encoded indices and dictionary of the values buffer `page` if it is a `Dict` page.
+fn dict_page(mut page: &[u8], format_version: u16) -> Result<Option<Cow<'_, [u8]>>> {
+    let (codec, compressed_size, _) = read_compress_header(&mut page, format_version)?;
+    let (compression, compressed_encoding) = split_codec(codec)?;
+    if compression != Compression::Dict {
+        return Ok(None);
+    }
+    let encoded = &page[..compressed_size];
+    Ok(Some(if compressed_encoding {
+        Cow::Owned(decompress_encoding(encoded)?)
+    } else {
+        Cow::Borrowed(encoded)
+    }))
+}
+
+fn read_binary_keys<O: Offset, R: NativeReadBuf>(
+    reader: &mut R,
+    field: &Field,
+    page_metas: Vec<PageMeta>,
+    format_version: u16,
+) -> Result<(PrimitiveArray<u32>, Box<dyn Array>)> {
+    let mut scratch = vec![];
+    let mut offsets: Vec<O> = vec![O::default()];
+    let mut values: Vec<u8> = vec![];
+    let keys = read_keys(
+        reader,
+        field,
+        page_metas,
+        format_version,
+        |page, length, keys| match dict_page(page, format_version)? {
+            Some(encoded) => binary::decompress_dict_page(
+                &encoded,
+                length,
+                keys,
+                &mut offsets,
+                &mut values,
+                format_version,
+            ),
+            None => {
+                let start = offsets.len() - 1;
+                decompress_binary(
+                    &mut &page[..],
+                    length,
+                    &mut offsets,
+                    &mut values,
+                    &mut scratch,
+                    format_version,
+                )?;
+                keys.extend(start as u32..(offsets.len() - 1) as u32);
+                Ok(())
+            }
+        },
+    )?;
+    let offsets = OffsetsBuffer::try_from(offsets)?;
+    let values: Box<dyn Array> = match field.data_type().to_physical_type() {
+        PhysicalType::Utf8 | PhysicalType::LargeUtf8 => {
+            Utf8Array::<O>::try_new(field.data_type().clone(), offsets, values.into(), None)?
+                .boxed()
+        }
+        _ => BinaryArray::<O>::try_new(field.data_type().clone(), offsets, values.into(), None)?
+            .boxed(),
+    };
+    Ok((keys, values))
+}
+
+/// Reads a column written from a `DictionaryArray`, the keys from the pages of `meta`
+/// and the values from `meta.dictionary`. The reader must be positioned at the start of
+/// the column, like for [`read_simple`].
+pub fn read_dictionary_column<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    meta: &ColumnMeta,
+) -> Result<Box<dyn Array>> {
+    let (key_type, value_type) = match field.data_type().to_logical_type() {
+        DataType::Dictionary(key_type, value_type, _) => (*key_type, value_type.as_ref().clone()),
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Column {} is not a dictionary column, found {other:?}",
+                field.name
+            )))
+        }
+    };
+    let values_meta = meta.dictionary.as_ref().ok_or_else(|| {
+        Error::OutOfSpec(format!("Column {} has no dictionary values", field.name))
+    })?;
+    let read_options = file_read_options(reader)?;
+
+    let (keys, page_metas) = read_column_pages(reader, meta)?;
+    let position = meta.offset + meta.total_len();
+    reader.seek(SeekFrom::Current(
+        values_meta.offset as i64 - position as i64,
+    ))?;
+    let values_field = Field::new(field.name.clone(), value_type, field.is_nullable);
+    let values = read_simple_with_options(
+        reader,
+        values_field,
+        values_meta.pages.clone(),
+        read_options,
+    )?;
+
+    with_match_dictionary_key_type!(key_type, |$T| {
+        let keys = read_integer::<$T, _>(
+            &mut keys.as_slice(),
+            field.is_nullable,
+            <$T as arrow::types::NativeType>::PRIMITIVE.into(),
+            page_metas,
+            read_options.page_format_version()?,
+        )?;
+        let keys: &PrimitiveArray<$T> = keys.as_any().downcast_ref().unwrap();
+        let array = DictionaryArray::<$T>::try_new(field.data_type().clone(), keys.clone(), values)?;
+        Ok(Box::new(array) as Box<dyn Array>)
+    })
+}
+
+/// Reads the number of values and the validity of the non-nested column `meta`
+/// without decoding its values, for the queries that only count the values or the nulls.
+/// The values of a page are skipped with its length, the validity is `None` if the
+/// column has no nulls. `reader` must be at the start of the column and is left at its end.
+pub fn read_validity_only<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: &Field,
+    meta: &ColumnMeta,
+) -> Result<(u64, Option<Bitmap>)> {
+    if !is_primitive(field.data_type()) {
+        return Err(Error::NotYetImplemented(format!(
+            "Reading the validity of nested column {}",
+            field.name
+        )));
+    }
+    let num_values = meta.pages.iter().map(|p| p.num_values).sum::<u64>();
+    if field.data_type().to_physical_type() == PhysicalType::Null {
+        reader.seek(SeekFrom::Current(meta.total_len() as i64))?;
+        return Ok((num_values, Some(Bitmap::new_zeroed(num_values as usize))));
+    }
+    if !has_nulls(field.is_nullable, &meta.pages) {
+        reader.seek(SeekFrom::Current(meta.total_len() as i64))?;
+        return Ok((num_values, None));
+    }
+
+    check_unencrypted(&meta.pages)?;
+    let format_version = file_format_version(reader, &ReadOptions::default())?;
+    let mut validity_builder = MutableBitmap::with_capacity(num_values as usize);
+    if meta.zstd_dictionary.is_some() {
+        // the pages are compressed as a whole
+        let (pages, page_metas) = read_column_pages(reader, meta)?;
+        read_pages_validity(
+            &mut std::io::Cursor::new(pages),
+            &page_metas,
+            &mut validity_builder,
+            format_version,
+        )?;
+    } else {
+        read_pages_validity(reader, &meta.pages, &mut validity_builder, format_version)?;
+    }
+    Ok((num_values, Some(validity_builder.into())))
+}
+
+fn read_pages_validity<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    page_metas: &[PageMeta],
+    validity_builder: &mut MutableBitmap,
+    format_version: u16,
+) -> Result<()> {
+    for page_meta in page_metas {
+        let mut page = Read::take(&mut *reader, page_meta.length);
+        read_validity(
+            &mut page,
+            page_meta.num_values as usize,
+            validity_builder,
+            format_version,
+        )?;
+        let values_len = page.limit();
+        reader.seek(SeekFrom::Current(values_len as i64))?;
+    }
+    Ok(())
+}
+
+/// Reads the non-nested column `meta` like [`read_simple`], decoding its pages in
+/// parallel on the rayon thread pool, then concatenating them. Every page is read by
+/// its own clone of `reader`, seeked to the [`ColumnMeta::page_ranges`] of the page,
+/// so `reader` is the start of the file rather than of the column.
+/// The array is the same as the one read serially.
+#[cfg(feature = "rayon")]
+pub fn read_simple_parallel<R: NativeReadBuf + Seek + Clone + Sync>(
+    reader: &R,
+    field: Field,
+    meta: &ColumnMeta,
+    read_options: ReadOptions,
+) -> Result<Box<dyn Array>> {
+    use rayon::prelude::*;
+
+    check_unencrypted(&meta.pages)?;
+    if meta.pages.is_empty() {
+        return Ok(new_empty_array(field.data_type().clone()));
+    }
+    let read_options = ReadOptions {
+        format_version: Some(file_format_version(&mut reader.clone(), &read_options)?),
+        ..read_options
+    };
+    let zstd_dictionary = meta
+        .zstd_dictionary
+        .as_deref()
+        .map(crate::compression::ZstdDecoderDictionary::new);
+    let arrays = meta
+        .page_ranges()
+        .into_par_iter()
+        .zip(meta.pages.par_iter())
+        .map(|(range, page_meta)| {
+            let mut reader = reader.clone();
+            reader.seek(SeekFrom::Start(range.start))?;
+            let mut page = vec![0u8; page_meta.length as usize];
+            reader.read_exact(&mut page)?;
+            let mut page_meta = page_meta.clone();
+            if let Some(dictionary) = &zstd_dictionary {
+                page = super::reader::decompress_page(
+                    &page,
+                    dictionary,
+                    read_options.max_decode_bytes,
+                )?;
+                // the checksum is of the compressed page
+                page_meta.length = page.len() as u64;
+                page_meta.checksum = None;
+                page_meta.zstd_dictionary = false;
+            }
+            read_simple_with_options(
+                &mut page.as_slice(),
+                field.clone(),
+                vec![page_meta],
+                read_options,
+            )
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+    concatenate(&arrays)
+}
+
+/// Reads the rows `[start, end)` of the non-nested column `meta`, decoding only the
+/// pages overlapping them, see [`ColumnMeta::pages_for_row_range`].
+/// `reader` must be at the start of the column, `end` is clamped to the number of rows.
+pub fn read_row_range<R: NativeReadBuf + Seek>(
+    reader: &mut R,
+    field: Field,
+    meta: &ColumnMeta,
+    start: u64,
+    end: u64,
+) -> Result<Box<dyn Array>> {
+    let (_, range_meta, row_offset) = meta.pages_for_row_range(start, end);
+    if range_meta.pages.is_empty() {
+        return Ok(new_empty_array(field.data_type().clone()));
+    }
+    let read_options = file_read_options(reader)?;
+    reader.seek(SeekFrom::Current(
+        range_meta.offset as i64 - meta.offset as i64,
+    ))?;
+    let (pages, page_metas) = read_column_pages(reader, &range_meta)?;
+    let num_rows = page_metas.iter().map(|page| page.num_values).sum::<u64>();
+    let length = end.min(start + num_rows - row_offset) - start;
+
+    let array = read_simple_with_options(&mut pages.as_slice(), field, page_metas, read_options)?;
+    Ok(array.sliced(row_offset as usize, length as usize))
+}
+
+/// Reads the value at `index` of a binary page of `num_values` values written with
+/// `WriteOptions::binary_restart_points`, decoding only the lengths of the block of
+/// 128 values holding it. Returns `None` if the value is null.
+/// `format_version` is the [`super::reader::read_format_version`] of the file of the page.
+pub fn read_value_at(
+    mut page: &[u8],
+    is_nullable: bool,
+    num_values: usize,
+    index: usize,
+    format_version: u16,
+) -> Result<Option<&[u8]>> {
+    if is_nullable {
+        let mut validity = MutableBitmap::with_capacity(num_values);
+        read_validity(&mut page, num_values, &mut validity, format_version)?;
+        if index < validity.len() && !validity.get(index) {
+            return Ok(None);
+        }
+    }
+    let (codec, compressed_size, _) = read_compress_header(&mut page, format_version)?;
+    let compression = Compression::from_codec(codec)?;
+    if compression != Compression::RestartPoints {
+        return Err(Error::OutOfSpec(format!(
+            "Page is not written with restart points, found {compression:?} page"
+        )));
+    }
+    let input = page
+        .get(..compressed_size)
+        .ok_or_else(|| Error::OutOfSpec("Unexpected end of the page".to_string()))?;
+    value_at(input, num_values, index).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, Cursor};
+
+    use arrow::array::{Array, BinaryArray, BooleanArray, Int64Array};
+    use arrow::chunk::Chunk;
+    use arrow::datatypes::{Field, Schema};
+
+    use super::read_simple;
+    use crate::read::read_basic::VALIDITY_DECODES;
+    use crate::write::{NativeWriter, WriteOptions};
+
+    #[test]
+    fn test_skip_validity_without_nulls() {
+        let arrays: Vec<Box<dyn Array>> = vec![
+            Box::new(Int64Array::from_iter((0..1000).map(Some))),
+            Box::new(BinaryArray::<i32>::from_iter(
+                (0..1000).map(|i| Some(format!("v{i}"))),
+            )),
+            Box::new(BooleanArray::from_iter((0..1000).map(|i| Some(i % 3 == 0)))),
+        ];
+        let fields: Vec<Field> = arrays
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect();
+        let mut bytes = vec![];
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            Schema::from(fields.clone()),
+            WriteOptions {
+                max_page_size: Some(300),
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&Chunk::new(arrays.clone())).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas;
+
+        VALIDITY_DECODES.with(|decodes| decodes.set(0));
+        for ((field, meta), expected) in fields.into_iter().zip(metas).zip(arrays) {
+            assert!(meta.pages.iter().all(|page| page.null_count == Some(0)));
+            let mut reader = Cursor::new(bytes.as_slice());
+            reader.consume(meta.offset as usize);
+            let array = read_simple(&mut reader, field, meta.pages).unwrap();
+            assert!(array.validity().is_none());
+            assert_eq!(array.as_ref(), expected.as_ref());
+        }
+        assert_eq!(VALIDITY_DECODES.with(|decodes| decodes.get()), 0);
+    }
+}
@@ -0,0 +1,215 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::sync::Arc;
+
+use arrow::array::Array;
+use arrow::chunk::Chunk;
+use arrow::compute::concatenate::concatenate;
+use arrow::datatypes::Schema;
+use arrow::error::{Error, Result};
+
+use super::deserialize::{column_iter_to_arrays, ArrayIter, DynIter};
+use super::reader::{
+    is_primitive, project_columns, read_footer_row_groups, read_format_version,
+    shared_native_readers,
+};
+use super::ReadOptions;
+
+/// Reads the columns of a strawboat file as [`Chunk`]s of `batch_size` rows.
+///
+/// Each column is deserialized by its own [`ArrayIter`], one array per page, and
+/// the arrays are sliced and concatenated into batches, so the page boundaries
+/// of the columns need not align. The last batch holds the remaining rows.
+pub struct ChunkReader<'a> {
+    columns: Vec<ColumnBuffer<'a>>,
+    batch_size: usize,
+    finished: bool,
+}
+
+/// The arrays read from a column and not yet yielded.
+struct ColumnBuffer<'a> {
+    iter: ArrayIter<'a>,
+    arrays: VecDeque<Box<dyn Array>>,
+    len: usize,
+}
+
+impl<'a> ColumnBuffer<'a> {
+    /// Reads arrays until `len` rows are buffered or the column ends.
+    fn fill(&mut self, len: usize) -> Result<()> {
+        while self.len < len {
+            match self.iter.next() {
+                Some(array) => {
+                    let array = array?;
+                    self.len += array.len();
+                    self.arrays.push_back(array);
+                }
+                None => break,
+            }
+        }
+        Ok(())
+    }
+
+    /// Takes the first `len` buffered rows, `len` being at most the buffered rows.
+    fn take(&mut self, len: usize) -> Result<Box<dyn Array>> {
+        let mut arrays = vec![];
+        let mut remaining = len;
+        while remaining > 0 {
+            let array = self.arrays.pop_front().unwrap();
+            if array.len() > remaining {
+                self.arrays
+                    .push_front(array.sliced(remaining, array.len() - remaining));
+                arrays.push(array.sliced(0, remaining));
+                remaining = 0;
+            } else {
+                remaining -= array.len();
+                arrays.push(array);
+            }
+        }
+        self.len -= len;
+
+        if arrays.len() == 1 {
+            return Ok(arrays.pop().unwrap());
+        }
+        let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+        concatenate(&arrays)
+    }
+}
+
+impl<'a> ChunkReader<'a> {
+    /// Creates a [`ChunkReader`] over the arrays of the columns, e.g. created by
+    /// [`column_iter_to_arrays`].
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    pub fn new(iters: Vec<ArrayIter<'a>>, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "The batch size must be positive");
+        let columns = iters
+            .into_iter()
+            .map(|iter| ColumnBuffer {
+                iter,
+                arrays: VecDeque::new(),
+                len: 0,
+            })
+            .collect();
+        Self {
+            columns,
+            batch_size,
+            finished: false,
+        }
+    }
+
+    fn read_batch(&mut self) -> Result<Option<Chunk<Box<dyn Array>>>> {
+        for column in self.columns.iter_mut() {
+            column.fill(self.batch_size)?;
+        }
+        let len = match self.columns.iter().map(|column| column.len).min() {
+            Some(len) => len.min(self.batch_size),
+            None => return Ok(None),
+        };
+        if len == 0 {
+            if let Some(column) = self.columns.iter().find(|column| column.len > 0) {
+                return Err(Error::OutOfSpec(format!(
+                    "The columns have 0 and {} rows left",
+                    column.len
+                )));
+            }
+            return Ok(None);
+        }
+
+        let arrays = self
+            .columns
+            .iter_mut()
+            .map(|column| column.take(len))
+            .collect::<Result<Vec<_>>>()?;
+        Chunk::try_new(arrays).map(Some)
+    }
+}
+
+impl ChunkReader<'static> {
+    /// Creates a [`ChunkReader`] over the top level fields at `projection`, or every
+    /// field if `projection` is `None`, of a file held in memory. Returns the schema
+    /// of the projected fields along with the reader. The row groups of the file are
+    /// read one after the other.
+    pub fn try_from_shared(
+        bytes: Arc<[u8]>,
+        projection: Option<&[usize]>,
+        batch_size: usize,
+    ) -> Result<(Schema, Self)> {
+        let format_version = read_format_version(&mut Cursor::new(bytes.clone()))?;
+        let read_options = ReadOptions {
+            format_version: Some(format_version),
+            ..Default::default()
+        };
+        let (file_schema, row_groups) = read_footer_row_groups(&mut Cursor::new(bytes.clone()))?;
+        let projection = match projection {
+            Some(projection) => projection.to_vec(),
+            None => (0..file_schema.fields.len()).collect(),
+        };
+
+        let mut iters: Vec<Option<ArrayIter<'static>>> = vec![];
+        iters.resize_with(projection.len(), || None);
+        for row_group in row_groups.iter() {
+            let columns = project_columns(&file_schema, &row_group.columns, &projection)?;
+            for (column, iter) in columns.into_iter().zip(iters.iter_mut()) {
+                let readers = shared_native_readers(bytes.clone(), &column.metas)
+                    .into_iter()
+                    .map(|reader| reader.with_read_options(read_options))
+                    .collect();
+                let is_nested = !is_primitive(column.field.data_type());
+                let array_iter =
+                    column_iter_to_arrays(readers, column.leaves, column.field, is_nested)?;
+                *iter = Some(match iter.take() {
+                    Some(previous) => DynIter::new(previous.chain(array_iter)),
+                    None => array_iter,
+                });
+            }
+        }
+        let iters = iters
+            .into_iter()
+            .map(|iter| iter.unwrap_or_else(|| DynIter::new(std::iter::empty())))
+            .collect();
+
+        let fields = projection
+            .iter()
+            .map(|index| file_schema.fields[*index].clone())
+            .collect();
+        let schema = Schema {
+            fields,
+            metadata: file_schema.metadata,
+        };
+        Ok((schema, Self::new(iters, batch_size)))
+    }
+}
+
+impl<'a> Iterator for ChunkReader<'a> {
+    type Item = Result<Chunk<Box<dyn Array>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let chunk = self.read_batch().transpose();
+        // a failed batch ends the iteration
+        if !matches!(chunk, Some(Ok(_))) {
+            self.finished = true;
+        }
+        chunk
+    }
+}
@@ -16,10 +16,11 @@
 // under the License.
 
 use super::{array::*, PageIterator};
+use crate::util::bits_to_f16;
 use crate::with_match_integer_double_type;
 use arrow::array::*;
-use arrow::datatypes::{DataType, Field, PhysicalType};
-use arrow::error::Result;
+use arrow::datatypes::{DataType, Field, PhysicalType, PrimitiveType};
+use arrow::error::{Error, Result};
 use arrow::io::parquet::read::{n_columns, InitNested, NestedState};
 use parquet2::metadata::ColumnDescriptor;
 
@@ -112,6 +113,10 @@ where
     Ok(match data_type.to_physical_type() {
         Null => DynIter::new(NullIter::new(reader, data_type)),
         Boolean => DynIter::new(BooleanIter::new(reader, is_nullable, data_type)),
+        Primitive(PrimitiveType::Float16) => DynIter::new(
+            IntegerIter::<_, u16>::new(reader, is_nullable, DataType::UInt16)
+                .map(move |array| array.and_then(|array| bits_to_f16(array, data_type.clone()))),
+        ),
         Primitive(primitive) => with_match_integer_double_type!(primitive,
         |$I| {
             DynIter::new(IntegerIter::<_, $I>::new(
@@ -132,7 +137,13 @@ where
         LargeBinary | LargeUtf8 => {
             DynIter::new(BinaryIter::<_, i64>::new(reader, is_nullable, data_type))
         }
-        FixedSizeBinary => unimplemented!(),
+        FixedSizeBinary => DynIter::new(FixedSizeBinaryIter::new(reader, is_nullable, data_type)),
+        Dictionary(_) => {
+            return Err(Error::NotYetImplemented(format!(
+                "Dictionary column {} must be read with read_dictionary_column",
+                field.name
+            )))
+        }
         _ => unreachable!(),
     })
 }
@@ -149,7 +160,12 @@ where
     use PhysicalType::*;
 
     Ok(match field.data_type().to_physical_type() {
-        Null => unimplemented!(),
+        Null => {
+            return Err(Error::NotYetImplemented(format!(
+                "Reading the nested null column {}",
+                field.name
+            )))
+        }
         Boolean => {
             init.push(InitNested::Primitive(field.is_nullable));
             DynIter::new(BooleanNestedIter::new(
@@ -159,6 +175,22 @@ where
                 init,
             ))
         }
+        Primitive(PrimitiveType::Float16) => {
+            init.push(InitNested::Primitive(field.is_nullable));
+            let data_type = field.data_type().clone();
+            DynIter::new(
+                IntegerNestedIter::<_, u16>::new(
+                    readers.pop().unwrap(),
+                    DataType::UInt16,
+                    leaves.pop().unwrap(),
+                    init,
+                )
+                .map(move |result| {
+                    let (nested, array) = result?;
+                    Ok((nested, bits_to_f16(array, data_type.clone())?))
+                }),
+            )
+        }
         Primitive(primitive) => with_match_integer_double_type!(primitive,
         |$I| {
             init.push(InitNested::Primitive(field.is_nullable));
@@ -197,8 +229,15 @@ where
                 init,
             ))
         }
-
-        FixedSizeBinary => unimplemented!(),
+        FixedSizeBinary => {
+            init.push(InitNested::Primitive(field.is_nullable));
+            DynIter::new(FixedSizeBinaryNestedIter::new(
+                readers.pop().unwrap(),
+                field.data_type().clone(),
+                leaves.pop().unwrap(),
+                init,
+            ))
+        }
         _ => match field.data_type().to_logical_type() {
             DataType::List(inner)
             | DataType::LargeList(inner)
@@ -234,6 +273,8 @@ where
 }
 
 /// An iterator adapter that maps [`PageIterator`]s into an iterator of [`Array`]s.
+/// The pages are read with the [`ReadOptions::format_version`](super::ReadOptions)
+/// of the readers.
 pub fn column_iter_to_arrays<'a, I: 'a>(
     mut readers: Vec<I>,
     leaves: Vec<ColumnDescriptor>,
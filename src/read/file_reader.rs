@@ -0,0 +1,202 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::{Cursor, Read, Seek, SeekFrom};
+
+use arrow::array::Array;
+use arrow::chunk::Chunk;
+use arrow::datatypes::Schema;
+use arrow::error::{Error, Result};
+
+use super::deserialize::column_iter_to_arrays;
+use super::reader::{
+    is_primitive, project_columns, read_footer_row_groups, read_format_version, NativeReader,
+    ProjectedColumn,
+};
+use super::ReadOptions;
+
+/// Reads a strawboat file as [`Chunk`]s, one per page of its columns.
+///
+/// The footer is read once by [`FileReader::try_new`], then every call to `next`
+/// seeks to the next page of each projected leaf column, so a single `reader`
+/// serves all the columns. The row groups of the file are read one after the other.
+pub struct FileReader<R: Read + Seek> {
+    reader: R,
+    schema: Schema,
+    row_groups: Vec<RowGroupColumns>,
+    current_row_group: usize,
+    current_page: usize,
+    read_options: ReadOptions,
+}
+
+/// The projected columns of a row group.
+struct RowGroupColumns {
+    columns: Vec<ProjectedColumn>,
+    /// The offset of the next page of each leaf column, by projected column.
+    offsets: Vec<Vec<u64>>,
+    num_pages: usize,
+}
+
+impl RowGroupColumns {
+    fn try_new(columns: Vec<ProjectedColumn>) -> Result<Self> {
+        let num_pages = columns
+            .iter()
+            .flat_map(|column| column.metas.iter())
+            .map(|meta| meta.pages.len())
+            .next()
+            .unwrap_or(0);
+        let offsets = columns
+            .iter()
+            .map(|column| {
+                column
+                    .metas
+                    .iter()
+                    .map(|meta| {
+                        if meta.pages.len() != num_pages {
+                            return Err(Error::OutOfSpec(format!(
+                                "The columns have {} and {} pages",
+                                num_pages,
+                                meta.pages.len()
+                            )));
+                        }
+                        Ok(meta.offset)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self {
+            columns,
+            offsets,
+            num_pages,
+        })
+    }
+}
+
+impl<R: Read + Seek> FileReader<R> {
+    /// Creates a new [`FileReader`] reading the top level fields at `projection`,
+    /// or every field if `projection` is `None`.
+    pub fn try_new(mut reader: R, projection: Option<&[usize]>) -> Result<Self> {
+        let format_version = read_format_version(&mut reader)?;
+        let (file_schema, row_groups) = read_footer_row_groups(&mut reader)?;
+        let projection = match projection {
+            Some(projection) => projection.to_vec(),
+            None => (0..file_schema.fields.len()).collect(),
+        };
+        let row_groups = row_groups
+            .iter()
+            .map(|row_group| {
+                let columns = project_columns(&file_schema, &row_group.columns, &projection)?;
+                RowGroupColumns::try_new(columns)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let fields = projection
+            .iter()
+            .map(|index| file_schema.fields[*index].clone())
+            .collect();
+        let schema = Schema {
+            fields,
+            metadata: file_schema.metadata,
+        };
+        Ok(Self {
+            reader,
+            schema,
+            row_groups,
+            current_row_group: 0,
+            current_page: 0,
+            read_options: ReadOptions {
+                format_version: Some(format_version),
+                ..Default::default()
+            },
+        })
+    }
+
+    /// Deserializes the pages with `read_options`, see [`ReadOptions::validate`].
+    /// The format version is kept to the one read from the file.
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        self.read_options = ReadOptions {
+            format_version: self.read_options.format_version,
+            ..read_options
+        };
+        self
+    }
+
+    /// The schema of the projected fields.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Consumes itself into the inner reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read_page(&mut self) -> Result<Chunk<Box<dyn Array>>> {
+        let page = self.current_page;
+        let row_group = &mut self.row_groups[self.current_row_group];
+        let mut arrays = Vec::with_capacity(row_group.columns.len());
+        for (column, offsets) in row_group.columns.iter().zip(row_group.offsets.iter_mut()) {
+            let mut readers = Vec::with_capacity(column.metas.len());
+            for (meta, offset) in column.metas.iter().zip(offsets.iter_mut()) {
+                let page_meta = &meta.pages[page];
+                let mut bytes = vec![0u8; page_meta.length as usize];
+                self.reader.seek(SeekFrom::Start(*offset))?;
+                self.reader.read_exact(&mut bytes)?;
+                *offset += page_meta.length;
+
+                let reader = NativeReader::new(Cursor::new(bytes), vec![page_meta.clone()], vec![])
+                    .with_zstd_dictionary(meta.zstd_dictionary.clone())
+                    .with_read_options(self.read_options);
+                readers.push(reader);
+            }
+            let is_nested = !is_primitive(column.field.data_type());
+            let mut array_iter = column_iter_to_arrays(
+                readers,
+                column.leaves.clone(),
+                column.field.clone(),
+                is_nested,
+            )?;
+            let array = array_iter.next().ok_or_else(|| {
+                Error::OutOfSpec(format!("Missing page {} of {}", page, column.field.name))
+            })??;
+            arrays.push(array);
+        }
+        Chunk::try_new(arrays)
+    }
+}
+
+impl<R: Read + Seek> Iterator for FileReader<R> {
+    type Item = Result<Chunk<Box<dyn Array>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let row_group = self.row_groups.get(self.current_row_group)?;
+            if self.current_page < row_group.num_pages {
+                break;
+            }
+            self.current_row_group += 1;
+            self.current_page = 0;
+        }
+        let chunk = self.read_page();
+        // a failed page ends the iteration
+        match chunk {
+            Ok(_) => self.current_page += 1,
+            Err(_) => self.current_row_group = self.row_groups.len(),
+        };
+        Some(chunk)
+    }
+}
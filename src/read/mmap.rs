@@ -0,0 +1,272 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::{BufRead, Read, Seek, SeekFrom};
+use std::sync::Arc;
+
+use arrow::array::PrimitiveArray;
+use arrow::buffer::Buffer;
+use arrow::datatypes::DataType;
+use arrow::error::{Error, Result};
+use arrow::types::NativeType;
+use memmap2::Mmap;
+
+use super::{split_borrowed, NativeReadBuf};
+use crate::compression::Compression;
+use crate::COMPRESS_HEADER_SIZE;
+
+/// A reader over a memory mapped file.
+///
+/// The values of the primitive pages that are not compressed (`Compression::None`)
+/// are read as buffers over the mapped memory instead of being copied.
+#[derive(Debug, Clone)]
+pub struct MmapReader {
+    mmap: Arc<Mmap>,
+    pos: usize,
+}
+
+impl MmapReader {
+    pub fn new(mmap: Arc<Mmap>) -> Self {
+        Self { mmap, pos: 0 }
+    }
+
+    /// Maps the whole file into memory.
+    ///
+    /// # Safety
+    /// The file must not be modified while it is mapped, see [`Mmap::map`].
+    pub unsafe fn try_new(file: &std::fs::File) -> Result<Self> {
+        Ok(Self::new(Arc::new(Mmap::map(file)?)))
+    }
+
+    fn remaining(&self) -> &[u8] {
+        &self.mmap[self.pos.min(self.mmap.len())..]
+    }
+}
+
+impl Read for MmapReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.remaining().read(buf)?;
+        self.pos += size;
+        Ok(size)
+    }
+}
+
+impl BufRead for MmapReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt;
+    }
+}
+
+impl Seek for MmapReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => (self.mmap.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => (self.pos as u64).checked_add_signed(offset),
+        };
+        match pos {
+            Some(pos) => {
+                self.pos = pos as usize;
+                Ok(pos)
+            }
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+impl NativeReadBuf for MmapReader {
+    fn buffer_bytes(&self) -> &[u8] {
+        self.remaining()
+    }
+
+    fn mmap(&self) -> Option<(&Arc<Mmap>, usize)> {
+        Some((&self.mmap, self.pos))
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.remaining().len())
+    }
+
+    fn in_memory(&self) -> bool {
+        true
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        let start = self.pos.min(self.mmap.len());
+        split_borrowed(self.remaining(), len)?;
+        self.pos = start + len;
+        Ok(&self.mmap[start..start + len])
+    }
+}
+
+/// Reads the values of a page as a buffer over the memory map of the reader
+/// if the page is not compressed, returns `None` and reads nothing otherwise.
+/// `format_version` is the version of the file, see [`super::ReadOptions::format_version`].
+pub(crate) fn read_mapped_values<T: NativeType, R: NativeReadBuf>(
+    reader: &mut R,
+    length: usize,
+    format_version: u16,
+) -> Result<Option<Buffer<T>>> {
+    let (mmap, pos) = match reader.mmap() {
+        // the smaller headers of the buffers of the files of version 0 are read by copying
+        Some((mmap, pos)) if format_version > 0 => (mmap.clone(), pos),
+        _ => return Ok(None),
+    };
+    let header = match mmap.get(pos..pos + COMPRESS_HEADER_SIZE) {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let compressed_size = u64::from_le_bytes(header[1..9].try_into().unwrap()) as usize;
+    let uncompressed_size = u64::from_le_bytes(header[9..17].try_into().unwrap()) as usize;
+    let size = length * std::mem::size_of::<T>();
+    let start = pos + COMPRESS_HEADER_SIZE;
+    if header[0] != u8::from(Compression::None)
+        || compressed_size != size
+        || uncompressed_size != size
+        || start + size > mmap.len()
+        || reader
+            .remaining_len()
+            .map_or(false, |len| COMPRESS_HEADER_SIZE + size > len)
+    {
+        return Ok(None);
+    }
+    reader.consume(COMPRESS_HEADER_SIZE + size);
+    // Safety: the region is within the map, which is kept alive by the buffer
+    unsafe { mapped_buffer(mmap, start, length) }.map(Some)
+}
+
+/// Layout of the Arrow C data interface, the same as `arrow::ffi::ArrowArray`
+/// whose fields can't be set outside of arrow.
+#[repr(C)]
+struct ForeignArray {
+    length: i64,
+    null_count: i64,
+    offset: i64,
+    n_buffers: i64,
+    n_children: i64,
+    buffers: *mut *const std::os::raw::c_void,
+    children: *mut *mut ForeignArray,
+    dictionary: *mut ForeignArray,
+    release: Option<unsafe extern "C" fn(array: *mut ForeignArray)>,
+    private_data: *mut std::os::raw::c_void,
+}
+
+/// Owns the map and the buffer pointers of a [`ForeignArray`].
+struct PrivateData {
+    _mmap: Arc<Mmap>,
+    buffers: Box<[*const std::os::raw::c_void; 2]>,
+}
+
+unsafe extern "C" fn release(array: *mut ForeignArray) {
+    if array.is_null() {
+        return;
+    }
+    let array = &mut *array;
+    drop(Box::from_raw(array.private_data as *mut PrivateData));
+    array.release = None;
+}
+
+/// Creates a buffer of `length` values starting at `start` of the map, which is
+/// kept alive as long as the buffer. The values are copied if `start` is not
+/// aligned to `T`.
+///
+/// # Safety
+/// `start..start + length * size_of::<T>()` must be within the map.
+unsafe fn mapped_buffer<T: NativeType>(
+    mmap: Arc<Mmap>,
+    start: usize,
+    length: usize,
+) -> Result<Buffer<T>> {
+    let values = mmap.as_ptr().add(start) as *const std::os::raw::c_void;
+    let mut private_data = Box::new(PrivateData {
+        _mmap: mmap,
+        buffers: Box::new([std::ptr::null(), values]),
+    });
+    let array = ForeignArray {
+        length: length as i64,
+        null_count: 0,
+        offset: 0,
+        n_buffers: 2,
+        n_children: 0,
+        buffers: private_data.buffers.as_mut_ptr(),
+        children: std::ptr::null_mut(),
+        dictionary: std::ptr::null_mut(),
+        release: Some(release),
+        private_data: Box::into_raw(private_data) as *mut std::os::raw::c_void,
+    };
+    let array = std::mem::transmute::<ForeignArray, arrow::ffi::ArrowArray>(array);
+    let array = arrow::ffi::import_array_from_c(array, DataType::from(T::PRIMITIVE))?;
+    array
+        .as_any()
+        .downcast_ref::<PrimitiveArray<T>>()
+        .map(|array| array.values().clone())
+        .ok_or_else(|| Error::OutOfSpec("Unexpected type of a mapped array".to_string()))
+}
+
+/// Collects the values of the pages of a column, keeping the buffers
+/// of mapped pages so that a column of one such page is not copied.
+pub(crate) struct PageValues<T: NativeType> {
+    buffers: Vec<Buffer<T>>,
+    values: Vec<T>,
+    capacity: usize,
+}
+
+impl<T: NativeType> PageValues<T> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            buffers: vec![],
+            values: Vec::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The values of the pages that are decompressed.
+    pub fn values_mut(&mut self) -> &mut Vec<T> {
+        &mut self.values
+    }
+
+    pub fn push_buffer(&mut self, buffer: Buffer<T>) {
+        if !self.values.is_empty() {
+            self.buffers.push(std::mem::take(&mut self.values).into());
+        }
+        self.buffers.push(buffer);
+    }
+
+    pub fn finish(mut self) -> Buffer<T> {
+        if self.buffers.is_empty() {
+            return self.values.into();
+        }
+        if !self.values.is_empty() {
+            self.buffers.push(std::mem::take(&mut self.values).into());
+        }
+        if self.buffers.len() == 1 {
+            return self.buffers.pop().unwrap();
+        }
+        let mut values = Vec::with_capacity(self.capacity);
+        for buffer in &self.buffers {
+            values.extend_from_slice(buffer.as_slice());
+        }
+        values.into()
+    }
+}
@@ -17,14 +17,64 @@
 
 mod array;
 pub mod batch_read;
+mod chunk_reader;
+pub use chunk_reader::ChunkReader;
 pub mod deserialize;
 pub use deserialize::{column_iter_to_arrays, ArrayIter};
+mod file_reader;
+pub use file_reader::FileReader;
+mod mmap;
+pub use mmap::MmapReader;
+pub mod predicate;
+mod range;
+pub use range::{RangePageReader, RangeReader};
 pub(crate) mod read_basic;
 use std::io::BufReader;
+use std::sync::Arc;
 pub mod reader;
 
+use arrow::error::{Error, Result};
+
 pub trait NativeReadBuf: std::io::BufRead {
     fn buffer_bytes(&self) -> &[u8];
+
+    /// The memory map the reader reads from and its position in the map,
+    /// used to read the values of uncompressed pages without copying.
+    fn mmap(&self) -> Option<(&Arc<memmap2::Mmap>, usize)> {
+        None
+    }
+
+    /// The number of bytes left to read, if known. The sizes of the compressed
+    /// buffers are checked against it, so that a corrupted size is an error.
+    fn remaining_len(&self) -> Option<usize> {
+        None
+    }
+
+    /// Whether the reader holds the bytes left to read in memory, which
+    /// [`NativeReadBuf::read_borrowed`] then returns without copying.
+    fn in_memory(&self) -> bool {
+        false
+    }
+
+    /// Consumes the next `len` bytes of a reader [`NativeReadBuf::in_memory`] and
+    /// returns them borrowed from its memory. Other readers return an error.
+    fn read_borrowed(&mut self, _len: usize) -> std::io::Result<&[u8]> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the reader does not hold its bytes in memory",
+        ))
+    }
+}
+
+/// Splits the first `len` bytes off `bytes`, or errors if it has fewer.
+pub(crate) fn split_borrowed(bytes: &[u8], len: usize) -> std::io::Result<(&[u8], &[u8])> {
+    if len > bytes.len() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "failed to fill whole buffer",
+        ));
+    }
+    Ok(bytes.split_at(len))
 }
 
 impl<R: std::io::Read> NativeReadBuf for BufReader<R> {
@@ -37,6 +87,20 @@ impl NativeReadBuf for &[u8] {
     fn buffer_bytes(&self) -> &[u8] {
         self
     }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.len())
+    }
+
+    fn in_memory(&self) -> bool {
+        true
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        let (bytes, rest) = split_borrowed(self, len)?;
+        *self = rest;
+        Ok(bytes)
+    }
 }
 
 impl<T: AsRef<[u8]>> NativeReadBuf for std::io::Cursor<T> {
@@ -44,14 +108,138 @@ impl<T: AsRef<[u8]>> NativeReadBuf for std::io::Cursor<T> {
         let len = self.position().min(self.get_ref().as_ref().len() as u64);
         &self.get_ref().as_ref()[(len as usize)..]
     }
+
+    fn remaining_len(&self) -> Option<usize> {
+        Some(self.buffer_bytes().len())
+    }
+
+    fn in_memory(&self) -> bool {
+        true
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        let start = self.get_ref().as_ref().len() - self.buffer_bytes().len();
+        split_borrowed(self.buffer_bytes(), len)?;
+        self.set_position((start + len) as u64);
+        Ok(&self.get_ref().as_ref()[start..start + len])
+    }
+}
+
+/// A reader limited to the bytes of a page, see [`PageMeta::length`](crate::PageMeta).
+impl<R: NativeReadBuf> NativeReadBuf for std::io::Take<R> {
+    fn buffer_bytes(&self) -> &[u8] {
+        let bytes = self.get_ref().buffer_bytes();
+        &bytes[..bytes.len().min(self.limit() as usize)]
+    }
+
+    fn mmap(&self) -> Option<(&Arc<memmap2::Mmap>, usize)> {
+        self.get_ref().mmap()
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        let limit = self.limit() as usize;
+        Some(match self.get_ref().remaining_len() {
+            Some(len) => len.min(limit),
+            None => limit,
+        })
+    }
+
+    fn in_memory(&self) -> bool {
+        self.get_ref().in_memory()
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        let limit = self.limit();
+        if len as u64 > limit {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        self.set_limit(limit - len as u64);
+        self.get_mut().read_borrowed(len)
+    }
+}
+
+impl<R: NativeReadBuf + ?Sized> NativeReadBuf for &mut R {
+    fn buffer_bytes(&self) -> &[u8] {
+        (**self).buffer_bytes()
+    }
+
+    fn mmap(&self) -> Option<(&Arc<memmap2::Mmap>, usize)> {
+        (**self).mmap()
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn in_memory(&self) -> bool {
+        (**self).in_memory()
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        (**self).read_borrowed(len)
+    }
 }
 
 impl<B: NativeReadBuf + ?Sized> NativeReadBuf for Box<B> {
     fn buffer_bytes(&self) -> &[u8] {
         (**self).buffer_bytes()
     }
+
+    fn mmap(&self) -> Option<(&Arc<memmap2::Mmap>, usize)> {
+        (**self).mmap()
+    }
+
+    fn remaining_len(&self) -> Option<usize> {
+        (**self).remaining_len()
+    }
+
+    fn in_memory(&self) -> bool {
+        (**self).in_memory()
+    }
+
+    fn read_borrowed(&mut self, len: usize) -> std::io::Result<&[u8]> {
+        (**self).read_borrowed(len)
+    }
+}
+
+/// Options of the deserialization of the pages of a column.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReadOptions {
+    /// Checks that the offsets of the binary and utf8 values are monotonic and
+    /// within their values, returning an error on a corrupted file.
+    /// Off by default, as the offsets decoded from a valid file need no check.
+    /// Utf8 values are always validated as UTF-8.
+    pub validate: bool,
+    /// The maximum number of bytes of values decoded from a binary or utf8 page,
+    /// returning an error instead of allocating them, for instance for a `Dict` page
+    /// whose indices repeat large values. It also bounds the size of the pages
+    /// compressed with the zstd dictionary of their column.
+    /// `None`, the default, is unlimited.
+    pub max_decode_bytes: Option<usize>,
+    /// The version of the header of the file, read with
+    /// [`reader::read_format_version`], which the layout of the pages depends on.
+    /// The readers of the files, e.g. [`FileReader`], set it. `None`, the default,
+    /// is unknown: the functions given a reader of the whole file read it from its
+    /// header, and the others return an error instead of reading the pages.
+    pub format_version: Option<u16>,
+}
+
+impl ReadOptions {
+    /// The [`ReadOptions::format_version`] of the pages, an error if it is unknown.
+    pub(crate) fn page_format_version(&self) -> Result<u16> {
+        self.format_version.ok_or_else(|| {
+            Error::InvalidArgumentError(
+                "The format version of the file of the pages is unknown, set `ReadOptions::format_version` to the version read with `read_format_version`".to_string(),
+            )
+        })
+    }
 }
 
 pub trait PageIterator {
     fn swap_buffer(&mut self, buffer: &mut Vec<u8>);
+
+    /// The [`ReadOptions`] of the deserialization of the pages.
+    fn read_options(&self) -> ReadOptions {
+        ReadOptions::default()
+    }
 }
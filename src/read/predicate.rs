@@ -0,0 +1,212 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::cmp::Ordering;
+
+use arrow::datatypes::{PhysicalType, PrimitiveType};
+use arrow::scalar::{BinaryScalar, PrimitiveScalar, Scalar, Utf8Scalar};
+use arrow::types::NativeType;
+
+use crate::{with_match_integer_double_type, PageMeta, PageStatistics};
+
+/// The comparison of a [`PagePredicate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ComparisonOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// A `column <op> literal` predicate, used to skip the pages whose [`PageStatistics`]
+/// show that none of their values can match. The literal must have the data type of the
+/// column, other literals never skip a page.
+#[derive(Debug)]
+pub struct PagePredicate {
+    pub op: ComparisonOp,
+    pub literal: Box<dyn Scalar>,
+}
+
+impl PagePredicate {
+    pub fn new(op: ComparisonOp, literal: Box<dyn Scalar>) -> Self {
+        Self { op, literal }
+    }
+
+    /// Whether a page with `statistics` may hold a value matching the predicate.
+    /// Pages without statistics always may.
+    pub fn may_match(&self, statistics: Option<&PageStatistics>) -> bool {
+        let statistics = match statistics {
+            Some(statistics) => statistics,
+            None => return true,
+        };
+        let (min, max) = match compare(self.literal.as_ref(), statistics) {
+            Some(orderings) => orderings,
+            None => return true,
+        };
+        // `min` and `max` are the orderings of the bounds relative to the literal
+        match self.op {
+            ComparisonOp::Eq => min != Ordering::Greater && max != Ordering::Less,
+            ComparisonOp::NotEq => !(min == Ordering::Equal && max == Ordering::Equal),
+            ComparisonOp::Lt => min == Ordering::Less,
+            ComparisonOp::LtEq => min != Ordering::Greater,
+            ComparisonOp::Gt => max == Ordering::Greater,
+            ComparisonOp::GtEq => max != Ordering::Less,
+        }
+    }
+}
+
+/// A filter of the values of a non-nested column, evaluated against the metas of its
+/// pages by [`crate::ColumnMeta::select_pages`]. The literals must have the data type
+/// of the column, other literals select every page holding a valid value.
+/// Nulls only match [`Predicate::IsNull`].
+#[derive(Debug)]
+pub enum Predicate {
+    Eq(Box<dyn Scalar>),
+    Lt(Box<dyn Scalar>),
+    Gt(Box<dyn Scalar>),
+    /// The values between the two literals, both included.
+    Between(Box<dyn Scalar>, Box<dyn Scalar>),
+    IsNull,
+}
+
+impl Predicate {
+    /// Whether the page may hold a value matching the predicate.
+    /// The pages of only nulls never match a comparison, the pages with an
+    /// unknown null count may hold nulls.
+    pub fn may_match(&self, page: &PageMeta) -> bool {
+        if let Predicate::IsNull = self {
+            return page.null_count != Some(0);
+        }
+        if page
+            .null_count
+            .map_or(false, |null_count| null_count >= page.num_values)
+        {
+            return false;
+        }
+        let statistics = match &page.statistics {
+            Some(statistics) => statistics,
+            None => return true,
+        };
+        match self {
+            Predicate::Eq(literal) => compare(literal.as_ref(), statistics)
+                .map_or(true, |(min, max)| {
+                    min != Ordering::Greater && max != Ordering::Less
+                }),
+            Predicate::Lt(literal) => {
+                compare(literal.as_ref(), statistics).map_or(true, |(min, _)| min == Ordering::Less)
+            }
+            Predicate::Gt(literal) => compare(literal.as_ref(), statistics)
+                .map_or(true, |(_, max)| max == Ordering::Greater),
+            Predicate::Between(low, high) => {
+                compare(low.as_ref(), statistics).map_or(true, |(_, max)| max != Ordering::Less)
+                    && compare(high.as_ref(), statistics)
+                        .map_or(true, |(min, _)| min != Ordering::Greater)
+            }
+            Predicate::IsNull => unreachable!(),
+        }
+    }
+
+    /// Whether every value of the page matches the predicate, in which case the
+    /// rows of the page need no exact filter.
+    pub fn matches_all(&self, page: &PageMeta) -> bool {
+        if let Predicate::IsNull = self {
+            return page
+                .null_count
+                .map_or(false, |null_count| null_count >= page.num_values);
+        }
+        if page.null_count != Some(0) || page.num_values == 0 {
+            return false;
+        }
+        let statistics = match &page.statistics {
+            Some(statistics) => statistics,
+            None => return false,
+        };
+        match self {
+            Predicate::Eq(literal) => compare(literal.as_ref(), statistics)
+                .map_or(false, |orderings| {
+                    orderings == (Ordering::Equal, Ordering::Equal)
+                }),
+            Predicate::Lt(literal) => compare(literal.as_ref(), statistics)
+                .map_or(false, |(_, max)| max == Ordering::Less),
+            Predicate::Gt(literal) => compare(literal.as_ref(), statistics)
+                .map_or(false, |(min, _)| min == Ordering::Greater),
+            Predicate::Between(low, high) => {
+                compare(low.as_ref(), statistics).map_or(false, |(min, _)| min != Ordering::Less)
+                    && compare(high.as_ref(), statistics)
+                        .map_or(false, |(_, max)| max != Ordering::Greater)
+            }
+            Predicate::IsNull => unreachable!(),
+        }
+    }
+}
+
+/// The orderings of the min and the max of `statistics` relative to `literal`,
+/// `None` if they cannot be compared.
+fn compare(literal: &dyn Scalar, statistics: &PageStatistics) -> Option<(Ordering, Ordering)> {
+    use PhysicalType::*;
+
+    let data_type = literal.data_type().to_physical_type();
+    let literal = literal.as_any();
+    match data_type {
+        Primitive(PrimitiveType::Float16 | PrimitiveType::DaysMs | PrimitiveType::MonthDayNano) => {
+            None
+        }
+        Primitive(primitive) => with_match_integer_double_type!(primitive,
+        |$T| {
+            compare_primitive::<$T>(literal.downcast_ref()?, statistics)
+        },
+        |$T| {
+            compare_primitive::<$T>(literal.downcast_ref()?, statistics)
+        }),
+        Binary => {
+            let literal = literal.downcast_ref::<BinaryScalar<i32>>()?.value()?;
+            compare_bytes(literal, statistics)
+        }
+        LargeBinary => {
+            let literal = literal.downcast_ref::<BinaryScalar<i64>>()?.value()?;
+            compare_bytes(literal, statistics)
+        }
+        Utf8 => {
+            let literal = literal.downcast_ref::<Utf8Scalar<i32>>()?.value()?;
+            compare_bytes(literal.as_bytes(), statistics)
+        }
+        LargeUtf8 => {
+            let literal = literal.downcast_ref::<Utf8Scalar<i64>>()?.value()?;
+            compare_bytes(literal.as_bytes(), statistics)
+        }
+        _ => None,
+    }
+}
+
+fn compare_primitive<T: NativeType + PartialOrd>(
+    literal: &PrimitiveScalar<T>,
+    statistics: &PageStatistics,
+) -> Option<(Ordering, Ordering)> {
+    let literal = literal.value().as_ref()?;
+    let min = T::from_le_bytes(statistics.min.as_slice().try_into().ok()?);
+    let max = T::from_le_bytes(statistics.max.as_slice().try_into().ok()?);
+    Some((min.partial_cmp(literal)?, max.partial_cmp(literal)?))
+}
+
+fn compare_bytes(literal: &[u8], statistics: &PageStatistics) -> Option<(Ordering, Ordering)> {
+    Some((
+        statistics.min.as_slice().cmp(literal),
+        statistics.max.as_slice().cmp(literal),
+    ))
+}
@@ -0,0 +1,231 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::{Read, Seek, SeekFrom};
+
+use arrow::error::{Error, Result};
+use bytes::Bytes;
+
+use crate::compression::ZstdDecoderDictionary;
+use crate::{ColumnMeta, PageMeta};
+
+use super::reader::{decode_page, read_format_version, verify_page_checksum};
+use super::{PageIterator, ReadOptions};
+use crate::encryption::ColumnKey;
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptionKey;
+
+/// A file read by ranges of bytes, e.g. an object in an object store where each
+/// range is one ranged GET, so that only the footer and the read pages are fetched.
+///
+/// Every [`Read`] + [`Seek`] reader is a [`RangeReader`].
+pub trait RangeReader {
+    /// The length of the file in bytes.
+    fn file_len(&mut self) -> Result<u64>;
+
+    /// Reads the `len` bytes of the file at `offset`.
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Bytes>;
+}
+
+impl<R: Read + Seek> RangeReader for R {
+    fn file_len(&mut self) -> Result<u64> {
+        Ok(self.seek(SeekFrom::End(0))?)
+    }
+
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Bytes> {
+        self.seek(SeekFrom::Start(offset))?;
+        let mut bytes = vec![0u8; len];
+        self.read_exact(&mut bytes)?;
+        Ok(bytes.into())
+    }
+}
+
+/// Reads the last `len` bytes of the file of `file_len` bytes.
+pub(crate) fn read_tail<R: RangeReader>(
+    reader: &mut R,
+    file_len: u64,
+    len: usize,
+) -> Result<Bytes> {
+    let offset = file_len.checked_sub(len as u64).ok_or_else(|| {
+        Error::OutOfSpec(format!(
+            "The file of {file_len} bytes is shorter than its footer of {len} bytes"
+        ))
+    })?;
+    reader.read_range(offset, len)
+}
+
+/// Reads the pages of a column with one [`RangeReader::read_range`] per page,
+/// like a [`super::reader::NativeReader`] reads them from a [`Read`] + [`Seek`] reader.
+/// The [`ReadOptions::format_version`] is read from the header of the file with the
+/// first page unless it is set with [`RangePageReader::with_read_options`].
+pub struct RangePageReader<R: RangeReader> {
+    reader: R,
+    /// The offset of the next page.
+    offset: u64,
+    page_metas: Vec<PageMeta>,
+    current_page: usize,
+    scratch: Vec<u8>,
+    zstd_dictionary: Option<ZstdDecoderDictionary>,
+    decryption_key: Option<ColumnKey>,
+    first_page: usize,
+    verify_checksums: bool,
+    read_options: ReadOptions,
+}
+
+impl<R: RangeReader> RangePageReader<R> {
+    /// Creates a [`RangePageReader`] of the pages of the column `meta`.
+    pub fn new(reader: R, meta: &ColumnMeta) -> Self {
+        Self {
+            reader,
+            offset: meta.offset,
+            page_metas: meta.pages.clone(),
+            current_page: 0,
+            scratch: vec![],
+            zstd_dictionary: meta
+                .zstd_dictionary
+                .as_deref()
+                .map(ZstdDecoderDictionary::new),
+            decryption_key: None,
+            first_page: 0,
+            verify_checksums: false,
+            read_options: ReadOptions::default(),
+        }
+    }
+
+    /// Verifies the checksum of every page that has one as it is read,
+    /// see `WriteOptions::page_checksum`.
+    pub fn with_checksum_verification(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Deserializes the pages with `read_options`, see [`ReadOptions::validate`].
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        self.read_options = read_options;
+        self
+    }
+
+    /// Decrypts the encrypted pages with `key`, see `NativeReader::with_decryption_key`.
+    #[cfg(feature = "encryption")]
+    pub fn with_decryption_key(mut self, column: usize, key: Option<EncryptionKey>) -> Self {
+        self.decryption_key = key.map(|key| ColumnKey { key, column });
+        self
+    }
+
+    /// The index in its column of the first page of the reader, see
+    /// `NativeReader::with_first_page_index`.
+    #[cfg(feature = "encryption")]
+    pub fn with_first_page_index(mut self, first_page: usize) -> Self {
+        self.first_page = first_page;
+        self
+    }
+
+    /// Check whether there is more data to read,
+    /// returns true, if current page is not the last one, false otherwise
+    pub fn has_next(&self) -> bool {
+        self.current_page < self.page_metas.len()
+    }
+
+    /// Returns current page number
+    pub fn current_page(&self) -> usize {
+        self.current_page
+    }
+
+    /// Skips the next `n` pages without reading them.
+    pub fn skip_n_pages(&mut self, n: usize) {
+        assert!(n <= self.page_metas.len() - self.current_page);
+        self.offset += self.page_metas[self.current_page..self.current_page + n]
+            .iter()
+            .map(|page_meta| page_meta.length)
+            .sum::<u64>();
+        self.current_page += n;
+    }
+
+    fn read_page(&mut self) -> Result<(u64, Vec<u8>)> {
+        if self.read_options.format_version.is_none() {
+            self.read_options.format_version = Some(read_format_version(&mut self.reader)?);
+        }
+        let page_meta = &self.page_metas[self.current_page];
+        let bytes = self
+            .reader
+            .read_range(self.offset, page_meta.length as usize)?;
+        if bytes.len() != page_meta.length as usize {
+            return Err(Error::OutOfSpec(format!(
+                "Read {} bytes of page {} of {} bytes",
+                bytes.len(),
+                self.current_page,
+                page_meta.length
+            )));
+        }
+        let index = self.current_page;
+        self.offset += page_meta.length;
+        self.current_page += 1;
+        if self.verify_checksums {
+            verify_page_checksum(page_meta, index, &bytes)?;
+        }
+        let decoded = decode_page(
+            page_meta,
+            self.first_page + index,
+            &bytes,
+            self.decryption_key.as_ref(),
+            self.zstd_dictionary.as_ref(),
+            self.read_options.max_decode_bytes,
+        )?;
+        let page = match decoded {
+            Some(page) => page,
+            None => {
+                let mut buffer = std::mem::take(&mut self.scratch);
+                buffer.clear();
+                buffer.extend_from_slice(&bytes);
+                buffer
+            }
+        };
+        Ok((page_meta.num_values, page))
+    }
+}
+
+impl<R: RangeReader> PageIterator for RangePageReader<R> {
+    fn swap_buffer(&mut self, scratch: &mut Vec<u8>) {
+        std::mem::swap(&mut self.scratch, scratch)
+    }
+
+    fn read_options(&self) -> ReadOptions {
+        self.read_options
+    }
+}
+
+impl<R: RangeReader> Iterator for RangePageReader<R> {
+    type Item = Result<(u64, Vec<u8>)>;
+
+    /// Reads the next nth page of data, skipping the intermediate pages
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.page_metas.len() - self.current_page {
+            self.skip_n_pages(self.page_metas.len() - self.current_page);
+            return None;
+        }
+        self.skip_n_pages(n);
+        self.next()
+    }
+
+    /// Reads the next page of data
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.has_next() {
+            return None;
+        }
+        Some(self.read_page())
+    }
+}
@@ -19,69 +19,196 @@ use std::convert::TryInto;
 use std::io::Read;
 
 use super::NativeReadBuf;
+use crate::compression::{CommonCompression, Compression};
+use crate::{PageMeta, COMPRESS_HEADER_SIZE, COMPRESS_HEADER_SIZE_V0};
 
 use arrow::{
     bitmap::{Bitmap, MutableBitmap},
-    error::Result,
+    error::{Error, Result},
     io::parquet::read::{init_nested, InitNested, NestedState},
 };
 
-use futures::{AsyncRead, AsyncReadExt};
 use parquet2::{
     encoding::hybrid_rle::{BitmapIter, Decoder, HybridEncoded, HybridRleDecoder},
     metadata::ColumnDescriptor,
     read::levels::get_bit_width,
 };
 
+#[cfg(test)]
+thread_local! {
+    /// Number of pages whose validity has been decoded by [`read_validity`] in this thread.
+    pub(crate) static VALIDITY_DECODES: std::cell::Cell<usize> = std::cell::Cell::new(0);
+}
+
+/// Reads the `u32` length and the uncompressed levels of a file of version 0.
+fn read_levels_v0<R: NativeReadBuf>(reader: &mut R) -> Result<Vec<u8>> {
+    let len = read_u32(reader, &mut [0u8; 4])? as usize;
+    read_raw_levels(reader, len)
+}
+
+fn read_raw_levels<R: NativeReadBuf>(reader: &mut R, len: usize) -> Result<Vec<u8>> {
+    if reader
+        .remaining_len()
+        .map_or(false, |remaining| len > remaining)
+    {
+        return Err(Error::OutOfSpec(format!(
+            "The {len} bytes of levels exceed the bytes left in the page"
+        )));
+    }
+    let mut levels = vec![0u8; len];
+    reader.read_exact(&mut levels)?;
+    Ok(levels)
+}
+
+/// Reads the validity of a page of a file of the header `format_version`, see
+/// [`super::ReadOptions::format_version`]. The levels of the files of version 0
+/// are not compressed.
 pub fn read_validity<R: NativeReadBuf>(
     reader: &mut R,
     length: usize,
     builder: &mut MutableBitmap,
+    format_version: u16,
 ) -> Result<()> {
-    let mut buf = vec![0u8; 4];
-    let def_levels_len = read_u32(reader, buf.as_mut_slice())?;
-    if def_levels_len == 0 {
+    #[cfg(test)]
+    VALIDITY_DECODES.with(|decodes| decodes.set(decodes.get() + 1));
+    let def_levels = if format_version == 0 {
+        read_levels_v0(reader)?
+    } else {
+        let (codec, compressed_size, uncompressed_size) =
+            read_compress_header(reader, format_version)?;
+        if codec == u8::from(Compression::SparseValidity) {
+            return read_sparse_validity(
+                reader,
+                length,
+                compressed_size,
+                uncompressed_size,
+                builder,
+            );
+        }
+        read_levels_body(reader, codec, compressed_size, uncompressed_size)?
+    };
+    if def_levels.is_empty() {
         return Ok(());
     }
-    let mut def_levels = vec![0u8; def_levels_len as usize];
-    reader.read_exact(def_levels.as_mut_slice())?;
 
     let decoder = Decoder::new(def_levels.as_slice(), 1);
     for encoded in decoder {
-        let encoded = encoded.unwrap();
-        match encoded {
+        match encoded? {
             HybridEncoded::Bitpacked(r) => {
                 let bitmap_iter = BitmapIter::new(r, 0, length);
                 for v in bitmap_iter {
                     unsafe { builder.push_unchecked(v) };
                 }
             }
-            HybridEncoded::Rle(_, _) => unreachable!(),
+            HybridEncoded::Rle(_, _) => {
+                return Err(Error::OutOfSpec(
+                    "The validity of a page must be bit-packed".to_string(),
+                ))
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads a validity written as the number of nulls before each of its `num_valid`
+/// valid values, filling the runs of nulls without decoding a bit per value.
+fn read_sparse_validity<R: NativeReadBuf>(
+    reader: &mut R,
+    length: usize,
+    size: usize,
+    num_valid: usize,
+    builder: &mut MutableBitmap,
+) -> Result<()> {
+    let mut buf = vec![0u8; size];
+    reader.read_exact(&mut buf)?;
+    let mut buf = buf.as_slice();
+    let mut remaining = length;
+    for _ in 0..num_valid {
+        let nulls = read_uleb128(&mut buf)? as usize;
+        if nulls >= remaining {
+            return Err(Error::OutOfSpec(format!(
+                "The sparse validity of a page exceeds its {length} values"
+            )));
         }
+        builder.extend_constant(nulls, false);
+        builder.push(true);
+        remaining -= nulls + 1;
+    }
+    builder.extend_constant(remaining, false);
+    Ok(())
+}
+
+/// Skips the def levels of a page without decoding them,
+/// used when the page is known to have no null values.
+pub fn skip_validity<R: NativeReadBuf>(reader: &mut R, format_version: u16) -> Result<()> {
+    let compressed_size = if format_version == 0 {
+        read_u32(reader, &mut [0u8; 4])? as usize
+    } else {
+        read_compress_header(reader, format_version)?.1
+    };
+    let skipped = std::io::copy(
+        &mut reader.by_ref().take(compressed_size as u64),
+        &mut std::io::sink(),
+    )?;
+    if skipped != compressed_size as u64 {
+        return Err(Error::OutOfSpec(
+            "Unexpected end of the def levels".to_string(),
+        ));
     }
     Ok(())
 }
 
+/// Whether the validity of a page needs to be decoded, a nullable column
+/// without any null values is read without validity.
+pub fn has_nulls(is_nullable: bool, page_metas: &[PageMeta]) -> bool {
+    is_nullable && page_metas.iter().any(|page| page.null_count != Some(0))
+}
+
 pub fn read_validity_nested<R: NativeReadBuf>(
     reader: &mut R,
     num_values: usize,
     leaf: &ColumnDescriptor,
     init: Vec<InitNested>,
+    format_version: u16,
 ) -> Result<(NestedState, Option<Bitmap>)> {
-    let mut buf = vec![0u8; 4];
-    let additional = read_u32(reader, buf.as_mut_slice())?;
-    let rep_levels_len = read_u32(reader, buf.as_mut_slice())?;
-    let def_levels_len = read_u32(reader, buf.as_mut_slice())?;
     let max_rep_level = leaf.descriptor.max_rep_level;
     let max_def_level = leaf.descriptor.max_def_level;
 
-    let mut rep_levels = vec![0u8; rep_levels_len as usize];
-    reader.read_exact(rep_levels.as_mut_slice())?;
-    let mut def_levels = vec![0u8; def_levels_len as usize];
-    reader.read_exact(def_levels.as_mut_slice())?;
+    // the levels of a column without optional or repeated levels are all zeros,
+    // they are not written and every row has a single valid value
+    let (additional, levels, rep_levels_len) = if format_version == 0 {
+        // the files of version 0 have the levels of every column, uncompressed
+        let mut buf = vec![0u8; 4];
+        let additional = read_u32(reader, buf.as_mut_slice())?;
+        let rep_levels_len = read_u32(reader, buf.as_mut_slice())? as u64;
+        let def_levels_len = read_u32(reader, buf.as_mut_slice())? as usize;
+        let mut levels = read_raw_levels(reader, rep_levels_len as usize)?;
+        levels.extend(read_raw_levels(reader, def_levels_len)?);
+        (additional, levels, rep_levels_len)
+    } else if max_rep_level == 0 && max_def_level == 0 {
+        (num_values as u32, vec![], 0)
+    } else {
+        let mut buf = vec![0u8; 4];
+        let additional = read_u32(reader, buf.as_mut_slice())?;
+        let mut buf = vec![0u8; 8];
+        let rep_levels_len = read_u64(reader, buf.as_mut_slice())?;
+        let def_levels_len = read_u64(reader, buf.as_mut_slice())?;
+
+        let levels = read_levels(reader, format_version)?;
+        if levels.len() != (rep_levels_len + def_levels_len) as usize {
+            return Err(Error::OutOfSpec(format!(
+                "The levels of a page have {} bytes, expected {} rep and {} def level bytes",
+                levels.len(),
+                rep_levels_len,
+                def_levels_len
+            )));
+        }
+        (additional, levels, rep_levels_len)
+    };
+    let (rep_levels, def_levels) = levels.split_at(rep_levels_len as usize);
 
-    let reps = HybridRleDecoder::try_new(&rep_levels, get_bit_width(max_rep_level), num_values)?;
-    let defs = HybridRleDecoder::try_new(&def_levels, get_bit_width(max_def_level), num_values)?;
+    let reps = HybridRleDecoder::try_new(rep_levels, get_bit_width(max_rep_level), num_values)?;
+    let defs = HybridRleDecoder::try_new(def_levels, get_bit_width(max_def_level), num_values)?;
     let mut page_iter = reps.zip(defs).peekable();
 
     let mut nested = init_nested(&init, num_values);
@@ -151,12 +278,12 @@ pub fn read_validity_nested<R: NativeReadBuf>(
             }
         }
 
-        let next_rep = *page_iter
-            .peek()
-            .map(|x| x.0.as_ref())
-            .transpose()
-            .unwrap() // todo: fix this
-            .unwrap_or(&0);
+        let next_rep = match page_iter.peek() {
+            Some((Ok(rep), _)) => *rep,
+            // the invalid level is returned by the next iteration
+            Some((Err(_), _)) => continue,
+            None => 0,
+        };
 
         if next_rep == 0 && rows == additional {
             break;
@@ -172,19 +299,76 @@ pub fn read_validity_nested<R: NativeReadBuf>(
     Ok((nested, validity))
 }
 
+/// Reads the rep and def levels of a page, which are compressed
+/// like the other buffers unless the codec is `None`.
+fn read_levels<R: NativeReadBuf>(reader: &mut R, format_version: u16) -> Result<Vec<u8>> {
+    let (codec, compressed_size, uncompressed_size) = read_compress_header(reader, format_version)?;
+    read_levels_body(reader, codec, compressed_size, uncompressed_size)
+}
+
+fn read_levels_body<R: NativeReadBuf>(
+    reader: &mut R,
+    codec: u8,
+    compressed_size: usize,
+    uncompressed_size: usize,
+) -> Result<Vec<u8>> {
+    let c = CommonCompression::try_from(&Compression::from_codec(codec)?)?;
+    let mut levels = vec![0u8; uncompressed_size];
+    if c == CommonCompression::None {
+        if compressed_size != uncompressed_size {
+            return Err(Error::OutOfSpec(format!(
+                "Uncompressed levels of {uncompressed_size} bytes are stored in {compressed_size} bytes",
+            )));
+        }
+        reader.read_exact(&mut levels)?;
+    } else {
+        let mut compressed = vec![0u8; compressed_size];
+        reader.read_exact(&mut compressed)?;
+        c.decompress(&compressed, &mut levels)?;
+    }
+    Ok(levels)
+}
+
 #[inline(always)]
 pub fn read_u32<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<u32> {
     r.read_exact(buf)?;
     Ok(u32::from_le_bytes(buf.try_into().unwrap()))
 }
 
-pub fn read_compress_header<R: Read>(r: &mut R) -> Result<(u8, usize, usize)> {
-    let mut header = vec![0u8; 9];
-    r.read_exact(&mut header)?;
+/// Reads the header of a compressed buffer: its codec, compressed size and uncompressed size.
+/// The compressed size is checked against the bytes left in `r` when they are known.
+/// The sizes are `u32` in the files of the header `format_version` 0, see
+/// [`super::ReadOptions::format_version`].
+pub fn read_compress_header<R: NativeReadBuf>(
+    r: &mut R,
+    format_version: u16,
+) -> Result<(u8, usize, usize)> {
+    let mut header = [0u8; COMPRESS_HEADER_SIZE];
+    let (compressed_size, uncompressed_size) = if format_version == 0 {
+        let header = &mut header[..COMPRESS_HEADER_SIZE_V0];
+        r.read_exact(header)?;
+        (
+            u32::from_le_bytes(header[1..5].try_into().unwrap()) as u64,
+            u32::from_le_bytes(header[5..9].try_into().unwrap()) as u64,
+        )
+    } else {
+        r.read_exact(&mut header)?;
+        (
+            u64::from_le_bytes(header[1..9].try_into().unwrap()),
+            u64::from_le_bytes(header[9..17].try_into().unwrap()),
+        )
+    };
+    if let Some(remaining) = r.remaining_len() {
+        if compressed_size > remaining as u64 {
+            return Err(Error::OutOfSpec(format!(
+                "The compressed size {compressed_size} exceeds the {remaining} bytes left in the page"
+            )));
+        }
+    }
     Ok((
         header[0],
-        u32::from_le_bytes(header[1..5].try_into().unwrap()) as usize,
-        u32::from_le_bytes(header[5..9].try_into().unwrap()) as usize,
+        compressed_size as usize,
+        uncompressed_size as usize,
     ))
 }
 
@@ -194,7 +378,18 @@ pub fn read_u64<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<u64> {
     Ok(u64::from_le_bytes(buf.try_into().unwrap()))
 }
 
-pub async fn read_u32_async<R: AsyncRead + Unpin>(r: &mut R, buf: &mut [u8]) -> Result<u32> {
-    r.read_exact(buf).await?;
-    Ok(u32::from_le_bytes(buf.try_into().unwrap()))
+/// Reads an unsigned LEB128 encoded integer.
+pub fn read_uleb128<R: Read>(r: &mut R) -> Result<u64> {
+    let mut result = 0u64;
+    let mut byte = [0u8; 1];
+    for shift in (0..64).step_by(7) {
+        r.read_exact(&mut byte)?;
+        result |= ((byte[0] & 0x7f) as u64) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(Error::OutOfSpec(
+        "LEB128 value does not fit in u64".to_string(),
+    ))
 }
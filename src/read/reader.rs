@@ -15,17 +15,31 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::io::{Read, Seek, SeekFrom};
+use std::borrow::Cow;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::sync::Arc;
 
-use arrow::datatypes::{DataType, PhysicalType, Schema};
-use arrow::error::Result;
+use arrow::datatypes::{DataType, Field, PhysicalType, Schema};
+use arrow::error::{Error, Result};
 use arrow::io::ipc::read::deserialize_schema;
+use arrow::io::parquet::read::{n_columns, ColumnDescriptor};
 
-use crate::{ColumnMeta, PageMeta};
+use crate::compression::binary::check_decode_size;
+use crate::compression::{decompress_zstd_with_dict, ZstdDecoderDictionary};
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptionKey;
+use crate::encryption::{decrypt_page, ColumnKey};
+use crate::{
+    to_parquet_schema, BloomFilter, ColumnMeta, ColumnStatistics, Compression, PageMeta,
+    PageStatistics, RowGroupMeta, ARROW_MAGIC, CONTINUATION_MARKER, FOOTER_VERSION_SIZE,
+    FORMAT_VERSION, SINGLE_ROW_GROUP_VERSION, VARINT_META_FLAG, VERSIONED_META_FLAG,
+};
 
 use super::{
-    read_basic::{read_u32, read_u32_async, read_u64},
-    NativeReadBuf, PageIterator,
+    predicate::PagePredicate,
+    range::{read_tail, RangeReader},
+    read_basic::{read_u64, read_uleb128},
+    NativeReadBuf, PageIterator, ReadOptions,
 };
 
 use futures::{AsyncRead, AsyncReadExt, AsyncSeek, AsyncSeekExt};
@@ -53,19 +67,69 @@ pub struct NativeReader<R: NativeReadBuf> {
     page_metas: Vec<PageMeta>,
     current_page: usize,
     scratch: Vec<u8>,
+    zstd_dictionary: Option<ZstdDecoderDictionary>,
+    decryption_key: Option<ColumnKey>,
+    first_page: usize,
+    verify_checksums: bool,
+    read_options: ReadOptions,
 }
 
 impl<R: NativeReadBuf> NativeReader<R> {
-    /// Creates a new [`NativeReader`]
+    /// Creates a new [`NativeReader`]. The pages are deserialized with the
+    /// [`ReadOptions::format_version`] set with [`NativeReader::with_read_options`],
+    /// an error if it is not set.
     pub fn new(page_reader: R, page_metas: Vec<PageMeta>, scratch: Vec<u8>) -> Self {
         Self {
             page_reader,
             page_metas,
             current_page: 0,
             scratch,
+            zstd_dictionary: None,
+            decryption_key: None,
+            first_page: 0,
+            verify_checksums: false,
+            read_options: ReadOptions::default(),
         }
     }
 
+    /// Verifies the checksum of every page that has one as it is read,
+    /// see `WriteOptions::page_checksum`.
+    pub fn with_checksum_verification(mut self, verify_checksums: bool) -> Self {
+        self.verify_checksums = verify_checksums;
+        self
+    }
+
+    /// Deserializes the pages with `read_options`, see [`ReadOptions::validate`].
+    pub fn with_read_options(mut self, read_options: ReadOptions) -> Self {
+        self.read_options = read_options;
+        self
+    }
+
+    /// Decompresses the pages with the zstd dictionary of the column,
+    /// required for the columns whose [`ColumnMeta`] has one.
+    pub fn with_zstd_dictionary(mut self, zstd_dictionary: Option<Vec<u8>>) -> Self {
+        self.zstd_dictionary = zstd_dictionary.as_deref().map(ZstdDecoderDictionary::new);
+        self
+    }
+
+    /// Decrypts the encrypted pages with `key`, the key of the leaf column at
+    /// `column` from the `KeyProvider` it was written with, see [`crate::encryption`].
+    /// With a key, the plaintext pages are rejected.
+    #[cfg(feature = "encryption")]
+    pub fn with_decryption_key(mut self, column: usize, key: Option<EncryptionKey>) -> Self {
+        self.decryption_key = key.map(|key| ColumnKey { key, column });
+        self
+    }
+
+    /// The index in its column of the first page of the reader, for the readers of
+    /// the pages of an encrypted column after the first ones, e.g. of a
+    /// [`ColumnMeta::slice`], as every page is authenticated with its index.
+    #[cfg(feature = "encryption")]
+    pub fn with_first_page_index(mut self, first_page: usize) -> Self {
+        self.first_page = first_page;
+        self
+    }
+
     /// Check whether there is more data to read,
     /// returns true, if current page is not the last one, false otherwise
     pub fn has_next(&self) -> bool {
@@ -78,10 +142,43 @@ impl<R: NativeReadBuf> NativeReader<R> {
     }
 }
 
+impl NativeReader<Cursor<Arc<[u8]>>> {
+    /// Creates a [`NativeReader`] of the column `meta` over a file held in memory.
+    /// The `bytes` are shared rather than copied, so any number of column readers
+    /// can be opened over the same file. The [`ReadOptions::format_version`] is read
+    /// from the header of the file, it is unknown if `bytes` are not a strawboat file.
+    pub fn from_shared(bytes: Arc<[u8]>, meta: &ColumnMeta) -> Self {
+        let mut reader = Cursor::new(bytes);
+        let read_options = ReadOptions {
+            format_version: read_format_version(&mut reader).ok(),
+            ..Default::default()
+        };
+        reader.set_position(meta.offset);
+        Self::new(reader, meta.pages.clone(), vec![])
+            .with_zstd_dictionary(meta.zstd_dictionary.clone())
+            .with_read_options(read_options)
+    }
+}
+
+/// Creates one [`NativeReader`] per column over the same in-memory file.
+pub fn shared_native_readers(
+    bytes: Arc<[u8]>,
+    metas: &[ColumnMeta],
+) -> Vec<NativeReader<Cursor<Arc<[u8]>>>> {
+    metas
+        .iter()
+        .map(|meta| NativeReader::from_shared(bytes.clone(), meta))
+        .collect()
+}
+
 impl<R: NativeReadBuf> PageIterator for NativeReader<R> {
     fn swap_buffer(&mut self, scratch: &mut Vec<u8>) {
         std::mem::swap(&mut self.scratch, scratch)
     }
+
+    fn read_options(&self) -> ReadOptions {
+        self.read_options
+    }
 }
 
 impl<R: NativeReadBuf + std::io::Seek> Iterator for NativeReader<R> {
@@ -126,12 +223,124 @@ impl<R: NativeReadBuf + std::io::Seek> Iterator for NativeReader<R> {
         if let Some(err) = self.page_reader.read_exact(&mut buffer).err() {
             return Some(Result::Err(err.into()));
         }
+        // the page is read, the next one follows it whether it is valid or not
+        let index = self.current_page;
         self.current_page += 1;
-        Some(Ok((page_meta.num_values, buffer)))
+        if self.verify_checksums {
+            if let Err(err) = verify_page_checksum(page_meta, index, &buffer) {
+                self.scratch = buffer;
+                return Some(Err(err));
+            }
+        }
+        match decode_page(
+            page_meta,
+            self.first_page + index,
+            &buffer,
+            self.decryption_key.as_ref(),
+            self.zstd_dictionary.as_ref(),
+            self.read_options.max_decode_bytes,
+        ) {
+            Ok(None) => Some(Ok((page_meta.num_values, buffer))),
+            page => {
+                self.scratch = buffer;
+                Some(page.map(|page| (page_meta.num_values, page.unwrap())))
+            }
+        }
     }
 }
 
 impl<R: NativeReadBuf + std::io::Seek> NativeReader<R> {
+    /// Reads the next page like [`Iterator::next`], but borrows its bytes from the
+    /// reader instead of copying them when the reader holds the file in memory,
+    /// see [`NativeReadBuf::in_memory`]. The pages of the other readers, and the
+    /// pages decompressed with a zstd dictionary, are owned.
+    pub fn next_page(&mut self) -> Option<Result<(u64, Cow<'_, [u8]>)>> {
+        if !self.page_reader.in_memory() {
+            return self
+                .next()
+                .map(|page| page.map(|(num_values, page)| (num_values, Cow::Owned(page))));
+        }
+        let page_meta = self.page_metas.get(self.current_page)?;
+        let page = match self.page_reader.read_borrowed(page_meta.length as usize) {
+            Ok(page) => page,
+            Err(err) => return Some(Err(err.into())),
+        };
+        let index = self.current_page;
+        self.current_page += 1;
+        if self.verify_checksums {
+            if let Err(err) = verify_page_checksum(page_meta, index, page) {
+                return Some(Err(err));
+            }
+        }
+        let decoded = decode_page(
+            page_meta,
+            self.first_page + index,
+            page,
+            self.decryption_key.as_ref(),
+            self.zstd_dictionary.as_ref(),
+            self.read_options.max_decode_bytes,
+        );
+        Some(decoded.map(|decoded| {
+            let page = decoded.map_or(Cow::Borrowed(page), Cow::Owned);
+            (page_meta.num_values, page)
+        }))
+    }
+
+    /// Reads the page at `index` like [`Iterator::next`], checking its checksum if
+    /// asked to, decrypting it and decompressing it with the zstd dictionary of the column.
+    /// The reader is left at the same page and position.
+    pub fn read_page(&mut self, index: usize) -> Result<(u64, Vec<u8>)> {
+        let page = self.read_raw_page(index)?;
+        let page_meta = &self.page_metas[index];
+        if self.verify_checksums {
+            verify_page_checksum(page_meta, index, &page)?;
+        }
+        let decoded = decode_page(
+            page_meta,
+            self.first_page + index,
+            &page,
+            self.decryption_key.as_ref(),
+            self.zstd_dictionary.as_ref(),
+            self.read_options.max_decode_bytes,
+        )?;
+        Ok((page_meta.num_values, decoded.unwrap_or(page)))
+    }
+
+    /// Reads the bytes of the page at `index` as they are stored in the file, without
+    /// checking, decrypting or decompressing them. The reader is left at the same page and position.
+    pub fn read_raw_page(&mut self, index: usize) -> Result<Vec<u8>> {
+        let length = self.page_meta(index)?.length as usize;
+        let page_offset = |pages: &[PageMeta]| pages.iter().map(|page| page.length).sum::<u64>();
+        // the page is found from the start of the current page
+        let distance = if index >= self.current_page {
+            page_offset(&self.page_metas[self.current_page..index]) as i64
+        } else {
+            -(page_offset(&self.page_metas[index..self.current_page]) as i64)
+        };
+
+        let position = self.page_reader.stream_position()?;
+        let page = position
+            .checked_add_signed(distance)
+            .ok_or_else(|| Error::OutOfSpec(format!("Page {index} is before the column")))
+            .and_then(|page_start| {
+                self.page_reader.seek(SeekFrom::Start(page_start))?;
+                let mut page = vec![0u8; length];
+                self.page_reader.read_exact(&mut page)?;
+                Ok(page)
+            });
+        self.page_reader.seek(SeekFrom::Start(position))?;
+        page
+    }
+
+    fn page_meta(&self, index: usize) -> Result<&PageMeta> {
+        self.page_metas.get(index).ok_or_else(|| {
+            Error::InvalidArgumentError(format!(
+                "Page {index} is out of range, the column has {} pages",
+                self.page_metas.len()
+            ))
+        })
+    }
+
     /// Skips the next page
     pub fn skip_page(&mut self) -> Result<()> {
         if self.current_page == self.page_metas.len() {
@@ -143,40 +352,589 @@ impl<R: NativeReadBuf + std::io::Seek> NativeReader<R> {
         self.current_page += 1;
         Ok(())
     }
+
+    /// Skips the next `n` pages with a single seek over their summed lengths.
+    pub fn skip_n_pages(&mut self, n: usize) -> Result<()> {
+        assert!(n <= self.page_metas.len() - self.current_page);
+        let length = self.page_metas[self.current_page..self.current_page + n]
+            .iter()
+            .map(|page_meta| page_meta.length)
+            .sum::<u64>();
+        if length > 0 {
+            self.page_reader.seek(SeekFrom::Current(length as i64))?;
+        }
+        self.current_page += n;
+        Ok(())
+    }
+
+    /// Skips the next pages whose statistics show that none of their values match
+    /// `predicate`, stopping before the first page that may match.
+    /// Returns the number of skipped pages.
+    pub fn skip_pages_not_matching(&mut self, predicate: &PagePredicate) -> Result<usize> {
+        let mut skipped = 0;
+        while let Some(page_meta) = self.page_metas.get(self.current_page) {
+            if predicate.may_match(page_meta.statistics.as_ref()) {
+                break;
+            }
+            self.skip_page()?;
+            skipped += 1;
+        }
+        Ok(skipped)
+    }
+}
+
+/// Checks the bytes of the page at `index` against its checksum, if it has one.
+pub(crate) fn verify_page_checksum(page_meta: &PageMeta, index: usize, page: &[u8]) -> Result<()> {
+    match page_meta.checksum {
+        Some(checksum) if crc32fast::hash(page) != checksum => Err(Error::OutOfSpec(format!(
+            "Checksum mismatch of page {index}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// Errors if one of the pages is encrypted or compressed with the zstd dictionary
+/// of its column, for the reads of pages from their stored bytes.
+pub(crate) fn check_plaintext(page_metas: &[PageMeta]) -> Result<()> {
+    check_unencrypted(page_metas)?;
+    match page_metas.iter().position(|page| page.zstd_dictionary) {
+        Some(index) => Err(Error::InvalidArgumentError(format!(
+            "Page {index} is compressed with the zstd dictionary of its column, \
+             its column must be read with a NativeReader or read_column_pages"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Errors if one of the pages is encrypted, for the reads of pages that
+/// decompress the pages compressed with a zstd dictionary themselves.
+pub(crate) fn check_unencrypted(page_metas: &[PageMeta]) -> Result<()> {
+    match page_metas.iter().position(|page| page.encrypted) {
+        Some(index) => Err(Error::InvalidArgumentError(format!(
+            "Page {index} is encrypted, its column must be read with a NativeReader and its key"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Decrypts the stored bytes of the page at `index` of its column if it is encrypted,
+/// then decompresses them with the zstd dictionary of its column if it has one.
+/// Returns `None` if the stored bytes are the page.
+/// The pages of a column read with a key must all be encrypted, as the flag of a
+/// page in the footer is not authenticated.
+pub(crate) fn decode_page(
+    page_meta: &PageMeta,
+    index: usize,
+    page: &[u8],
+    key: Option<&ColumnKey>,
+    zstd_dictionary: Option<&ZstdDecoderDictionary>,
+    max_decode_bytes: Option<usize>,
+) -> Result<Option<Vec<u8>>> {
+    let decrypted = match (page_meta.encrypted, key) {
+        (true, key) => Some(decrypt_page(key, index, page)?),
+        (false, Some(key)) => {
+            return Err(Error::OutOfSpec(format!(
+                "Page {index} of the encrypted column {} is not encrypted",
+                key.column
+            )))
+        }
+        (false, None) => None,
+    };
+    match zstd_dictionary {
+        Some(dictionary) => decompress_page(
+            decrypted.as_deref().unwrap_or(page),
+            dictionary,
+            max_decode_bytes,
+        )
+        .map(Some),
+        None if page_meta.zstd_dictionary => Err(Error::InvalidArgumentError(format!(
+            "Page {index} is compressed with the zstd dictionary of its column, \
+             which the reader must be given"
+        ))),
+        None => Ok(decrypted),
+    }
+}
+
+/// The largest ratio of the decompressed size of a zstd frame to its size: its
+/// blocks decompress to at most 128 KiB and take at least 4 bytes.
+const ZSTD_MAX_RATIO: usize = 128 * 1024 / 4;
+
+/// Decompresses a page compressed with the zstd dictionary of its column.
+/// The size of the page, written before the frame, is checked against the
+/// largest size the frame can decompress to and `max_decode_bytes` before it
+/// is allocated, see [`ReadOptions::max_decode_bytes`].
+pub(crate) fn decompress_page(
+    compressed: &[u8],
+    dictionary: &ZstdDecoderDictionary,
+    max_decode_bytes: Option<usize>,
+) -> Result<Vec<u8>> {
+    if compressed.len() < 8 {
+        return Err(Error::OutOfSpec(
+            "Missing the size of the zstd dictionary page".to_string(),
+        ));
+    }
+    let size = u64::from_le_bytes(compressed[..8].try_into().unwrap());
+    let frame = &compressed[8..];
+    let max_size = frame.len().saturating_mul(ZSTD_MAX_RATIO);
+    if size > max_size as u64 {
+        return Err(Error::OutOfSpec(format!(
+            "The zstd dictionary page of {size} bytes exceeds the {max_size} bytes of its frame"
+        )));
+    }
+    let size = size as usize;
+    check_decode_size(size, max_decode_bytes)?;
+    let mut page = vec![0u8; size];
+    decompress_zstd_with_dict(frame, &mut page, dictionary)?;
+    Ok(page)
+}
+
+/// Reads all the pages of the column `meta`, decompressing them if the column has
+/// a zstd dictionary. The returned bytes and page metas can be read like any other
+/// column, e.g. by [`super::batch_read::batch_read_array`].
+/// The pages of an encrypted column are read with a [`NativeReader`] instead.
+pub fn read_column_pages<R: Read>(
+    reader: &mut R,
+    meta: &ColumnMeta,
+) -> Result<(Vec<u8>, Vec<PageMeta>)> {
+    check_unencrypted(&meta.pages)?;
+    let mut bytes = vec![0u8; meta.total_len() as usize];
+    reader.read_exact(&mut bytes)?;
+    let dictionary = match &meta.zstd_dictionary {
+        Some(dictionary) => ZstdDecoderDictionary::new(dictionary),
+        None => return Ok((bytes, meta.pages.clone())),
+    };
+
+    let mut pages = Vec::with_capacity(bytes.len());
+    let mut page_metas = Vec::with_capacity(meta.pages.len());
+    let mut compressed = bytes.as_slice();
+    for page_meta in &meta.pages {
+        let (page, rest) = compressed.split_at(page_meta.length as usize);
+        let page = decompress_page(page, &dictionary, None)?;
+        compressed = rest;
+        // the checksum is of the compressed page
+        page_metas.push(PageMeta {
+            length: page.len() as u64,
+            checksum: None,
+            zstd_dictionary: false,
+            ..page_meta.clone()
+        });
+        pages.extend_from_slice(&page);
+    }
+    Ok((pages, page_metas))
+}
+
+/// Checks that `eos` is the end-of-stream marker every file ends with, so that
+/// padded or concatenated files are rejected before any footer offset is trusted.
+fn check_eos(eos: &[u8]) -> Result<()> {
+    if eos[..4] != CONTINUATION_MARKER || eos[4..8] != [0u8; 4] {
+        return Err(Error::OutOfSpec(
+            "missing EOS marker at the end of the file".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// How the column metas of a footer are written, given by the flags of its meta size.
+#[derive(Debug, Clone, Copy)]
+struct MetaFormat {
+    /// The values are LEB128 encoded.
+    varint: bool,
+    /// The column metas end with the format version and the magic.
+    versioned: bool,
+}
+
+/// Splits the meta size of the tail into the size in bytes and
+/// the format of the column metas.
+fn split_meta_size(meta_size: u32) -> (usize, MetaFormat) {
+    (
+        (meta_size & !(VARINT_META_FLAG | VERSIONED_META_FLAG)) as usize,
+        MetaFormat {
+            varint: meta_size & VARINT_META_FLAG != 0,
+            versioned: meta_size & VERSIONED_META_FLAG != 0,
+        },
+    )
+}
+
+/// Checks the format version and the magic that end the column metas of a
+/// versioned footer, and returns the version. Footers without them are of version 0.
+fn check_footer_version(version_and_magic: &[u8]) -> Result<u16> {
+    if version_and_magic.len() != FOOTER_VERSION_SIZE || version_and_magic[2..] != ARROW_MAGIC[..] {
+        return Err(Error::OutOfSpec(
+            "missing strawboat magic in the footer, not a strawboat file".to_string(),
+        ));
+    }
+    let version = u16::from_le_bytes(version_and_magic[..2].try_into().unwrap());
+    if version > FORMAT_VERSION {
+        return Err(Error::OutOfSpec(format!(
+            "unsupported strawboat version {version}, the latest supported version is {FORMAT_VERSION}"
+        )));
+    }
+    Ok(version)
+}
+
+fn deserialize_meta(buf: Vec<u8>, format: MetaFormat) -> Result<Vec<ColumnMeta>> {
+    deserialize_footer_meta(buf, format)?
+        .single_row_group()
+        .map(|(metas, _)| metas)
+}
+
+/// The column metas of a footer and the numbers of rows that follow them.
+struct FooterMeta {
+    column_metas: Vec<ColumnMeta>,
+    /// `None` for the files written before it was recorded.
+    num_rows: Option<u64>,
+    /// The number of rows of each row group of a file with several row groups,
+    /// empty for the other files.
+    row_group_rows: Vec<u64>,
+}
+
+impl FooterMeta {
+    /// The column metas and the number of rows of a file with a single row group.
+    fn single_row_group(self) -> Result<(Vec<ColumnMeta>, Option<u64>)> {
+        if !self.row_group_rows.is_empty() {
+            return Err(Error::OutOfSpec(format!(
+                "The file has {} row groups, read their metas with `read_row_groups`",
+                self.row_group_rows.len()
+            )));
+        }
+        Ok((self.column_metas, self.num_rows))
+    }
+
+    /// Splits the column metas into the row groups. `schema_num_rows` gives the
+    /// number of rows of a file with a single row group written before it was recorded.
+    fn row_groups(
+        self,
+        schema_num_rows: impl FnOnce(&[ColumnMeta]) -> Option<u64>,
+    ) -> Result<Vec<RowGroupMeta>> {
+        if self.row_group_rows.is_empty() {
+            let num_rows = self
+                .num_rows
+                .or_else(|| schema_num_rows(&self.column_metas));
+            return Ok(vec![RowGroupMeta {
+                num_rows,
+                columns: self.column_metas,
+            }]);
+        }
+        let num_row_groups = self.row_group_rows.len();
+        if self.column_metas.len() % num_row_groups != 0 {
+            return Err(Error::OutOfSpec(format!(
+                "The {} column metas can't be split into {num_row_groups} row groups",
+                self.column_metas.len()
+            )));
+        }
+        let num_columns = self.column_metas.len() / num_row_groups;
+        let mut column_metas = self.column_metas.into_iter();
+        Ok(self
+            .row_group_rows
+            .into_iter()
+            .map(|num_rows| RowGroupMeta {
+                num_rows: Some(num_rows),
+                columns: column_metas.by_ref().take(num_columns).collect(),
+            })
+            .collect())
+    }
 }
 
-fn deserialize_meta(buf: Vec<u8>) -> Result<Vec<ColumnMeta>> {
+/// Deserializes the column metas and the numbers of rows that follow them.
+fn deserialize_footer_meta(mut buf: Vec<u8>, format: MetaFormat) -> Result<FooterMeta> {
+    // the footers written before the version only hold the offset and pages of the columns
+    let mut version = 0;
+    if format.versioned {
+        let metas_size = buf.len().checked_sub(FOOTER_VERSION_SIZE).ok_or_else(|| {
+            Error::OutOfSpec("missing strawboat version in the footer".to_string())
+        })?;
+        version = check_footer_version(&buf[metas_size..])?;
+        buf.truncate(metas_size);
+    }
+    let varint = format.varint;
     let mut buf_reader = std::io::Cursor::new(buf);
     let mut buf = vec![0u8; 8];
-    let meta_len = read_u64(&mut buf_reader, buf.as_mut_slice())?;
+    let mut read_value = |reader: &mut Cursor<Vec<u8>>| {
+        if varint {
+            read_uleb128(reader)
+        } else {
+            read_u64(reader, buf.as_mut_slice())
+        }
+    };
+    let meta_len = read_value(&mut buf_reader)?;
     let mut metas = Vec::with_capacity(meta_len as usize);
     for _i in 0..meta_len {
-        let offset = read_u64(&mut buf_reader, buf.as_mut_slice())?;
-        let page_num = read_u64(&mut buf_reader, buf.as_mut_slice())?;
-        let mut pages = Vec::with_capacity(page_num as usize);
-        for _p in 0..page_num {
-            let length = read_u64(&mut buf_reader, buf.as_mut_slice())?;
-            let num_values = read_u64(&mut buf_reader, buf.as_mut_slice())?;
+        metas.push(deserialize_column_meta(
+            &mut buf_reader,
+            version,
+            &mut read_value,
+        )?);
+    }
+    let has_more = |buf_reader: &Cursor<Vec<u8>>| {
+        (buf_reader.position() as usize) < buf_reader.get_ref().len()
+    };
+    let num_rows = if version > 0 && has_more(&buf_reader) {
+        Some(read_value(&mut buf_reader)?)
+    } else {
+        None
+    };
+    let mut row_group_rows = vec![];
+    if version > 0 && has_more(&buf_reader) {
+        let num_row_groups = read_value(&mut buf_reader)?;
+        for _ in 0..num_row_groups {
+            row_group_rows.push(read_value(&mut buf_reader)?);
+        }
+    }
+    Ok(FooterMeta {
+        column_metas: metas,
+        num_rows,
+        row_group_rows,
+    })
+}
+
+/// Deserializes a column meta of a footer of `version`, where the footers of
+/// version 0 only hold the offset of the column and the length and number of
+/// values of its pages.
+fn deserialize_column_meta(
+    buf_reader: &mut Cursor<Vec<u8>>,
+    version: u16,
+    read_value: &mut impl FnMut(&mut Cursor<Vec<u8>>) -> Result<u64>,
+) -> Result<ColumnMeta> {
+    let offset = read_value(buf_reader)?;
+    let page_num = read_value(buf_reader)?;
+    let mut pages = Vec::with_capacity(page_num as usize);
+    for _p in 0..page_num {
+        let length = read_value(buf_reader)?;
+        let num_values = read_value(buf_reader)?;
+        if version == 0 {
+            pages.push(PageMeta {
+                length,
+                num_values,
+                null_count: None,
+                statistics: None,
+                checksum: None,
+                encrypted: false,
+                zstd_dictionary: false,
+            });
+            continue;
+        }
+        let null_count = read_value(buf_reader)?;
+        let statistics = deserialize_statistics(buf_reader, read_value, "page")?;
+
+        let flag = read_value(buf_reader)?;
+        if flag > 7 {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid checksum flag {flag} in the page meta"
+            )));
+        }
+        let checksum = match flag & 1 {
+            1 => Some(read_value(buf_reader)? as u32),
+            _ => None,
+        };
+
+        pages.push(PageMeta {
+            length,
+            num_values,
+            null_count: (flag & 4 == 0).then_some(null_count),
+            statistics,
+            checksum,
+            encrypted: flag & 2 != 0,
+            zstd_dictionary: false,
+        });
+    }
+    if version == 0 {
+        return Ok(ColumnMeta {
+            offset,
+            pages,
+            zstd_dictionary: None,
+            dictionary: None,
+            bloom_filter: None,
+            null_count: None,
+            dominant_codec: Compression::default(),
+            codec_counts: vec![],
+            statistics: None,
+            sorted: false,
+            distinct_count: None,
+        });
+    }
+    let zstd_dictionary_len = read_value(buf_reader)? as usize;
+    let zstd_dictionary = if zstd_dictionary_len > 0 {
+        let mut zstd_dictionary = vec![0u8; zstd_dictionary_len];
+        buf_reader.read_exact(&mut zstd_dictionary)?;
+        // every page of the column is compressed with it
+        for page in &mut pages {
+            page.zstd_dictionary = true;
+        }
+        Some(zstd_dictionary)
+    } else {
+        None
+    };
+    let dictionary = match read_value(buf_reader)? {
+        0 => None,
+        1 => Some(Box::new(deserialize_column_meta(
+            buf_reader, version, read_value,
+        )?)),
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid dictionary flag {other} in the column meta"
+            )))
+        }
+    };
+    let bloom_filter = match read_value(buf_reader)? {
+        0 => None,
+        1 => Some(BloomFilter {
+            offset: read_value(buf_reader)?,
+            length: read_value(buf_reader)?,
+            bitset: vec![],
+        }),
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid bloom filter flag {other} in the column meta"
+            )))
+        }
+    };
+    let null_count = match read_value(buf_reader)? {
+        0 => None,
+        1 => Some(read_value(buf_reader)?),
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid null count flag {other} in the column meta"
+            )))
+        }
+    };
+    let dominant_codec = read_value(buf_reader)?;
+    let dominant_codec = u8::try_from(dominant_codec)
+        .map_err(|_| {
+            Error::OutOfSpec(format!(
+                "unsupported codec {dominant_codec}, upgrade strawboat"
+            ))
+        })
+        .and_then(Compression::from_codec)?;
+    let codec_counts = (0..read_value(buf_reader)?)
+        .map(|_| {
+            let codec = read_value(buf_reader)?;
+            let codec = u8::try_from(codec)
+                .map_err(|_| {
+                    Error::OutOfSpec(format!("unsupported codec {codec}, upgrade strawboat"))
+                })
+                .and_then(Compression::from_codec)?;
+            Ok((codec, read_value(buf_reader)?))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let statistics = deserialize_statistics(buf_reader, read_value, "column")?;
+    let sorted = match read_value(buf_reader)? {
+        0 => false,
+        1 => true,
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid sorted flag {other} in the column meta"
+            )))
+        }
+    };
+    let distinct_count = match read_value(buf_reader)? {
+        0 => None,
+        1 => Some(read_value(buf_reader)?),
+        other => {
+            return Err(Error::OutOfSpec(format!(
+                "Invalid distinct count flag {other} in the column meta"
+            )))
+        }
+    };
+    Ok(ColumnMeta {
+        offset,
+        pages,
+        zstd_dictionary,
+        dictionary,
+        bloom_filter,
+        null_count,
+        dominant_codec,
+        codec_counts,
+        statistics,
+        sorted,
+        distinct_count,
+    })
+}
 
-            pages.push(PageMeta { length, num_values });
+/// Reads the statistics flag of a page or column meta, and the min and max it announces.
+fn deserialize_statistics(
+    buf_reader: &mut Cursor<Vec<u8>>,
+    read_value: &mut impl FnMut(&mut Cursor<Vec<u8>>) -> Result<u64>,
+    meta: &str,
+) -> Result<Option<PageStatistics>> {
+    match read_value(buf_reader)? {
+        0 => Ok(None),
+        1 => {
+            let mut read_bound = |buf_reader: &mut Cursor<Vec<u8>>| -> Result<Vec<u8>> {
+                let mut bound = vec![0u8; read_value(buf_reader)? as usize];
+                buf_reader.read_exact(&mut bound)?;
+                Ok(bound)
+            };
+            let min = read_bound(buf_reader)?;
+            let max = read_bound(buf_reader)?;
+            Ok(Some(PageStatistics { min, max }))
         }
-        metas.push(ColumnMeta { offset, pages })
+        other => Err(Error::OutOfSpec(format!(
+            "Invalid statistics flag {other} in the {meta} meta"
+        ))),
+    }
+}
+
+/// Reads the column metas of a file from its footer. The bitsets of their bloom
+/// filters are loaded by [`read_bloom_filters`], as each is one more read.
+pub fn read_meta<Reader: RangeReader>(reader: &mut Reader) -> Result<Vec<ColumnMeta>> {
+    read_footer_meta(reader)
+}
+
+/// Reads the statistics of the columns of a file from its footer, in the order of
+/// [`read_meta`], without reading their pages or bloom filters.
+pub fn read_statistics<Reader: RangeReader>(reader: &mut Reader) -> Result<Vec<ColumnStatistics>> {
+    Ok(read_footer_meta(reader)?
+        .iter()
+        .map(ColumnMeta::column_statistics)
+        .collect())
+}
+
+/// Loads the bitsets of the bloom filters of `metas`, which the footer only locates,
+/// with one read per bloom filter.
+pub fn read_bloom_filters<Reader: RangeReader>(
+    reader: &mut Reader,
+    metas: &mut [ColumnMeta],
+) -> Result<()> {
+    for bloom_filter in metas
+        .iter_mut()
+        .filter_map(|meta| meta.bloom_filter.as_mut())
+    {
+        bloom_filter.bitset = reader
+            .read_range(bloom_filter.offset, bloom_filter.length as usize)?
+            .to_vec();
+    }
+    Ok(())
+}
+
+pub async fn read_bloom_filters_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
+    reader: &mut Reader,
+    metas: &mut [ColumnMeta],
+) -> Result<()> {
+    for bloom_filter in metas
+        .iter_mut()
+        .filter_map(|meta| meta.bloom_filter.as_mut())
+    {
+        reader.seek(SeekFrom::Start(bloom_filter.offset)).await?;
+        bloom_filter.bitset = vec![0u8; bloom_filter.length as usize];
+        reader.read_exact(&mut bloom_filter.bitset).await?;
     }
-    Ok(metas)
+    Ok(())
 }
 
-pub fn read_meta<Reader: Read + Seek>(reader: &mut Reader) -> Result<Vec<ColumnMeta>> {
+/// Reads the column metas of the footer.
+fn read_footer_meta<Reader: RangeReader>(reader: &mut Reader) -> Result<Vec<ColumnMeta>> {
+    let file_len = reader.file_len()?;
     // EOS(8 bytes) + meta_size(4 bytes) = 12 bytes
-    reader.seek(SeekFrom::End(-12))?;
-    let mut buf = vec![0u8; 4];
-    let meta_size = read_u32(reader, buf.as_mut_slice())? as usize;
-    reader.seek(SeekFrom::End(-16 - meta_size as i64))?;
+    let buf = read_tail(reader, file_len, 12)?;
+    check_eos(&buf[4..])?;
+    let (meta_size, format) = split_meta_size(u32::from_le_bytes(buf[0..4].try_into().unwrap()));
 
-    let mut meta_buf = vec![0u8; meta_size];
-    reader.read_exact(&mut meta_buf)?;
-    deserialize_meta(meta_buf)
+    let meta_buf = read_tail(reader, file_len, meta_size + 16)?;
+    deserialize_meta(meta_buf[..meta_size].to_vec(), format)
 }
 
+/// Reads the column metas of a file like [`read_meta`], their bloom filters are
+/// loaded by [`read_bloom_filters_async`].
 pub async fn read_meta_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
     reader: &mut Reader,
     total_len: Option<usize>,
@@ -193,15 +951,18 @@ pub async fn read_meta_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
             let mut footer_reader = std::io::Cursor::new(buf);
             // EOS(8 bytes) + meta_size(4 bytes) = 12 bytes
             footer_reader.seek(SeekFrom::End(-12))?;
-            let mut buf = vec![0u8; 4];
-            let meta_size = read_u32(&mut footer_reader, buf.as_mut_slice())? as usize;
+            let mut buf = vec![0u8; 12];
+            footer_reader.read_exact(&mut buf)?;
+            check_eos(&buf[4..])?;
+            let (meta_size, format) =
+                split_meta_size(u32::from_le_bytes(buf[0..4].try_into().unwrap()));
 
             let footer_size = meta_size + 16;
             if footer_size <= pre_read_len {
                 footer_reader.seek(SeekFrom::End(-16 - meta_size as i64))?;
                 let mut meta_buf = vec![0u8; meta_size];
                 footer_reader.read_exact(&mut meta_buf)?;
-                deserialize_meta(meta_buf)
+                deserialize_meta(meta_buf, format)
             } else {
                 // The readed data is not long enough to hold the meta data.
                 // Should read again.
@@ -209,34 +970,75 @@ pub async fn read_meta_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
                 let mut buf = vec![0u8; footer_size];
                 reader.read_exact(&mut buf).await?;
                 let mut final_reader = std::io::Cursor::new(buf);
-                read_meta(&mut final_reader)
+                read_footer_meta(&mut final_reader)
             }
         }
         _ => {
             reader.seek(SeekFrom::End(-12)).await?;
-            let mut buf = vec![0u8; 4];
-            let meta_size = read_u32_async(reader, buf.as_mut_slice()).await? as usize;
+            let mut buf = vec![0u8; 12];
+            reader.read_exact(&mut buf).await?;
+            check_eos(&buf[4..])?;
+            let (meta_size, format) =
+                split_meta_size(u32::from_le_bytes(buf[0..4].try_into().unwrap()));
             let mut meta_buf = vec![0u8; meta_size];
             reader.seek(SeekFrom::End(-16 - meta_size as i64)).await?;
             reader.read_exact(&mut meta_buf).await?;
-            deserialize_meta(meta_buf)
+            deserialize_meta(meta_buf, format)
         }
     }
 }
 
-pub fn infer_schema<Reader: Read + Seek>(reader: &mut Reader) -> Result<Schema> {
+/// Reads the version of the header of a file, which the layout of its pages depends
+/// on, see [`ReadOptions::format_version`]. The files written before the version was
+/// recorded have version 0.
+pub fn read_format_version<Reader: RangeReader>(reader: &mut Reader) -> Result<u16> {
+    let header = reader.read_range(0, ARROW_MAGIC.len() + 2)?;
+    if header[..ARROW_MAGIC.len()] != ARROW_MAGIC[..] {
+        return Err(Error::OutOfSpec(
+            "missing strawboat magic in the header, not a strawboat file".to_string(),
+        ));
+    }
+    let version = u16::from_le_bytes(header[ARROW_MAGIC.len()..].try_into().unwrap());
+    if version > SINGLE_ROW_GROUP_VERSION {
+        return Err(Error::OutOfSpec(format!(
+            "unsupported strawboat version {version} of the header, the latest supported version is {SINGLE_ROW_GROUP_VERSION}"
+        )));
+    }
+    Ok(version)
+}
+
+/// The [`ReadOptions::format_version`] of `read_options`, or the [`read_format_version`]
+/// of the file of `reader` if it is unknown, leaving `reader` at its position.
+/// `reader` seeks from the start of the file.
+pub(crate) fn file_format_version<R: Read + Seek>(
+    reader: &mut R,
+    read_options: &ReadOptions,
+) -> Result<u16> {
+    if let Some(version) = read_options.format_version {
+        return Ok(version);
+    }
+    let position = reader.stream_position()?;
+    let version = read_format_version(reader)?;
+    reader.seek(SeekFrom::Start(position))?;
+    Ok(version)
+}
+
+pub fn infer_schema<Reader: RangeReader>(reader: &mut Reader) -> Result<Schema> {
+    let file_len = reader.file_len()?;
     // EOS(8 bytes) + meta_size(4 bytes) + schema_size(4bytes) = 16 bytes
-    reader.seek(SeekFrom::End(-16))?;
-    let mut buf = vec![0u8; 4];
-    let schema_size = read_u32(reader, buf.as_mut_slice())? as usize;
-    let column_meta_size = read_u32(reader, buf.as_mut_slice())? as usize;
-
-    reader.seek(SeekFrom::Current(
-        -(column_meta_size as i64) - (schema_size as i64) - 8,
-    ))?;
-    let mut schema_bytes = vec![0u8; schema_size];
-    reader.read_exact(&mut schema_bytes)?;
-    let (schema, _) = deserialize_schema(&schema_bytes).expect("deserialize schema error");
+    let buf = read_tail(reader, file_len, 16)?;
+    check_eos(&buf[8..])?;
+    let schema_size = u32::from_le_bytes(buf[0..4].try_into().unwrap()) as usize;
+    let (column_meta_size, format) =
+        split_meta_size(u32::from_le_bytes(buf[4..8].try_into().unwrap()));
+
+    // the schema, then the column metas, which end with the version and magic
+    let footer = read_tail(reader, file_len, schema_size + column_meta_size + 16)?;
+    if format.versioned {
+        let metas_end = schema_size + column_meta_size;
+        check_footer_version(&footer[metas_end.saturating_sub(FOOTER_VERSION_SIZE)..metas_end])?;
+    }
+    let (schema, _) = deserialize_schema(&footer[..schema_size]).expect("deserialize schema error");
     Ok(schema)
 }
 
@@ -245,13 +1047,24 @@ pub async fn infer_schema_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
 ) -> Result<Schema> {
     // EOS(8 bytes) + meta_size(4 bytes) + schema_size(4bytes) = 16 bytes
     reader.seek(SeekFrom::End(-16)).await?;
-    let mut buf = vec![0u8; 4];
-    let schema_size = read_u32_async(reader, buf.as_mut_slice()).await? as usize;
-    let column_meta_size = read_u32_async(reader, buf.as_mut_slice()).await? as usize;
+    let mut buf = vec![0u8; 16];
+    reader.read_exact(&mut buf).await?;
+    check_eos(&buf[8..])?;
+    let schema_size = u32::from_le_bytes(buf[0..4].try_into().unwrap()) as usize;
+    let (column_meta_size, format) =
+        split_meta_size(u32::from_le_bytes(buf[4..8].try_into().unwrap()));
+    if format.versioned {
+        reader
+            .seek(SeekFrom::End(-16 - FOOTER_VERSION_SIZE as i64))
+            .await?;
+        let mut version_and_magic = [0u8; FOOTER_VERSION_SIZE];
+        reader.read_exact(&mut version_and_magic).await?;
+        check_footer_version(&version_and_magic)?;
+    }
 
     reader
-        .seek(SeekFrom::Current(
-            -(column_meta_size as i64) - (schema_size as i64) - 8,
+        .seek(SeekFrom::End(
+            -(column_meta_size as i64) - (schema_size as i64) - 16,
         ))
         .await?;
     let mut schema_bytes = vec![0u8; schema_size];
@@ -260,3 +1073,175 @@ pub async fn infer_schema_async<Reader: AsyncRead + AsyncSeek + Send + Unpin>(
     let (schema, _) = deserialize_schema(&schema_bytes).expect("deserialize schema error");
     Ok(schema)
 }
+
+/// The footer of a strawboat file, which is enough to catalog the file
+/// without reading any page body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogEntry {
+    pub schema: Schema,
+    /// Number of rows, recorded in the footer. For the files written before it
+    /// was recorded, taken from the first non-nested column, and `None` if every
+    /// column of such a file is nested.
+    pub num_rows: Option<u64>,
+    /// The metas of the leaf columns, with their number of pages per encoding
+    /// in [`ColumnMeta::codec_counts`].
+    pub column_metas: Vec<ColumnMeta>,
+}
+
+impl CatalogEntry {
+    /// The number of pages per encoding of all the columns, in the order the
+    /// encodings were first used.
+    pub fn codec_histogram(&self) -> Vec<(Compression, u64)> {
+        let mut histogram: Vec<(Compression, u64)> = vec![];
+        for (codec, pages) in self.column_metas.iter().flat_map(|meta| &meta.codec_counts) {
+            match histogram.iter_mut().find(|(c, _)| c == codec) {
+                Some((_, count)) => *count += pages,
+                None => histogram.push((*codec, *pages)),
+            }
+        }
+        histogram
+    }
+}
+
+/// Reads the schema and column metas of a file with two reads,
+/// one for the fixed size tail and one for the whole footer.
+/// The bitsets of the bloom filters are loaded by [`read_bloom_filters`].
+pub fn read_catalog_entry<Reader: RangeReader>(reader: &mut Reader) -> Result<CatalogEntry> {
+    read_footer(reader)
+}
+
+/// Reads the number of rows of a file from its footer, with two reads.
+/// See [`CatalogEntry::num_rows`] for the files written before it was recorded.
+/// The rows of all the row groups are counted.
+pub fn infer_row_count<Reader: RangeReader>(reader: &mut Reader) -> Result<Option<u64>> {
+    let (_, row_groups) = read_footer_row_groups(reader)?;
+    Ok(row_groups.iter().map(|row_group| row_group.num_rows).sum())
+}
+
+/// Reads the metas of the row groups of a file from its footer, the bitsets of
+/// the bloom filters of their columns are loaded by [`read_bloom_filters`].
+/// The columns of each row group are read like the columns of a file with a single
+/// row group, which is returned as such.
+pub fn read_row_groups<Reader: RangeReader>(reader: &mut Reader) -> Result<Vec<RowGroupMeta>> {
+    let (_, row_groups) = read_footer_row_groups(reader)?;
+    Ok(row_groups)
+}
+
+/// Reads the schema and the metas of the row groups of the footer.
+pub(crate) fn read_footer_row_groups<Reader: RangeReader>(
+    reader: &mut Reader,
+) -> Result<(Schema, Vec<RowGroupMeta>)> {
+    let (schema, footer_meta) = read_footer_meta_and_schema(reader)?;
+    let row_groups = footer_meta.row_groups(|metas| schema_num_rows(&schema, metas))?;
+    Ok((schema, row_groups))
+}
+
+/// Reads the schema and the column metas of the footer.
+fn read_footer<Reader: RangeReader>(reader: &mut Reader) -> Result<CatalogEntry> {
+    let (schema, footer_meta) = read_footer_meta_and_schema(reader)?;
+    let (column_metas, num_rows) = footer_meta.single_row_group()?;
+    let num_rows = num_rows.or_else(|| schema_num_rows(&schema, &column_metas));
+    Ok(CatalogEntry {
+        schema,
+        num_rows,
+        column_metas,
+    })
+}
+
+/// The number of rows of the first non-nested column, `None` if every column is nested.
+fn schema_num_rows(schema: &Schema, column_metas: &[ColumnMeta]) -> Option<u64> {
+    let mut leaf_index = 0;
+    for field in schema.fields.iter() {
+        if is_primitive(field.data_type()) {
+            return column_metas
+                .get(leaf_index)
+                .map(|meta| meta.pages.iter().map(|page| page.num_values).sum::<u64>());
+        }
+        leaf_index += n_columns(field.data_type());
+    }
+    None
+}
+
+fn read_footer_meta_and_schema<Reader: RangeReader>(
+    reader: &mut Reader,
+) -> Result<(Schema, FooterMeta)> {
+    let file_len = reader.file_len()?;
+    // EOS(8 bytes) + meta_size(4 bytes) + schema_size(4bytes) = 16 bytes
+    let tail = read_tail(reader, file_len, 16)?;
+    check_eos(&tail[8..])?;
+    let schema_size = u32::from_le_bytes(tail[0..4].try_into().unwrap()) as usize;
+    let (meta_size, format) = split_meta_size(u32::from_le_bytes(tail[4..8].try_into().unwrap()));
+
+    let footer = read_tail(reader, file_len, schema_size + meta_size + 16)?;
+    let meta_buf = footer[schema_size..schema_size + meta_size].to_vec();
+
+    let (schema, _) = deserialize_schema(&footer[..schema_size])?;
+    Ok((schema, deserialize_footer_meta(meta_buf, format)?))
+}
+
+/// The leaf columns of a top level field selected by [`project_columns`].
+#[derive(Debug, Clone)]
+pub struct ProjectedColumn {
+    /// Index of the field in the schema.
+    pub index: usize,
+    pub field: Field,
+    /// The metas of the leaf columns of the field, in the order of `leaves`.
+    pub metas: Vec<ColumnMeta>,
+    pub leaves: Vec<ColumnDescriptor>,
+}
+
+/// Selects the leaf columns of the top level fields of `schema` at `projection`,
+/// given the `metas` of every leaf column of the file.
+/// A nested field spans `n_columns` leaf columns, so the metas and descriptors of
+/// each [`ProjectedColumn`] line up with its field.
+pub fn project_columns(
+    schema: &Schema,
+    metas: &[ColumnMeta],
+    projection: &[usize],
+) -> Result<Vec<ProjectedColumn>> {
+    let schema_descriptor = to_parquet_schema(schema)?;
+    let leaves = schema_descriptor.columns();
+    if leaves.len() != metas.len() {
+        return Err(Error::OutOfSpec(format!(
+            "The schema has {} leaf columns but the file has {} column metas",
+            leaves.len(),
+            metas.len()
+        )));
+    }
+
+    let mut starts = Vec::with_capacity(schema.fields.len());
+    let mut leaf_index = 0;
+    for field in schema.fields.iter() {
+        starts.push(leaf_index);
+        leaf_index += n_columns(field.data_type());
+    }
+
+    projection
+        .iter()
+        .map(|&index| {
+            let field = schema.fields.get(index).ok_or_else(|| {
+                Error::InvalidArgumentError(format!(
+                    "Projected field {index} is out of bounds, the schema has {} fields",
+                    schema.fields.len()
+                ))
+            })?;
+            let start = starts[index];
+            let end = start + n_columns(field.data_type());
+            Ok(ProjectedColumn {
+                index,
+                field: field.clone(),
+                metas: metas[start..end].to_vec(),
+                leaves: leaves[start..end].to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Reads the schema and the column metas of the top level fields at `projection`.
+pub fn read_meta_projected<Reader: RangeReader>(
+    reader: &mut Reader,
+    projection: &[usize],
+) -> Result<Vec<ProjectedColumn>> {
+    let entry = read_catalog_entry(reader)?;
+    project_columns(&entry.schema, &entry.column_metas, projection)
+}
@@ -15,10 +15,17 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::{compression::Compression, read::PageIterator, CommonCompression};
+use crate::{
+    compression::{
+        binary::NESTED_EXCEPTIONS_FLAG, decompress_encoding, integer::SORTED_DICT_FLAG,
+        split_codec, Compression,
+    },
+    read::PageIterator,
+    CommonCompression, COMPRESS_HEADER_SIZE,
+};
 use arrow::{
     datatypes::{Field, PhysicalType},
-    error::Result,
+    error::{Error, Result},
     types::PrimitiveType,
 };
 
@@ -30,9 +37,9 @@ pub struct ColumnInfo {
 
 #[derive(Debug)]
 pub struct PageInfo {
-    pub validity_size: Option<u32>,
-    pub compressed_size: u32,
-    pub uncompressed_size: u32,
+    pub validity_size: Option<u64>,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
     pub body: PageBody,
 }
 
@@ -45,7 +52,18 @@ pub enum PageBody {
     Patas,
     Bitpack,
     DeltaBitpack,
+    Fsst,
+    IncrementalBinary,
+    Gorilla,
+    For,
+    RestartPoints,
+    ZigzagDelta,
+    AllNull,
+    Chimp,
+    DeltaDelta,
     Common(CommonCompression),
+    /// A codec of [`crate::compression::registry::CUSTOM_CODECS`].
+    Custom(u8),
 }
 
 #[derive(Debug)]
@@ -56,24 +74,33 @@ pub struct FreqPageBody {
 
 #[derive(Debug)]
 pub struct DictPageBody {
+    /// The width in bytes of the indices, 1, 2 or 4.
+    pub index_width: u8,
     pub indices: Box<PageInfo>,
     pub unique_num: u32,
 }
 
-pub fn stat_simple<'a, I: 'a>(reader: I, field: Field) -> Result<ColumnInfo>
+pub fn stat_simple<'a, I: 'a>(mut reader: I, field: Field) -> Result<ColumnInfo>
 where
     I: Iterator<Item = Result<(u64, Vec<u8>)>> + PageIterator + Send + Sync,
 {
     let mut pages = vec![];
-    for compressed in reader {
+    while let Some(compressed) = reader.next() {
         let (_, buffer) = compressed?;
+        // the version may be read with the first page
+        if reader.read_options().page_format_version()? == 0 {
+            return Err(Error::NotYetImplemented(
+                "Collecting the statistics of the pages of a file of version 0".to_string(),
+            ));
+        }
 
         let mut buffer = buffer.as_slice();
         let mut opt_validity_size = None;
         if field.is_nullable {
-            let validity_size = u32::from_le_bytes(buffer[0..4].try_into().unwrap());
-            buffer = &buffer[4 + validity_size as usize..];
-            opt_validity_size = Some(u32::from_le_bytes(buffer[0..4].try_into().unwrap()));
+            // the def levels are compressed, after a codec byte and their sizes
+            let validity_size = u64::from_le_bytes(buffer[1..9].try_into().unwrap());
+            buffer = &buffer[COMPRESS_HEADER_SIZE + validity_size as usize..];
+            opt_validity_size = Some(validity_size);
         };
 
         let physical_type = field.data_type.to_physical_type();
@@ -85,24 +112,48 @@ where
 
 fn stat_body(
     buffer: &mut &[u8],
-    opt_validity_size: Option<u32>,
+    opt_validity_size: Option<u64>,
     physical_type: PhysicalType,
 ) -> Result<PageInfo> {
+    if buffer.len() < COMPRESS_HEADER_SIZE {
+        return Err(Error::OutOfSpec(
+            "Missing the header of a compressed buffer".to_string(),
+        ));
+    }
     let codec = buffer[0];
-    let compressed_size = u32::from_le_bytes(buffer[1..5].try_into().unwrap());
-    let uncompressed_size = u32::from_le_bytes(buffer[5..9].try_into().unwrap());
-    let codec = Compression::from_codec(codec)?;
-    *buffer = &buffer[9..];
+    let compressed_size = u64::from_le_bytes(buffer[1..9].try_into().unwrap());
+    let uncompressed_size = u64::from_le_bytes(buffer[9..17].try_into().unwrap());
+    let (codec, compressed_encoding) = split_codec(codec)?;
+    *buffer = &buffer[COMPRESS_HEADER_SIZE..];
+    if compressed_size > buffer.len() as u64 {
+        return Err(Error::OutOfSpec(format!(
+            "The compressed size {compressed_size} exceeds the {} bytes left in the page",
+            buffer.len()
+        )));
+    }
 
     let body = match codec {
         Compression::Rle => PageBody::Rle,
-        Compression::Dict => stat_dict_body(buffer, physical_type)?,
+        Compression::Dict if compressed_encoding => {
+            stat_dict_body(&decompress_encoding(&buffer[..compressed_size as usize])?)?
+        }
+        Compression::Dict => stat_dict_body(buffer)?,
         Compression::OneValue => PageBody::OneValue,
         Compression::Freq => stat_freq_body(buffer, physical_type)?,
         Compression::Bitpacking => PageBody::Bitpack,
         Compression::DeltaBitpacking => PageBody::DeltaBitpack,
         Compression::Patas => PageBody::Patas,
-        _ => PageBody::Common(CommonCompression::try_from(&codec).unwrap()),
+        Compression::Fsst => PageBody::Fsst,
+        Compression::IncrementalBinary => PageBody::IncrementalBinary,
+        Compression::Gorilla => PageBody::Gorilla,
+        Compression::For => PageBody::For,
+        Compression::RestartPoints => PageBody::RestartPoints,
+        Compression::ZigzagDelta => PageBody::ZigzagDelta,
+        Compression::AllNull => PageBody::AllNull,
+        Compression::Chimp => PageBody::Chimp,
+        Compression::DeltaDelta => PageBody::DeltaDelta,
+        Compression::Custom(codec) => PageBody::Custom(codec),
+        _ => PageBody::Common(CommonCompression::try_from(&codec)?),
     };
     *buffer = &buffer[compressed_size as usize..];
     Ok(PageInfo {
@@ -133,8 +184,17 @@ fn stat_freq_body(mut buffer: &[u8], physical_type: PhysicalType) -> Result<Page
             let len = u64::from_le_bytes(buffer[0..8].try_into().unwrap());
             buffer = &buffer[8 + len as usize..];
             let exceptions_bitmap_size = u32::from_le_bytes(buffer[0..4].try_into().unwrap());
+            // the exceptions of older pages are not a nested page
+            let nested_exceptions = exceptions_bitmap_size & NESTED_EXCEPTIONS_FLAG != 0;
+            let exceptions_bitmap_size = exceptions_bitmap_size & !NESTED_EXCEPTIONS_FLAG;
+            buffer = &buffer[4 + exceptions_bitmap_size as usize..];
+            let exceptions = if nested_exceptions {
+                Some(Box::new(stat_body(&mut buffer, None, physical_type)?))
+            } else {
+                None
+            };
             Ok(PageBody::Freq(FreqPageBody {
-                exceptions: None,
+                exceptions,
                 exceptions_bitmap_size,
             }))
         }
@@ -142,10 +202,23 @@ fn stat_freq_body(mut buffer: &[u8], physical_type: PhysicalType) -> Result<Page
     }
 }
 
-fn stat_dict_body(mut buffer: &[u8], physical_type: PhysicalType) -> Result<PageBody> {
-    let indices = stat_body(&mut buffer, None, physical_type)?;
-    let unique_num = u32::from_le_bytes(buffer[0..4].try_into().unwrap());
+fn stat_dict_body(mut buffer: &[u8]) -> Result<PageBody> {
+    let index_width = buffer[0];
+    let index_type = match index_width {
+        1 => PrimitiveType::UInt8,
+        2 => PrimitiveType::UInt16,
+        4 => PrimitiveType::UInt32,
+        _ => {
+            return Err(Error::OutOfSpec(format!(
+                "invalid dictionary index width {index_width}"
+            )))
+        }
+    };
+    buffer = &buffer[1..];
+    let indices = stat_body(&mut buffer, None, PhysicalType::Primitive(index_type))?;
+    let unique_num = u32::from_le_bytes(buffer[0..4].try_into().unwrap()) & !SORTED_DICT_FLAG;
     Ok(PageBody::Dict(DictPageBody {
+        index_width,
         indices: Box::new(indices),
         unique_num,
     }))
@@ -163,7 +236,7 @@ fn size_of_primitive(p: PrimitiveType) -> usize {
         PrimitiveType::UInt16 => 2,
         PrimitiveType::UInt32 => 4,
         PrimitiveType::UInt64 => 8,
-        PrimitiveType::Float16 => unimplemented!(),
+        PrimitiveType::Float16 => 2,
         PrimitiveType::Float32 => 4,
         PrimitiveType::Float64 => 8,
         PrimitiveType::DaysMs => unimplemented!(),
@@ -176,16 +249,19 @@ mod test {
     use std::io::BufRead;
 
     use arrow::{
-        array::{Array, BinaryArray},
+        array::{Array, BinaryArray, BooleanArray, Float64Array, Int32Array, Int64Array},
         chunk::Chunk,
         datatypes::{Field, Schema},
     };
 
     use crate::{
+        compression::Compression,
         read::reader::{is_primitive, NativeReader},
+        read::ReadOptions,
         stat::PageBody,
         util::env::{remove_all_env, set_dict_env, set_freq_env},
         write::{NativeWriter, WriteOptions},
+        SINGLE_ROW_GROUP_VERSION,
     };
 
     use super::{stat_simple, ColumnInfo};
@@ -195,12 +271,20 @@ mod test {
     const COLUMN_SIZE: usize = PAGE_SIZE * PAGE_PER_COLUMN;
 
     fn write_and_stat_simple_column(array: Box<dyn Array>) -> ColumnInfo {
+        write_and_stat_simple_column_with_forbidden(array, vec![])
+    }
+
+    fn write_and_stat_simple_column_with_forbidden(
+        array: Box<dyn Array>,
+        forbidden_compressions: Vec<Compression>,
+    ) -> ColumnInfo {
         assert!(is_primitive(array.data_type()));
         let options = WriteOptions {
             default_compression: crate::CommonCompression::Lz4,
             max_page_size: Some(PAGE_SIZE),
             default_compress_ratio: Some(1.2),
-            forbidden_compressions: vec![],
+            forbidden_compressions,
+            ..Default::default()
         };
 
         let mut bytes = Vec::new();
@@ -221,7 +305,11 @@ mod test {
         let mut range_bytes = std::io::Cursor::new(bytes.clone());
         range_bytes.consume(meta.offset as usize);
 
-        let native_reader = NativeReader::new(range_bytes, meta.pages, vec![]);
+        let native_reader =
+            NativeReader::new(range_bytes, meta.pages, vec![]).with_read_options(ReadOptions {
+                format_version: Some(SINGLE_ROW_GROUP_VERSION),
+                ..Default::default()
+            });
         stat_simple(native_reader, field).unwrap()
     }
 
@@ -246,6 +334,7 @@ mod test {
             match p.body {
                 PageBody::Dict(dict) => {
                     assert_eq!(dict.unique_num, 1);
+                    assert_eq!(dict.index_width, 1);
                     assert_eq!(dict.indices.validity_size, None);
                     assert!(matches!(dict.indices.body, PageBody::OneValue));
                 }
@@ -261,10 +350,136 @@ mod test {
             assert_eq!(p.validity_size, None);
             match p.body {
                 PageBody::Freq(freq) => {
-                    assert!(freq.exceptions.is_none());
+                    let exceptions = freq.exceptions.unwrap();
+                    assert_eq!(exceptions.validity_size, None);
                 }
                 _ => panic!("expect freq page"),
             }
         }
     }
+
+    #[test]
+    fn test_stat_page_codecs() {
+        remove_all_env();
+        // constant, then ascending, then scattered values
+        let values = (0..COLUMN_SIZE as i64).map(|i| match i as usize / PAGE_SIZE {
+            0..=2 => 7,
+            3..=5 => i * 3,
+            _ => (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) as i64,
+        });
+        let array = Box::new(Int64Array::from_values(values));
+        let column_info = write_and_stat_simple_column(array);
+        assert_eq!(column_info.pages.len(), PAGE_PER_COLUMN);
+        for (i, p) in column_info.pages.iter().enumerate() {
+            match i {
+                0..=2 => assert!(matches!(p.body, PageBody::OneValue)),
+                3..=5 => assert!(matches!(
+                    p.body,
+                    PageBody::ZigzagDelta | PageBody::DeltaDelta
+                )),
+                _ => assert!(matches!(p.body, PageBody::Common(_))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_stat_one_value_with_nulls() {
+        remove_all_env();
+        let validity = (0..COLUMN_SIZE).map(|i| i % 3 != 2);
+        let arrays: Vec<Box<dyn Array>> = vec![
+            Box::new(Int32Array::from_iter(
+                validity.clone().map(|v| v.then_some(5)),
+            )),
+            Box::new(Float64Array::from_iter(
+                validity.clone().map(|v| v.then_some(5.0)),
+            )),
+            Box::new(BinaryArray::<i32>::from_iter(
+                validity.clone().map(|v| v.then_some("a")),
+            )),
+            Box::new(BooleanArray::from_iter(validity.map(|v| v.then_some(true)))),
+        ];
+        for array in arrays {
+            let column_info = write_and_stat_simple_column(array);
+            assert_eq!(column_info.pages.len(), 10);
+            for p in column_info.pages {
+                assert!(p.validity_size.is_some());
+                assert!(matches!(p.body, PageBody::OneValue));
+            }
+        }
+    }
+
+    #[test]
+    fn test_stat_boolean() {
+        remove_all_env();
+        // constant, then long runs, then scattered values
+        let values = (0..COLUMN_SIZE).map(|i| match i / PAGE_SIZE {
+            0..=2 => true,
+            3..=5 => i / 256 % 2 == 0,
+            _ => (i as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 63 == 0,
+        });
+        let array = Box::new(BooleanArray::from_iter(values.map(Some)));
+        let column_info = write_and_stat_simple_column(array);
+        assert_eq!(column_info.pages.len(), PAGE_PER_COLUMN);
+        for (i, p) in column_info.pages.iter().enumerate() {
+            assert_eq!(p.validity_size, None);
+            match i {
+                0..=2 => assert!(matches!(p.body, PageBody::OneValue)),
+                3..=5 => assert!(matches!(p.body, PageBody::Rle)),
+                _ => assert!(matches!(p.body, PageBody::Common(_))),
+            }
+        }
+    }
+
+    #[test]
+    fn test_stat_binary() {
+        remove_all_env();
+        // 300 distinct values, the indices of the dictionary take 2 bytes
+        let values = (0..COLUMN_SIZE)
+            .map(|i| (i % PAGE_SIZE % 7 != 3).then(|| format!("value_{}", i % 300)));
+        let array = Box::new(BinaryArray::<i32>::from_iter(values));
+        let forbidden = Compression::ALL
+            .into_iter()
+            .filter(|c| !c.raw_mode() && *c != Compression::Dict)
+            .collect();
+        let column_info = write_and_stat_simple_column_with_forbidden(array, forbidden);
+        assert_eq!(column_info.pages.len(), PAGE_PER_COLUMN);
+        for p in column_info.pages {
+            assert!(p.validity_size.is_some());
+            match p.body {
+                PageBody::Dict(dict) => {
+                    assert_eq!(dict.unique_num, 300);
+                    assert_eq!(dict.index_width, 2);
+                    assert_eq!(dict.indices.validity_size, None);
+                }
+                other => panic!("expect dict page, got {other:?}"),
+            }
+        }
+
+        // a single value but for a few pages
+        let values = (0..COLUMN_SIZE).map(|i| match i / PAGE_SIZE {
+            0 => format!("{i}"),
+            _ => "same".to_string(),
+        });
+        let array = Box::new(BinaryArray::<i64>::from_iter_values(values));
+        let column_info = write_and_stat_simple_column(array);
+        assert!(!matches!(column_info.pages[0].body, PageBody::OneValue));
+        for p in &column_info.pages[1..] {
+            assert!(matches!(p.body, PageBody::OneValue));
+        }
+    }
+
+    #[test]
+    fn test_stat_compressed_validity() {
+        remove_all_env();
+        // a single null per page, the def levels are almost all ones
+        let array = Box::new(Int32Array::from_iter(
+            (0..COLUMN_SIZE).map(|i| (i % PAGE_SIZE != 0).then_some(i as i32)),
+        ));
+        let column_info = write_and_stat_simple_column(array);
+        assert_eq!(column_info.pages.len(), 10);
+        for p in column_info.pages {
+            // the raw def levels take a bit per value
+            assert!(p.validity_size.unwrap() < (PAGE_SIZE / 8 / 4) as u64);
+        }
+    }
 }
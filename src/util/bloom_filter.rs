@@ -0,0 +1,32 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+/// Fixed seeds of the bloom filter hash, the filters are persisted so the hash
+/// must not depend on the process like the `DictMap` one does.
+const BLOOM_FILTER_SEEDS: [u64; 4] = [
+    0x243f_6a88_85a3_08d3,
+    0x1319_8a2e_0370_7344,
+    0xa409_3822_299f_31d0,
+    0x082e_fa98_ec4e_6c89,
+];
+
+/// The hash of a value inserted in a column bloom filter, `value` is
+/// the little endian bytes of a primitive value or the bytes of a binary value.
+pub(crate) fn bloom_filter_hash(value: &[u8]) -> u64 {
+    let [k0, k1, k2, k3] = BLOOM_FILTER_SEEDS;
+    ahash::RandomState::with_seeds(k0, k1, k2, k3).hash_one(value)
+}
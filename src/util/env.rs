@@ -50,6 +50,15 @@ pub fn check_patas_env() -> bool {
     check_env(PATAS_ENV)
 }
 
+/// Whether any of the environment variables forcing an encoding is set.
+pub fn check_encoding_env() -> bool {
+    check_dict_env()
+        || check_rle_env()
+        || check_freq_env()
+        || check_bitpack_env()
+        || check_patas_env()
+}
+
 pub fn remove_env(env: &str) {
     env::remove_var(env);
 }
@@ -0,0 +1,61 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Half floats are written as their raw bits, through the integer compressions.
+
+use arrow::array::{Array, PrimitiveArray};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::error::Result;
+use arrow::io::parquet::write::{self, SchemaDescriptor};
+use arrow::types::f16;
+
+pub(crate) fn f16_to_bits(array: &PrimitiveArray<f16>) -> PrimitiveArray<u16> {
+    let values: Vec<u16> = array.values().iter().map(|v| v.to_bits()).collect();
+    PrimitiveArray::new(DataType::UInt16, values.into(), array.validity().cloned())
+}
+
+pub(crate) fn bits_to_f16(array: Box<dyn Array>, data_type: DataType) -> Result<Box<dyn Array>> {
+    let array: &PrimitiveArray<u16> = array.as_any().downcast_ref().unwrap();
+    let values: Vec<f16> = array.values().iter().map(|v| f16::from_bits(*v)).collect();
+    let array = PrimitiveArray::try_new(data_type, values.into(), array.validity().cloned())?;
+    Ok(Box::new(array) as Box<dyn Array>)
+}
+
+/// Converts a [`Schema`] to the parquet [`SchemaDescriptor`] the columns are written and read with.
+/// Unlike `arrow::io::parquet::write::to_parquet_schema`, this accepts `Float16` fields.
+pub fn to_parquet_schema(schema: &Schema) -> Result<SchemaDescriptor> {
+    let fields: Vec<Field> = schema.fields.iter().map(half_as_bits_field).collect();
+    write::to_parquet_schema(&Schema::from(fields))
+}
+
+fn half_as_bits_field(field: &Field) -> Field {
+    let data_type = match field.data_type() {
+        DataType::Float16 => DataType::UInt16,
+        DataType::List(inner) => DataType::List(Box::new(half_as_bits_field(inner))),
+        DataType::LargeList(inner) => DataType::LargeList(Box::new(half_as_bits_field(inner))),
+        DataType::FixedSizeList(inner, size) => {
+            DataType::FixedSizeList(Box::new(half_as_bits_field(inner)), *size)
+        }
+        DataType::Map(inner, sorted) => DataType::Map(Box::new(half_as_bits_field(inner)), *sorted),
+        DataType::Struct(fields) => {
+            DataType::Struct(fields.iter().map(half_as_bits_field).collect())
+        }
+        other => other.clone(),
+    };
+    Field::new(field.name.clone(), data_type, field.is_nullable)
+        .with_metadata(field.metadata.clone())
+}
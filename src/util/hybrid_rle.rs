@@ -0,0 +1,118 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::Write;
+
+use parquet2::encoding::{ceil8, hybrid_rle::encode_u32, uleb128};
+
+/// Levels repeated at least this many times are written as a run.
+const MIN_RUN_LENGTH: usize = 8;
+
+/// Encodes `levels` of `num_bits` bits with the hybrid RLE / bit-packing of parquet,
+/// which [`parquet2::encoding::hybrid_rle::HybridRleDecoder`] decodes: the levels
+/// repeated at least 8 times are written as runs, the others bit-packed in groups of 8.
+pub(crate) fn encode_hybrid_rle<W: Write>(
+    writer: &mut W,
+    levels: &[u32],
+    num_bits: u32,
+) -> std::io::Result<()> {
+    let mut packed_start = 0;
+    let mut i = 0;
+    while i < levels.len() {
+        let run = levels[i..]
+            .iter()
+            .take_while(|level| **level == levels[i])
+            .count();
+        // a bit-packed run continues until its last group of 8 levels is full
+        let partial_group = (i - packed_start) % MIN_RUN_LENGTH;
+        if partial_group != 0 {
+            i += run.min(MIN_RUN_LENGTH - partial_group);
+            continue;
+        }
+        if run < MIN_RUN_LENGTH {
+            i += run;
+            continue;
+        }
+        if packed_start < i {
+            encode_u32(writer, levels[packed_start..i].iter().copied(), num_bits)?;
+        }
+        write_run(writer, levels[i], run, num_bits)?;
+        i += run;
+        packed_start = i;
+    }
+    if packed_start < levels.len() {
+        encode_u32(writer, levels[packed_start..].iter().copied(), num_bits)?;
+    }
+    Ok(())
+}
+
+fn write_run<W: Write>(
+    writer: &mut W,
+    level: u32,
+    run: usize,
+    num_bits: u32,
+) -> std::io::Result<()> {
+    let mut header = [0u8; 10];
+    let used = uleb128::encode((run as u64) << 1, &mut header);
+    writer.write_all(&header[..used])?;
+    writer.write_all(&level.to_le_bytes()[..ceil8(num_bits as usize)])
+}
+
+#[cfg(test)]
+mod tests {
+    use parquet2::encoding::hybrid_rle::{encode_u32, HybridRleDecoder};
+
+    use super::encode_hybrid_rle;
+
+    #[test]
+    fn test_encode_hybrid_rle() {
+        let cases: Vec<(Vec<u32>, u32)> = vec![
+            (vec![], 1),
+            (vec![1; 5], 1),
+            (vec![3; 1000], 2),
+            ((0..100).map(|i| i % 3).collect(), 2),
+            // runs that start in the middle of a bit-packed group
+            ((0..1000).map(|i| u32::from(i % 100 > 3)).collect(), 1),
+            ((0..1000).map(|i| (i / 13) % 5).collect(), 3),
+            (
+                (0..777)
+                    .map(|i| if i % 50 < 30 { 9 } else { i % 7 })
+                    .collect(),
+                4,
+            ),
+            (vec![300; 20], 9),
+        ];
+        for (levels, num_bits) in cases {
+            let mut encoded = vec![];
+            encode_hybrid_rle(&mut encoded, &levels, num_bits).unwrap();
+            let decoded = HybridRleDecoder::try_new(&encoded, num_bits, levels.len())
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(decoded, levels);
+        }
+
+        // a long run takes a couple of bytes instead of a bit per level
+        let levels = vec![1; 4096];
+        let mut encoded = vec![];
+        encode_hybrid_rle(&mut encoded, &levels, 1).unwrap();
+        let mut packed = vec![];
+        encode_u32(&mut packed, levels.iter().copied(), 1).unwrap();
+        assert_eq!(encoded.len(), 3);
+        assert!(packed.len() > 512);
+    }
+}
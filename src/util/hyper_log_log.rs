@@ -0,0 +1,94 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use super::bloom_filter_hash;
+
+/// The number of bits of the hash that select a register.
+const PRECISION: u32 = 12;
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// The standard error of [`HyperLogLog::estimate`], `1.04 / sqrt(NUM_REGISTERS)`.
+pub(crate) const HYPER_LOG_LOG_ERROR: f64 = 0.01625;
+
+/// Estimates the number of distinct values added to it in a fixed 4KB of registers,
+/// whatever the number of values.
+pub(crate) struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    pub(crate) fn new() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+
+    pub(crate) fn add(&mut self, value: &[u8]) {
+        self.add_hash(bloom_filter_hash(value));
+    }
+
+    /// Adds a value by its [`bloom_filter_hash`].
+    pub(crate) fn add_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // the position of the first set bit of the rest of the hash, at most 64 - PRECISION + 1
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() + 1;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank as u8);
+    }
+
+    /// The estimated number of distinct values, with the linear counting
+    /// correction of the small cardinalities.
+    pub(crate) fn estimate(&self) -> f64 {
+        let m = NUM_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let estimate = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|r| **r == 0).count();
+        if estimate <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HyperLogLog, HYPER_LOG_LOG_ERROR};
+
+    #[test]
+    fn test_estimate() {
+        for num_values in [0usize, 10, 1000, 50_000, 300_000] {
+            let mut hll = HyperLogLog::new();
+            // every value is added twice
+            for i in (0..num_values).chain(0..num_values) {
+                hll.add(format!("value {i}").as_bytes());
+            }
+            let estimate = hll.estimate();
+            let error = (estimate - num_values as f64).abs() / (num_values.max(1) as f64);
+            assert!(
+                error < 4.0 * HYPER_LOG_LOG_ERROR,
+                "{num_values} values estimated as {estimate}"
+            );
+        }
+    }
+}
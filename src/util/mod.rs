@@ -16,15 +16,25 @@
 // under the License.
 
 #[allow(dead_code)]
-mod bit_pack;
+pub mod bit_pack;
 #[allow(dead_code)]
 mod bit_util;
+mod bloom_filter;
 mod byte_writer;
+#[allow(dead_code)]
 pub mod env;
+mod float16;
+mod hybrid_rle;
+mod hyper_log_log;
 pub mod memory;
 
 pub use bit_util::*;
+pub(crate) use bloom_filter::bloom_filter_hash;
 pub use byte_writer::ByteWriter;
+pub use float16::to_parquet_schema;
+pub(crate) use float16::{bits_to_f16, f16_to_bits};
+pub(crate) use hybrid_rle::encode_hybrid_rle;
+pub(crate) use hyper_log_log::{HyperLogLog, HYPER_LOG_LOG_ERROR};
 
 #[macro_export]
 macro_rules! with_match_primitive_type {(
@@ -52,6 +62,24 @@ macro_rules! with_match_primitive_type {(
     }
 })}
 
+#[macro_export]
+macro_rules! with_match_dictionary_key_type {(
+    $key_type:expr, | $_:tt $T:ident | $($body:tt)*
+) => ({
+    macro_rules! __with_ty__ {( $_ $T:ident ) => ( $($body)* )}
+    use arrow::datatypes::IntegerType::*;
+    match $key_type {
+        Int8 => __with_ty__! { i8 },
+        Int16 => __with_ty__! { i16 },
+        Int32 => __with_ty__! { i32 },
+        Int64 => __with_ty__! { i64 },
+        UInt8 => __with_ty__! { u8 },
+        UInt16 => __with_ty__! { u16 },
+        UInt32 => __with_ty__! { u32 },
+        UInt64 => __with_ty__! { u64 },
+    }
+})}
+
 #[macro_export]
 macro_rules! with_match_integer_double_type {
     (
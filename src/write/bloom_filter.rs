@@ -0,0 +1,134 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashSet;
+
+use arrow::array::{Array, BinaryArray, FixedSizeBinaryArray, PrimitiveArray, Utf8Array};
+use arrow::datatypes::PhysicalType;
+use arrow::types::{NativeType, Offset};
+
+use crate::util::{bloom_filter_hash, HyperLogLog};
+use crate::with_match_primitive_type;
+
+/// The false positive probability the filters are sized for.
+const BLOOM_FILTER_FPP: f64 = 0.01;
+/// Size of a split block, the filter bitset is a multiple of it.
+const BLOCK_BYTES: usize = 32;
+const MAX_BLOOM_FILTER_BYTES: usize = 128 * 1024 * 1024;
+
+/// Builds the split-block bloom filter of the valid values of a leaf column,
+/// `None` for the types without bloom filter and columns without valid values.
+pub(crate) fn column_bloom_filter(array: &dyn Array) -> Option<Vec<u8>> {
+    let mut hashes = HashSet::new();
+    let hashed = hash_values(array, |hash| {
+        hashes.insert(hash);
+    });
+    if !hashed || hashes.is_empty() {
+        return None;
+    }
+
+    let mut bitset = vec![0u8; optimal_num_of_bytes(hashes.len())];
+    for hash in hashes {
+        parquet2::bloom_filter::insert(&mut bitset, hash);
+    }
+    Some(bitset)
+}
+
+/// Estimates the number of distinct valid values of a leaf column with a [`HyperLogLog`],
+/// `None` for the types without bloom filter.
+pub(crate) fn column_distinct_count(array: &dyn Array) -> Option<u64> {
+    let mut hll = HyperLogLog::new();
+    if !hash_values(array, |hash| hll.add_hash(hash)) {
+        return None;
+    }
+    Some(hll.estimate().round() as u64)
+}
+
+/// The [`bloom_filter_hash`] of the value of each row of a leaf column, `None` for
+/// the null rows, or `None` for the types without bloom filter.
+pub(crate) fn row_hashes(array: &dyn Array) -> Option<Vec<Option<u64>>> {
+    let mut hashes = Vec::with_capacity(array.len());
+    hash_rows(array, |hash| hashes.push(hash)).then_some(hashes)
+}
+
+/// Calls `f` with the [`bloom_filter_hash`] of each valid value of a leaf column,
+/// returns `false` for the types without bloom filter.
+fn hash_values(array: &dyn Array, mut f: impl FnMut(u64)) -> bool {
+    hash_rows(array, |hash| {
+        if let Some(hash) = hash {
+            f(hash)
+        }
+    })
+}
+
+/// Calls `f` with the hash of the value of each row, `None` for the null rows.
+fn hash_rows(array: &dyn Array, f: impl FnMut(Option<u64>)) -> bool {
+    use PhysicalType::*;
+
+    match array.data_type().to_physical_type() {
+        Primitive(primitive) => with_match_primitive_type!(primitive, |$T| {
+            primitive_hashes::<$T>(array, f)
+        }),
+        Binary => binary_hashes::<i32>(array, f),
+        LargeBinary => binary_hashes::<i64>(array, f),
+        Utf8 => utf8_hashes::<i32>(array, f),
+        LargeUtf8 => utf8_hashes::<i64>(array, f),
+        FixedSizeBinary => {
+            let array: &FixedSizeBinaryArray = array.as_any().downcast_ref().unwrap();
+            array
+                .iter()
+                .map(|value| value.map(bloom_filter_hash))
+                .for_each(f)
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// The size of a filter holding `ndv` distinct values at [`BLOOM_FILTER_FPP`],
+/// rounded up to a power of two.
+fn optimal_num_of_bytes(ndv: usize) -> usize {
+    let num_bits = -8.0 * ndv as f64 / (1.0 - BLOOM_FILTER_FPP.powf(1.0 / 8.0)).ln();
+    let num_bytes = (num_bits / 8.0).ceil() as usize;
+    num_bytes
+        .next_power_of_two()
+        .clamp(BLOCK_BYTES, MAX_BLOOM_FILTER_BYTES)
+}
+
+fn primitive_hashes<T: NativeType>(array: &dyn Array, f: impl FnMut(Option<u64>)) {
+    let array: &PrimitiveArray<T> = array.as_any().downcast_ref().unwrap();
+    array
+        .iter()
+        .map(|value| value.map(|value| bloom_filter_hash(value.to_le_bytes().as_ref())))
+        .for_each(f)
+}
+
+fn binary_hashes<O: Offset>(array: &dyn Array, f: impl FnMut(Option<u64>)) {
+    let array: &BinaryArray<O> = array.as_any().downcast_ref().unwrap();
+    array
+        .iter()
+        .map(|value| value.map(bloom_filter_hash))
+        .for_each(f)
+}
+
+fn utf8_hashes<O: Offset>(array: &dyn Array, f: impl FnMut(Option<u64>)) {
+    let array: &Utf8Array<O> = array.as_any().downcast_ref().unwrap();
+    array
+        .iter()
+        .map(|value| value.map(|value| bloom_filter_hash(value.as_bytes())))
+        .for_each(f)
+}
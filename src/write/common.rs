@@ -15,33 +15,525 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
 
 use arrow::array::*;
 use arrow::chunk::Chunk;
+use arrow::compute::aggregate::estimated_bytes_size;
+use arrow::datatypes::{DataType, PhysicalType};
 use arrow::error::Result;
+use arrow::io::parquet::read::n_columns;
 use arrow::io::parquet::write::{
-    num_values, slice_parquet_array, to_leaves, to_nested, to_parquet_leaves, SchemaDescriptor,
+    num_values, slice_parquet_array, to_leaves, to_nested, to_parquet_leaves, Nested,
+    SchemaDescriptor,
 };
+use arrow::types::Offset;
+use parquet2::schema::types::PrimitiveType;
 
 use crate::compression::CommonCompression;
 use crate::compression::Compression;
+use crate::compression::{compress_zstd_with_dict, train_zstd_dictionary};
+use crate::encryption::{encrypt_page, ColumnKey};
+#[cfg(feature = "encryption")]
+use crate::encryption::{KeyProvider, SharedKeyProvider};
+use crate::util::env::check_encoding_env;
+use crate::BloomFilter;
 use crate::ColumnMeta;
 use crate::PageMeta;
+use crate::PageStatistics;
 use crate::CONTINUATION_MARKER;
+use crate::{with_match_dictionary_key_type, with_match_primitive_type};
 
-use super::{write, NativeWriter};
+use super::{
+    bloom_filter::{column_bloom_filter, column_distinct_count, row_hashes},
+    report::{ColumnEncodingReport, PageEncodingReport},
+    serialize::write_simple,
+    statistics::page_statistics,
+    write,
+    writer::OffsetWriter,
+    NativeWriter,
+};
+
+/// A column zstd dictionary is trained from the first pages of the column,
+/// up to this many times the dictionary size as recommended by zstd.
+const ZSTD_DICTIONARY_SAMPLE_RATIO: usize = 100;
+
+/// The `Dict` encoding is not evaluated for a page with at least this ratio of
+/// unique values, unless [`WriteOptions::max_dict_unique_ratio`] is set.
+pub const DEFAULT_MAX_DICT_UNIQUE_RATIO: f64 = 1.0 / 3.0;
+
+/// The ratio over which a sorted column uses a delta encoding when
+/// [`WriteOptions::default_compress_ratio`] is not set.
+pub const DEFAULT_SORTED_COMPRESS_RATIO: f64 = 2.0;
 
 /// Options declaring the behaviour of writing to IPC
-#[derive(Debug, Clone, PartialEq, Default)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WriteOptions {
     /// Whether the buffers should be compressed and which codec to use.
     /// Note: to use compression the crate must be compiled with feature `io_ipc_compression`.
     pub default_compression: CommonCompression,
+    /// Overrides `default_compression` for the leaf columns at these indexes,
+    /// which are the indexes of their [`ColumnMeta`]s.
+    pub column_compressions: HashMap<usize, CommonCompression>,
+    /// Overrides `compression_level` for the leaf columns at these indexes,
+    /// e.g. a high zstd level for a column that is rarely read.
+    pub column_compression_levels: HashMap<usize, i32>,
+    /// Declares the values of every leaf column sorted in ascending order, see
+    /// `sorted_columns`.
+    pub sorted: bool,
+    /// Declares the values of the leaf columns at these indexes sorted in ascending
+    /// order, which the writer then trusts instead of checking it: their pages
+    /// evaluate the delta encodings and front coding, even without
+    /// `default_compress_ratio` (over [`DEFAULT_SORTED_COMPRESS_RATIO`]), and their
+    /// [`ColumnMeta::sorted`] is set. The pages of a declared column whose values
+    /// are not sorted may not be readable.
+    pub sorted_columns: HashSet<usize>,
     /// If some encoding method performs over this ratio, we will switch to use it.
     pub default_compress_ratio: Option<f64>,
     pub max_page_size: Option<usize>,
+    /// Ends a page before its values in any leaf column exceed this many bytes, as
+    /// estimated from the widths of the values and the lengths of the binary ones.
+    /// The pages of all the leaf columns of a chunk still hold the same rows, and
+    /// a page holds at least one row. Combined with `max_page_size`, a page ends
+    /// at whichever limit comes first.
+    pub max_page_bytes: Option<usize>,
+    /// Ends a page before any of its non-nested leaf columns has more than this many
+    /// distinct valid values, counted by their hashes, so the dictionary of a `Dict`
+    /// page and the width of its indices stay bounded. Like `max_page_bytes`, the
+    /// pages of all the leaf columns of a chunk still hold the same rows.
+    /// Only the leaf columns whose pages may be `Dict` encoded are counted, which
+    /// needs `default_compress_ratio`. The hashes of their rows are computed for the
+    /// whole chunk first, 16 bytes per row and counted column.
+    pub max_dict_entries: Option<usize>,
     pub forbidden_compressions: Vec<Compression>,
+    /// Sort the dictionary of `Dict` pages and store it delta (integer) or
+    /// prefix (binary) encoded, the indices then follow the sorted order.
+    pub sorted_dictionary: bool,
+    /// Level of `default_compression`, only used by zstd and brotli. `None` uses the codec default.
+    pub compression_level: Option<i32>,
+    /// Encode the offsets, lengths and value counts of the footer as LEB128 varints
+    /// instead of `u64`, which keeps the footer of small files small.
+    pub varint_footer: bool,
+    /// Write binary pages with a restart point every 128 values, so a single value
+    /// can be read with [`crate::read::batch_read::read_value_at`].
+    pub binary_restart_points: bool,
+    /// Store the offsets of the `LargeBinary` and `LargeUtf8` pages whose values fit in
+    /// 4GB as `u32` instead of `i64`, halving their size. They are flagged by the
+    /// uncompressed size of the offsets buffer, 4 bytes per offset, and read back as `i64`.
+    /// Only the `Basic` encoded pages are affected.
+    pub narrow_large_offsets: bool,
+    /// Train a zstd dictionary of at most this many bytes from the first pages of each
+    /// column and compress every page of the column with it at `compression_level`,
+    /// the dictionary is stored in the [`ColumnMeta`]. The pages are encoded without
+    /// `default_compression`. Columns with too little data to train a dictionary
+    /// are written as usual, with `default_compression`.
+    pub zstd_dictionary_size: Option<usize>,
+    /// Build a bloom filter of the valid values of each integer, float and binary column,
+    /// written after the pages of the column, see [`ColumnMeta::bloom_contains`].
+    pub bloom_filter: bool,
+    /// Estimate the number of distinct valid values of each integer, float and binary
+    /// column with a HyperLogLog, stored in [`ColumnMeta::distinct_count`].
+    pub distinct_count: bool,
+    /// Skip the `Dict` encoding of the pages whose ratio of unique values to values
+    /// is at least this, without building their dictionary.
+    /// `None` uses [`DEFAULT_MAX_DICT_UNIQUE_RATIO`].
+    pub max_dict_unique_ratio: Option<f64>,
+    /// Compress the output of the `Rle` and `Dict` encodings again with
+    /// `default_compression`, which shrinks their runs and dictionaries further.
+    pub compress_encodings: bool,
+    /// Store a crc32 of the bytes of each page in its [`PageMeta`], which a
+    /// [`crate::read::reader::NativeReader`] verifies when asked to.
+    pub page_checksum: bool,
+    /// Number of consecutive values of each of the samples that the ratios of the
+    /// encodings are estimated on. `None` uses [`crate::compression::SAMPLE_SIZE`].
+    pub sample_size: Option<usize>,
+    /// Map -0.0 to 0.0 and every NaN to the canonical NaN before encoding a float page,
+    /// which is read back with them. Otherwise the float pages round-trip bit-exactly,
+    /// -0.0 and 0.0, like NaNs of different bits, are distinct values of the `Dict`,
+    /// `Freq`, `Rle` and `OneValue` encodings.
+    pub canonicalize_floats: bool,
+    /// Truncate the min and max of the binary and utf8 pages and columns to this many
+    /// bytes, the max rounded up by incrementing its last byte, so they remain bounds
+    /// of the values. A max of only 0xFF bytes is kept whole. `None` keeps them whole.
+    pub statistics_truncate_length: Option<usize>,
+    /// Compute and store the min and max of every page and column, which the page
+    /// predicates and [`crate::read::reader::read_statistics`] rely on. Off, the
+    /// statistics are only written for the `statistics_columns` and the pages are
+    /// written without scanning their values for them.
+    pub write_statistics: bool,
+    /// The indexes of the leaf columns written with statistics even without
+    /// `write_statistics`.
+    pub statistics_columns: HashSet<usize>,
+    /// Write the validity of the non-nested pages with over this ratio of null values
+    /// as the gaps between their valid values, LEB128 encoded, when it is smaller than
+    /// the bitmap. It is flagged by the [`Compression::SparseValidity`] codec of the
+    /// validity and read back without decoding a bit per value.
+    pub sparse_validity_null_ratio: Option<f64>,
+    /// Encode the leaf columns of a chunk in parallel on the rayon thread pool,
+    /// each into its own buffer. The file is the same as the one written serially.
+    #[cfg(feature = "rayon")]
+    pub parallel_encoding: bool,
+    /// Encrypt the pages of the leaf columns that the provider has a key for,
+    /// see [`crate::encryption`]. The other columns are written in plaintext.
+    #[cfg(feature = "encryption")]
+    pub key_provider: Option<SharedKeyProvider>,
+}
+
+/// LZ4 and pages of 8192 rows, without the specialized encodings.
+///
+/// ```
+/// use strawboat::{write::WriteOptions, CommonCompression};
+///
+/// let options = WriteOptions::default();
+/// assert_eq!(options.default_compression, CommonCompression::Lz4);
+/// assert_eq!(options.max_page_size, Some(8192));
+/// ```
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            default_compression: CommonCompression::Lz4,
+            column_compressions: HashMap::new(),
+            column_compression_levels: HashMap::new(),
+            sorted: false,
+            sorted_columns: HashSet::new(),
+            default_compress_ratio: None,
+            max_page_size: Some(8192),
+            max_page_bytes: None,
+            max_dict_entries: None,
+            forbidden_compressions: vec![],
+            sorted_dictionary: false,
+            compression_level: None,
+            varint_footer: false,
+            binary_restart_points: false,
+            narrow_large_offsets: false,
+            zstd_dictionary_size: None,
+            bloom_filter: false,
+            distinct_count: false,
+            max_dict_unique_ratio: None,
+            compress_encodings: false,
+            page_checksum: false,
+            sample_size: None,
+            canonicalize_floats: false,
+            statistics_truncate_length: None,
+            write_statistics: true,
+            statistics_columns: HashSet::new(),
+            sparse_validity_null_ratio: None,
+            #[cfg(feature = "rayon")]
+            parallel_encoding: false,
+            #[cfg(feature = "encryption")]
+            key_provider: None,
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Writes the values as they are, in a single page per chunk.
+    ///
+    /// ```
+    /// use strawboat::{write::WriteOptions, CommonCompression};
+    ///
+    /// let options = WriteOptions::uncompressed();
+    /// assert_eq!(options.default_compression, CommonCompression::None);
+    /// assert_eq!(options.max_page_size, None);
+    /// ```
+    pub fn uncompressed() -> Self {
+        Self {
+            default_compression: CommonCompression::None,
+            max_page_size: None,
+            ..Default::default()
+        }
+    }
+
+    /// Favors write and read speed: LZ4 only, without the specialized encodings,
+    /// and large pages of 64K rows.
+    ///
+    /// ```
+    /// use strawboat::{write::WriteOptions, CommonCompression};
+    ///
+    /// let options = WriteOptions::fast();
+    /// assert_eq!(options.default_compression, CommonCompression::Lz4);
+    /// assert_eq!(options.default_compress_ratio, None);
+    /// ```
+    pub fn fast() -> Self {
+        Self {
+            default_compression: CommonCompression::Lz4,
+            default_compress_ratio: None,
+            max_page_size: Some(64 * 1024),
+            ..Default::default()
+        }
+    }
+
+    /// LZ4 with the specialized encodings used wherever they do clearly better.
+    ///
+    /// ```
+    /// use strawboat::write::WriteOptions;
+    ///
+    /// let options = WriteOptions::balanced();
+    /// assert_eq!(options.default_compress_ratio, Some(2.0));
+    /// ```
+    pub fn balanced() -> Self {
+        Self {
+            default_compression: CommonCompression::Lz4,
+            default_compress_ratio: Some(2.0),
+            max_page_size: Some(8 * 1024),
+            ..Default::default()
+        }
+    }
+
+    /// Favors file size: every specialized encoding, sorted dictionaries,
+    /// zstd level 19 as the fallback, and pages of up to 1MB of values
+    /// whatever their number of rows, see `max_page_bytes`.
+    ///
+    /// ```
+    /// use strawboat::{write::WriteOptions, CommonCompression};
+    ///
+    /// let options = WriteOptions::max_compression();
+    /// assert_eq!(options.default_compression, CommonCompression::Zstd);
+    /// assert_eq!(options.compression_level, Some(19));
+    /// assert_eq!(options.max_page_bytes, Some(1024 * 1024));
+    /// ```
+    pub fn max_compression() -> Self {
+        Self {
+            default_compression: CommonCompression::Zstd,
+            compression_level: Some(19),
+            default_compress_ratio: Some(1.5),
+            max_page_size: None,
+            max_page_bytes: Some(1024 * 1024),
+            sorted_dictionary: true,
+            ..Default::default()
+        }
+    }
+}
+
+impl WriteOptions {
+    /// Returns a [`WriteOptionsBuilder`] starting from the default options.
+    ///
+    /// ```
+    /// use strawboat::{write::WriteOptions, CommonCompression};
+    ///
+    /// let options = WriteOptions::builder()
+    ///     .compression(CommonCompression::Zstd)
+    ///     .max_page_size(8 * 1024)
+    ///     .column_compression(1, CommonCompression::Lz4)
+    ///     .build();
+    /// assert_eq!(options.max_page_size, Some(8 * 1024));
+    /// ```
+    pub fn builder() -> WriteOptionsBuilder {
+        WriteOptionsBuilder::default()
+    }
+
+    /// Whether the `Dict` encoding is worth evaluating for a page of `tuple_count`
+    /// values with `unique_count` unique ones.
+    pub(crate) fn dict_candidate(&self, unique_count: usize, tuple_count: usize) -> bool {
+        (unique_count as f64) < self.dict_unique_ratio_limit() * tuple_count as f64
+    }
+
+    /// The ratio of unique values from which the `Dict` encoding is skipped.
+    pub(crate) fn dict_unique_ratio_limit(&self) -> f64 {
+        self.max_dict_unique_ratio
+            .unwrap_or(DEFAULT_MAX_DICT_UNIQUE_RATIO)
+    }
+
+    /// The options of the leaf column at `index`, with its `column_compressions`,
+    /// `column_compression_levels`, `sorted_columns` and `statistics_columns` overrides.
+    fn column_options(&self, index: usize) -> Cow<'_, WriteOptions> {
+        let compression = self.column_compressions.get(&index);
+        let level = self.column_compression_levels.get(&index);
+        let sorted = self.sorted_columns.contains(&index);
+        let statistics = self.statistics_columns.contains(&index);
+        if compression.is_none() && level.is_none() && !sorted && !statistics {
+            return Cow::Borrowed(self);
+        }
+        Cow::Owned(WriteOptions {
+            default_compression: compression.copied().unwrap_or(self.default_compression),
+            compression_level: level.copied().or(self.compression_level),
+            sorted: self.sorted || sorted,
+            write_statistics: self.write_statistics || statistics,
+            ..self.clone()
+        })
+    }
+
+    /// The key the pages of the leaf column at `index` are encrypted with, if any.
+    #[cfg(feature = "encryption")]
+    fn column_key(&self, index: usize) -> Result<Option<ColumnKey>> {
+        match &self.key_provider {
+            Some(provider) => Ok(provider
+                .0
+                .column_key(index)?
+                .map(|key| ColumnKey { key, column: index })),
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn column_key(&self, _index: usize) -> Result<Option<ColumnKey>> {
+        Ok(None)
+    }
+
+    /// Whether the specialized encodings of a page are evaluated, which needs the stats
+    /// of its values. Otherwise it is compressed with `default_compression` without
+    /// scanning them.
+    pub(crate) fn evaluates_encodings(&self) -> bool {
+        self.compress_ratio().is_some() || (cfg!(debug_assertions) && check_encoding_env())
+    }
+
+    /// Whether the pages of a leaf column of `data_type` may be `Dict` encoded: the
+    /// specialized encodings other than the sorted ones are evaluated, `Dict` is not
+    /// forbidden and the binary pages are not forced to restart points.
+    pub(crate) fn dict_allowed(&self, data_type: &DataType) -> bool {
+        use PhysicalType::*;
+        let binary = matches!(
+            data_type.to_physical_type(),
+            Binary | LargeBinary | Utf8 | LargeUtf8
+        );
+        let dict_type = binary || matches!(data_type.to_physical_type(), Primitive(_));
+        dict_type
+            && matches!(self.compress_ratio(), Some((_, false)))
+            && !self.forbidden_compressions.contains(&Compression::Dict)
+            && !(binary && self.binary_restart_points)
+    }
+
+    /// The ratio over which a page uses a specialized encoding, and whether only the
+    /// encodings of sorted values are evaluated because it is a declared sorted one.
+    pub(crate) fn compress_ratio(&self) -> Option<(f64, bool)> {
+        match self.default_compress_ratio {
+            Some(ratio) => Some((ratio, false)),
+            None if self.sorted => Some((DEFAULT_SORTED_COMPRESS_RATIO, true)),
+            None => None,
+        }
+    }
+}
+
+/// Builds [`WriteOptions`] without naming its fields.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptionsBuilder {
+    options: WriteOptions,
+}
+
+impl WriteOptionsBuilder {
+    /// Sets the codec of the buffers that no specialized encoding does better on.
+    pub fn compression(mut self, compression: CommonCompression) -> Self {
+        self.options.default_compression = compression;
+        self
+    }
+
+    /// Sets the maximum number of rows of a page.
+    pub fn max_page_size(mut self, max_page_size: usize) -> Self {
+        self.options.max_page_size = Some(max_page_size);
+        self
+    }
+
+    /// Sets the maximum number of bytes of the values of a page.
+    pub fn max_page_bytes(mut self, max_page_bytes: usize) -> Self {
+        self.options.max_page_bytes = Some(max_page_bytes);
+        self
+    }
+
+    /// Sets the maximum number of distinct values of a page of a leaf column.
+    pub fn max_dict_entries(mut self, max_dict_entries: usize) -> Self {
+        self.options.max_dict_entries = Some(max_dict_entries);
+        self
+    }
+
+    /// Truncates the min and max of the binary and utf8 columns to `length` bytes.
+    pub fn statistics_truncate_length(mut self, length: usize) -> Self {
+        self.options.statistics_truncate_length = Some(length);
+        self
+    }
+
+    /// Writes the validity of the pages with over `ratio` null values as the gaps
+    /// between their valid values.
+    pub fn sparse_validity(mut self, ratio: f64) -> Self {
+        self.options.sparse_validity_null_ratio = Some(ratio);
+        self
+    }
+
+    /// Writes the min and max of the pages and columns, on by default.
+    pub fn write_statistics(mut self, write_statistics: bool) -> Self {
+        self.options.write_statistics = write_statistics;
+        self
+    }
+
+    /// Writes the statistics of the leaf column at `index` even without `write_statistics`.
+    pub fn statistics_column(mut self, index: usize) -> Self {
+        self.options.statistics_columns.insert(index);
+        self
+    }
+
+    /// Estimates the number of distinct values of each column.
+    pub fn distinct_count(mut self) -> Self {
+        self.options.distinct_count = true;
+        self
+    }
+
+    /// Declares the values of the leaf column at `index` sorted in ascending order.
+    pub fn sorted_column(mut self, index: usize) -> Self {
+        self.options.sorted_columns.insert(index);
+        self
+    }
+
+    /// Compresses the leaf column at `index` with `compression` instead of the default one.
+    pub fn column_compression(mut self, index: usize, compression: CommonCompression) -> Self {
+        self.options.column_compressions.insert(index, compression);
+        self
+    }
+
+    /// Compresses the leaf column at `index` at `level` instead of the default level.
+    pub fn column_compression_level(mut self, index: usize, level: i32) -> Self {
+        self.options.column_compression_levels.insert(index, level);
+        self
+    }
+
+    /// Uses a specialized encoding when it compresses over `ratio`.
+    pub fn compress_ratio(mut self, ratio: f64) -> Self {
+        self.options.default_compress_ratio = Some(ratio);
+        self
+    }
+
+    /// Never uses the `compression` encoding.
+    pub fn forbid(mut self, compression: Compression) -> Self {
+        if !self.options.forbidden_compressions.contains(&compression) {
+            self.options.forbidden_compressions.push(compression);
+        }
+        self
+    }
+
+    /// Skips the `Dict` encoding of pages with at least `ratio` unique values.
+    pub fn max_dict_unique_ratio(mut self, ratio: f64) -> Self {
+        self.options.max_dict_unique_ratio = Some(ratio);
+        self
+    }
+
+    /// Compresses the output of the `Rle` and `Dict` encodings with the default compression.
+    pub fn compress_encodings(mut self) -> Self {
+        self.options.compress_encodings = true;
+        self
+    }
+
+    /// Stores the offsets of the large binary pages as `u32` when their values fit.
+    pub fn narrow_large_offsets(mut self) -> Self {
+        self.options.narrow_large_offsets = true;
+        self
+    }
+
+    /// Estimates the ratios of the encodings on samples of `sample_size` consecutive values.
+    pub fn sample_size(mut self, sample_size: usize) -> Self {
+        self.options.sample_size = Some(sample_size);
+        self
+    }
+
+    /// Encrypts the pages of the leaf columns that `provider` has a key for.
+    #[cfg(feature = "encryption")]
+    pub fn key_provider(mut self, provider: impl KeyProvider + 'static) -> Self {
+        self.options.key_provider = Some(SharedKeyProvider::new(provider));
+        self
+    }
+
+    pub fn build(self) -> WriteOptions {
+        self.options
+    }
 }
 
 impl<W: Write> NativeWriter<W> {
@@ -51,74 +543,665 @@ impl<W: Write> NativeWriter<W> {
         schema_descriptor: SchemaDescriptor,
         chunk: &Chunk<Box<dyn Array>>,
     ) -> Result<()> {
-        let page_size = self
-            .options
-            .max_page_size
-            .unwrap_or(chunk.len())
-            .min(chunk.len());
-
-        for (array, type_) in chunk
-            .arrays()
+        let page_ranges = page_ranges(&self.options, chunk);
+        let leaves = leaf_columns(schema_descriptor, chunk)?;
+
+        #[cfg(feature = "rayon")]
+        if self.options.parallel_encoding {
+            return self.encode_leaves_parallel(leaves, &page_ranges);
+        }
+
+        for (index, leaf) in leaves.into_iter().enumerate() {
+            let key = self.options.column_key(index)?;
+            let (meta, pages) = encode_column(
+                &mut self.writer,
+                &self.options.column_options(index),
+                &mut self.scratch,
+                leaf,
+                &page_ranges,
+                key.as_ref(),
+            )?;
+            self.push_column(meta, pages);
+        }
+
+        Ok(())
+    }
+
+    /// Encodes every leaf column into its own buffer on the rayon thread pool,
+    /// then appends the buffers in order. The bytes are the same as the serial ones.
+    #[cfg(feature = "rayon")]
+    fn encode_leaves_parallel(
+        &mut self,
+        leaves: Vec<LeafColumn>,
+        page_ranges: &[(usize, usize)],
+    ) -> Result<()> {
+        use rayon::prelude::*;
+
+        let options = &self.options;
+        let columns = leaves
+            .into_par_iter()
+            .enumerate()
+            .map(|(index, leaf)| {
+                let mut writer = OffsetWriter {
+                    w: vec![],
+                    offset: 0,
+                };
+                let key = options.column_key(index)?;
+                let (meta, pages) = encode_column(
+                    &mut writer,
+                    &options.column_options(index),
+                    &mut vec![],
+                    leaf,
+                    page_ranges,
+                    key.as_ref(),
+                )?;
+                Ok((writer.w, meta, pages))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        for (buffer, mut meta, pages) in columns {
+            shift_offsets(&mut meta, self.writer.offset);
+            self.writer.write_all(&buffer)?;
+            self.push_column(meta, pages);
+        }
+        Ok(())
+    }
+
+    fn push_column(&mut self, meta: ColumnMeta, pages: Vec<PageEncodingReport>) {
+        self.encoding_reports.push(ColumnEncodingReport {
+            column: self.metas.len(),
+            pages,
+        });
+        self.metas.push(meta);
+    }
+}
+
+/// The `(offset, length)` rows of the pages of `chunk`, the same for all its leaf columns.
+/// An empty chunk has no pages.
+fn page_ranges(options: &WriteOptions, chunk: &Chunk<Box<dyn Array>>) -> Vec<(usize, usize)> {
+    let num_rows = chunk.len();
+    let page_size = options.max_page_size.unwrap_or(num_rows).max(1);
+    if options.max_page_bytes.is_none() && options.max_dict_entries.is_none() {
+        return (0..num_rows)
+            .step_by(page_size)
+            .map(|offset| (offset, page_size.min(num_rows - offset)))
+            .collect();
+    }
+    let max_page_bytes = options.max_page_bytes.unwrap_or(usize::MAX);
+    let max_dict_entries = options.max_dict_entries.unwrap_or(usize::MAX);
+
+    let mut leaves = vec![];
+    let mut hashes = vec![];
+    let mut leaf = 0;
+    for array in chunk.arrays() {
+        if options.max_page_bytes.is_some() {
+            leaves.extend(leaf_row_bytes(array.as_ref()));
+        }
+        if options.max_dict_entries.is_some() {
+            hashes.extend(leaf_row_hashes(array.as_ref(), options, &mut leaf));
+        }
+    }
+    let mut ranges = vec![];
+    let mut start = 0;
+    let mut page_bytes = vec![0; leaves.len()];
+    let mut page_values = vec![HashSet::new(); hashes.len()];
+    for row in 0..num_rows {
+        let rows = row - start;
+        let overflows = leaves
             .iter()
-            .zip(schema_descriptor.fields().to_vec())
-        {
-            let array = array.as_ref();
-            let nested = to_nested(array, &type_)?;
-            let types: Vec<parquet2::schema::types::PrimitiveType> = to_parquet_leaves(type_);
-            let leaf_arrays = to_leaves(array);
-            let length = array.len();
+            .zip(page_bytes.iter())
+            .any(|(row_bytes, bytes)| bytes + row_bytes[row] > max_page_bytes)
+            || hashes
+                .iter()
+                .zip(page_values.iter())
+                .any(|(row_hashes, values)| {
+                    let new_value = row_hashes[row].map_or(false, |hash| !values.contains(&hash));
+                    new_value && values.len() >= max_dict_entries
+                });
+        if rows > 0 && (rows == page_size || overflows) {
+            ranges.push((start, rows));
+            start = row;
+            page_bytes.fill(0);
+            page_values.iter_mut().for_each(HashSet::clear);
+        }
+        for (bytes, row_bytes) in page_bytes.iter_mut().zip(leaves.iter()) {
+            *bytes += row_bytes[row];
+        }
+        for (values, row_hashes) in page_values.iter_mut().zip(hashes.iter()) {
+            if let Some(hash) = row_hashes[row] {
+                values.insert(hash);
+            }
+        }
+    }
+    if start < num_rows {
+        ranges.push((start, num_rows - start));
+    }
+    ranges
+}
 
-            for ((leaf_array, nested), type_) in leaf_arrays
+/// The hash of the value of each row of the non-nested leaf columns of `array`
+/// that may be `Dict` encoded, whose values are a row each, see
+/// [`WriteOptions::max_dict_entries`]. `leaf` is the index of the first leaf
+/// column of `array`, advanced past its leaf columns.
+fn leaf_row_hashes(
+    array: &dyn Array,
+    options: &WriteOptions,
+    leaf: &mut usize,
+) -> Vec<Vec<Option<u64>>> {
+    match array.data_type().to_physical_type() {
+        PhysicalType::Struct => {
+            let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+            array
+                .values()
                 .iter()
-                .zip(nested.into_iter())
-                .zip(types.into_iter())
+                .flat_map(|values| leaf_row_hashes(values.as_ref(), options, leaf))
+                .collect()
+        }
+        _ => {
+            let index = *leaf;
+            *leaf += n_columns(array.data_type());
+            if !options
+                .column_options(index)
+                .dict_allowed(array.data_type())
             {
-                let start = self.writer.offset;
-                let leaf_array = leaf_array.to_boxed();
-
-                let page_metas: Vec<PageMeta> = (0..length)
-                    .step_by(page_size)
-                    .map(|offset| {
-                        let length = if offset + page_size > length {
-                            length - offset
-                        } else {
-                            page_size
-                        };
-                        let mut sub_array = leaf_array.clone();
-                        let mut sub_nested = nested.clone();
-                        slice_parquet_array(sub_array.as_mut(), &mut sub_nested, offset, length);
-                        let page_start = self.writer.offset;
-                        write(
-                            &mut self.writer,
-                            sub_array.as_ref(),
-                            &sub_nested,
-                            type_.clone(),
-                            length,
-                            self.options.clone(),
-                            &mut self.scratch,
-                        )
-                        .unwrap();
-
-                        let page_end = self.writer.offset;
-                        let num_values = num_values(&sub_nested);
-                        PageMeta {
-                            length: (page_end - page_start),
-                            num_values: num_values as u64,
-                        }
-                    })
-                    .collect();
-
-                self.metas.push(ColumnMeta {
-                    offset: start,
-                    pages: page_metas,
+                return vec![];
+            }
+            row_hashes(array).into_iter().collect()
+        }
+    }
+}
+
+/// The estimated bytes of the values of each row of `array` in each of its leaf
+/// columns, in the order of [`to_leaves`].
+fn leaf_row_bytes(array: &dyn Array) -> Vec<Vec<usize>> {
+    use PhysicalType::*;
+    match array.data_type().to_physical_type() {
+        Null => vec![vec![0; array.len()]],
+        // a bit per value, counted as a byte
+        Boolean => vec![vec![1; array.len()]],
+        Primitive(primitive) => with_match_primitive_type!(primitive, |$T| {
+            vec![vec![std::mem::size_of::<$T>(); array.len()]]
+        }),
+        Dictionary(key_type) => with_match_dictionary_key_type!(key_type, |$T| {
+            vec![vec![std::mem::size_of::<$T>(); array.len()]]
+        }),
+        FixedSizeBinary => {
+            let array = array
+                .as_any()
+                .downcast_ref::<FixedSizeBinaryArray>()
+                .unwrap();
+            vec![vec![array.size(); array.len()]]
+        }
+        Binary => {
+            let array = array.as_any().downcast_ref::<BinaryArray<i32>>().unwrap();
+            vec![binary_row_bytes(array.offsets().buffer())]
+        }
+        LargeBinary => {
+            let array = array.as_any().downcast_ref::<BinaryArray<i64>>().unwrap();
+            vec![binary_row_bytes(array.offsets().buffer())]
+        }
+        Utf8 => {
+            let array = array.as_any().downcast_ref::<Utf8Array<i32>>().unwrap();
+            vec![binary_row_bytes(array.offsets().buffer())]
+        }
+        LargeUtf8 => {
+            let array = array.as_any().downcast_ref::<Utf8Array<i64>>().unwrap();
+            vec![binary_row_bytes(array.offsets().buffer())]
+        }
+        Struct => {
+            let array = array.as_any().downcast_ref::<StructArray>().unwrap();
+            array
+                .values()
+                .iter()
+                .flat_map(|values| leaf_row_bytes(values.as_ref()))
+                .collect()
+        }
+        List => {
+            let array = array.as_any().downcast_ref::<ListArray<i32>>().unwrap();
+            list_row_bytes(array.offsets().buffer(), array.values().as_ref())
+        }
+        LargeList => {
+            let array = array.as_any().downcast_ref::<ListArray<i64>>().unwrap();
+            list_row_bytes(array.offsets().buffer(), array.values().as_ref())
+        }
+        Map => {
+            let array = array.as_any().downcast_ref::<MapArray>().unwrap();
+            list_row_bytes(array.offsets().buffer(), array.field().as_ref())
+        }
+        // not written by `to_leaves`, which errors first
+        _ => vec![vec![0; array.len()]],
+    }
+}
+
+/// The bytes of each value of a binary array and its offset.
+fn binary_row_bytes<O: Offset>(offsets: &[O]) -> Vec<usize> {
+    offsets
+        .windows(2)
+        .map(|window| (window[1] - window[0]).to_usize() + std::mem::size_of::<O>())
+        .collect()
+}
+
+/// Sums the bytes of the values of each list in each leaf column of `values`.
+fn list_row_bytes<O: Offset>(offsets: &[O], values: &dyn Array) -> Vec<Vec<usize>> {
+    leaf_row_bytes(values)
+        .into_iter()
+        .map(|value_bytes| {
+            offsets
+                .windows(2)
+                .map(|window| {
+                    value_bytes[window[0].to_usize()..window[1].to_usize()]
+                        .iter()
+                        .sum::<usize>()
+                        + std::mem::size_of::<O>()
                 })
+                .collect()
+        })
+        .collect()
+}
+
+/// A leaf array with its nested information and parquet type.
+type LeafColumn = (Box<dyn Array>, Vec<Nested>, PrimitiveType);
+
+fn leaf_columns(
+    schema_descriptor: SchemaDescriptor,
+    chunk: &Chunk<Box<dyn Array>>,
+) -> Result<Vec<LeafColumn>> {
+    let mut leaves = vec![];
+    for (array, type_) in chunk
+        .arrays()
+        .iter()
+        .zip(schema_descriptor.fields().to_vec())
+    {
+        let array = array.as_ref();
+        let nested = to_nested(array, &type_)?;
+        let types: Vec<PrimitiveType> = to_parquet_leaves(type_);
+        let leaf_arrays = to_leaves(array);
+
+        for ((leaf_array, nested), type_) in leaf_arrays
+            .iter()
+            .zip(nested.into_iter())
+            .zip(types.into_iter())
+        {
+            leaves.push((leaf_array.to_boxed(), nested, type_));
+        }
+    }
+    Ok(leaves)
+}
+
+/// Writes the pages of a leaf column, followed by the values of a dictionary column
+/// and the bloom filter, and returns the meta of the column and the encodings of
+/// its pages.
+/// The encoding of each page is chosen from the statistics of the page alone,
+/// so the pages of a column whose values change in distribution differ in encoding.
+/// The pages are encrypted with `key` if the column has one.
+fn encode_column<W: Write>(
+    writer: &mut OffsetWriter<W>,
+    options: &WriteOptions,
+    scratch: &mut Vec<u8>,
+    (leaf_array, nested, type_): LeafColumn,
+    page_ranges: &[(usize, usize)],
+    key: Option<&ColumnKey>,
+) -> Result<(ColumnMeta, Vec<PageEncodingReport>)> {
+    let start = writer.offset;
+    let statistics = options_statistics(options, leaf_array.as_ref(), &nested);
+    let distinct_count = options
+        .distinct_count
+        .then(|| column_distinct_count(leaf_array.as_ref()))
+        .flatten();
+
+    let pages = || {
+        page_ranges.iter().map(|&(offset, length)| {
+            let mut sub_array = leaf_array.clone();
+            let mut sub_nested = nested.clone();
+            slice_parquet_array(sub_array.as_mut(), &mut sub_nested, offset, length);
+            let uncompressed_size = estimated_bytes_size(sub_array.as_ref()) as u64;
+            (sub_array, sub_nested, length, uncompressed_size)
+        })
+    };
+
+    if let Some(dictionary_size) = options.zstd_dictionary_size {
+        let page_options = WriteOptions {
+            default_compression: CommonCompression::None,
+            ..options.clone()
+        };
+        let mut buffers = vec![];
+        let mut page_metas = vec![];
+        let mut codecs = vec![];
+        let mut uncompressed_sizes = vec![];
+        for (sub_array, sub_nested, length, uncompressed_size) in pages() {
+            let mut buffer = vec![];
+            let codec = write(
+                &mut buffer,
+                sub_array.as_ref(),
+                &sub_nested,
+                type_.clone(),
+                length,
+                page_options.clone(),
+                scratch,
+            )?;
+            codecs.push(codec);
+            uncompressed_sizes.push(uncompressed_size);
+            buffers.push(buffer);
+            page_metas.push(PageMeta {
+                length: 0,
+                num_values: num_values(&sub_nested) as u64,
+                null_count: Some(sub_array.null_count() as u64),
+                statistics: options_statistics(options, sub_array.as_ref(), &sub_nested),
+                checksum: None,
+                encrypted: key.is_some(),
+                zstd_dictionary: false,
+            });
+        }
+
+        let mut sample_size = 0;
+        let sample_pages = buffers
+            .iter()
+            .take_while(|buffer| {
+                sample_size += buffer.len();
+                sample_size <= dictionary_size * ZSTD_DICTIONARY_SAMPLE_RATIO
+            })
+            .count();
+        let samples = &buffers[..sample_pages.max(1)];
+        // the pages of the columns with too little data to train a dictionary
+        // are encoded again with `default_compression` below
+        if let Some(zstd_dictionary) = train_zstd_dictionary(samples, dictionary_size) {
+            // the level of the column, see `column_compression_levels`
+            let level = options.compression_level.unwrap_or(0);
+            for (index, (buffer, page_meta)) in
+                buffers.iter().zip(page_metas.iter_mut()).enumerate()
+            {
+                let page_start = writer.offset;
+                let mut page_writer =
+                    PageWriter::new(&mut *writer, options.page_checksum, key, index);
+                scratch.clear();
+                compress_zstd_with_dict(buffer, scratch, level, &zstd_dictionary)?;
+                page_writer.write_all(&(buffer.len() as u64).to_le_bytes())?;
+                page_writer.write_all(scratch)?;
+                page_meta.checksum = page_writer.finish()?;
+                page_meta.length = writer.offset - page_start;
+                page_meta.zstd_dictionary = true;
             }
+
+            let dictionary = write_dictionary_values(
+                writer,
+                options,
+                scratch,
+                leaf_array.as_ref(),
+                &type_,
+                key,
+                page_metas.len(),
+            )?;
+            let bloom_filter = write_bloom_filter(writer, options, leaf_array.as_ref())?;
+            let reports = page_reports(&page_metas, &codecs, &uncompressed_sizes);
+            let codec_counts = codec_counts(&codecs);
+            let meta = ColumnMeta {
+                offset: start,
+                null_count: page_metas.iter().map(|page| page.null_count).sum(),
+                pages: page_metas,
+                zstd_dictionary: Some(zstd_dictionary),
+                dictionary,
+                bloom_filter,
+                dominant_codec: dominant_codec(&codec_counts),
+                codec_counts,
+                statistics,
+                sorted: options.sorted,
+                distinct_count,
+            };
+            return Ok((meta, reports));
         }
+    }
 
-        Ok(())
+    let mut codecs = vec![];
+    let mut uncompressed_sizes = vec![];
+    let page_metas: Vec<PageMeta> = pages()
+        .enumerate()
+        .map(
+            |(index, (sub_array, sub_nested, length, uncompressed_size))| {
+                let page_start = writer.offset;
+                let mut page_writer =
+                    PageWriter::new(&mut *writer, options.page_checksum, key, index);
+                let codec = write(
+                    &mut page_writer,
+                    sub_array.as_ref(),
+                    &sub_nested,
+                    type_.clone(),
+                    length,
+                    options.clone(),
+                    scratch,
+                )?;
+                codecs.push(codec);
+                uncompressed_sizes.push(uncompressed_size);
+                let checksum = page_writer.finish()?;
+
+                let page_end = writer.offset;
+                let num_values = num_values(&sub_nested);
+                Ok(PageMeta {
+                    length: (page_end - page_start),
+                    num_values: num_values as u64,
+                    null_count: Some(sub_array.null_count() as u64),
+                    statistics: options_statistics(options, sub_array.as_ref(), &sub_nested),
+                    checksum,
+                    encrypted: key.is_some(),
+                    zstd_dictionary: false,
+                })
+            },
+        )
+        .collect::<Result<_>>()?;
+
+    let dictionary = write_dictionary_values(
+        writer,
+        options,
+        scratch,
+        leaf_array.as_ref(),
+        &type_,
+        key,
+        page_metas.len(),
+    )?;
+    let bloom_filter = write_bloom_filter(writer, options, leaf_array.as_ref())?;
+    let reports = page_reports(&page_metas, &codecs, &uncompressed_sizes);
+    let codec_counts = codec_counts(&codecs);
+    let meta = ColumnMeta {
+        offset: start,
+        null_count: page_metas.iter().map(|page| page.null_count).sum(),
+        pages: page_metas,
+        zstd_dictionary: None,
+        dictionary,
+        bloom_filter,
+        dominant_codec: dominant_codec(&codec_counts),
+        codec_counts,
+        statistics,
+        sorted: options.sorted,
+        distinct_count,
+    };
+    Ok((meta, reports))
+}
+
+/// The statistics of a page or column, `None` without `write_statistics`.
+fn options_statistics(
+    options: &WriteOptions,
+    array: &dyn Array,
+    nested: &[Nested],
+) -> Option<PageStatistics> {
+    if !options.write_statistics {
+        return None;
+    }
+    page_statistics(array, nested, options.statistics_truncate_length)
+}
+
+fn page_reports(
+    page_metas: &[PageMeta],
+    codecs: &[Compression],
+    uncompressed_sizes: &[u64],
+) -> Vec<PageEncodingReport> {
+    page_metas
+        .iter()
+        .zip(codecs)
+        .zip(uncompressed_sizes)
+        .map(
+            |((page_meta, codec), uncompressed_size)| PageEncodingReport {
+                codec: *codec,
+                compressed_size: page_meta.length,
+                uncompressed_size: *uncompressed_size,
+            },
+        )
+        .collect()
+}
+
+/// Writes the bytes of a page through to the file, computing their crc32 if asked to.
+/// The page at `index` of an encrypted column is buffered and written encrypted by
+/// `finish`, its crc32 is of the encrypted bytes.
+struct PageWriter<'a, W: Write> {
+    w: &'a mut W,
+    hasher: Option<crc32fast::Hasher>,
+    encryption: Option<(&'a ColumnKey, usize, Vec<u8>)>,
+}
+
+impl<'a, W: Write> PageWriter<'a, W> {
+    fn new(w: &'a mut W, checksum: bool, key: Option<&'a ColumnKey>, index: usize) -> Self {
+        Self {
+            w,
+            hasher: checksum.then(crc32fast::Hasher::new),
+            encryption: key.map(|key| (key, index, vec![])),
+        }
+    }
+
+    /// Writes the encrypted page, if any, and returns the checksum of the page.
+    fn finish(mut self) -> Result<Option<u32>> {
+        if let Some((key, index, page)) = self.encryption.take() {
+            let page = encrypt_page(key, index, &page)?;
+            self.write_all(&page)?;
+        }
+        Ok(self.hasher.map(|hasher| hasher.finalize()))
     }
 }
 
+impl<W: Write> Write for PageWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if let Some((_, _, page)) = self.encryption.as_mut() {
+            page.extend_from_slice(buf);
+            return Ok(buf.len());
+        }
+        let size = self.w.write(buf)?;
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(&buf[..size]);
+        }
+        Ok(size)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.w.flush()
+    }
+}
+
+/// The number of pages per encoding, in the order the encodings were first used.
+fn codec_counts(codecs: &[Compression]) -> Vec<(Compression, u64)> {
+    let mut counts: Vec<(Compression, u64)> = vec![];
+    for codec in codecs {
+        match counts.iter_mut().find(|(c, _)| c == codec) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((*codec, 1)),
+        }
+    }
+    counts
+}
+
+/// The encoding used by most pages, the first one used on ties.
+pub(crate) fn dominant_codec(counts: &[(Compression, u64)]) -> Compression {
+    counts
+        .iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(codec, _)| *codec)
+        .unwrap_or_default()
+}
+
+/// Moves the offsets of a column encoded into its own buffer to where the buffer is written.
+#[cfg(feature = "rayon")]
+fn shift_offsets(meta: &mut ColumnMeta, base: u64) {
+    meta.offset += base;
+    if let Some(dictionary) = meta.dictionary.as_mut() {
+        shift_offsets(dictionary, base);
+    }
+    if let Some(bloom_filter) = meta.bloom_filter.as_mut() {
+        bloom_filter.offset += base;
+    }
+}
+
+/// Writes the values of a `DictionaryArray` column as a single page after its
+/// `num_pages` key pages, encrypted as the page following them.
+fn write_dictionary_values<W: Write>(
+    writer: &mut OffsetWriter<W>,
+    options: &WriteOptions,
+    scratch: &mut Vec<u8>,
+    array: &dyn Array,
+    type_: &PrimitiveType,
+    key: Option<&ColumnKey>,
+    num_pages: usize,
+) -> Result<Option<Box<ColumnMeta>>> {
+    let values = match array.data_type().to_physical_type() {
+        PhysicalType::Dictionary(key_type) => with_match_dictionary_key_type!(key_type, |$T| {
+            let array: &DictionaryArray<$T> = array.as_any().downcast_ref().unwrap();
+            array.values().clone()
+        }),
+        _ => return Ok(None),
+    };
+
+    let start = writer.offset;
+    let mut page_writer = PageWriter::new(&mut *writer, false, key, num_pages);
+    let codec = write_simple(
+        &mut page_writer,
+        values.as_ref(),
+        type_.clone(),
+        options.clone(),
+        scratch,
+    )?;
+    page_writer.finish()?;
+    Ok(Some(Box::new(ColumnMeta {
+        offset: start,
+        pages: vec![PageMeta {
+            length: writer.offset - start,
+            num_values: values.len() as u64,
+            null_count: Some(values.null_count() as u64),
+            statistics: None,
+            checksum: None,
+            encrypted: key.is_some(),
+            zstd_dictionary: false,
+        }],
+        zstd_dictionary: None,
+        dictionary: None,
+        bloom_filter: None,
+        null_count: Some(values.null_count() as u64),
+        dominant_codec: codec,
+        codec_counts: vec![(codec, 1)],
+        statistics: None,
+        sorted: false,
+        distinct_count: None,
+    })))
+}
+
+/// Writes the bloom filter of a column after its pages if `WriteOptions::bloom_filter` is set.
+fn write_bloom_filter<W: Write>(
+    writer: &mut OffsetWriter<W>,
+    options: &WriteOptions,
+    array: &dyn Array,
+) -> Result<Option<BloomFilter>> {
+    if !options.bloom_filter {
+        return Ok(None);
+    }
+    let bitset = match column_bloom_filter(array) {
+        Some(bitset) => bitset,
+        None => return Ok(None),
+    };
+
+    let offset = writer.offset;
+    writer.write_all(&bitset)?;
+    Ok(Some(BloomFilter {
+        offset,
+        length: bitset.len() as u64,
+        bitset,
+    }))
+}
+
 /// Write a record batch to the writer, writing the message size before the message
 /// if the record batch is being written to a stream
 pub fn write_continuation<W: Write>(writer: &mut W, total_len: i32) -> Result<usize> {
@@ -0,0 +1,210 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::io::{Read, Seek, SeekFrom, Write};
+
+use arrow::datatypes::DataType;
+use arrow::error::{Error, Result};
+use arrow::io::parquet::read::n_columns;
+
+use crate::read::reader::{
+    read_bloom_filters, read_catalog_entry, read_format_version, CatalogEntry,
+};
+use crate::{BloomFilter, ColumnMeta, Compression, SINGLE_ROW_GROUP_VERSION};
+
+use super::{common::dominant_codec, statistics::merge_statistics, NativeWriter, WriteOptions};
+
+/// Merges strawboat files of the same schema into one file written to `output`,
+/// the rows of each column following the order of `inputs`.
+///
+/// The pages are self-contained, so their bytes are copied as they are, without
+/// being decoded, and only their offsets in the footer change. The bloom filter of
+/// a column is kept when every input has one of the same size, the union of them.
+/// Its min and max are kept when every input has them, the smallest min and the
+/// largest max, but its distinct count is dropped, as the estimates of the inputs
+/// can't be added.
+/// Returns the column metas and the size of the file in bytes.
+///
+/// # Errors
+/// Errors if there are no inputs, if they are files of an older version, if their
+/// schemas differ, or if a column has a zstd dictionary or is a dictionary column,
+/// as their pages depend on the dictionary of their file. The encrypted columns
+/// are rejected too, as their pages are authenticated with their index.
+pub fn merge_files<R: Read + Seek, W: Write>(
+    mut inputs: Vec<R>,
+    output: W,
+) -> Result<(Vec<ColumnMeta>, u64)> {
+    for (i, input) in inputs.iter_mut().enumerate() {
+        let version = read_format_version(input)?;
+        if version != SINGLE_ROW_GROUP_VERSION {
+            return Err(Error::InvalidArgumentError(format!(
+                "The pages of the file {i} of version {version} can't be copied to a file of version {SINGLE_ROW_GROUP_VERSION}"
+            )));
+        }
+    }
+    let entries = inputs
+        .iter_mut()
+        .map(|input| {
+            let mut entry = read_catalog_entry(input)?;
+            read_bloom_filters(input, &mut entry.column_metas)?;
+            Ok(entry)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let schema = match entries.first() {
+        Some(entry) => entry.schema.clone(),
+        None => {
+            return Err(Error::InvalidArgumentError(
+                "There are no files to merge".to_string(),
+            ))
+        }
+    };
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.schema != schema {
+            return Err(Error::InvalidArgumentError(format!(
+                "The schema of the file {i} differs from the schema of the file 0"
+            )));
+        }
+    }
+    let num_columns = entries[0].column_metas.len();
+    check_columns(&entries, num_columns)?;
+    // the type of each leaf column, `None` for the leaves of the fields with several
+    let leaf_types: Vec<Option<DataType>> = schema
+        .fields
+        .iter()
+        .flat_map(|field| match n_columns(&field.data_type) {
+            1 => vec![Some(field.data_type.clone())],
+            n => vec![None; n],
+        })
+        .collect();
+
+    let mut writer = NativeWriter::new(output, schema, WriteOptions::default());
+    let num_rows: Option<u64> = entries.iter().map(|entry| entry.num_rows).sum();
+    writer.row_group_rows.extend(num_rows);
+    writer.start()?;
+    for column in 0..num_columns {
+        let offset = writer.writer.offset;
+        let mut pages = vec![];
+        for (input, entry) in inputs.iter_mut().zip(entries.iter()) {
+            let meta = &entry.column_metas[column];
+            input.seek(SeekFrom::Start(meta.offset))?;
+            let length = meta.total_len();
+            let copied = std::io::copy(&mut input.take(length), &mut writer.writer)?;
+            if copied != length {
+                return Err(Error::OutOfSpec(format!(
+                    "The pages of the column {column} end {} bytes past the end of their file",
+                    length - copied
+                )));
+            }
+            pages.extend(meta.pages.iter().cloned());
+        }
+
+        let metas: Vec<&ColumnMeta> = entries
+            .iter()
+            .map(|entry| &entry.column_metas[column])
+            .collect();
+        let bloom_filter = merge_bloom_filters(&metas).map(|bitset| BloomFilter {
+            offset: writer.writer.offset,
+            length: bitset.len() as u64,
+            bitset,
+        });
+        if let Some(bloom_filter) = &bloom_filter {
+            writer.writer.write_all(&bloom_filter.bitset)?;
+        }
+
+        let statistics = match leaf_types.get(column) {
+            Some(Some(data_type)) => {
+                let statistics: Vec<_> =
+                    metas.iter().map(|meta| meta.statistics.as_ref()).collect();
+                merge_statistics(data_type, &statistics)
+            }
+            _ => None,
+        };
+        let codec_counts = merge_codec_counts(&metas);
+        writer.metas.push(ColumnMeta {
+            offset,
+            pages,
+            zstd_dictionary: None,
+            dictionary: None,
+            bloom_filter,
+            null_count: metas.iter().map(|meta| meta.null_count).sum(),
+            dominant_codec: dominant_codec(&codec_counts),
+            codec_counts,
+            statistics,
+            sorted: false,
+            distinct_count: None,
+        });
+    }
+    writer.write_footer()?;
+    let size = writer.total_size() as u64;
+    Ok((writer.metas, size))
+}
+
+/// Checks that the columns of every file can be merged by copying their pages.
+fn check_columns(entries: &[CatalogEntry], num_columns: usize) -> Result<()> {
+    for (i, entry) in entries.iter().enumerate() {
+        if entry.column_metas.len() != num_columns {
+            return Err(Error::OutOfSpec(format!(
+                "The file {i} has {} columns instead of {num_columns}",
+                entry.column_metas.len()
+            )));
+        }
+        for (column, meta) in entry.column_metas.iter().enumerate() {
+            if meta.dictionary.is_some() {
+                return Err(Error::InvalidArgumentError(format!(
+                    "The dictionary column {column} can't be merged"
+                )));
+            }
+            if meta.zstd_dictionary.is_some() {
+                return Err(Error::InvalidArgumentError(format!(
+                    "The column {column} compressed with a zstd dictionary can't be merged"
+                )));
+            }
+            if meta.pages.iter().any(|page| page.encrypted) {
+                return Err(Error::InvalidArgumentError(format!(
+                    "The encrypted column {column} can't be merged"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The union of the bloom filters of the column in every file, `None` if a file
+/// has none or their sizes differ.
+fn merge_bloom_filters(metas: &[&ColumnMeta]) -> Option<Vec<u8>> {
+    let mut bitset = metas[0].bloom_filter.as_ref()?.bitset.clone();
+    for meta in &metas[1..] {
+        let other = &meta.bloom_filter.as_ref()?.bitset;
+        if other.len() != bitset.len() {
+            return None;
+        }
+        bitset.iter_mut().zip(other).for_each(|(a, b)| *a |= b);
+    }
+    Some(bitset)
+}
+
+/// The sum of the numbers of pages per encoding of the column in every file.
+fn merge_codec_counts(metas: &[&ColumnMeta]) -> Vec<(Compression, u64)> {
+    let mut counts: Vec<(Compression, u64)> = vec![];
+    for (codec, pages) in metas.iter().flat_map(|meta| &meta.codec_counts) {
+        match counts.iter_mut().find(|(c, _)| c == codec) {
+            Some((_, count)) => *count += pages,
+            None => counts.push((*codec, *pages)),
+        }
+    }
+    counts
+}
@@ -16,12 +16,21 @@
 // under the License.
 
 pub(crate) mod binary;
+mod bloom_filter;
 pub(crate) mod boolean;
 pub(crate) mod common;
+pub(crate) mod fixed_binary;
+mod merge;
 pub(crate) mod primitive;
+mod report;
 mod serialize;
+mod statistics;
 pub(crate) mod writer;
 
-pub use common::WriteOptions;
+pub use common::{
+    WriteOptions, WriteOptionsBuilder, DEFAULT_MAX_DICT_UNIQUE_RATIO, DEFAULT_SORTED_COMPRESS_RATIO,
+};
+pub use merge::merge_files;
+pub use report::{ColumnEncodingReport, PageEncodingReport};
 pub use serialize::write;
-pub use writer::NativeWriter;
+pub use writer::{write_file, NativeWriter};
@@ -19,11 +19,12 @@ use std::io::Write;
 
 use arrow::array::Array;
 use arrow::error::Result;
-use arrow::types::i256;
+use arrow::types::{f16, i256};
 use arrow::{array::PrimitiveArray, types::NativeType};
 
 use crate::compression::double::compress_double;
 use crate::compression::integer::compress_integer;
+use crate::util::f16_to_bits;
 
 use super::WriteOptions;
 
@@ -87,7 +88,11 @@ pub(crate) fn write_primitive<T: NativeType, W: Write>(
             compress_double(array, write_options, scratch)?;
         }
 
-        arrow::types::PrimitiveType::Float16 => unimplemented!(),
+        arrow::types::PrimitiveType::Float16 => {
+            let array: &PrimitiveArray<f16> = array.as_any().downcast_ref().unwrap();
+            let array = f16_to_bits(array);
+            compress_integer(&array, write_options, scratch)?;
+        }
         arrow::types::PrimitiveType::DaysMs => unimplemented!(),
         arrow::types::PrimitiveType::MonthDayNano => unimplemented!(),
     }
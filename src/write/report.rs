@@ -0,0 +1,48 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::compression::Compression;
+
+/// The encodings chosen for the pages of a leaf column, see
+/// [`super::NativeWriter::encoding_reports`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnEncodingReport {
+    /// The index of the [`crate::ColumnMeta`] of the column in
+    /// [`super::NativeWriter::metas`].
+    pub column: usize,
+    pub pages: Vec<PageEncodingReport>,
+}
+
+/// The encoding chosen for the values of a page and the sizes it led to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageEncodingReport {
+    pub codec: Compression,
+    /// The bytes of the page in the file, its levels and validity included.
+    pub compressed_size: u64,
+    /// The estimated bytes of the arrow array of the page.
+    pub uncompressed_size: u64,
+}
+
+impl ColumnEncodingReport {
+    /// The ratio of the uncompressed to the compressed bytes of the pages.
+    pub fn compress_ratio(&self) -> f64 {
+        let (compressed, uncompressed) = self.pages.iter().fold((0, 0), |(c, u), page| {
+            (c + page.compressed_size, u + page.uncompressed_size)
+        });
+        uncompressed as f64 / compressed.max(1) as f64
+    }
+}
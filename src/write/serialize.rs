@@ -21,18 +21,28 @@ use arrow::{
     array::*,
     bitmap::Bitmap,
     datatypes::{DataType, PhysicalType},
-    error::Result,
-    io::parquet::write::{write_def_levels, write_rep_and_def, Nested, Version},
+    error::{Error, Result},
+    io::parquet::write::{num_values, write_def_levels, write_rep_and_def, Nested, Version},
 };
-use parquet2::schema::{
-    types::{FieldInfo, PrimitiveType},
-    Repetition,
+use parquet2::{
+    encoding::{hybrid_rle::HybridRleDecoder, uleb128},
+    read::levels::get_bit_width,
+    schema::{
+        types::{FieldInfo, PrimitiveType},
+        Repetition,
+    },
 };
 
-use super::{boolean::write_bitmap, primitive::write_primitive, WriteOptions};
-use crate::{with_match_primitive_type, write::binary::write_binary};
+use super::{
+    boolean::write_bitmap, fixed_binary::write_fixed_binary, primitive::write_primitive,
+    WriteOptions,
+};
+use crate::{
+    compression::split_codec, util::encode_hybrid_rle, with_match_dictionary_key_type,
+    with_match_primitive_type, write::binary::write_binary, CommonCompression, Compression,
+};
 
-/// Writes an [`Array`] to the file
+/// Writes an [`Array`] to the file, returns the encoding of its values
 pub fn write<W: Write>(
     w: &mut W,
     array: &dyn Array,
@@ -41,51 +51,91 @@ pub fn write<W: Write>(
     length: usize,
     write_options: WriteOptions,
     scratch: &mut Vec<u8>,
-) -> Result<()> {
+) -> Result<Compression> {
     if nested.len() == 1 {
         return write_simple(w, array, type_, write_options, scratch);
     }
     write_nested(w, array, nested, length, write_options, scratch)
 }
 
-/// Writes an [`Array`] to `arrow_data`
+/// Writes an [`Array`] to `arrow_data`, returns the encoding of its values
 pub fn write_simple<W: Write>(
     w: &mut W,
     array: &dyn Array,
     type_: PrimitiveType,
     write_options: WriteOptions,
     scratch: &mut Vec<u8>,
-) -> Result<()> {
+) -> Result<Compression> {
     use PhysicalType::*;
 
     let is_optional = is_nullable(&type_.field_info);
+    if is_optional && is_all_null(array) {
+        write_validity::<W>(
+            w,
+            is_optional,
+            array.validity(),
+            array.len(),
+            &write_options,
+            scratch,
+        )?;
+        write_all_null(w)?;
+        return Ok(Compression::AllNull);
+    }
     match array.data_type().to_physical_type() {
-        Null => {}
+        Null => return Ok(Compression::None),
         Boolean => {
             let array: &BooleanArray = array.as_any().downcast_ref().unwrap();
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
             write_bitmap::<W>(w, array, write_options, scratch)?
         }
         Primitive(primitive) => with_match_primitive_type!(primitive, |$T| {
             let array: &PrimitiveArray<$T> = array.as_any().downcast_ref().unwrap();
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
             write_primitive::<$T, W>(w, array, write_options, scratch)?;
         }),
         Binary => {
             let array: &BinaryArray<i32> = array.as_any().downcast_ref().unwrap();
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
             write_binary::<i32, W>(w, array, write_options, scratch)?;
         }
         LargeBinary => {
             let array: &BinaryArray<i64> = array.as_any().downcast_ref().unwrap();
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
             write_binary::<i64, W>(w, array, write_options, scratch)?;
         }
@@ -93,7 +143,14 @@ pub fn write_simple<W: Write>(
             let binary_array: &Utf8Array<i32> = array.as_any().downcast_ref().unwrap();
 
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
 
             let binary_array = BinaryArray::new(
@@ -108,30 +165,67 @@ pub fn write_simple<W: Write>(
             let binary_array: &Utf8Array<i64> = array.as_any().downcast_ref().unwrap();
 
             if is_optional {
-                write_validity::<W>(w, is_optional, array.validity(), array.len(), scratch)?;
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
             }
 
             let binary_array = BinaryArray::new(
-                DataType::Binary,
+                DataType::LargeBinary,
                 binary_array.offsets().clone(),
                 binary_array.values().clone(),
                 binary_array.validity().cloned(),
             );
             write_binary::<i64, W>(w, &binary_array, write_options, scratch)?;
         }
+        FixedSizeBinary => {
+            let array: &FixedSizeBinaryArray = array.as_any().downcast_ref().unwrap();
+            if is_optional {
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
+            }
+            write_fixed_binary::<W>(w, array, write_options, scratch)?;
+        }
         Struct => unreachable!(),
         List => unreachable!(),
         FixedSizeList => unreachable!(),
-        Dictionary(_key_type) => unreachable!(),
+        Dictionary(key_type) => with_match_dictionary_key_type!(key_type, |$T| {
+            let array: &DictionaryArray<$T> = array.as_any().downcast_ref().unwrap();
+            if is_optional {
+                write_validity::<W>(
+                    w,
+                    is_optional,
+                    array.validity(),
+                    array.len(),
+                    &write_options,
+                    scratch,
+                )?;
+            }
+            // the values are written once for the column, see `NativeWriter::encode_chunk`
+            write_primitive::<$T, W>(w, array.keys(), write_options, scratch)?;
+        }),
         Union => unreachable!(),
         Map => unreachable!(),
         _ => todo!(),
     }
 
-    Ok(())
+    values_compression(scratch)
 }
 
-/// Writes a nested [`Array`] to `arrow_data`
+/// Writes a nested [`Array`] to `arrow_data`, returns the encoding of its values.
+/// The offsets of the `List`, `LargeList` and `Map` levels are not written, the reader
+/// rebuilds them from the rep and def levels, whose runs are RLE encoded.
 pub fn write_nested<W: Write>(
     w: &mut W,
     array: &dyn Array,
@@ -139,14 +233,20 @@ pub fn write_nested<W: Write>(
     length: usize,
     write_options: WriteOptions,
     scratch: &mut Vec<u8>,
-) -> Result<()> {
-    write_nested_validity::<W>(w, nested, length, scratch)?;
+) -> Result<Compression> {
+    if !is_required_nested(nested) {
+        write_nested_validity::<W>(w, nested, length, &write_options, scratch)?;
+    }
+    if is_all_null(array) {
+        write_all_null(w)?;
+        return Ok(Compression::AllNull);
+    }
 
     scratch.clear();
 
     use PhysicalType::*;
     match array.data_type().to_physical_type() {
-        Null => {}
+        Null => return Ok(Compression::None),
         Boolean => {
             let array: &BooleanArray = array.as_any().downcast_ref().unwrap();
             write_bitmap::<W>(w, array, write_options, scratch)?
@@ -185,16 +285,24 @@ pub fn write_nested<W: Write>(
 
             write_binary::<i64, W>(w, &binary_array, write_options, scratch)?;
         }
+        FixedSizeBinary => {
+            let array: &FixedSizeBinaryArray = array.as_any().downcast_ref().unwrap();
+            write_fixed_binary::<W>(w, array, write_options, scratch)?;
+        }
         Struct => unreachable!(),
         List => unreachable!(),
         FixedSizeList => unreachable!(),
-        Dictionary(_key_type) => unreachable!(),
+        Dictionary(_key_type) => {
+            return Err(Error::NotYetImplemented(
+                "Writing nested dictionary arrays is not yet implemented".to_string(),
+            ))
+        }
         Union => unreachable!(),
         Map => unreachable!(),
         _ => todo!(),
     }
 
-    Ok(())
+    values_compression(scratch)
 }
 
 fn write_validity<W: Write>(
@@ -202,32 +310,181 @@ fn write_validity<W: Write>(
     is_optional: bool,
     validity: Option<&Bitmap>,
     length: usize,
+    write_options: &WriteOptions,
     scratch: &mut Vec<u8>,
 ) -> Result<()> {
     scratch.clear();
 
+    if let (true, Some(validity), Some(ratio)) = (
+        is_optional,
+        validity,
+        write_options.sparse_validity_null_ratio,
+    ) {
+        if validity.unset_bits() as f64 > ratio * length as f64
+            && write_sparse_validity(w, validity, scratch)?
+        {
+            return Ok(());
+        }
+        scratch.clear();
+    }
+
     write_def_levels(scratch, is_optional, validity, length, Version::V2)?;
-    let def_levels_len = scratch.len();
-    w.write_all(&(def_levels_len as u32).to_le_bytes())?;
-    w.write_all(&scratch[..def_levels_len])?;
+    write_buffer(w, scratch, write_options)
+}
 
-    Ok(())
+/// Writes `validity` as the number of nulls before each valid value, LEB128 encoded,
+/// after a [`Compression::SparseValidity`] header whose uncompressed size is the number
+/// of valid values. Returns `false` without writing if it is not smaller than the bitmap.
+fn write_sparse_validity<W: Write>(
+    w: &mut W,
+    validity: &Bitmap,
+    scratch: &mut Vec<u8>,
+) -> Result<bool> {
+    let bitmap_size = (validity.len() + 7) / 8;
+    let mut container = [0u8; 10];
+    let mut next = 0;
+    for (index, is_valid) in validity.iter().enumerate() {
+        if is_valid {
+            let used = uleb128::encode((index - next) as u64, &mut container);
+            scratch.extend_from_slice(&container[..used]);
+            if scratch.len() >= bitmap_size {
+                return Ok(false);
+            }
+            next = index + 1;
+        }
+    }
+    let num_valid = validity.len() - validity.unset_bits();
+    w.write_all(&[u8::from(Compression::SparseValidity)])?;
+    w.write_all(&(scratch.len() as u64).to_le_bytes())?;
+    w.write_all(&(num_valid as u64).to_le_bytes())?;
+    w.write_all(scratch)?;
+    Ok(true)
+}
+
+/// Whether every level of a nested column is required and not repeated,
+/// its rep and def levels are then all zeros and are not written.
+fn is_required_nested(nested: &[Nested]) -> bool {
+    nested.iter().all(|n| match n {
+        Nested::Primitive(_, is_optional, _) | Nested::Struct(_, is_optional, _) => !is_optional,
+        Nested::List(_) | Nested::LargeList(_) => false,
+    })
 }
 
 fn write_nested_validity<W: Write>(
     w: &mut W,
     nested: &[Nested],
     length: usize,
+    write_options: &WriteOptions,
     scratch: &mut Vec<u8>,
 ) -> Result<()> {
     scratch.clear();
 
     let (rep_levels_len, def_levels_len) = write_rep_and_def(Version::V2, nested, scratch)?;
+    let (rep_levels_len, def_levels_len) =
+        compact_levels(nested, scratch, rep_levels_len, def_levels_len)?;
     w.write_all(&(length as u32).to_le_bytes())?;
-    w.write_all(&(rep_levels_len as u32).to_le_bytes())?;
-    w.write_all(&(def_levels_len as u32).to_le_bytes())?;
-    w.write_all(&scratch[..scratch.len()])?;
+    w.write_all(&(rep_levels_len as u64).to_le_bytes())?;
+    w.write_all(&(def_levels_len as u64).to_le_bytes())?;
+    write_buffer(w, scratch, write_options)
+}
+
+/// Re-encodes the rep and def levels that arrow bit-packs in `scratch` with the
+/// runs of the hybrid RLE, for each of them that it makes smaller, and returns
+/// their new lengths. The levels of deeply nested columns repeat a lot, e.g. the
+/// def levels of the values that are all valid.
+fn compact_levels(
+    nested: &[Nested],
+    scratch: &mut Vec<u8>,
+    rep_levels_len: usize,
+    def_levels_len: usize,
+) -> Result<(usize, usize)> {
+    let length = num_values(nested);
+    let (max_rep_level, max_def_level) = max_levels(nested);
+    let mut compact = Vec::with_capacity(scratch.len());
+    let mut lengths = [rep_levels_len, def_levels_len];
+    let mut start = 0;
+    for (levels_len, max_level) in lengths.iter_mut().zip([max_rep_level, max_def_level]) {
+        let packed = &scratch[start..start + *levels_len];
+        start += *levels_len;
+        if packed.is_empty() {
+            continue;
+        }
+        let num_bits = get_bit_width(max_level);
+        let levels = HybridRleDecoder::try_new(packed, num_bits, length)?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let compact_start = compact.len();
+        encode_hybrid_rle(&mut compact, &levels, num_bits)?;
+        if compact.len() - compact_start >= packed.len() {
+            compact.truncate(compact_start);
+            compact.extend_from_slice(packed);
+        }
+        *levels_len = compact.len() - compact_start;
+    }
+    *scratch = compact;
+    Ok((lengths[0], lengths[1]))
+}
+
+/// The max rep and def levels of a nested column.
+fn max_levels(nested: &[Nested]) -> (i16, i16) {
+    nested
+        .iter()
+        .fold((0, 0), |(rep, def), nested| match nested {
+            Nested::Primitive(_, is_optional, _) | Nested::Struct(_, is_optional, _) => {
+                (rep, def + *is_optional as i16)
+            }
+            Nested::List(list) => (rep + 1, def + 1 + list.is_optional as i16),
+            Nested::LargeList(list) => (rep + 1, def + 1 + list.is_optional as i16),
+        })
+}
+
+/// Writes `buffer` compressed with the default compression, after the codec,
+/// compressed size and uncompressed size header every compressed buffer has.
+fn write_buffer<W: Write>(w: &mut W, buffer: &[u8], write_options: &WriteOptions) -> Result<()> {
+    let c = if buffer.is_empty() {
+        CommonCompression::None
+    } else {
+        write_options.default_compression
+    };
+    w.write_all(&[u8::from(c.to_compression())])?;
+    if c == CommonCompression::None {
+        w.write_all(&(buffer.len() as u64).to_le_bytes())?;
+        w.write_all(&(buffer.len() as u64).to_le_bytes())?;
+        w.write_all(buffer)?;
+        return Ok(());
+    }
+
+    let mut compressed = Vec::with_capacity(buffer.len());
+    let compressed_size =
+        c.compress_with_level(buffer, &mut compressed, write_options.compression_level)?;
+    w.write_all(&(compressed_size as u64).to_le_bytes())?;
+    w.write_all(&(buffer.len() as u64).to_le_bytes())?;
+    w.write_all(&compressed)?;
+    Ok(())
+}
+
+/// Whether the values of a page are all null and can be written with [`write_all_null`].
+/// The encoding of the values buffer the value writers leave in `scratch`.
+fn values_compression(scratch: &[u8]) -> Result<Compression> {
+    match scratch.first() {
+        Some(codec) => split_codec(*codec).map(|(compression, _)| compression),
+        None => Err(Error::OutOfSpec(
+            "The values of a page were not written".to_string(),
+        )),
+    }
+}
+
+fn is_all_null(array: &dyn Array) -> bool {
+    !array.is_empty()
+        && array.null_count() == array.len()
+        && array.data_type().to_physical_type() != PhysicalType::Null
+}
 
+/// Writes the values of a page whose values are all null as an empty buffer,
+/// the validity is enough to read the page back.
+fn write_all_null<W: Write>(w: &mut W) -> Result<()> {
+    w.write_all(&[u8::from(Compression::AllNull)])?;
+    w.write_all(&0u64.to_le_bytes())?;
+    w.write_all(&0u64.to_le_bytes())?;
     Ok(())
 }
 
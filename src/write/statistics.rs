@@ -0,0 +1,279 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::cmp::Ordering;
+
+use arrow::array::{Array, BinaryArray, PrimitiveArray, Utf8Array};
+use arrow::compute::aggregate::{
+    max_binary, max_primitive, max_string, min_binary, min_primitive, min_string, SimdOrd,
+};
+use arrow::datatypes::{DataType, PhysicalType, PrimitiveType};
+use arrow::io::parquet::write::Nested;
+use arrow::types::{simd::Simd, NativeType, Offset};
+use num::Float;
+
+use crate::{with_match_integer_double_type, PageStatistics};
+
+/// The min and max of the valid values of a page or column, `None` for nested ones
+/// and the types without statistics. The binary bounds are truncated to
+/// `truncate_length` bytes, see [`truncate_statistics`].
+pub(crate) fn page_statistics(
+    array: &dyn Array,
+    nested: &[Nested],
+    truncate_length: Option<usize>,
+) -> Option<PageStatistics> {
+    use PhysicalType::*;
+
+    if nested.len() != 1 {
+        return None;
+    }
+    match array.data_type().to_physical_type() {
+        Primitive(PrimitiveType::Int8) => primitive_statistics::<i8>(array),
+        Primitive(PrimitiveType::Int16) => primitive_statistics::<i16>(array),
+        Primitive(PrimitiveType::Int32) => primitive_statistics::<i32>(array),
+        Primitive(PrimitiveType::Int64) => primitive_statistics::<i64>(array),
+        Primitive(PrimitiveType::UInt8) => primitive_statistics::<u8>(array),
+        Primitive(PrimitiveType::UInt16) => primitive_statistics::<u16>(array),
+        Primitive(PrimitiveType::UInt32) => primitive_statistics::<u32>(array),
+        Primitive(PrimitiveType::UInt64) => primitive_statistics::<u64>(array),
+        Primitive(PrimitiveType::Float32) => float_statistics::<f32>(array),
+        Primitive(PrimitiveType::Float64) => float_statistics::<f64>(array),
+        Binary => binary_statistics::<i32>(array, truncate_length),
+        LargeBinary => binary_statistics::<i64>(array, truncate_length),
+        Utf8 => utf8_statistics::<i32>(array, truncate_length),
+        LargeUtf8 => utf8_statistics::<i64>(array, truncate_length),
+        _ => None,
+    }
+}
+
+fn primitive_statistics<T>(array: &dyn Array) -> Option<PageStatistics>
+where
+    T: NativeType + Simd,
+    T::Simd: SimdOrd<T>,
+{
+    let array: &PrimitiveArray<T> = array.as_any().downcast_ref().unwrap();
+    Some(PageStatistics {
+        min: min_primitive(array)?.to_le_bytes().as_ref().to_vec(),
+        max: max_primitive(array)?.to_le_bytes().as_ref().to_vec(),
+    })
+}
+
+fn float_statistics<T>(array: &dyn Array) -> Option<PageStatistics>
+where
+    T: NativeType + Simd + Float,
+    T::Simd: SimdOrd<T>,
+{
+    let typed: &PrimitiveArray<T> = array.as_any().downcast_ref().unwrap();
+    // NaN is ordered above every value, which would hide the largest value of the page
+    if typed.iter().flatten().any(|v| v.is_nan()) {
+        return None;
+    }
+    primitive_statistics::<T>(array)
+}
+
+fn binary_statistics<O: Offset>(
+    array: &dyn Array,
+    truncate_length: Option<usize>,
+) -> Option<PageStatistics> {
+    let array: &BinaryArray<O> = array.as_any().downcast_ref().unwrap();
+    let (min, max) = (min_binary(array)?, max_binary(array)?);
+    Some(truncate_statistics(min, max, truncate_length))
+}
+
+fn utf8_statistics<O: Offset>(
+    array: &dyn Array,
+    truncate_length: Option<usize>,
+) -> Option<PageStatistics> {
+    let array: &Utf8Array<O> = array.as_any().downcast_ref().unwrap();
+    let (min, max) = (min_string(array)?, max_string(array)?);
+    Some(truncate_statistics(
+        min.as_bytes(),
+        max.as_bytes(),
+        truncate_length,
+    ))
+}
+
+/// The statistics of the concatenation of columns of `data_type`, the min of
+/// their mins and the max of their maxes, `None` if one of them has none.
+pub(crate) fn merge_statistics(
+    data_type: &DataType,
+    statistics: &[Option<&PageStatistics>],
+) -> Option<PageStatistics> {
+    use PhysicalType::*;
+
+    let statistics = statistics.iter().copied().collect::<Option<Vec<_>>>()?;
+    match data_type.to_physical_type() {
+        Primitive(PrimitiveType::Float16 | PrimitiveType::DaysMs | PrimitiveType::MonthDayNano) => {
+            None
+        }
+        Primitive(primitive) => with_match_integer_double_type!(primitive,
+        |$T| {
+            merge_bounds(&statistics, decode_primitive::<$T>)
+        },
+        |$T| {
+            merge_bounds(&statistics, decode_primitive::<$T>)
+        }),
+        Binary | LargeBinary | Utf8 | LargeUtf8 => merge_bounds(&statistics, Some),
+        _ => None,
+    }
+}
+
+fn decode_primitive<T: NativeType>(bytes: &[u8]) -> Option<T> {
+    Some(T::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// The smallest min and the largest max of `statistics`, compared once decoded
+/// with `decode`, `None` if one of them can't be decoded or compared.
+fn merge_bounds<'a, T: PartialOrd>(
+    statistics: &[&'a PageStatistics],
+    decode: impl Fn(&'a [u8]) -> Option<T>,
+) -> Option<PageStatistics> {
+    let (first, rest) = statistics.split_first()?;
+    let (mut min, mut max) = (&first.min, &first.max);
+    let (mut min_value, mut max_value) = (decode(min)?, decode(max)?);
+    for statistics in rest {
+        let value = decode(&statistics.min)?;
+        if value.partial_cmp(&min_value)? == Ordering::Less {
+            (min, min_value) = (&statistics.min, value);
+        }
+        let value = decode(&statistics.max)?;
+        if value.partial_cmp(&max_value)? == Ordering::Greater {
+            (max, max_value) = (&statistics.max, value);
+        }
+    }
+    Some(PageStatistics {
+        min: min.clone(),
+        max: max.clone(),
+    })
+}
+
+/// Truncates `min` to its first `length` bytes, a lower bound of it, and `max` to
+/// its first `length` bytes with the last one incremented, an upper bound of it.
+/// The trailing 0xFF bytes of the max, which can't be incremented, are dropped
+/// first, and a max of only 0xFF bytes is kept whole.
+/// The truncated utf8 bounds compare like the strings but may not be valid utf8.
+fn truncate_statistics(min: &[u8], max: &[u8], length: Option<usize>) -> PageStatistics {
+    let length = match length {
+        Some(length) => length,
+        None => {
+            return PageStatistics {
+                min: min.to_vec(),
+                max: max.to_vec(),
+            }
+        }
+    };
+    let min = min[..min.len().min(length)].to_vec();
+    let max = if max.len() <= length {
+        max.to_vec()
+    } else {
+        let mut truncated = max[..length].to_vec();
+        match truncated.iter().rposition(|byte| *byte != u8::MAX) {
+            Some(last) => {
+                truncated.truncate(last + 1);
+                truncated[last] += 1;
+                truncated
+            }
+            None => max.to_vec(),
+        }
+    };
+    PageStatistics { min, max }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::DataType;
+
+    use super::{merge_statistics, truncate_statistics};
+    use crate::PageStatistics;
+
+    #[test]
+    fn test_merge_statistics() {
+        let statistics = |min: f64, max: f64| PageStatistics {
+            min: min.to_le_bytes().to_vec(),
+            max: max.to_le_bytes().to_vec(),
+        };
+        let (a, b) = (statistics(-2.5, 1.0), statistics(-1.0, 300.0));
+        assert_eq!(
+            merge_statistics(&DataType::Float64, &[Some(&a), Some(&b)]),
+            Some(statistics(-2.5, 300.0))
+        );
+        // a column without statistics drops them
+        assert_eq!(
+            merge_statistics(&DataType::Float64, &[Some(&a), None]),
+            None
+        );
+
+        let bytes = |min: &[u8], max: &[u8]| PageStatistics {
+            min: min.to_vec(),
+            max: max.to_vec(),
+        };
+        let (a, b) = (bytes(b"b", b"x"), bytes(b"ab", b"xa"));
+        assert_eq!(
+            merge_statistics(&DataType::Utf8, &[Some(&a), Some(&b)]),
+            Some(bytes(b"ab", b"xa"))
+        );
+    }
+
+    #[test]
+    fn test_truncate_statistics() {
+        let truncate = |min: &[u8], max: &[u8], length| {
+            let statistics = truncate_statistics(min, max, Some(length));
+            (statistics.min, statistics.max)
+        };
+        // short values are kept whole
+        assert_eq!(truncate(b"ab", b"cd", 2), (b"ab".to_vec(), b"cd".to_vec()));
+        assert_eq!(
+            truncate(b"abcdef", b"uvwxyz", 3),
+            (b"abc".to_vec(), b"uvx".to_vec())
+        );
+        // the trailing 0xFF bytes of the max are dropped before the increment
+        assert_eq!(
+            truncate(b"a", &[1, 0xFF, 0xFF, 7], 3),
+            (b"a".to_vec(), vec![2])
+        );
+        assert_eq!(truncate(b"a", &[1, 0xFE, 0xFF, 7], 3).1, vec![1, 0xFF]);
+        // a max of only 0xFF bytes can't be rounded up
+        assert_eq!(
+            truncate(b"a", &[0xFF, 0xFF, 0xFF, 0], 3).1,
+            vec![0xFF, 0xFF, 0xFF, 0]
+        );
+        // a truncation to nothing keeps the max whole
+        assert_eq!(truncate(b"abc", b"xyz", 0), (vec![], b"xyz".to_vec()));
+    }
+
+    #[test]
+    fn test_truncated_statistics_are_bounds() {
+        let values: Vec<Vec<u8>> = vec![
+            vec![0xFF; 10],
+            vec![0xFF, 0xFF, 0xFE, 0xFF, 0xFF],
+            vec![0, 0, 0, 1],
+            vec![0x61; 100],
+            vec![0x61, 0xFF, 0xFF, 0xFF],
+            vec![0x62],
+        ];
+        for min in values.iter() {
+            for max in values.iter().filter(|max| *max >= min) {
+                for length in 0..12 {
+                    let statistics = truncate_statistics(min, max, Some(length));
+                    assert!(statistics.min.len() <= length);
+                    assert!(statistics.min <= *min);
+                    assert!(statistics.max >= *max, "{max:?} truncated to {length}");
+                }
+            }
+        }
+    }
+}
@@ -18,17 +18,25 @@
 use std::io::Write;
 
 use super::common::write_continuation;
-use super::{super::ARROW_MAGIC, common::WriteOptions};
+use super::{
+    super::{ARROW_MAGIC, FORMAT_VERSION},
+    common::WriteOptions,
+    report::ColumnEncodingReport,
+};
 use arrow::datatypes::Schema;
 
 use arrow::array::Array;
 use arrow::chunk::Chunk;
+use arrow::compute::concatenate::concatenate;
 use arrow::error::{Error, Result};
 use arrow::io::ipc::write::{default_ipc_fields, schema_to_bytes};
 
-use arrow::io::parquet::write::to_parquet_schema;
+use parquet2::encoding::uleb128;
 
-use crate::ColumnMeta;
+use crate::{
+    to_parquet_schema, ColumnMeta, PageStatistics, RowGroupMeta, SINGLE_ROW_GROUP_VERSION,
+    VARINT_META_FLAG, VERSIONED_META_FLAG,
+};
 
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) enum State {
@@ -47,8 +55,13 @@ pub struct NativeWriter<W: Write> {
     /// A reference to the schema, used in validating record batches
     pub(crate) schema: Schema,
 
-    /// Record blocks that will be written as part of the strawboat footer
+    /// Record blocks that will be written as part of the strawboat footer,
+    /// the leaf columns of each row group after the ones of the previous row group
     pub metas: Vec<ColumnMeta>,
+    /// The number of rows of each row group, empty if unknown
+    pub(crate) row_group_rows: Vec<u64>,
+    /// The encodings of the pages of each column of `metas`
+    pub(crate) encoding_reports: Vec<ColumnEncodingReport>,
 
     pub(crate) scratch: Vec<u8>,
     /// Whether the writer footer has been written, and the writer is finished
@@ -75,11 +88,19 @@ impl<W: Write> NativeWriter<W> {
             options,
             schema,
             metas: Vec::with_capacity(num_cols),
+            row_group_rows: vec![],
+            encoding_reports: vec![],
             scratch: Vec::with_capacity(0),
             state: State::None,
         }
     }
 
+    /// The codec chosen for each page of each column written so far, with the sizes
+    /// of the pages, in the order of [`NativeWriter::metas`].
+    pub fn encoding_reports(&self) -> &[ColumnEncodingReport] {
+        &self.encoding_reports
+    }
+
     /// Consumes itself into the inner writer
     pub fn into_inner(self) -> W {
         self.writer.w
@@ -96,18 +117,23 @@ impl<W: Write> NativeWriter<W> {
         }
         // write magic to header
         self.writer.write_all(&ARROW_MAGIC[..])?;
-        // create an 8-byte boundary after the header
-        self.writer.write_all(&[0, 0])?;
+        // the format version also creates an 8-byte boundary after the header,
+        // the row groups are only known to the footer
+        self.writer
+            .write_all(&SINGLE_ROW_GROUP_VERSION.to_le_bytes())?;
 
         self.state = State::Started;
         Ok(())
     }
 
-    /// Writes [`Chunk`] to the file
+    /// Writes [`Chunk`] to the file as the pages of a row group, see [`Self::finish_row_group`].
+    /// The pages of a column are contiguous in the file, so a row group holds one chunk.
+    /// # Errors
+    /// Errors if a chunk has been written since the previous row group.
     pub fn write(&mut self, chunk: &Chunk<Box<dyn Array>>) -> Result<()> {
         if self.state == State::Written {
             return Err(Error::OutOfSpec(
-                "The strawboat file can only accept one RowGroup in a single file".to_string(),
+                "A row group of the strawboat file holds one chunk. Call `finish_row_group` before writing the next chunk".to_string(),
             ));
         }
         if self.state != State::Started {
@@ -115,24 +141,77 @@ impl<W: Write> NativeWriter<W> {
                 "The strawboat file must be started before it can be written to. Call `start` before `write`".to_string(),
             ));
         }
-        assert_eq!(chunk.arrays().len(), self.schema.fields.len());
+        if chunk.arrays().len() != self.schema.fields.len() {
+            return Err(Error::InvalidArgumentError(format!(
+                "The chunk has {} arrays but the schema has {} fields",
+                chunk.arrays().len(),
+                self.schema.fields.len()
+            )));
+        }
 
         let schema_descriptor = to_parquet_schema(&self.schema)?;
         self.encode_chunk(schema_descriptor, chunk)?;
-
+        self.row_group_rows.push(chunk.len() as u64);
         self.state = State::Written;
         Ok(())
     }
 
-    /// Write footer and closing tag, then mark the writer as done
-    pub fn finish(&mut self) -> Result<()> {
+    /// Ends the row group of the chunk written since the previous row group, so the
+    /// next chunk can be written as a new row group, see
+    /// [`crate::read::reader::read_row_groups`].
+    /// # Errors
+    /// Errors if no chunk has been written to the row group.
+    pub fn finish_row_group(&mut self) -> Result<()> {
         if self.state != State::Written {
             return Err(Error::OutOfSpec(
-                "The strawboat file must be written before it can be finished. Call `start` before `finish`".to_string(),
+                "A row group must be written before it can be finished. Call `write` before `finish_row_group`".to_string(),
             ));
         }
+        self.state = State::Started;
+        Ok(())
+    }
+
+    /// The metas of the row groups finished so far.
+    pub fn row_groups(&self) -> Vec<RowGroupMeta> {
+        if self.row_group_rows.is_empty() {
+            return vec![RowGroupMeta {
+                num_rows: None,
+                columns: self.metas.clone(),
+            }];
+        }
+        let num_columns = self.metas.len() / self.row_group_rows.len();
+        self.row_group_rows
+            .iter()
+            .zip(self.metas.chunks(num_columns.max(1)))
+            .map(|(num_rows, columns)| RowGroupMeta {
+                num_rows: Some(*num_rows),
+                columns: columns.to_vec(),
+            })
+            .collect()
+    }
+
+    /// Write footer and closing tag, then mark the writer as done
+    pub fn finish(&mut self) -> Result<()> {
+        match self.state {
+            State::Written => {}
+            // the last chunk ended a row group
+            State::Started if !self.row_group_rows.is_empty() => {}
+            _ => {
+                return Err(Error::OutOfSpec(
+                    "The strawboat file must be written before it can be finished. Call `start` before `finish`".to_string(),
+                ))
+            }
+        }
+        self.write_footer()
+    }
+
+    /// Writes the footer of the written `metas` and the closing tag, then marks
+    /// the writer as done.
+    pub(crate) fn write_footer(&mut self) -> Result<()> {
         // write footer
         // footer = schema(variable bytes) + column_meta(variable bytes)
+        // + num_rows(variable bytes, if known)
+        // + row groups(variable bytes, if several) + version(2 bytes) + magic(6 bytes)
         // + schema size(4 bytes) + column_meta size(4bytes) + EOS(8 bytes)
         let schema_bytes = schema_to_bytes(&self.schema, &default_ipc_fields(&self.schema.fields));
         // write the schema, set the written bytes to the schema
@@ -140,25 +219,51 @@ impl<W: Write> NativeWriter<W> {
 
         let meta_start = self.writer.offset();
         {
-            self.writer.write_all(&self.metas.len().to_le_bytes())?;
-            for meta in &self.metas {
-                self.writer.write_all(&meta.offset.to_le_bytes())?;
-                self.writer.write_all(&meta.pages.len().to_le_bytes())?;
+            let varint = self.options.varint_footer;
+            let writer = &mut self.writer;
+            let mut container = [0u8; 10];
+            let mut write_value = |writer: &mut OffsetWriter<W>, value: u64| -> Result<()> {
+                if varint {
+                    let len = uleb128::encode(value, &mut container);
+                    writer.write_all(&container[..len])?;
+                } else {
+                    writer.write_all(&value.to_le_bytes())?;
+                }
+                Ok(())
+            };
 
-                for page in meta.pages.iter() {
-                    self.writer.write_all(&page.length.to_le_bytes())?;
-                    self.writer.write_all(&page.num_values.to_le_bytes())?;
+            write_value(writer, self.metas.len() as u64)?;
+            for meta in &self.metas {
+                write_column_meta(writer, meta, &mut write_value)?;
+            }
+            // the readers of the files written before ignore it
+            if !self.row_group_rows.is_empty() {
+                write_value(writer, self.row_group_rows.iter().sum())?;
+            }
+            // the number of row groups followed by the number of rows of each
+            if self.row_group_rows.len() > 1 {
+                write_value(writer, self.row_group_rows.len() as u64)?;
+                for num_rows in &self.row_group_rows {
+                    write_value(writer, *num_rows)?;
                 }
             }
         }
+        let version = if self.row_group_rows.len() > 1 {
+            FORMAT_VERSION
+        } else {
+            SINGLE_ROW_GROUP_VERSION
+        };
+        self.writer.write_all(&version.to_le_bytes())?;
+        self.writer.write_all(&ARROW_MAGIC)?;
         let meta_end = self.writer.offset();
+        let meta_size = footer_meta_size(meta_end - meta_start, self.options.varint_footer)?;
 
         // 4 bytes for schema size
         let schema_size = schema_bytes.len();
         self.writer.write_all(&(schema_size as u32).to_le_bytes())?;
-        // 4 bytes for meta_size
-        self.writer
-            .write_all(&((meta_end - meta_start) as u32).to_le_bytes())?;
+        // 4 bytes for meta_size, the highest bit flags a varint footer
+        // and the next one the version and magic
+        self.writer.write_all(&meta_size.to_le_bytes())?;
         // write EOS
         write_continuation(&mut self.writer, 0)?;
         self.writer.flush()?;
@@ -172,6 +277,165 @@ impl<W: Write> NativeWriter<W> {
     }
 }
 
+/// Writes `chunks` as a whole strawboat file to `writer`: the header, the pages, and
+/// the footer with the schema and the column metas read by `read_meta` and `infer_schema`.
+/// The chunks are concatenated into the one row group of the file.
+/// Returns the column metas and the size of the file in bytes.
+pub fn write_file<W: Write>(
+    writer: W,
+    schema: Schema,
+    chunks: &[Chunk<Box<dyn Array>>],
+    options: WriteOptions,
+) -> Result<(Vec<ColumnMeta>, u64)> {
+    let mut writer = NativeWriter::new(writer, schema, options);
+    writer.start()?;
+    match chunks {
+        [] => {}
+        [chunk] => writer.write(chunk)?,
+        chunks => writer.write(&concatenate_chunks(chunks)?)?,
+    }
+    writer.finish()?;
+    let size = writer.total_size() as u64;
+    Ok((writer.metas, size))
+}
+
+/// The concatenation of the arrays of each column of `chunks`, which are not empty.
+pub(crate) fn concatenate_chunks(
+    chunks: &[Chunk<Box<dyn Array>>],
+) -> Result<Chunk<Box<dyn Array>>> {
+    let num_arrays = chunks[0].arrays().len();
+    if chunks
+        .iter()
+        .any(|chunk| chunk.arrays().len() != num_arrays)
+    {
+        return Err(Error::InvalidArgumentError(
+            "The chunks have different numbers of arrays".to_string(),
+        ));
+    }
+    let arrays = (0..num_arrays)
+        .map(|i| {
+            let arrays: Vec<&dyn Array> = chunks
+                .iter()
+                .map(|chunk| chunk.arrays()[i].as_ref())
+                .collect();
+            concatenate(&arrays)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Chunk::try_new(arrays)
+}
+
+/// The meta size of the tail, with the flags of the format of the column metas.
+/// The flags take the two highest bits, so the column metas are under 1GB.
+fn footer_meta_size(size: usize, varint: bool) -> Result<u32> {
+    if size >= VERSIONED_META_FLAG as usize {
+        return Err(Error::InvalidArgumentError(format!(
+            "The column metas of {size} bytes exceed the {VERSIONED_META_FLAG} bytes of a footer"
+        )));
+    }
+    let mut meta_size = size as u32 | VERSIONED_META_FLAG;
+    if varint {
+        meta_size |= VARINT_META_FLAG;
+    }
+    Ok(meta_size)
+}
+
+fn write_column_meta<W: Write>(
+    writer: &mut OffsetWriter<W>,
+    meta: &ColumnMeta,
+    write_value: &mut impl FnMut(&mut OffsetWriter<W>, u64) -> Result<()>,
+) -> Result<()> {
+    write_value(writer, meta.offset)?;
+    write_value(writer, meta.pages.len() as u64)?;
+
+    for page in meta.pages.iter() {
+        write_value(writer, page.length)?;
+        write_value(writer, page.num_values)?;
+        write_value(writer, page.null_count.unwrap_or_default())?;
+        write_statistics(writer, page.statistics.as_ref(), write_value)?;
+        // 1 followed by the checksum for pages with one, 0 otherwise,
+        // with the second bit set for encrypted pages and the third one
+        // for the pages whose null count is unknown
+        let flags = (page.encrypted as u64) << 1 | (page.null_count.is_none() as u64) << 2;
+        match page.checksum {
+            Some(checksum) => {
+                write_value(writer, 1 | flags)?;
+                write_value(writer, checksum as u64)?;
+            }
+            None => write_value(writer, flags)?,
+        }
+    }
+
+    // 0 for columns without zstd dictionary
+    let zstd_dictionary = meta.zstd_dictionary.as_deref().unwrap_or_default();
+    write_value(writer, zstd_dictionary.len() as u64)?;
+    writer.write_all(zstd_dictionary)?;
+
+    // 1 followed by the values column for dictionary columns, 0 otherwise
+    match &meta.dictionary {
+        Some(dictionary) => {
+            write_value(writer, 1)?;
+            write_column_meta(writer, dictionary, write_value)?;
+        }
+        None => write_value(writer, 0)?,
+    }
+
+    // 1 followed by the offset and length of the bloom filter, 0 otherwise
+    match &meta.bloom_filter {
+        Some(bloom_filter) => {
+            write_value(writer, 1)?;
+            write_value(writer, bloom_filter.offset)?;
+            write_value(writer, bloom_filter.length)?;
+        }
+        None => write_value(writer, 0)?,
+    }
+
+    // 1 followed by the null count for columns with a known one, 0 otherwise
+    match meta.null_count {
+        Some(null_count) => {
+            write_value(writer, 1)?;
+            write_value(writer, null_count)?;
+        }
+        None => write_value(writer, 0)?,
+    }
+    write_value(writer, u8::from(meta.dominant_codec) as u64)?;
+    // the number of encodings followed by each encoding and its number of pages
+    write_value(writer, meta.codec_counts.len() as u64)?;
+    for (codec, pages) in &meta.codec_counts {
+        write_value(writer, u8::from(*codec) as u64)?;
+        write_value(writer, *pages)?;
+    }
+    write_statistics(writer, meta.statistics.as_ref(), write_value)?;
+    write_value(writer, meta.sorted as u64)?;
+
+    // 1 followed by the estimate for columns with a distinct count, 0 otherwise
+    match meta.distinct_count {
+        Some(distinct_count) => {
+            write_value(writer, 1)?;
+            write_value(writer, distinct_count)
+        }
+        None => write_value(writer, 0),
+    }
+}
+
+/// Writes 1 followed by the min and max for a page or column with statistics, 0 otherwise.
+fn write_statistics<W: Write>(
+    writer: &mut OffsetWriter<W>,
+    statistics: Option<&PageStatistics>,
+    write_value: &mut impl FnMut(&mut OffsetWriter<W>, u64) -> Result<()>,
+) -> Result<()> {
+    match statistics {
+        Some(statistics) => {
+            write_value(writer, 1)?;
+            for bound in [&statistics.min, &statistics.max] {
+                write_value(writer, bound.len() as u64)?;
+                writer.write_all(bound)?;
+            }
+        }
+        None => write_value(writer, 0)?,
+    }
+    Ok(())
+}
+
 pub struct OffsetWriter<W: Write> {
     pub w: W,
     pub offset: u64,
@@ -198,3 +462,29 @@ impl<W: std::io::Write> OffsetWrite for OffsetWriter<W> {
         self.offset as usize
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::footer_meta_size;
+    use crate::{VARINT_META_FLAG, VERSIONED_META_FLAG};
+
+    #[test]
+    fn test_footer_meta_size() {
+        assert_eq!(
+            footer_meta_size(100, false).unwrap(),
+            100 | VERSIONED_META_FLAG
+        );
+        assert_eq!(
+            footer_meta_size(100, true).unwrap(),
+            100 | VERSIONED_META_FLAG | VARINT_META_FLAG
+        );
+        let max = VERSIONED_META_FLAG as usize - 1;
+        assert_eq!(
+            footer_meta_size(max, false).unwrap() & !VERSIONED_META_FLAG,
+            max as u32
+        );
+        // the size would overwrite the flags
+        assert!(footer_meta_size(max + 1, false).is_err());
+        assert!(footer_meta_size(u32::MAX as usize + 1, true).is_err());
+    }
+}
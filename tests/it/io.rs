@@ -17,30 +17,53 @@
 
 use arrow::{
     array::{
-        Array, BinaryArray, BooleanArray, Float32Array, Float64Array, Int16Array, Int32Array,
-        Int64Array, Int8Array, ListArray, MapArray, PrimitiveArray, StructArray, UInt16Array,
-        UInt32Array, UInt64Array, UInt8Array, Utf8Array,
+        Array, BinaryArray, BooleanArray, DictionaryArray, FixedSizeBinaryArray, Float32Array,
+        Float64Array, Int128Array, Int16Array, Int32Array, Int64Array, Int8Array, ListArray,
+        MapArray, NullArray, PrimitiveArray, StructArray, UInt16Array, UInt32Array, UInt64Array,
+        UInt8Array, Utf8Array,
     },
     bitmap::{Bitmap, MutableBitmap},
     chunk::Chunk,
     compute,
-    datatypes::{DataType, Field, Schema},
-    io::parquet::{
-        read::{n_columns, ColumnDescriptor},
-        write::to_parquet_schema,
+    datatypes::{DataType, Field, Schema, TimeUnit},
+    ffi,
+    io::{
+        ipc::read as ipc_read,
+        parquet::read::{n_columns, ColumnDescriptor},
     },
     offset::OffsetsBuffer,
+    scalar::{PrimitiveScalar, Utf8Scalar},
+    types::{f16, i256},
 };
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use std::io::{BufRead, BufReader};
+use std::borrow::Cow;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::sync::Arc;
 use strawboat::{
+    compression::{
+        integer::{IntegerCompression, IntegerStats},
+        registry::{register_integer_compression, CUSTOM_CODECS},
+    },
+    interop::{parquet_to_strawboat, to_ipc},
     read::{
-        batch_read::batch_read_array,
+        batch_read::{
+            batch_read_array, batch_read_array_with_options, read_as_dictionary, read_column_ffi,
+            read_dict_column_with_external_dict, read_dictionary_column, read_row_range,
+            read_simple, read_validity_only, read_value_at,
+        },
         deserialize::column_iter_to_arrays,
-        reader::{is_primitive, NativeReader},
+        predicate::{ComparisonOp, PagePredicate, Predicate},
+        reader::{
+            infer_row_count, infer_schema, is_primitive, read_bloom_filters, read_catalog_entry,
+            read_column_pages, read_format_version, read_meta, read_meta_projected,
+            shared_native_readers, NativeReader,
+        },
+        ChunkReader, FileReader, MmapReader, NativeReadBuf, ReadOptions,
     },
-    write::{NativeWriter, WriteOptions},
-    ColumnMeta, CommonCompression, PageMeta,
+    stat::{stat_simple, PageBody},
+    to_parquet_schema,
+    write::{merge_files, write_file, NativeWriter, WriteOptions, WriteOptionsBuilder},
+    ColumnMeta, CommonCompression, Compression, PageMeta,
 };
 
 pub const WRITE_PAGE: usize = 2048;
@@ -117,164 +140,4154 @@ fn test_dict() {
 }
 
 #[test]
-fn test_freq() {
-    let size = WRITE_PAGE * 5;
-    let mut values: Vec<u32> = Vec::with_capacity(size);
-    for _ in 0..5 {
-        values.extend_from_slice(&vec![20; WRITE_PAGE - 3]);
-        values.push(10000);
-        values.push(10000);
-        values.push(10000);
+fn test_sorted_dict() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, 8)) as _,
+        Box::new(create_random_index(size, 0.4, 8)) as _,
+        Box::new(create_random_string(size, 0.4, 8)) as _,
+    ]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(2.0f64),
+            sorted_dictionary: true,
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_compress_encodings() {
+    let size = 10000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_bool(size, 0.1)) as _,
+        Box::new(create_random_index(size, 0.1, 8)) as _,
+        Box::new(create_random_double(size, 0.5, 8)) as _,
+        Box::new(create_random_string(size, 0.4, 8)) as _,
+        Box::new(Utf8Array::<i32>::from_iter_values(
+            (0..size).map(|i| format!("https://example.com/{}", i % 100)),
+        )) as _,
+    ]);
+    let write = |options: WriteOptions| {
+        let fields: Vec<Field> = chunk
+            .iter()
+            .map(|array| Field::new("name", array.data_type().clone(), true))
+            .collect();
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(&mut bytes, Schema::from(fields), options);
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        bytes.len()
+    };
+    let options = WriteOptions::builder()
+        .compression(CommonCompression::Lz4)
+        .max_page_size(WRITE_PAGE)
+        .compress_ratio(2.0)
+        .forbid(Compression::Freq);
+    assert!(write(options.clone().compress_encodings().build()) < write(options.clone().build()));
+
+    test_write_read_with_options(chunk, options.compress_encodings().build());
+}
+
+#[test]
+fn test_write_options_presets() {
+    let size = 100000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_bool(size, 0.1)) as _,
+        Box::new(create_random_index(size, 0.1, 100)) as _,
+        Box::new(create_random_double(size, 0.1, 100)) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+    ]);
+
+    let write = |options: WriteOptions| {
+        let fields: Vec<Field> = chunk
+            .iter()
+            .map(|array| Field::new("name", array.data_type().clone(), true))
+            .collect();
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(&mut bytes, Schema::from(fields), options);
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        bytes.len()
+    };
+    assert!(write(WriteOptions::max_compression()) < write(WriteOptions::fast()));
+
+    for options in [
+        WriteOptions::fast(),
+        WriteOptions::balanced(),
+        WriteOptions::max_compression(),
+    ] {
+        test_write_read_with_options(chunk.clone(), options);
+    }
+}
+
+#[test]
+fn test_write_options_builder() {
+    let options = WriteOptions::builder()
+        .compression(CommonCompression::Snappy)
+        .max_page_size(WRITE_PAGE)
+        .column_compression(1, CommonCompression::Zstd)
+        .column_compression_level(1, 19)
+        .compress_ratio(2.0)
+        .forbid(Compression::Dict)
+        .forbid(Compression::Dict)
+        .build();
+    assert_eq!(
+        options,
+        WriteOptions {
+            default_compression: CommonCompression::Snappy,
+            column_compressions: [(1, CommonCompression::Zstd)].into_iter().collect(),
+            column_compression_levels: [(1, 19)].into_iter().collect(),
+            default_compress_ratio: Some(2.0),
+            max_page_size: Some(WRITE_PAGE),
+            forbidden_compressions: vec![Compression::Dict],
+            ..Default::default()
+        }
+    );
+    assert_eq!(WriteOptions::builder().build(), WriteOptions::default());
+    assert_eq!(
+        WriteOptions::builder().max_page_bytes(4096).build(),
+        WriteOptions {
+            max_page_bytes: Some(4096),
+            ..Default::default()
+        }
+    );
+
+    let size = WRITE_PAGE * 2;
+    let values: Vec<Box<dyn Array>> = (0..3)
+        .map(|_| Box::new(Int32Array::from_vec((0..size as i32).collect())) as _)
+        .collect();
+    let chunk = Chunk::new(values);
+    let fields: Vec<Field> = (0..3)
+        .map(|i| Field::new(format!("c{i}"), DataType::Int32, false))
+        .collect();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(fields),
+        WriteOptions::builder()
+            .compression(CommonCompression::Lz4)
+            .max_page_size(WRITE_PAGE)
+            .column_compression(1, CommonCompression::Zstd)
+            .build(),
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    // the pages of a required column start with the header of the values
+    for (meta, codec) in metas.iter().zip([
+        CommonCompression::Lz4,
+        CommonCompression::Zstd,
+        CommonCompression::Lz4,
+    ]) {
+        let mut offset = meta.offset as usize;
+        for page in &meta.pages {
+            assert_eq!(bytes[offset], u8::from(codec.to_compression()));
+            offset += page.length as usize;
+        }
+    }
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_forced_compression_without_encodings() {
+    let size = WRITE_PAGE * 2;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(Float64Array::from_vec(vec![1.5; size])) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+        Box::new(BooleanArray::from_iter((0..size).map(|i| Some(i % 3 == 0)))) as _,
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+    // without `default_compress_ratio` no encoding is evaluated, the pages of every
+    // column use its codec even when an encoding would do better
+    let options = (0..4)
+        .fold(
+            WriteOptions::builder().max_page_size(WRITE_PAGE),
+            |builder, index| builder.column_compression(index, CommonCompression::Zstd),
+        )
+        .build();
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(&mut bytes, schema, &[chunk.clone()], options.clone()).unwrap();
+    for meta in &metas {
+        assert_eq!(meta.dominant_codec, Compression::Zstd);
+    }
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_max_page_bytes() {
+    let size = 3000;
+    let mut rng = StdRng::seed_from_u64(42);
+    // strings from a byte to a few kilobytes
+    let strings = Utf8Array::<i32>::from_iter_values((0..size).map(|i| {
+        let len = if i % 100 == 0 {
+            rng.gen_range(1000..5000)
+        } else {
+            rng.gen_range(1..50)
+        };
+        "x".repeat(len)
+    }));
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(strings.clone()) as _,
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+    ]);
+    let max_page_bytes = 4096;
+
+    for max_page_size in [None, Some(100)] {
+        let options = WriteOptions {
+            max_page_size,
+            max_page_bytes: Some(max_page_bytes),
+            ..Default::default()
+        };
+        let fields: Vec<Field> = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect();
+        let mut bytes = vec![];
+        let (metas, _) = write_file(
+            &mut bytes,
+            Schema::from(fields),
+            &[chunk.clone()],
+            options.clone(),
+        )
+        .unwrap();
+
+        // the pages of every column hold the same rows
+        let rows: Vec<u64> = metas[0].pages.iter().map(|p| p.num_values).collect();
+        assert_eq!(rows.iter().sum::<u64>(), size as u64);
+        // each long string ends a page
+        assert!(rows.len() > size / 100);
+        assert_eq!(
+            metas[1]
+                .pages
+                .iter()
+                .map(|p| p.num_values)
+                .collect::<Vec<_>>(),
+            rows
+        );
+        assert_eq!(metas[2].pages.len(), rows.len());
+
+        let mut offset = 0;
+        for num_rows in rows {
+            let num_rows = num_rows as usize;
+            if let Some(max_page_size) = max_page_size {
+                assert!(num_rows <= max_page_size);
+            }
+            let mut page = strings.clone();
+            page.slice(offset, num_rows);
+            let page_bytes = page.offsets().range() as usize + num_rows * 4;
+            // a single row may exceed the budget
+            assert!(page_bytes <= max_page_bytes || num_rows == 1);
+            offset += num_rows;
+        }
+
+        test_write_read_with_options(chunk.clone(), options);
+    }
+}
+
+#[test]
+fn test_max_dict_entries() {
+    let size = 5000;
+    // the number of distinct values grows with the rows of a page
+    let values = Int64Array::from_iter((0..size).map(|i| (i % 7 != 0).then_some(i / 4 % 600)));
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(values.clone()) as _,
+        Box::new(create_random_string(size as usize, 0.1, 50)) as _,
+        Box::new(create_list(size as usize, 0.2)) as _,
+    ]);
+    let max_dict_entries = 100;
+
+    for max_page_size in [None, Some(300)] {
+        let options = WriteOptions {
+            max_page_size,
+            max_dict_entries: Some(max_dict_entries),
+            default_compress_ratio: Some(1.2),
+            ..Default::default()
+        };
+        let fields: Vec<Field> = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect();
+        let mut bytes = vec![];
+        let (metas, _) = write_file(
+            &mut bytes,
+            Schema::from(fields),
+            &[chunk.clone()],
+            options.clone(),
+        )
+        .unwrap();
+
+        // the pages of every column hold the same rows
+        let rows: Vec<u64> = metas[0].pages.iter().map(|p| p.num_values).collect();
+        assert_eq!(rows.iter().sum::<u64>(), size as u64);
+        for meta in &metas[1..] {
+            assert_eq!(meta.pages.len(), rows.len());
+        }
+
+        let mut offset = 0;
+        for num_rows in rows {
+            let num_rows = num_rows as usize;
+            let distinct = |len: usize| {
+                let mut page = values.clone();
+                page.slice(offset, len);
+                page.iter()
+                    .flatten()
+                    .collect::<std::collections::HashSet<_>>()
+                    .len()
+            };
+            assert!(distinct(num_rows) <= max_dict_entries);
+            // a page only ends early on a new value over the limit
+            let end = offset + num_rows;
+            if end < size as usize && max_page_size.map_or(true, |max| num_rows < max) {
+                assert!(distinct(num_rows + 1) > max_dict_entries);
+            }
+            offset = end;
+        }
+
+        test_write_read_with_options(chunk.clone(), options);
+    }
+
+    // only the leaf columns that may be Dict encoded end the pages
+    let page_rows = |chunk: Chunk<Box<dyn Array>>, options: WriteOptions| {
+        let fields: Vec<Field> = chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect();
+        let (metas, _) = write_file(vec![], Schema::from(fields), &[chunk], options).unwrap();
+        metas[0]
+            .pages
+            .iter()
+            .map(|page| page.num_values)
+            .collect::<Vec<_>>()
+    };
+    let ids = Utf8Array::<i32>::from_iter_values((0..size).map(|i| format!("id{i}")));
+    let options = WriteOptions {
+        max_page_size: None,
+        max_dict_entries: Some(max_dict_entries),
+        default_compress_ratio: Some(1.2),
+        binary_restart_points: true,
+        ..Default::default()
+    };
+    let rows = page_rows(Chunk::new(vec![values.clone().boxed()]), options.clone());
+    assert!(rows.len() > 1);
+    let chunk = Chunk::new(vec![ids.boxed(), values.boxed()]);
+    assert_eq!(page_rows(chunk.clone(), options.clone()), rows);
+    let options = WriteOptions {
+        binary_restart_points: false,
+        ..options
+    };
+    assert_eq!(page_rows(chunk.clone(), options.clone()).len(), 50);
+    let options = WriteOptions {
+        default_compress_ratio: None,
+        ..options
+    };
+    assert_eq!(page_rows(chunk, options), vec![size as u64]);
+}
+
+/// Stores the addresses of the 192.168.0.0/16 subnet as their 16 host bits.
+struct SubnetEncoding;
+
+const SUBNET: u32 = 0xC0A8_0000;
+
+impl IntegerCompression<u32> for SubnetEncoding {
+    fn compress(
+        &self,
+        array: &PrimitiveArray<u32>,
+        _stats: &IntegerStats<u32>,
+        _write_options: &WriteOptions,
+        output: &mut Vec<u8>,
+    ) -> arrow::error::Result<usize> {
+        let start = output.len();
+        for value in array.values().iter() {
+            output.extend_from_slice(&((value - SUBNET) as u16).to_le_bytes());
+        }
+        Ok(output.len() - start)
+    }
+
+    fn decompress(
+        &self,
+        input: &[u8],
+        length: usize,
+        output: &mut Vec<u32>,
+    ) -> arrow::error::Result<()> {
+        output.extend(
+            input[..length * 2]
+                .chunks_exact(2)
+                .map(|host| SUBNET + u16::from_le_bytes([host[0], host[1]]) as u32),
+        );
+        Ok(())
+    }
+
+    fn to_compression(&self) -> Compression {
+        Compression::Custom(100)
+    }
+
+    fn compress_ratio(&self, stats: &IntegerStats<u32>) -> f64 {
+        // the null slots hold any value
+        if stats.null_count == 0 && stats.min >= SUBNET && stats.max <= SUBNET | 0xFFFF {
+            2.0
+        } else {
+            0.0
+        }
+    }
+}
+
+#[test]
+fn test_custom_compression() {
+    register_integer_compression(SubnetEncoding).unwrap();
+    // a codec is registered once per type, in the reserved range
+    assert!(register_integer_compression(SubnetEncoding).is_err());
+    assert!(CUSTOM_CODECS.contains(&100));
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let array = UInt32Array::from_vec(
+        (0..WRITE_PAGE * 3)
+            .map(|_| SUBNET + rng.gen_range(0..256))
+            .collect(),
+    );
+    let options = WriteOptions {
+        default_compress_ratio: Some(1.5),
+        forbidden_compressions: Compression::ALL.to_vec(),
+        max_page_size: Some(WRITE_PAGE),
+        ..Default::default()
+    };
+    let chunk = Chunk::new(vec![Box::new(array) as Box<dyn Array>]);
+    let schema = Schema::from(vec![Field::new("ip", DataType::UInt32, false)]);
+    let mut bytes = vec![];
+    let (metas, _) = write_file(&mut bytes, schema, &[chunk.clone()], options.clone()).unwrap();
+    assert_eq!(metas[0].dominant_codec, Compression::Custom(100));
+    let page = metas[0].offset as usize;
+    assert_eq!(bytes[page], 100);
+    test_write_read_with_options(chunk, options);
+
+    // a page of an unregistered codec is rejected
+    bytes[page] = 101;
+    let reader = FileReader::try_new(std::io::Cursor::new(&bytes), None).unwrap();
+    let err = reader
+        .collect::<arrow::error::Result<Vec<_>>>()
+        .unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("No integer encoding is registered with the codec 101"));
+}
+
+#[test]
+fn test_unknown_codec() {
+    let size = WRITE_PAGE * 2;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.0, size)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+    ]);
+    let fields: Vec<Field> = chunk
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), i > 0))
+        .collect();
+    let schema = Schema::from(fields);
+    let mut bytes = vec![];
+    let options = WriteOptions {
+        max_page_size: Some(WRITE_PAGE),
+        ..Default::default()
+    };
+    let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk], options).unwrap();
+
+    // the codec byte of the values of a page written by a newer version
+    let unknown_codec = 50;
+    assert!(Compression::from_codec(unknown_codec).is_err());
+    let expected = format!("unsupported codec {unknown_codec}, upgrade strawboat");
+    for (i, field) in schema.fields.iter().enumerate() {
+        let meta = &metas[i];
+        let page = &meta.pages[1];
+        let page_start = meta.offset + meta.pages[0].length;
+        let mut bytes = bytes.clone();
+        // the values follow the levels of the nullable columns
+        let codec_pos = if field.is_nullable {
+            // the number of rows and the sizes of the rep and def levels of a nested page
+            let levels_len: u64 = if is_primitive(field.data_type()) {
+                0
+            } else {
+                20
+            };
+            let header_pos = (page_start + levels_len) as usize;
+            let compressed_size =
+                u64::from_le_bytes(bytes[header_pos + 1..header_pos + 9].try_into().unwrap());
+            header_pos + 17 + compressed_size as usize
+        } else {
+            page_start as usize
+        };
+        assert!(codec_pos < (page_start + page.length) as usize);
+        bytes[codec_pos] = unknown_codec;
+
+        let err = FileReader::try_new(std::io::Cursor::new(&bytes), Some(&[i]))
+            .unwrap()
+            .collect::<arrow::error::Result<Vec<_>>>()
+            .unwrap_err();
+        assert!(err.to_string().contains(&expected), "{err}");
+
+        let leaves = to_parquet_schema(&schema).unwrap().columns().to_vec();
+        let mut reader = std::io::Cursor::new(&bytes);
+        reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+        let err = batch_read_array(
+            vec![BufReader::new(reader)],
+            vec![leaves[i].clone()],
+            field.clone(),
+            !is_primitive(field.data_type()),
+            vec![meta.pages.clone()],
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains(&expected), "{err}");
+
+        if is_primitive(field.data_type()) {
+            let mut reader = std::io::Cursor::new(&bytes);
+            reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+            let reader = NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_read_options(file_read_options(&bytes));
+            let err = stat_simple(reader, field.clone()).unwrap_err();
+            assert!(err.to_string().contains(&expected), "{err}");
+        }
+    }
+}
+
+#[test]
+fn test_column_compression_levels() {
+    let size = WRITE_PAGE * 4;
+    let mut rng = StdRng::seed_from_u64(42);
+    let words = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta"];
+    let array = Utf8Array::<i32>::from_iter_values((0..size).map(|_| {
+        (0..rng.gen_range(1..8))
+            .map(|_| words[rng.gen_range(0..words.len())])
+            .collect::<Vec<_>>()
+            .join(" ")
+    }));
+    let chunk = Chunk::new(vec![
+        array.clone().boxed(),
+        array.clone().boxed(),
+        array.boxed(),
+    ]);
+    let options = WriteOptions::builder()
+        .compression(CommonCompression::Zstd)
+        .max_page_size(WRITE_PAGE)
+        .column_compression_level(1, 19)
+        .build();
+    let options = WriteOptions {
+        compression_level: Some(1),
+        ..options
+    };
+
+    let fields: Vec<Field> = (0..3)
+        .map(|i| Field::new(format!("c{i}"), DataType::Utf8, false))
+        .collect();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(&mut bytes, Schema::from(fields), options.clone());
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    // the other columns keep the default level
+    assert_eq!(metas[0].total_len(), metas[2].total_len());
+    assert!(metas[1].total_len() < metas[0].total_len());
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_max_dict_unique_ratio() {
+    // every value appears twice, half of the values are unique
+    let array =
+        Utf8Array::<i32>::from_iter_values((0..WRITE_PAGE).map(|i| format!("{:032}", i / 2)));
+    let chunk = Chunk::new(vec![Box::new(array) as Box<dyn Array>]);
+    let first_codec = |builder: WriteOptionsBuilder| {
+        let options = builder
+            .max_page_size(WRITE_PAGE)
+            .compress_ratio(1.5)
+            .forbid(Compression::OneValue)
+            .forbid(Compression::Freq)
+            .forbid(Compression::Rle)
+            .forbid(Compression::Fsst)
+            .forbid(Compression::IncrementalBinary)
+            .build();
+        let field = Field::new("c", DataType::Utf8, false);
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(&mut bytes, Schema::from(vec![field]), options);
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let offset = writer.metas[0].offset as usize;
+        Compression::from_codec(bytes[offset]).unwrap()
+    };
+
+    assert_ne!(first_codec(WriteOptions::builder()), Compression::Dict);
+    assert_eq!(
+        first_codec(WriteOptions::builder().max_dict_unique_ratio(0.6)),
+        Compression::Dict
+    );
+}
+
+#[test]
+fn test_fsst() {
+    let size = 10000;
+    let urls = Utf8Array::<i32>::from_iter(
+        (0..size)
+            .map(|i| (i % 10 != 0).then(|| format!("https://example.com/{}/item?id={i}", i % 7))),
+    );
+    let chunk = Chunk::new(vec![Box::new(urls) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_binary_rle() {
+    let size = 10000;
+    let levels = ["DEBUG", "INFO", "WARN", "ERROR"];
+    let logs = Utf8Array::<i32>::from_iter(
+        (0..size).map(|i| (i % 11 != 0).then_some(levels[i / 500 % 4])),
+    );
+    let chunk = Chunk::new(vec![Box::new(logs) as _]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            forbidden_compressions: vec![Compression::Dict, Compression::Freq],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_binary_freq() {
+    let size = 10000;
+    let statuses = Utf8Array::<i32>::from_iter((0..size).map(|i| {
+        (i % 11 != 0).then(|| match i % 50 {
+            0 => format!("failed with code {}", i % 7),
+            _ => "ok".to_string(),
+        })
+    }));
+    let chunk = Chunk::new(vec![Box::new(statuses) as _]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            forbidden_compressions: vec![Compression::Dict, Compression::Rle],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_incremental_binary() {
+    let size = 10000;
+    let keys = Utf8Array::<i64>::from_iter(
+        (0..size).map(|i| (i % 13 != 0).then(|| format!("key/{:06}/{}", i / 3, i % 3))),
+    );
+    let chunk = Chunk::new(vec![Box::new(keys) as _]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            forbidden_compressions: vec![Compression::Fsst],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_narrow_large_offsets() {
+    let size = WRITE_PAGE * 3 + 7;
+    let strings = create_random_string(size + 5, 0.1, 100);
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        // the offsets of a slice do not start at 0
+        strings.clone().sliced(5, size).boxed(),
+        Utf8Array::<i32>::from_iter(strings.iter().map(|v| v.map(|v| v.len().to_string())))
+            .sliced(0, size)
+            .boxed(),
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("b", DataType::LargeBinary, true),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+    let write = |narrow_large_offsets: bool| {
+        let options = WriteOptions {
+            default_compression: CommonCompression::None,
+            max_page_size: Some(WRITE_PAGE),
+            narrow_large_offsets,
+            ..Default::default()
+        };
+        let mut bytes = Vec::new();
+        let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk.clone()], options).unwrap();
+        metas
+    };
+    let metas = write(false);
+    let narrow_metas = write(true);
+    // 4 bytes less for each offset of the large column, the other column is unchanged
+    assert_eq!(
+        metas[0].total_len() - narrow_metas[0].total_len(),
+        4 * (size + metas[0].pages.len()) as u64
+    );
+    assert_eq!(metas[1].total_len(), narrow_metas[1].total_len());
+
+    for compression in [
+        CommonCompression::Lz4,
+        CommonCompression::Zstd,
+        CommonCompression::None,
+    ] {
+        for default_compress_ratio in [None, Some(1.2f64)] {
+            test_write_read_with_options(
+                chunk.clone(),
+                WriteOptions {
+                    default_compression: compression,
+                    max_page_size: Some(WRITE_PAGE),
+                    default_compress_ratio,
+                    narrow_large_offsets: true,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+#[test]
+fn test_patas() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(Float32Array::from_iter(
+            (0..size).map(|i| (i % 13 != 0).then(|| (i / 10 % 40) as f32 * 0.3)),
+        )) as _,
+        Box::new(Float64Array::from_iter(
+            (0..size).map(|i| (i % 13 != 0).then(|| (i / 10 % 40) as f64 * 0.3)),
+        )) as _,
+    ]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            forbidden_compressions: vec![
+                Compression::Rle,
+                Compression::Dict,
+                Compression::Freq,
+                Compression::Gorilla,
+            ],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_gorilla() {
+    let size = 10000;
+    let readings = Float64Array::from_iter(
+        (0..size).map(|i| (i % 17 != 0).then(|| 20.0 + ((i / 10) as f64 * 0.25).sin().round())),
+    );
+    let chunk = Chunk::new(vec![Box::new(readings) as _]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            forbidden_compressions: vec![
+                Compression::Rle,
+                Compression::Dict,
+                Compression::Freq,
+                Compression::Patas,
+            ],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_chimp() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(Float64Array::from_iter((0..size).map(|i| {
+            (i % 17 != 0).then(|| ((20.0 + (i as f64 * 0.01).sin() * 5.0) * 100.0).round() / 100.0)
+        }))) as _,
+        Box::new(Float32Array::from_iter(
+            (0..size).map(|i| (i % 17 != 0).then(|| (i as f32 * 0.01).sin())),
+        )) as _,
+    ]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(0.0),
+            forbidden_compressions: vec![
+                Compression::OneValue,
+                Compression::Rle,
+                Compression::Dict,
+                Compression::Freq,
+                Compression::Patas,
+                Compression::Gorilla,
+            ],
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_for() {
+    let size = 10000;
+    let mut rng = StdRng::seed_from_u64(42);
+    let chunk = Chunk::new(vec![
+        Box::new(Int64Array::from_iter((0..size).map(|_| {
+            rng.gen_bool(0.9)
+                .then(|| 1_000_000_000 + rng.gen_range(0..5000))
+        }))) as _,
+        Box::new(Int16Array::from_vec(
+            (0..size).map(|_| rng.gen_range(-3000..-2900)).collect(),
+        )) as _,
+    ]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_zigzag_delta() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(Int64Array::from_vec(
+            (0..size).map(|i| 1_000_000_000_000 - i * 7).collect(),
+        )) as _,
+        Box::new(Int64Array::from_iter(
+            (0..size).map(|i| (i % 11 != 0).then(|| 500 - i * 3 + (i % 5) * 2)),
+        )) as _,
+    ]);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(1.2f64),
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_one_value_integer() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(Int64Array::from_vec(vec![20230101; size])) as _,
+        Box::new(Int32Array::from_iter(
+            (0..size).map(|i| (i % 3 != 0).then_some(-7)),
+        )) as _,
+        Box::new(UInt8Array::from_vec(vec![u8::MAX; size])) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("b", DataType::Int32, true),
+        Field::new("c", DataType::UInt8, false),
+    ]);
+    let options = WriteOptions {
+        max_page_size: Some(WRITE_PAGE),
+        default_compress_ratio: Some(1.2f64),
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(&mut bytes, schema, &[chunk.clone()], options.clone()).unwrap();
+    for meta in &metas {
+        assert_eq!(meta.dominant_codec, Compression::OneValue);
+        // the header, the validity and the single value
+        for page in &meta.pages {
+            assert!(page.length < 64);
+        }
+    }
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_sorted_int64_delta() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(Int64Array::from_vec(
+            (0..size).map(|i| i * 3 + i % 2).collect(),
+        )) as _,
+        Box::new(Int64Array::from_vec(
+            (0..size).map(|i| i64::MIN / 2 + i * 5).collect(),
+        )) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("b", DataType::Int64, false),
+    ]);
+    let options = WriteOptions {
+        default_compression: CommonCompression::None,
+        max_page_size: Some(WRITE_PAGE),
+        default_compress_ratio: Some(1.2f64),
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(&mut bytes, schema, options.clone());
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    for meta in &writer.metas {
+        assert!(matches!(
+            meta.dominant_codec,
+            Compression::ZigzagDelta | Compression::DeltaDelta
+        ));
+    }
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_sorted_columns() {
+    let size = 10000;
+    let values = || Int64Array::from_vec((0..size).map(|i| i * 3 + i % 2).collect());
+    let chunk = Chunk::new(vec![
+        Box::new(values()) as _,
+        Box::new(Utf8Array::<i32>::from_iter_values(
+            (0..size).map(|i| format!("clustered-key-{i:08}")),
+        )) as _,
+        Box::new(values()) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("s", DataType::Utf8, false),
+        Field::new("b", DataType::Int64, false),
+    ]);
+    let options = WriteOptions::builder()
+        .compression(CommonCompression::Lz4)
+        .max_page_size(WRITE_PAGE)
+        .sorted_column(0)
+        .sorted_column(1)
+        .build();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(&mut bytes, schema, options.clone());
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    // the declared sorted columns use the delta encodings without `default_compress_ratio`
+    assert!(matches!(
+        metas[0].dominant_codec,
+        Compression::ZigzagDelta | Compression::DeltaDelta
+    ));
+    assert_eq!(metas[1].dominant_codec, Compression::IncrementalBinary);
+    assert_eq!(metas[2].dominant_codec, Compression::Lz4);
+    assert_eq!(
+        metas.iter().map(|meta| meta.sorted).collect::<Vec<_>>(),
+        [true, true, false]
+    );
+    let mut reader = std::io::Cursor::new(bytes);
+    assert_eq!(read_meta(&mut reader).unwrap(), metas);
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_delta_delta() {
+    let size = 10000;
+    let start = 1_600_000_000_000_000_000i64;
+    let chunk = Chunk::new(vec![
+        Box::new(
+            Int64Array::from_vec(
+                (0..size)
+                    .map(|i| start + i * 1_000_000_000 + (i * 7919 % 13) * 1000)
+                    .collect(),
+            )
+            .to(DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        ) as _,
+        Box::new(Int64Array::from_iter(
+            (0..size).map(|i| (i % 7 != 0).then_some(start + i * 1_000_000)),
+        )) as _,
+        Box::new(
+            Int128Array::from_vec(
+                (0..size as i128)
+                    .map(|i| (start as i128) * 1000 + i * 1_000_000_007)
+                    .collect(),
+            )
+            .to(DataType::Decimal(38, 0)),
+        ) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("ts", DataType::Timestamp(TimeUnit::Nanosecond, None), false),
+        Field::new("n", DataType::Int64, true),
+        Field::new("d", DataType::Decimal(38, 0), false),
+    ]);
+    let options = WriteOptions {
+        default_compression: CommonCompression::Lz4,
+        max_page_size: Some(WRITE_PAGE),
+        default_compress_ratio: Some(1.2f64),
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(&mut bytes, schema, options.clone());
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    for meta in &writer.metas {
+        assert_eq!(meta.dominant_codec, Compression::DeltaDelta);
+    }
+
+    test_write_read_with_options(chunk, options);
+}
+
+#[test]
+fn test_zstd_dictionary() {
+    let size = 20000;
+    let chunk = Chunk::new(vec![
+        Box::new(Utf8Array::<i32>::from_iter((0..size).map(|i| {
+            (i % 13 != 0).then(|| format!("https://example.com/users/{}/orders/{}", i % 97, i))
+        }))) as _,
+        Box::new(Int64Array::from_vec(
+            (0..size).map(|i| i * 1000 % 7919).collect(),
+        )) as _,
+    ]);
+    let options = WriteOptions {
+        default_compression: CommonCompression::Zstd,
+        max_page_size: Some(64),
+        ..Default::default()
+    };
+    let dictionary_options = WriteOptions {
+        zstd_dictionary_size: Some(4 * 1024),
+        ..options.clone()
+    };
+    test_write_read_with_options(chunk.clone(), dictionary_options.clone());
+
+    let write = |options: WriteOptions| {
+        let mut bytes = Vec::new();
+        let fields: Vec<Field> = chunk
+            .iter()
+            .map(|array| Field::new("c", array.data_type().clone(), true))
+            .collect();
+        let mut writer = NativeWriter::new(&mut bytes, Schema::from(fields), options);
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        (writer.total_size(), writer.metas)
+    };
+    let (plain_size, _) = write(options);
+    let (dictionary_size, metas) = write(dictionary_options.clone());
+    assert!(metas.iter().all(|meta| meta.zstd_dictionary.is_some()));
+    assert!(metas
+        .iter()
+        .all(|meta| meta.pages.iter().all(|page| page.zstd_dictionary)));
+    assert!(dictionary_size < plain_size);
+
+    // the pages are compressed at the level of their column
+    let level_metas = |level: i32| {
+        write(WriteOptions {
+            column_compression_levels: [(0, level)].into_iter().collect(),
+            ..dictionary_options.clone()
+        })
+        .1
+    };
+    let (fast, strong) = (level_metas(1), level_metas(19));
+    assert_ne!(fast[0].pages, strong[0].pages);
+    assert_eq!(fast[1].pages, strong[1].pages);
+
+    let mut bytes = vec![];
+    let field = Field::new("c", chunk.arrays()[1].data_type().clone(), true);
+    let schema = Schema::from(vec![field.clone()]);
+    let chunk = Chunk::new(vec![chunk.arrays()[1].clone()]);
+    let (metas, _) = write_file(&mut bytes, schema, &[chunk], dictionary_options).unwrap();
+    let read_metas = read_meta(&mut std::io::Cursor::new(&bytes)).unwrap();
+    assert_eq!(read_metas, metas);
+    let meta = &metas[0];
+
+    // the pages can't be read from their stored bytes
+    let mut reader = std::io::Cursor::new(&bytes);
+    reader.consume(meta.offset as usize);
+    let err = read_simple(&mut reader, field, meta.pages.clone()).unwrap_err();
+    assert!(err.to_string().contains("zstd dictionary"), "{err}");
+    let reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+    let mut pages = NativeReader::new(reader, meta.pages.clone(), vec![]);
+    let err = pages.next().unwrap().unwrap_err();
+    assert!(err.to_string().contains("zstd dictionary"), "{err}");
+
+    let read = |bytes: &[u8], read_options: ReadOptions| {
+        let reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+        NativeReader::new(reader, meta.pages.clone(), vec![])
+            .with_zstd_dictionary(meta.zstd_dictionary.clone())
+            .with_read_options(read_options)
+            .next()
+            .unwrap()
+    };
+    let (_, page) = read(&bytes, ReadOptions::default()).unwrap();
+    let max_decode_bytes = ReadOptions {
+        max_decode_bytes: Some(page.len() - 1),
+        ..Default::default()
+    };
+    let err = read(&bytes, max_decode_bytes).unwrap_err();
+    assert!(err.to_string().contains("over the limit"), "{err}");
+
+    // a corrupted size of the page is not allocated
+    let mut corrupted = bytes.clone();
+    corrupted[meta.offset as usize..meta.offset as usize + 8]
+        .copy_from_slice(&u64::MAX.to_le_bytes());
+    let err = read(&corrupted, ReadOptions::default()).unwrap_err();
+    assert!(err.to_string().contains("bytes of its frame"), "{err}");
+
+    // too little data to train a dictionary
+    test_write_read_with_options(
+        Chunk::new(vec![Box::new(Int32Array::from_slice([1, 2, 3])) as _]),
+        WriteOptions {
+            zstd_dictionary_size: Some(16 * 1024),
+            ..Default::default()
+        },
+    );
+
+    // the pages of such columns are compressed with the default compression
+    let array = Utf8Array::<i32>::from_iter_values((0..100).map(|i| format!("value {i}")));
+    let field = Field::new("s", DataType::Utf8, false);
+    let mut bytes = vec![];
+    let (metas, _) = write_file(
+        &mut bytes,
+        Schema::from(vec![field.clone()]),
+        &[Chunk::new(vec![array.boxed()])],
+        WriteOptions {
+            default_compression: CommonCompression::Zstd,
+            zstd_dictionary_size: Some(16 * 1024),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert!(metas[0].zstd_dictionary.is_none());
+    let reader = NativeReader::from_shared(bytes.into(), &metas[0]);
+    let info = stat_simple(reader, field).unwrap();
+    assert!(!info.pages.is_empty());
+    for page in info.pages {
+        assert!(
+            matches!(page.body, PageBody::Common(CommonCompression::Zstd)),
+            "{:?}",
+            page.body
+        );
+    }
+}
+
+#[test]
+fn test_freq() {
+    let size = WRITE_PAGE * 5;
+    let mut values: Vec<u32> = Vec::with_capacity(size);
+    for _ in 0..5 {
+        values.extend_from_slice(&vec![20; WRITE_PAGE - 3]);
+        values.push(10000);
+        values.push(10000);
+        values.push(10000);
+    }
+
+    let chunk = Chunk::new(vec![Box::new(UInt32Array::from_vec(values)) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_bitpacking() {
+    let size = WRITE_PAGE * 5;
+    let chunk = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, 8)) as _,
+        Box::new(create_random_index(size, 0.5, 8)) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_bitpacking_narrow() {
+    let size = WRITE_PAGE * 5;
+    let chunk = Chunk::new(vec![
+        Box::new(UInt8Array::from_iter(
+            (0..size).map(|i| Some((i % 8) as u8)),
+        )) as _,
+        Box::new(Int16Array::from_iter(
+            (0..size).map(|i| (i % 5 != 0).then_some((i % 1000) as i16)),
+        )) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_bitpacking_signed() {
+    let size = WRITE_PAGE * 5;
+    let chunk = Chunk::new(vec![Box::new(Int32Array::from_iter(
+        (0..size).map(|i| (i % 3 != 0).then_some([-3, -1, -2, 0, 1][i % 5])),
+    )) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_deleta_bitpacking() {
+    let size = WRITE_PAGE * 5;
+    let chunk = Chunk::new(vec![
+        Box::new(UInt32Array::from_vec((0..size as u32).collect())) as _,
+        Box::new(Int32Array::from_vec((0..size as i32).collect())) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_onevalue() {
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(BooleanArray::from_iter((0..size).map(|_| Some(true)))) as _,
+        Box::new(BooleanArray::from_iter((0..size).map(|_| Some(false)))) as _,
+        Box::new(UInt32Array::from_vec(vec![3; size])) as _,
+        Box::new(create_random_index(size, 0.3, 1)) as _,
+        Box::new(create_random_string(size, 0.4, 1)) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_onevalue_with_nulls() {
+    let size = 10000;
+    let validity = (0..size).map(|i| i % 4 != 2);
+    let chunk = Chunk::new(vec![
+        Box::new(Int32Array::from_iter(
+            validity.clone().map(|v| v.then_some(5)),
+        )) as _,
+        Box::new(Float64Array::from_iter(
+            validity.clone().map(|v| v.then_some(5.0)),
+        )) as _,
+        Box::new(Utf8Array::<i32>::from_iter(
+            validity.clone().map(|v| v.then_some("five")),
+        )) as _,
+        Box::new(BooleanArray::from_iter(validity.map(|v| v.then_some(true)))) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_all_null_pages() {
+    let size = WRITE_PAGE * 3;
+    // the middle page is all null
+    let validity = |i: usize| !(WRITE_PAGE..WRITE_PAGE * 2).contains(&i) && i % 5 != 0;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(BooleanArray::from_iter(
+            (0..size).map(|i| validity(i).then_some(i % 2 == 0)),
+        )) as _,
+        Box::new(Int32Array::from_iter(
+            (0..size).map(|i| validity(i).then_some(i as i32)),
+        )) as _,
+        Box::new(Float64Array::from_iter(
+            (0..size).map(|i| validity(i).then_some(i as f64)),
+        )) as _,
+        Box::new(Utf8Array::<i32>::from_iter(
+            (0..size).map(|i| validity(i).then(|| format!("v{i}"))),
+        )) as _,
+        Box::new(FixedSizeBinaryArray::from_iter(
+            (0..size).map(|i| validity(i).then_some((i as u32).to_le_bytes())),
+            4,
+        )) as _,
+        Box::new(Int64Array::new_null(DataType::Int64, size)) as _,
+        Box::new(BinaryArray::<i64>::new_null(DataType::LargeBinary, size)) as _,
+    ]);
+    test_write_read(chunk.clone());
+
+    let fields: Vec<Field> = chunk
+        .iter()
+        .map(|array| Field::new("name", array.data_type().clone(), true))
+        .collect();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(fields),
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    // the all null pages are the compressed validity and an empty values buffer
+    for meta in &writer.metas {
+        assert!(meta.pages[1].length < 64, "{}", meta.pages[1].length);
+    }
+
+    let list = create_list(size, 0.2);
+    let values = Int32Array::new_null(DataType::Int32, list.values().len());
+    let list = ListArray::<i32>::new(
+        list.data_type().clone(),
+        list.offsets().clone(),
+        values.boxed(),
+        list.validity().cloned(),
+    );
+    test_write_read(Chunk::new(vec![Box::new(list) as _]));
+}
+
+#[test]
+fn test_null_column() {
+    let size = 1000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        NullArray::new(DataType::Null, size).boxed(),
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+    ]);
+    test_write_read(chunk.clone());
+
+    // the nested null columns are written but not read yet
+    let list = create_list(100, 0.2);
+    let list = ListArray::<i32>::new(
+        DataType::List(Box::new(Field::new("item", DataType::Null, true))),
+        list.offsets().clone(),
+        NullArray::new(DataType::Null, list.values().len()).boxed(),
+        list.validity().cloned(),
+    );
+    let schema = Schema::from(vec![Field::new("l", list.data_type().clone(), true)]);
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(
+        &mut bytes,
+        schema.clone(),
+        &[Chunk::new(vec![list.boxed()])],
+        WriteOptions::default(),
+    )
+    .unwrap();
+    let leaves = to_parquet_schema(&schema).unwrap().columns().to_vec();
+    let mut reader = std::io::Cursor::new(bytes.as_slice());
+    reader.consume(metas[0].offset as usize);
+    let reader = NativeReader::new(reader, metas[0].pages.clone(), vec![])
+        .with_read_options(file_read_options(&bytes));
+    assert!(
+        column_iter_to_arrays(vec![reader], leaves.clone(), schema.fields[0].clone(), true)
+            .is_err()
+    );
+    let mut reader = std::io::Cursor::new(bytes.as_slice());
+    reader.consume(metas[0].offset as usize);
+    assert!(batch_read_array(
+        vec![reader],
+        leaves,
+        schema.fields[0].clone(),
+        true,
+        vec![metas[0].pages.clone()]
+    )
+    .is_err());
+
+    for is_nullable in [false, true] {
+        let schema = Schema::from(
+            chunk
+                .iter()
+                .enumerate()
+                .map(|(i, array)| {
+                    Field::new(format!("c{i}"), array.data_type().clone(), is_nullable)
+                })
+                .collect::<Vec<_>>(),
+        );
+        let options = WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(2.0),
+            page_checksum: true,
+            bloom_filter: true,
+            distinct_count: true,
+            ..Default::default()
+        };
+        let mut bytes = Vec::new();
+        let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk.clone()], options).unwrap();
+        let mut reader = std::io::Cursor::new(&bytes);
+        let mut read_metas = read_meta(&mut reader).unwrap();
+        read_bloom_filters(&mut reader, &mut read_metas).unwrap();
+        assert_eq!(read_metas, metas);
+
+        // the null pages are empty, their length is in their page metas
+        let meta = &metas[1];
+        assert!(meta.pages.iter().all(|page| page.length == 0));
+        assert_eq!(
+            meta.pages.iter().map(|page| page.num_values).sum::<u64>(),
+            size as u64
+        );
+        assert_eq!(meta.null_count, Some(size as u64));
+        assert_eq!(meta.pages.len(), metas[0].pages.len());
+
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let array = read_simple(&mut reader, schema.fields[1].clone(), meta.pages.clone()).unwrap();
+        assert_eq!(array.as_ref(), chunk.arrays()[1].as_ref());
+
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let (num_values, validity) =
+            read_validity_only(&mut reader, &schema.fields[1], meta).unwrap();
+        assert_eq!(num_values, size as u64);
+        assert_eq!(validity.unwrap().unset_bits(), size);
+
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let reader = NativeReader::new(reader, meta.pages.clone(), vec![])
+            .with_read_options(file_read_options(&bytes));
+        let arrays = column_iter_to_arrays(vec![reader], vec![], schema.fields[1].clone(), false)
+            .unwrap()
+            .collect::<arrow::error::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            arrays.iter().map(|array| array.len()).collect::<Vec<_>>(),
+            meta.pages
+                .iter()
+                .map(|page| page.num_values as usize)
+                .collect::<Vec<_>>()
+        );
+        assert!(arrays
+            .iter()
+            .all(|array| array.data_type() == &DataType::Null));
+    }
+}
+
+#[test]
+fn test_sparse_validity() {
+    let size = WRITE_PAGE * 3;
+    // the middle page is half null, the others almost all null
+    let validity = |i: usize| match i / WRITE_PAGE {
+        1 => i % 2 == 0,
+        _ => i % 150 == 7 || i == size - 1,
+    };
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(BooleanArray::from_iter(
+            (0..size).map(|i| validity(i).then_some(i % 3 == 0)),
+        )) as _,
+        Box::new(Int32Array::from_iter(
+            (0..size).map(|i| validity(i).then_some(i as i32)),
+        )) as _,
+        Box::new(Float64Array::from_iter(
+            (0..size).map(|i| validity(i).then_some(i as f64)),
+        )) as _,
+        Box::new(Utf8Array::<i32>::from_iter(
+            (0..size).map(|i| validity(i).then(|| format!("v{i}"))),
+        )) as _,
+        Box::new(FixedSizeBinaryArray::from_iter(
+            (0..size).map(|i| validity(i).then_some((i as u32).to_le_bytes())),
+            4,
+        )) as _,
+    ]);
+    for compression in [CommonCompression::Lz4, CommonCompression::None] {
+        test_write_read_with_options(
+            chunk.clone(),
+            WriteOptions::builder()
+                .compression(compression)
+                .max_page_size(WRITE_PAGE)
+                .compress_ratio(2.0)
+                .sparse_validity(0.9)
+                .build(),
+        );
+    }
+
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .map(|array| Field::new("name", array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+    let write = |options: WriteOptions| {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(&mut bytes, schema.clone(), options);
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
+        (bytes, metas)
+    };
+    let options = WriteOptions::builder()
+        .compression(CommonCompression::None)
+        .max_page_size(WRITE_PAGE);
+    let (bytes, metas) = write(options.clone().sparse_validity(0.9).build());
+    let (_, bitmap_metas) = write(options.build());
+
+    let sparse_validity = u8::from(Compression::SparseValidity);
+    for (column, (meta, bitmap_meta)) in metas.iter().zip(bitmap_metas.iter()).enumerate() {
+        let page_codec = |page: usize| {
+            let offset = meta.offset + meta.pages[..page].iter().map(|p| p.length).sum::<u64>();
+            bytes[offset as usize]
+        };
+        assert_eq!(page_codec(0), sparse_validity);
+        assert_ne!(page_codec(1), sparse_validity);
+        assert_eq!(page_codec(2), sparse_validity);
+        assert!(meta.pages[0].length < bitmap_meta.pages[0].length);
+        assert_eq!(meta.pages[1].length, bitmap_meta.pages[1].length);
+
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let (num_values, read) =
+            read_validity_only(&mut reader, &schema.fields[column], meta).unwrap();
+        assert_eq!(num_values, size as u64);
+        assert_eq!(read.as_ref(), chunk.arrays()[column].validity());
+    }
+}
+
+#[test]
+fn test_compress_header() {
+    let array = Int64Array::from_vec((0..1000).collect());
+    let field = Field::new("c", DataType::Int64, false);
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(vec![field]),
+        WriteOptions::default(),
+    );
+    writer.start().unwrap();
+    writer
+        .write(&Chunk::new(vec![Box::new(array) as Box<dyn Array>]))
+        .unwrap();
+    writer.finish().unwrap();
+    let meta = writer.metas[0].clone();
+
+    // the format version follows the magic
+    assert_eq!(&bytes[..6], b"ARROW2");
+    assert_eq!(u16::from_le_bytes(bytes[6..8].try_into().unwrap()), 1);
+
+    // codec(1 byte) + compressed size(8 bytes) + uncompressed size(8 bytes)
+    let page = &bytes[meta.offset as usize..(meta.offset + meta.pages[0].length) as usize];
+    let compressed_size = u64::from_le_bytes(page[1..9].try_into().unwrap());
+    let uncompressed_size = u64::from_le_bytes(page[9..17].try_into().unwrap());
+    assert_eq!(compressed_size as usize, page.len() - 17);
+    assert_eq!(uncompressed_size, 1000 * 8);
+}
+
+#[test]
+fn test_struct() {
+    let struct_array = create_struct(1000, 0.2, 1000);
+    let chunk = Chunk::new(vec![Box::new(struct_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_file_reader() {
+    let size = 5000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_struct(size, 0.2, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let fields: Vec<Field> = chunk
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let schema = Schema::from(fields);
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut bytes = vec![];
+        write_file(
+            &mut bytes,
+            schema.clone(),
+            &[chunk.clone()],
+            WriteOptions {
+                default_compression: CommonCompression::Lz4,
+                max_page_size: Some(WRITE_PAGE),
+                zstd_dictionary_size,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        for projection in [None, Some(vec![3, 1]), Some(vec![2])] {
+            let reader =
+                FileReader::try_new(std::io::Cursor::new(&bytes), projection.as_deref()).unwrap();
+            let projection = projection.unwrap_or_else(|| (0..schema.fields.len()).collect());
+            let fields: Vec<Field> = projection
+                .iter()
+                .map(|i| schema.fields[*i].clone())
+                .collect();
+            assert_eq!(reader.schema().fields, fields);
+
+            let chunks = reader.collect::<arrow::error::Result<Vec<_>>>().unwrap();
+            assert_eq!(chunks.len(), (size + WRITE_PAGE - 1) / WRITE_PAGE);
+            for (i, index) in projection.iter().enumerate() {
+                let arrays: Vec<&dyn Array> = chunks
+                    .iter()
+                    .map(|chunk| chunk.arrays()[i].as_ref())
+                    .collect();
+                let array = compute::concatenate::concatenate(&arrays).unwrap();
+                assert_eq!(array.as_ref(), chunk.arrays()[*index].as_ref());
+            }
+        }
+    }
+
+    // out of bounds projections are rejected
+    let mut bytes = vec![];
+    write_file(&mut bytes, schema, &[chunk], WriteOptions::default()).unwrap();
+    assert!(FileReader::try_new(std::io::Cursor::new(&bytes), Some(&[4])).is_err());
+}
+
+#[test]
+fn test_chunk_reader() {
+    let size = 5000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_struct(size, 0.2, size)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let fields: Vec<Field> = chunk
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let schema = Schema::from(fields);
+
+    let write = |page_size: usize| {
+        let mut bytes = vec![];
+        write_file(
+            &mut bytes,
+            schema.clone(),
+            &[chunk.clone()],
+            WriteOptions {
+                default_compression: CommonCompression::Lz4,
+                max_page_size: Some(page_size),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        Arc::<[u8]>::from(bytes)
+    };
+    let check = |chunks: Vec<Chunk<Box<dyn Array>>>, batch_size: usize, projection: &[usize]| {
+        assert_eq!(chunks.len(), (size + batch_size - 1) / batch_size);
+        for (i, batch) in chunks.iter().enumerate() {
+            let len = batch_size.min(size - i * batch_size);
+            assert_eq!(batch.len(), len);
+            for (array, index) in batch.arrays().iter().zip(projection) {
+                let expected = chunk.arrays()[*index].sliced(i * batch_size, len);
+                assert_eq!(array, &expected);
+            }
+        }
+    };
+
+    let bytes = write(WRITE_PAGE);
+    for batch_size in [1000, WRITE_PAGE, 3000, size + 1] {
+        for projection in [None, Some(vec![2, 1])] {
+            let (read_schema, reader) =
+                ChunkReader::try_from_shared(bytes.clone(), projection.as_deref(), batch_size)
+                    .unwrap();
+            let projection = projection.unwrap_or_else(|| (0..schema.fields.len()).collect());
+            assert_eq!(read_schema.fields.len(), projection.len());
+
+            let chunks = reader.collect::<arrow::error::Result<Vec<_>>>().unwrap();
+            check(chunks, batch_size, &projection);
+        }
+    }
+
+    // the pages of the columns do not align
+    let page_sizes = [WRITE_PAGE, 700, 1500];
+    let files: Vec<Arc<[u8]>> = page_sizes
+        .iter()
+        .map(|page_size| write(*page_size))
+        .collect();
+    let leaves = to_parquet_schema(&schema).unwrap().columns().to_vec();
+    let column_iters = |files: &[Arc<[u8]>]| {
+        files
+            .iter()
+            .enumerate()
+            .map(|(i, bytes)| {
+                let metas = read_meta(&mut std::io::Cursor::new(bytes.clone())).unwrap();
+                let (start, n) = match i {
+                    0 => (0, 1),
+                    1 => (1, 2),
+                    _ => (3, 1),
+                };
+                let readers = shared_native_readers(bytes.clone(), &metas[start..start + n]);
+                let field = schema.fields[i].clone();
+                let is_nested = !is_primitive(field.data_type());
+                column_iter_to_arrays(readers, leaves[start..start + n].to_vec(), field, is_nested)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>()
+    };
+    for batch_size in [1, 999, 1000, 4096] {
+        let reader = ChunkReader::new(column_iters(&files), batch_size);
+        let chunks = reader.collect::<arrow::error::Result<Vec<_>>>().unwrap();
+        check(chunks, batch_size, &[0, 1, 2]);
+    }
+
+    // the columns have different lengths
+    let short = {
+        let mut bytes = vec![];
+        let chunk = Chunk::new(vec![chunk.arrays()[0].sliced(0, size - 10)]);
+        let schema = Schema::from(vec![schema.fields[0].clone()]);
+        write_file(&mut bytes, schema, &[chunk], WriteOptions::default()).unwrap();
+        Arc::<[u8]>::from(bytes)
+    };
+    let mut iters = column_iters(&[short]);
+    iters.extend(column_iters(&files).into_iter().skip(1).take(1));
+    let results: Vec<_> = ChunkReader::new(iters, 1000).collect();
+    assert_eq!(results.len(), 6);
+    assert!(results[..5].iter().all(|result| result.is_ok()));
+    assert!(results[5].is_err());
+}
+
+#[test]
+fn test_merge_files() {
+    let sizes = [3000, 200, 2500];
+    let chunks: Vec<Chunk<Box<dyn Array>>> = sizes
+        .iter()
+        .enumerate()
+        .map(|(i, size)| {
+            Chunk::new(vec![
+                Box::new(Int32Array::from_vec(
+                    (0..*size as i32)
+                        .map(|v| v % 100 + i as i32 * 100)
+                        .collect(),
+                )) as _,
+                Box::new(create_struct(*size, 0.2, *size)) as _,
+                Box::new(create_list(*size, 0.2)) as _,
+                Box::new(create_random_string(*size, 0.1, *size)) as _,
+            ])
+        })
+        .collect();
+    let fields: Vec<Field> = chunks[0]
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let schema = Schema::from(fields);
+
+    let files: Vec<Vec<u8>> = chunks
+        .iter()
+        .map(|chunk| {
+            let mut bytes = vec![];
+            write_file(
+                &mut bytes,
+                schema.clone(),
+                &[chunk.clone()],
+                WriteOptions {
+                    max_page_size: Some(1000),
+                    bloom_filter: true,
+                    default_compress_ratio: Some(2.0),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            bytes
+        })
+        .collect();
+    let inputs = files.iter().map(std::io::Cursor::new).collect();
+    let mut merged = vec![];
+    let (metas, size) = merge_files(inputs, &mut merged).unwrap();
+    assert_eq!(size, merged.len() as u64);
+    let mut reader = std::io::Cursor::new(&merged);
+    let mut read_metas = read_meta(&mut reader).unwrap();
+    read_bloom_filters(&mut reader, &mut read_metas).unwrap();
+    assert_eq!(read_metas, metas);
+    assert_eq!(
+        infer_row_count(&mut std::io::Cursor::new(&merged)).unwrap(),
+        Some(sizes.iter().sum::<usize>() as u64)
+    );
+
+    // the pages are copied as they are
+    let input_metas: Vec<Vec<ColumnMeta>> = files
+        .iter()
+        .map(|bytes| read_meta(&mut std::io::Cursor::new(bytes)).unwrap())
+        .collect();
+    for (column, meta) in metas.iter().enumerate() {
+        let pages: Vec<PageMeta> = input_metas
+            .iter()
+            .flat_map(|metas| metas[column].pages.clone())
+            .collect();
+        assert_eq!(meta.pages, pages);
+        assert_eq!(
+            meta.null_count,
+            input_metas
+                .iter()
+                .map(|m| m[column].null_count)
+                .sum::<Option<u64>>()
+        );
+        let pages: u64 = meta.codec_counts.iter().map(|(_, pages)| pages).sum();
+        assert_eq!(pages, meta.pages.len() as u64);
+    }
+    // the bloom filters of the same size are merged
+    let bloom_filter = metas[0].bloom_filter.as_ref().unwrap();
+    assert_eq!(
+        bloom_filter.length,
+        input_metas[0][0].bloom_filter.as_ref().unwrap().length
+    );
+    for value in [0i32, 99, 150, 299] {
+        assert!(metas[0].bloom_contains(&value.to_le_bytes()));
+    }
+    // and dropped otherwise
+    assert!(metas[3].bloom_filter.is_none());
+
+    // the min and max are merged, the values compared rather than their bytes
+    let statistics = metas[0].statistics.as_ref().unwrap();
+    assert_eq!(statistics.min, 0i32.to_le_bytes());
+    assert_eq!(statistics.max, 299i32.to_le_bytes());
+    let string_metas = input_metas.iter().map(|metas| metas.last().unwrap());
+    let statistics = metas.last().unwrap().statistics.as_ref().unwrap();
+    assert_eq!(
+        Some(&statistics.min),
+        string_metas
+            .clone()
+            .map(|meta| &meta.statistics.as_ref().unwrap().min)
+            .min()
+    );
+    assert_eq!(
+        Some(&statistics.max),
+        string_metas
+            .map(|meta| &meta.statistics.as_ref().unwrap().max)
+            .max()
+    );
+    assert!(metas.iter().all(|meta| meta.distinct_count.is_none()));
+
+    let reader = FileReader::try_new(std::io::Cursor::new(&merged), None).unwrap();
+    assert_eq!(reader.schema(), &schema);
+    let read_chunks = reader.collect::<arrow::error::Result<Vec<_>>>().unwrap();
+    for i in 0..schema.fields.len() {
+        let arrays: Vec<&dyn Array> = read_chunks
+            .iter()
+            .map(|chunk| chunk.arrays()[i].as_ref())
+            .collect();
+        let array = compute::concatenate::concatenate(&arrays).unwrap();
+        let arrays: Vec<&dyn Array> = chunks
+            .iter()
+            .map(|chunk| chunk.arrays()[i].as_ref())
+            .collect();
+        let expected = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(array, expected);
+    }
+
+    // the files must have the same schema
+    let mut other = vec![];
+    let chunk = Chunk::new(vec![chunks[0].arrays()[0].clone()]);
+    let other_schema = Schema::from(vec![schema.fields[0].clone()]);
+    write_file(
+        &mut other,
+        other_schema,
+        &[chunk.clone()],
+        WriteOptions::default(),
+    )
+    .unwrap();
+    let inputs = vec![
+        std::io::Cursor::new(&files[0]),
+        std::io::Cursor::new(&other),
+    ];
+    assert!(merge_files(inputs, vec![]).is_err());
+    assert!(merge_files(Vec::<std::io::Cursor<Vec<u8>>>::new(), vec![]).is_err());
+
+    // the pages of a zstd dictionary column depend on the dictionary of their file
+    let mut zstd = vec![];
+    let options = WriteOptions {
+        max_page_size: Some(64),
+        zstd_dictionary_size: Some(1024),
+        ..Default::default()
+    };
+    let chunk = Chunk::new(vec![chunks[0].arrays()[3].clone()]);
+    let zstd_schema = Schema::from(vec![schema.fields[3].clone()]);
+    let (zstd_metas, _) = write_file(&mut zstd, zstd_schema, &[chunk], options).unwrap();
+    assert!(zstd_metas[0].zstd_dictionary.is_some());
+    let inputs = vec![std::io::Cursor::new(&zstd), std::io::Cursor::new(&zstd)];
+    assert!(merge_files(inputs, vec![]).is_err());
+}
+
+#[test]
+fn test_to_ipc() {
+    let size = 5000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_struct(size, 0.2, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+        Box::new(create_map(size, 0.2)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let fields: Vec<Field> = chunk
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let schema = Schema::from(fields);
+    let mut bytes = vec![];
+    write_file(
+        &mut bytes,
+        schema.clone(),
+        &[chunk.clone()],
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let reader = FileReader::try_new(std::io::Cursor::new(&bytes), None).unwrap();
+    let mut ipc = vec![];
+    to_ipc(reader, &mut ipc).unwrap();
+
+    let mut ipc = std::io::Cursor::new(ipc);
+    let metadata = ipc_read::read_file_metadata(&mut ipc).unwrap();
+    assert_eq!(metadata.schema, schema);
+    let chunks = ipc_read::FileReader::new(ipc, metadata, None, None)
+        .collect::<arrow::error::Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(chunks.len(), (size + WRITE_PAGE - 1) / WRITE_PAGE);
+    for (i, array) in chunk.arrays().iter().enumerate() {
+        let arrays: Vec<&dyn Array> = chunks
+            .iter()
+            .map(|chunk| chunk.arrays()[i].as_ref())
+            .collect();
+        let result = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(result.as_ref(), array.as_ref());
+    }
+}
+
+#[test]
+fn test_parquet_to_strawboat() {
+    use arrow::io::parquet::write as parquet_write;
+
+    let size = 3000;
+    let chunks: Vec<Chunk<Box<dyn Array>>> = (0..3)
+        .map(|_| {
+            Chunk::new(vec![
+                Box::new(create_random_index(size, 0.1, size)) as _,
+                Box::new(create_struct(size, 0.2, size)) as _,
+                Box::new(create_list(size, 0.2)) as _,
+            ])
+        })
+        .collect();
+    let fields: Vec<Field> = chunks[0]
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let schema = Schema::from(fields);
+
+    let dir = tempfile::tempdir().unwrap();
+    let parquet_path = dir.path().join("input.parquet");
+    let out_path = dir.path().join("output.str");
+    {
+        let options = parquet_write::WriteOptions {
+            write_statistics: true,
+            compression: parquet_write::CompressionOptions::Zstd(None),
+            version: parquet_write::Version::V2,
+            data_pagesize_limit: None,
+        };
+        let encodings = schema
+            .fields
+            .iter()
+            .map(|f| parquet_write::transverse(&f.data_type, |_| parquet_write::Encoding::Plain))
+            .collect();
+        // one row group per chunk
+        let row_groups = parquet_write::RowGroupIterator::try_new(
+            chunks.iter().cloned().map(Ok),
+            &schema,
+            options,
+            encodings,
+        )
+        .unwrap();
+        let file = std::fs::File::create(&parquet_path).unwrap();
+        let mut writer = parquet_write::FileWriter::try_new(file, schema.clone(), options).unwrap();
+        for group in row_groups {
+            writer.write(group.unwrap()).unwrap();
+        }
+        writer.end(None).unwrap();
+    }
+
+    let metas = parquet_to_strawboat(&parquet_path, &out_path, None).unwrap();
+    // the row groups are written as the pages of a single column
+    assert_eq!(metas.len(), 4);
+    let num_values: u64 = metas[0].pages.iter().map(|p| p.num_values).sum();
+    assert_eq!(num_values, size as u64 * 3);
+
+    let reader = FileReader::try_new(std::fs::File::open(&out_path).unwrap(), None).unwrap();
+    assert_eq!(reader.schema(), &schema);
+    let results = reader.collect::<arrow::error::Result<Vec<_>>>().unwrap();
+    for i in 0..schema.fields.len() {
+        let expected: Vec<&dyn Array> = chunks.iter().map(|c| c.arrays()[i].as_ref()).collect();
+        let expected = compute::concatenate::concatenate(&expected).unwrap();
+        let arrays: Vec<&dyn Array> = results.iter().map(|c| c.arrays()[i].as_ref()).collect();
+        let array = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(array, expected);
+    }
+
+    // a parquet file without row groups has empty columns
+    {
+        let file = std::fs::File::create(&parquet_path).unwrap();
+        let options = parquet_write::WriteOptions {
+            write_statistics: true,
+            compression: parquet_write::CompressionOptions::Uncompressed,
+            version: parquet_write::Version::V2,
+            data_pagesize_limit: None,
+        };
+        let mut writer = parquet_write::FileWriter::try_new(file, schema.clone(), options).unwrap();
+        writer.end(None).unwrap();
+    }
+    parquet_to_strawboat(&parquet_path, &out_path, None).unwrap();
+    let reader = FileReader::try_new(std::fs::File::open(&out_path).unwrap(), None).unwrap();
+    assert_eq!(reader.schema(), &schema);
+    assert_eq!(reader.count(), 0);
+}
+
+#[test]
+fn test_float() {
+    let size = 1000;
+    let chunk = Chunk::new(vec![Box::new(create_random_double(size, 0.5, size)) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_float16() {
+    let size = 1000;
+    let values = create_random_half(size, 0.3, size);
+    let (offsets, bitmap) = create_random_offsets(size, 0.1);
+    let length = *offsets.last().unwrap() as usize;
+    let items = create_random_half(length, 0.2, 100);
+    let list = ListArray::try_new(
+        DataType::List(Box::new(Field::new("item", DataType::Float16, true))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        items.boxed(),
+        bitmap,
+    )
+    .unwrap();
+    let chunk = Chunk::new(vec![Box::new(values) as _, Box::new(list) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_fixed_size_binary() {
+    let size = 10000;
+    let (offsets, bitmap) = create_random_offsets(size, 0.1);
+    let length = *offsets.last().unwrap() as usize;
+    let items = create_random_fixed_binary(length, 0.2, 16);
+    let list = ListArray::try_new(
+        DataType::List(Box::new(Field::new(
+            "item",
+            items.data_type().clone(),
+            true,
+        ))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        items.boxed(),
+        bitmap,
+    )
+    .unwrap();
+    let chunk = Chunk::new(vec![
+        Box::new(create_random_fixed_binary(size, 0.0, 16)) as _,
+        Box::new(create_random_fixed_binary(size, 0.3, 16)) as _,
+        Box::new(create_random_fixed_binary(size, 0.5, 1)) as _,
+        Box::new(list) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_decimal() {
+    let chunk = Chunk::new(vec![
+        Box::new(
+            PrimitiveArray::<i128>::from_vec(vec![1, 2, 3, 4, 5, 6]).to(DataType::Decimal(10, 0)),
+        ) as _,
+        Box::new(
+            PrimitiveArray::<i128>::from(vec![
+                Some(-12345),
+                None,
+                Some(0),
+                Some(9999),
+                None,
+                Some(1),
+            ])
+            .to(DataType::Decimal(38, 4)),
+        ) as _,
+        Box::new(
+            PrimitiveArray::<i256>::from_vec((1..7).map(|v| i256::from_words(v, 0)).collect())
+                .to(DataType::Decimal256(20, 2)),
+        ) as _,
+        Box::new(
+            PrimitiveArray::<i256>::from(vec![
+                Some(i256::from_words(-1, -1)),
+                None,
+                Some(i256::from_words(0, i128::MAX)),
+                Some(i256::from_words(42, 0)),
+                None,
+                Some(i256::from_words(7, 0)),
+            ])
+            .to(DataType::Decimal256(76, 10)),
+        ) as _,
+    ]);
+    test_write_read(chunk);
+
+    let size = 10000;
+    let chunk = Chunk::new(vec![
+        Box::new(create_random_decimal(size, 0.0, size).to(DataType::Decimal(38, 3))) as _,
+        Box::new(create_random_decimal(size, 0.2, 8).to(DataType::Decimal(38, 10))) as _,
+    ]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list() {
+    let list_array = create_list(1000, 0.2);
+    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_map() {
+    let map_array = create_map(1000, 0.2);
+    let chunk = Chunk::new(vec![Box::new(map_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list_list() {
+    let l1 = create_list(2000, 0.2);
+
+    let mut offsets = vec![];
+    for i in (0..=1000).step_by(2) {
+        offsets.push(i);
+    }
+    let list_array = ListArray::try_new(
+        DataType::List(Box::new(Field::new("item", l1.data_type().clone(), true))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        l1.boxed(),
+        None,
+    )
+    .unwrap();
+
+    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list_struct() {
+    let s1 = create_struct(2000, 0.2, 2000);
+
+    let mut offsets = vec![];
+    for i in (0..=1000).step_by(2) {
+        offsets.push(i);
+    }
+    let list_array = ListArray::try_new(
+        DataType::List(Box::new(Field::new("item", s1.data_type().clone(), true))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        s1.boxed(),
+        None,
+    )
+    .unwrap();
+
+    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list_offsets_size() {
+    let num_lists = 1000;
+    let mut offsets = vec![0i32];
+    for i in 0..num_lists {
+        offsets.push(offsets.last().unwrap() + i * 7919 % 17);
+    }
+    let length = *offsets.last().unwrap() as usize;
+    let list_array = ListArray::try_new(
+        DataType::List(Box::new(Field::new("item", DataType::Int32, false))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        Int32Array::from_vec((0..length as i32).collect()).boxed(),
+        None,
+    )
+    .unwrap();
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![Box::new(list_array) as _]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(vec![Field::new(
+            "l",
+            chunk.arrays()[0].data_type().clone(),
+            false,
+        )]),
+        WriteOptions {
+            default_compression: CommonCompression::None,
+            max_page_size: None,
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    // the offsets are not stored, the levels that replace them take less than
+    // a byte per value, against 4 bytes per offset
+    let page_length = writer.metas[0].pages[0].length as usize;
+    let values_size = length * std::mem::size_of::<i32>();
+    assert!(page_length < values_size + length);
+
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list_map() {
+    let m1 = create_map(2000, 0.2);
+
+    let mut offsets = vec![];
+    for i in (0..=1000).step_by(2) {
+        offsets.push(i);
+    }
+    let list_array = ListArray::try_new(
+        DataType::List(Box::new(Field::new("item", m1.data_type().clone(), true))),
+        OffsetsBuffer::try_from(offsets).unwrap(),
+        m1.boxed(),
+        None,
+    )
+    .unwrap();
+
+    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_required_struct() {
+    let size = WRITE_PAGE * 2 + 100;
+    let names = || Utf8Array::<i32>::from_iter_values((0..size).map(|i| format!("n{}", i % 7)));
+    let ages = || Int32Array::from_vec((0..size as i32).collect());
+    let dt = DataType::Struct(vec![
+        Field::new("name", DataType::Utf8, false),
+        Field::new("age", DataType::Int32, false),
+    ]);
+    let struct_array = StructArray::try_new(
+        dt.clone(),
+        vec![Box::new(names()) as _, Box::new(ages()) as _],
+        None,
+    )
+    .unwrap();
+    let chunk = Chunk::new(vec![Box::new(struct_array) as _]);
+    test_write_read(chunk.clone());
+
+    let write_metas = |chunk: &Chunk<Box<dyn Array>>, schema: Schema| {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            schema,
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                default_compress_ratio: None,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(chunk).unwrap();
+        writer.finish().unwrap();
+        writer.metas.clone()
+    };
+    // the leaves of a required struct are written without levels,
+    // like the same required columns at the top level
+    let struct_metas = write_metas(&chunk, Schema::from(vec![Field::new("s", dt, false)]));
+    let flat_metas = write_metas(
+        &Chunk::new(vec![Box::new(names()) as _, Box::new(ages()) as _]),
+        Schema::from(vec![
+            Field::new("name", DataType::Utf8, false),
+            Field::new("age", DataType::Int32, false),
+        ]),
+    );
+    let page_lengths = |metas: &[ColumnMeta]| {
+        metas
+            .iter()
+            .map(|meta| meta.pages.iter().map(|p| p.length).collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(page_lengths(&struct_metas), page_lengths(&flat_metas));
+}
+
+#[test]
+fn test_struct_list() {
+    let size = 10000;
+    let null_density = 0.2;
+    let dt = DataType::Struct(vec![
+        Field::new("name", DataType::LargeBinary, true),
+        Field::new(
+            "age",
+            DataType::List(Box::new(Field::new("item", DataType::Int32, true))),
+            true,
+        ),
+    ]);
+    let struct_array = StructArray::try_new(
+        dt,
+        vec![
+            Box::new(create_random_string(size, null_density, size)) as _,
+            Box::new(create_list(size, null_density)) as _,
+        ],
+        None,
+    )
+    .unwrap();
+    let chunk = Chunk::new(vec![Box::new(struct_array) as _]);
+    test_write_read(chunk);
+}
+
+#[test]
+fn test_list_struct_list() {
+    let size = 6000;
+    for null_density in [0.0, 0.2] {
+        let inner = if null_density == 0.0 {
+            // lists of the same length without nulls, whose levels are long runs
+            let values = Int32Array::from_vec((0..size as i32 * 3).collect());
+            let offsets: Vec<i32> = (0..=size as i32).map(|i| i * 3).collect();
+            ListArray::try_new(
+                DataType::List(Box::new(Field::new("item", DataType::Int32, true))),
+                OffsetsBuffer::try_from(offsets).unwrap(),
+                values.boxed(),
+                None,
+            )
+            .unwrap()
+        } else {
+            create_list(size, null_density)
+        };
+        let dt = DataType::Struct(vec![
+            Field::new("name", DataType::LargeBinary, true),
+            Field::new("values", inner.data_type().clone(), true),
+        ]);
+        let struct_array = StructArray::try_new(
+            dt.clone(),
+            vec![
+                Box::new(create_random_string(size, null_density, size)) as _,
+                Box::new(inner) as _,
+            ],
+            None,
+        )
+        .unwrap();
+        let offsets: Vec<i32> = (0..=size as i32).step_by(3).collect();
+        let list_array = ListArray::try_new(
+            DataType::List(Box::new(Field::new("item", dt, true))),
+            OffsetsBuffer::try_from(offsets).unwrap(),
+            struct_array.boxed(),
+            None,
+        )
+        .unwrap();
+        let chunk = Chunk::new(vec![Box::new(list_array) as _]);
+        test_write_read(chunk);
+    }
+}
+
+#[test]
+fn test_read_meta_projected() {
+    let size = 1000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_struct(size, 0.2, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+        Box::new(create_map(size, 0.2)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let fields: Vec<Field> = chunk
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(fields),
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+
+    let projection = [4, 1, 3, 0];
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let columns = read_meta_projected(&mut reader, &projection).unwrap();
+    assert_eq!(columns.len(), projection.len());
+    for (column, index) in columns.into_iter().zip(projection) {
+        assert_eq!(column.index, index);
+        assert_eq!(column.field.name, format!("c{index}"));
+        assert_eq!(column.metas.len(), n_columns(column.field.data_type()));
+
+        let pages = column.metas.iter().map(|meta| meta.pages.clone()).collect();
+        let readers = column
+            .metas
+            .iter()
+            .map(|meta| {
+                let mut reader = std::io::Cursor::new(bytes.clone());
+                reader.consume(meta.offset as usize);
+                reader
+            })
+            .collect();
+        let is_nested = !is_primitive(column.field.data_type());
+        let array =
+            batch_read_array(readers, column.leaves, column.field, is_nested, pages).unwrap();
+        assert_eq!(&array, &chunk.arrays()[index]);
+    }
+
+    let mut reader = std::io::Cursor::new(bytes);
+    assert!(read_meta_projected(&mut reader, &[5]).is_err());
+}
+
+#[test]
+fn test_skip_n_pages() {
+    let size = 10000;
+    let page_size = 1000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(
+        &mut bytes,
+        schema.clone(),
+        &[chunk.clone()],
+        WriteOptions {
+            max_page_size: Some(page_size),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let leaves = to_parquet_schema(&schema).unwrap().columns().to_vec();
+
+    for (column, meta) in metas.iter().enumerate() {
+        assert_eq!(meta.skip_n_pages(0), *meta);
+        assert_eq!(meta.skip_n_pages(1), meta.skip_one_page());
+        assert_eq!(meta.skip_n_pages(7), meta.slice(7, 10));
+        let skipped = meta.skip_n_pages(10);
+        assert!(skipped.pages.is_empty());
+        assert_eq!(skipped.offset, meta.offset + meta.total_len());
+
+        for n in [0, 3, 9] {
+            let mut reader = std::io::Cursor::new(bytes.clone());
+            reader.consume(meta.offset as usize);
+            let mut native_reader = NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_read_options(file_read_options(&bytes));
+            native_reader.skip_n_pages(1).unwrap();
+            native_reader.skip_n_pages(n).unwrap();
+            assert_eq!(native_reader.current_page(), n + 1);
+            if n == 9 {
+                assert!(!native_reader.has_next());
+                continue;
+            }
+
+            let field = schema.fields[column].clone();
+            let mut array_iter = column_iter_to_arrays(
+                vec![native_reader],
+                vec![leaves[column].clone()],
+                field,
+                false,
+            )
+            .unwrap();
+            let array = array_iter.next().unwrap().unwrap();
+            let expected = chunk.arrays()[column].sliced((n + 1) * page_size, page_size);
+            assert_eq!(array, expected);
+        }
+    }
+}
+
+#[test]
+fn test_skip_pages_not_matching() {
+    let size = 10000;
+    let page_size = 1000;
+    let ints = Int32Array::from_vec((0..size as i32).collect());
+    let strings = Utf8Array::<i32>::from_iter_values((0..size).map(|i| format!("key-{i:05}")));
+    let chunk: Chunk<Box<dyn Array>> =
+        Chunk::new(vec![Box::new(ints) as _, Box::new(strings) as _]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int32, false),
+        Field::new("s", DataType::Utf8, false),
+    ]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema.clone(),
+        WriteOptions {
+            max_page_size: Some(page_size),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+
+    let written_metas = writer.metas.clone();
+
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let metas = read_meta(&mut reader).unwrap();
+    assert_eq!(metas, written_metas);
+    assert_eq!(metas[0].pages.len(), size / page_size);
+    let statistics = metas[0].pages[2].statistics.clone().unwrap();
+    assert_eq!(statistics.min, 2000i32.to_le_bytes());
+    assert_eq!(statistics.max, 2999i32.to_le_bytes());
+    let statistics = metas[1].pages[2].statistics.clone().unwrap();
+    assert_eq!(statistics.min, b"key-02000");
+    assert_eq!(statistics.max, b"key-02999");
+
+    let cases: Vec<(usize, PagePredicate, usize)> = vec![
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::Gt,
+                Box::new(PrimitiveScalar::from(Some(4500i32))),
+            ),
+            4,
+        ),
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::Eq,
+                Box::new(PrimitiveScalar::from(Some(7000i32))),
+            ),
+            7,
+        ),
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::GtEq,
+                Box::new(PrimitiveScalar::from(Some(3000i32))),
+            ),
+            3,
+        ),
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::Lt,
+                Box::new(PrimitiveScalar::from(Some(4500i32))),
+            ),
+            0,
+        ),
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::Gt,
+                Box::new(PrimitiveScalar::from(Some(9999i32))),
+            ),
+            10,
+        ),
+        (
+            1,
+            PagePredicate::new(
+                ComparisonOp::Eq,
+                Box::new(Utf8Scalar::<i32>::new(Some("key-05123"))),
+            ),
+            5,
+        ),
+        // a literal of another type never skips
+        (
+            0,
+            PagePredicate::new(
+                ComparisonOp::Gt,
+                Box::new(PrimitiveScalar::from(Some(9999i64))),
+            ),
+            0,
+        ),
+    ];
+    for (column, predicate, expected) in cases {
+        let meta = &metas[column];
+        let mut range_bytes = std::io::Cursor::new(bytes.clone());
+        range_bytes.consume(meta.offset as usize);
+        let mut native_reader = NativeReader::new(range_bytes, meta.pages.clone(), vec![])
+            .with_read_options(file_read_options(&bytes));
+        let skipped = native_reader.skip_pages_not_matching(&predicate).unwrap();
+        assert_eq!(skipped, expected);
+        if expected == meta.pages.len() {
+            assert!(!native_reader.has_next());
+            continue;
+        }
+
+        let field = schema.fields[column].clone();
+        let leaves = vec![to_parquet_schema(&schema).unwrap().columns()[column].clone()];
+        let mut array_iter =
+            column_iter_to_arrays(vec![native_reader], leaves, field, false).unwrap();
+        let array = array_iter.next().unwrap().unwrap();
+        let expected_array = chunk.arrays()[column].sliced(expected * page_size, page_size);
+        assert_eq!(array, expected_array);
+    }
+}
+
+#[test]
+fn test_select_pages() {
+    let page_size = 1000;
+    // a page of nulls, a page without nulls, a page with nulls and a constant page
+    let values = (0..page_size as i32)
+        .map(|_| None)
+        .chain((1000..2000).map(Some))
+        .chain((2000..3000).map(|i| (i % 10 != 0).then_some(i)))
+        .chain((0..page_size).map(|_| Some(5000)));
+    let ints = Int32Array::from_iter(values);
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![Box::new(ints.clone()) as _]);
+    let schema = Schema::from(vec![Field::new("i", DataType::Int32, true)]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema.clone(),
+        WriteOptions::builder().max_page_size(page_size).build(),
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let meta = read_meta(&mut reader).unwrap().remove(0);
+    assert_eq!(meta.pages.len(), 4);
+    assert!(meta.pages[0].statistics.is_none());
+
+    let literal = |value: i32| Box::new(PrimitiveScalar::from(Some(value))) as _;
+    let cases: Vec<(Predicate, Vec<usize>, Vec<usize>)> = vec![
+        (Predicate::Eq(literal(1500)), vec![1], vec![]),
+        (Predicate::Eq(literal(5000)), vec![3], vec![3]),
+        (Predicate::Lt(literal(1000)), vec![], vec![]),
+        (Predicate::Lt(literal(2500)), vec![1, 2], vec![1]),
+        (Predicate::Gt(literal(2998)), vec![2, 3], vec![3]),
+        (
+            Predicate::Between(literal(1900), literal(2100)),
+            vec![1, 2],
+            vec![],
+        ),
+        (
+            Predicate::Between(literal(0), literal(10000)),
+            vec![1, 2, 3],
+            vec![1, 3],
+        ),
+        (Predicate::IsNull, vec![0, 2], vec![0]),
+        // a literal of another type selects every page with a valid value
+        (
+            Predicate::Eq(Box::new(PrimitiveScalar::from(Some(1500i64)))),
+            vec![1, 2, 3],
+            vec![],
+        ),
+    ];
+    for (predicate, selected, all_matching) in cases {
+        assert_eq!(meta.select_pages(&predicate), selected, "{predicate:?}");
+        let matching: Vec<usize> = (0..meta.pages.len())
+            .filter(|index| predicate.matches_all(&meta.pages[*index]))
+            .collect();
+        assert_eq!(matching, all_matching, "{predicate:?}");
+    }
+
+    // only the selected pages are decoded, then filtered exactly
+    let (low, high) = (1900, 2100);
+    let selected = meta.select_pages(&Predicate::Between(literal(low), literal(high)));
+    let selected_meta = meta.slice(selected[0], selected[selected.len() - 1] + 1);
+    let read_options = file_read_options(&bytes);
+    let mut range_bytes = std::io::Cursor::new(bytes);
+    range_bytes.consume(selected_meta.offset as usize);
+    let native_reader =
+        NativeReader::new(range_bytes, selected_meta.pages, vec![]).with_read_options(read_options);
+    let leaves = vec![to_parquet_schema(&schema).unwrap().columns()[0].clone()];
+    let array_iter =
+        column_iter_to_arrays(vec![native_reader], leaves, schema.fields[0].clone(), false)
+            .unwrap();
+    let mut filtered: Vec<i32> = vec![];
+    for array in array_iter {
+        let array = array.unwrap();
+        let array = array.as_any().downcast_ref::<Int32Array>().unwrap();
+        filtered.extend(
+            array
+                .iter()
+                .flatten()
+                .filter(|v| (low..=high).contains(*v))
+                .copied(),
+        );
+    }
+    let expected: Vec<i32> = ints
+        .iter()
+        .flatten()
+        .filter(|v| (low..=high).contains(*v))
+        .copied()
+        .collect();
+    assert_eq!(filtered, expected);
+}
+
+#[test]
+fn test_truncated_statistics() {
+    let size = 3000;
+    let page_size = 1000;
+    let blob = |i: usize| format!("{i:04}{}", "x".repeat(10_000));
+    let strings = Utf8Array::<i32>::from_iter_values((0..size).map(blob));
+    let ints = Int32Array::from_vec((0..size as i32).collect());
+    let chunk: Chunk<Box<dyn Array>> =
+        Chunk::new(vec![Box::new(strings) as _, Box::new(ints) as _]);
+    let schema = Schema::from(vec![
+        Field::new("s", DataType::Utf8, false),
+        Field::new("i", DataType::Int32, false),
+    ]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema,
+        WriteOptions::builder()
+            .max_page_size(page_size)
+            .statistics_truncate_length(8)
+            .build(),
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let written_metas = writer.metas.clone();
+
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let metas = read_meta(&mut reader).unwrap();
+    assert_eq!(metas, written_metas);
+
+    let statistics = metas[0].pages[1].statistics.clone().unwrap();
+    assert_eq!(statistics.min, b"1000xxxx");
+    assert_eq!(statistics.max, b"1999xxxy");
+    let statistics = metas[0].statistics.clone().unwrap();
+    assert_eq!(statistics.min, b"0000xxxx");
+    assert_eq!(statistics.max, b"2999xxxy");
+    // the integer statistics are never truncated
+    let statistics = metas[1].statistics.clone().unwrap();
+    assert_eq!(statistics.min, 0i32.to_le_bytes());
+    assert_eq!(statistics.max, (size as i32 - 1).to_le_bytes());
+
+    // the truncated bounds still keep the pages of the matching values
+    let predicate = PagePredicate::new(
+        ComparisonOp::Eq,
+        Box::new(Utf8Scalar::<i32>::new(Some(blob(1999)))),
+    );
+    let mut range_bytes = std::io::Cursor::new(bytes);
+    range_bytes.consume(metas[0].offset as usize);
+    let mut native_reader = NativeReader::new(range_bytes, metas[0].pages.clone(), vec![]);
+    assert_eq!(
+        native_reader.skip_pages_not_matching(&predicate).unwrap(),
+        1
+    );
+}
+
+#[test]
+fn test_write_statistics() {
+    let size = 3000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(Int32Array::from_vec((0..size).collect())) as _,
+        Box::new(create_random_string(size as usize, 0.1, 100)) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int32, false),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema,
+        WriteOptions::builder()
+            .max_page_size(1000)
+            .write_statistics(false)
+            .statistics_column(1)
+            .build(),
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let written_metas = writer.metas.clone();
+
+    let mut reader = std::io::Cursor::new(bytes);
+    let metas = read_meta(&mut reader).unwrap();
+    assert_eq!(metas, written_metas);
+    assert!(metas[0].statistics.is_none());
+    assert!(metas[0].pages.iter().all(|page| page.statistics.is_none()));
+    assert!(metas[1].statistics.is_some());
+    assert!(metas[1].pages.iter().all(|page| page.statistics.is_some()));
+
+    // the statistics are written by default
+    assert!(WriteOptions::default().write_statistics);
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            write_statistics: false,
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn test_encoding_reports() {
+    let size = WRITE_PAGE * 3;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(Int64Array::from_vec(vec![7; size])) as _,
+        Box::new(create_random_string(size, 0.1, 8)) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int64, false),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema,
+        WriteOptions::builder()
+            .max_page_size(WRITE_PAGE)
+            .compress_ratio(2.0)
+            .build(),
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish_row_group().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+
+    let reports = writer.encoding_reports();
+    assert_eq!(reports.len(), writer.metas.len());
+    for (index, (report, meta)) in reports.iter().zip(writer.metas.iter()).enumerate() {
+        assert_eq!(report.column, index);
+        assert_eq!(report.pages.len(), meta.pages.len());
+        for (page, page_meta) in report.pages.iter().zip(meta.pages.iter()) {
+            assert_eq!(page.codec, meta.dominant_codec);
+            assert_eq!(page.compressed_size, page_meta.length);
+        }
+        // the footer counts the pages of each encoding
+        for (codec, pages) in &meta.codec_counts {
+            let count = report
+                .pages
+                .iter()
+                .filter(|page| page.codec == *codec)
+                .count();
+            assert_eq!(count as u64, *pages);
+        }
+    }
+    assert_eq!(reports[0].pages[0].codec, Compression::OneValue);
+    assert_eq!(
+        reports[0].pages[0].uncompressed_size,
+        (WRITE_PAGE * std::mem::size_of::<i64>()) as u64
+    );
+    assert!(reports[0].compress_ratio() > 100.0);
+}
+
+#[test]
+fn test_column_meta_null_count_and_codec() {
+    let size = WRITE_PAGE * 3;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(Int64Array::from_iter(
+            (0..size as i64).map(|i| (i % 4 != 0).then_some(i)),
+        )) as _,
+        Box::new(Int32Array::new_null(DataType::Int32, size)) as _,
+        Box::new(Utf8Array::<i32>::from_iter_values((0..size).map(|_| "a"))) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int64, true),
+        Field::new("n", DataType::Int32, true),
+        Field::new("s", DataType::Utf8, false),
+    ]);
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema.clone(),
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    assert_eq!(metas[0].null_count, Some(size as u64 / 4));
+    assert_eq!(metas[0].dominant_codec, Compression::Lz4);
+    assert_eq!(metas[1].null_count, Some(size as u64));
+    assert_eq!(metas[1].dominant_codec, Compression::AllNull);
+    assert_eq!(metas[2].null_count, Some(0));
+    assert_eq!(metas[2].dominant_codec, Compression::Lz4);
+    assert_eq!(metas[0].slice(1, 2).null_count, Some(WRITE_PAGE as u64 / 4));
+
+    let mut reader = std::io::Cursor::new(&bytes);
+    assert_eq!(read_meta(&mut reader).unwrap(), metas);
+
+    // metas serialized before the fields were added still deserialize,
+    // with an unknown null count
+    let json = r#"{"offset":8,"pages":[{"length":10,"num_values":1}]}"#;
+    let meta: ColumnMeta = serde_json::from_str(json).unwrap();
+    assert_eq!(meta.null_count, None);
+    assert_eq!(meta.pages[0].null_count, None);
+    assert_eq!(meta.dominant_codec, Compression::None);
+    let json = serde_json::to_string(&metas[1]).unwrap();
+    assert_eq!(serde_json::from_str::<ColumnMeta>(&json).unwrap(), metas[1]);
+
+    // the validity of the pages with an unknown null count is decoded
+    let mut meta = metas[0].clone();
+    meta.null_count = None;
+    meta.pages
+        .iter_mut()
+        .for_each(|page| page.null_count = None);
+    let mut reader = std::io::Cursor::new(bytes.as_slice());
+    reader.consume(meta.offset as usize);
+    let array = read_simple(&mut reader, schema.fields[0].clone(), meta.pages.clone()).unwrap();
+    assert_eq!(array.as_ref(), chunk.arrays()[0].as_ref());
+    let mut reader = std::io::Cursor::new(bytes.as_slice());
+    reader.consume(meta.offset as usize);
+    let (_, validity) = read_validity_only(&mut reader, &schema.fields[0], &meta).unwrap();
+    assert_eq!(validity.unwrap().unset_bits(), size / 4);
+}
+
+#[test]
+fn test_page_checksum() {
+    let size = WRITE_PAGE * 4;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, 8)) as _,
+        Box::new(create_random_string(size, 0.2, 8)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+    ]);
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let options = WriteOptions {
+            default_compression: CommonCompression::Zstd,
+            max_page_size: Some(WRITE_PAGE),
+            zstd_dictionary_size,
+            page_checksum: true,
+            ..Default::default()
+        };
+        test_write_read_with_options(chunk.clone(), options);
+    }
+
+    let array = Int64Array::from_vec((0..size as i64).collect());
+    let field = Field::new("c", DataType::Int64, false);
+    let write_file = |page_checksum: bool| {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            Schema::from(vec![field.clone()]),
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                page_checksum,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer
+            .write(&Chunk::new(vec![Box::new(array.clone()) as Box<dyn Array>]))
+            .unwrap();
+        writer.finish().unwrap();
+        let meta = writer.metas[0].clone();
+        (bytes, meta)
+    };
+
+    let (_, meta) = write_file(false);
+    assert!(meta.pages.iter().all(|page| page.checksum.is_none()));
+
+    let (mut bytes, meta) = write_file(true);
+    assert!(meta.pages.iter().all(|page| page.checksum.is_some()));
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    assert_eq!(read_meta(&mut reader).unwrap()[0], meta);
+
+    // flip a byte of the second page
+    let corrupted = (meta.offset + meta.pages[0].length) as usize + 20;
+    bytes[corrupted] ^= 0xff;
+    let read_pages = |verify: bool| {
+        let mut reader = std::io::Cursor::new(bytes.clone());
+        reader.consume(meta.offset as usize);
+        NativeReader::new(reader, meta.pages.clone(), vec![])
+            .with_checksum_verification(verify)
+            .collect::<arrow::error::Result<Vec<_>>>()
+    };
+    assert!(read_pages(false).is_ok());
+    let err = read_pages(true).unwrap_err();
+    assert!(err.to_string().contains("Checksum mismatch of page 1"));
+
+    // the corrupted page is skipped, the next ones are still read
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    reader.consume(meta.offset as usize);
+    let mut reader =
+        NativeReader::new(reader, meta.pages.clone(), vec![]).with_checksum_verification(true);
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_err());
+    assert_eq!(reader.current_page(), 2);
+    assert!(reader.next().unwrap().is_ok());
+    assert_eq!(reader.current_page(), 3);
+}
+
+#[test]
+fn test_validate_offsets() {
+    let array = BinaryArray::<i32>::from_slice(["aa", "bb", "cc"]);
+    let field = Field::new("c", DataType::Binary, false);
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(vec![field.clone()]),
+        WriteOptions::uncompressed(),
+    );
+    writer.start().unwrap();
+    writer
+        .write(&Chunk::new(vec![Box::new(array.clone()) as Box<dyn Array>]))
+        .unwrap();
+    writer.finish().unwrap();
+    let meta = writer.metas[0].clone();
+
+    let read = |bytes: &[u8], validate: bool| {
+        let read_options = ReadOptions {
+            validate,
+            ..file_read_options(bytes)
+        };
+        let mut reader = std::io::Cursor::new(bytes.to_vec());
+        reader.consume(meta.offset as usize);
+        let iter_result = column_iter_to_arrays(
+            vec![
+                NativeReader::new(reader.clone(), meta.pages.clone(), vec![])
+                    .with_read_options(read_options),
+            ],
+            vec![],
+            field.clone(),
+            false,
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+        let batch_result = batch_read_array_with_options(
+            vec![reader],
+            vec![],
+            field.clone(),
+            false,
+            vec![meta.pages.clone()],
+            read_options,
+        );
+        (iter_result, batch_result)
+    };
+    for validate in [false, true] {
+        let (iter_result, batch_result) = read(&bytes, validate);
+        assert_eq!(iter_result.unwrap().as_ref(), &array as &dyn Array);
+        assert_eq!(batch_result.unwrap().as_ref(), &array as &dyn Array);
+    }
+
+    // the uncompressed offsets [0, 2, 4, 6] follow the header of the page,
+    // corrupt them to [0, 5, 4, 6]
+    let offset = meta.offset as usize + 17 + 4;
+    assert_eq!(bytes[offset..offset + 4], 2i32.to_le_bytes());
+    bytes[offset..offset + 4].copy_from_slice(&5i32.to_le_bytes());
+    let (iter_result, batch_result) = read(&bytes, true);
+    assert!(iter_result.is_err());
+    assert!(batch_result.is_err());
+}
+
+#[test]
+fn test_corrupted_compressed_size() {
+    let size = WRITE_PAGE * 2;
+    let arrays: Vec<Box<dyn Array>> = vec![
+        Box::new(Int64Array::from_vec((0..size as i64).collect())),
+        Box::new(Utf8Array::<i32>::from_iter_values(
+            (0..size).map(|i| format!("v{i}")),
+        )),
+    ];
+    let fields: Vec<Field> = arrays
+        .iter()
+        .map(|array| Field::new("c", array.data_type().clone(), false))
+        .collect();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(fields.clone()),
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            ..WriteOptions::uncompressed()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&Chunk::new(arrays)).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    for (field, meta) in fields.into_iter().zip(metas) {
+        // the compressed size of the first buffer of the second page
+        let mut bytes = bytes.clone();
+        let pos = (meta.offset + meta.pages[0].length) as usize + 1;
+        bytes[pos..pos + 8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+
+        let mut reader = std::io::Cursor::new(bytes.clone());
+        reader.consume(meta.offset as usize);
+        let results = column_iter_to_arrays(
+            vec![NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_read_options(file_read_options(&bytes))],
+            vec![],
+            field.clone(),
+            false,
+        )
+        .unwrap()
+        .collect::<Vec<_>>();
+        assert!(results[0].is_ok());
+        let err = results[1].as_ref().unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "{err}");
+
+        // the size of a page is also bounded when the reader is not in memory
+        let mut reader = BufReader::new(std::io::Cursor::new(bytes));
+        reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+        let err =
+            batch_read_array(vec![reader], vec![], field, false, vec![meta.pages]).unwrap_err();
+        assert!(err.to_string().contains("exceeds"), "{err}");
+    }
+}
+
+#[test]
+fn test_read_validity_only() {
+    let size = WRITE_PAGE * 3 + 10;
+    let arrays: Vec<Box<dyn Array>> = vec![
+        Box::new(create_random_index(size, 0.3, size)),
+        Box::new(create_random_string(size, 0.1, 16)),
+        Box::new(Int64Array::from_vec((0..size as i64).collect())),
+        Box::new(Int32Array::new_null(DataType::Int32, size)),
+    ];
+    let fields: Vec<Field> = arrays
+        .iter()
+        .map(|array| Field::new("c", array.data_type().clone(), true))
+        .collect();
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            Schema::from(fields.clone()),
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                zstd_dictionary_size,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&Chunk::new(arrays.clone())).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
+
+        for ((field, meta), array) in fields.iter().zip(metas.iter()).zip(arrays.iter()) {
+            let mut reader = std::io::Cursor::new(&bytes);
+            reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+            let (num_values, validity) = read_validity_only(&mut reader, field, meta).unwrap();
+            assert_eq!(num_values, size as u64);
+            // a column without nulls has no validity
+            assert_eq!(validity.as_ref(), array.validity());
+            assert_eq!(reader.position(), meta.offset + meta.total_len());
+        }
+    }
+
+    let field = Field::new("c", create_list(10, 0.1).data_type().clone(), true);
+    let mut reader = std::io::Cursor::new(vec![]);
+    let meta: ColumnMeta = serde_json::from_str(r#"{"offset":0,"pages":[]}"#).unwrap();
+    assert!(read_validity_only(&mut reader, &field, &meta).is_err());
+}
+
+#[test]
+fn test_bloom_filter() {
+    let size = 10000;
+    let ints = Int64Array::from_iter((0..size as i64).map(|i| (i % 7 != 0).then_some(i * 3)));
+    let strings = Utf8Array::<i32>::from_iter_values((0..size).map(|i| format!("key-{i}")));
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(ints) as _,
+        Box::new(strings) as _,
+        Box::new(create_random_bool(size, 0.1)) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int64, true),
+        Field::new("s", DataType::Utf8, false),
+        Field::new("b", DataType::Boolean, true),
+    ]);
+
+    let write_file = |bloom_filter: bool| {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            schema.clone(),
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                bloom_filter,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
+        (bytes, metas)
+    };
+
+    let (bytes, written_metas) = write_file(false);
+    assert!(written_metas.iter().all(|meta| meta.bloom_filter.is_none()));
+    let mut reader = std::io::Cursor::new(bytes);
+    assert_eq!(read_meta(&mut reader).unwrap(), written_metas);
+
+    let (bytes, written_metas) = write_file(true);
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let mut metas = read_meta(&mut reader).unwrap();
+    // the footer only records where the bloom filters are
+    assert!(metas[..2]
+        .iter()
+        .all(|meta| meta.bloom_filter.as_ref().unwrap().bitset.is_empty()));
+    assert!(metas[0].bloom_contains(&(-1i64).to_le_bytes()));
+    read_bloom_filters(&mut reader, &mut metas).unwrap();
+    assert_eq!(metas, written_metas);
+    assert!(metas[2].bloom_filter.is_none());
+    let mut reader = std::io::Cursor::new(bytes);
+    let mut entry = read_catalog_entry(&mut reader).unwrap();
+    read_bloom_filters(&mut reader, &mut entry.column_metas).unwrap();
+    assert_eq!(entry.column_metas, metas);
+
+    for i in 0..size as i64 {
+        let contains = metas[0].bloom_contains(&(i * 3).to_le_bytes());
+        assert!(contains || i % 7 == 0);
+        assert!(metas[1].bloom_contains(format!("key-{i}").as_bytes()));
+    }
+    // the filters are sized for a false positive probability of 1%
+    let false_positives = (0..size as i64)
+        .filter(|i| metas[0].bloom_contains(&(i * 3 + 1).to_le_bytes()))
+        .count()
+        + (size..2 * size)
+            .filter(|i| metas[1].bloom_contains(format!("key-{i}").as_bytes()))
+            .count();
+    assert!(false_positives < size / 25, "{false_positives}");
+
+    // the footer alone does not hold the bitsets
+    let mut meta = metas[0].clone();
+    meta.bloom_filter.as_mut().unwrap().bitset.clear();
+    assert!(meta.bloom_contains(&1i64.to_le_bytes()));
+
+    test_write_read_with_options(
+        chunk,
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            bloom_filter: true,
+            ..Default::default()
+        },
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_parallel_encoding() {
+    let size = 5000;
+    let strings = Utf8Array::<i32>::from_slice(["apple", "banana", "cherry", "durian"]);
+    let keys = UInt32Array::from_vec((0..size).map(|i| (i % 4) as u32).collect());
+    let dictionary = DictionaryArray::try_from_keys(keys, strings.boxed()).unwrap();
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_struct(size, 0.2, size)) as _,
+        Box::new(create_list(size, 0.2)) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+        dictionary.boxed(),
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let write_file = |parallel_encoding: bool| {
+            let mut bytes = Vec::new();
+            let mut writer = NativeWriter::new(
+                &mut bytes,
+                schema.clone(),
+                WriteOptions {
+                    // small pages are encoded without sampling, so the bytes are deterministic
+                    max_page_size: Some(500),
+                    default_compress_ratio: Some(2.0),
+                    bloom_filter: true,
+                    zstd_dictionary_size,
+                    parallel_encoding,
+                    ..Default::default()
+                },
+            );
+            writer.start().unwrap();
+            writer.write(&chunk).unwrap();
+            writer.finish().unwrap();
+            let metas = writer.metas.clone();
+            (bytes, metas)
+        };
+
+        let (serial_bytes, serial_metas) = write_file(false);
+        let (parallel_bytes, parallel_metas) = write_file(true);
+        assert_eq!(parallel_metas, serial_metas);
+        assert_eq!(parallel_bytes, serial_bytes);
+    }
+
+    // dictionary columns are read with `read_dictionary_column`
+    let arrays = chunk.into_arrays();
+    test_write_read_with_options(
+        Chunk::new(arrays[..4].to_vec()),
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            parallel_encoding: true,
+            ..Default::default()
+        },
+    );
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_read_simple_parallel() {
+    use strawboat::read::batch_read::read_simple_parallel;
+
+    let size = WRITE_PAGE * 10 + 7;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+        Box::new(BooleanArray::from_iter((0..size).map(|i| Some(i % 3 == 0)))) as _,
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let options = WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            zstd_dictionary_size,
+            ..Default::default()
+        };
+        let mut bytes = Vec::new();
+        let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk.clone()], options).unwrap();
+        let reader = std::io::Cursor::new(bytes.as_slice());
+        for (meta, (field, array)) in metas.iter().zip(schema.fields.iter().zip(chunk.iter())) {
+            assert_eq!(meta.pages.len(), 11);
+            let ranges = meta.page_ranges();
+            assert_eq!(ranges[0].start, meta.offset);
+            assert_eq!(ranges[10].end, meta.offset + meta.total_len());
+            let result =
+                read_simple_parallel(&reader, field.clone(), meta, ReadOptions::default()).unwrap();
+            assert_eq!(result.as_ref(), array.as_ref());
+        }
+    }
+}
+
+#[cfg(feature = "encryption")]
+#[test]
+fn test_page_encryption() {
+    use strawboat::encryption::{EncryptionKey, KeyProvider};
+    use strawboat::read::RangePageReader;
+
+    const KEY: EncryptionKey = [7; 32];
+    struct Keys(Vec<usize>);
+    impl KeyProvider for Keys {
+        fn column_key(&self, column: usize) -> arrow::error::Result<Option<EncryptionKey>> {
+            Ok(self.0.contains(&column).then_some(KEY))
+        }
+    }
+
+    let size = WRITE_PAGE * 3 + 7;
+    let strings = Utf8Array::<i32>::from_slice(["apple", "banana", "cherry", "durian"]);
+    let keys = UInt32Array::from_vec((0..size).map(|i| (i % 4) as u32).collect());
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+        DictionaryArray::try_from_keys(keys, strings.boxed())
+            .unwrap()
+            .boxed(),
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+    let write = |keys: Option<Keys>, zstd_dictionary_size: Option<usize>| {
+        let mut builder = WriteOptions::builder().max_page_size(WRITE_PAGE);
+        if let Some(keys) = keys {
+            builder = builder.key_provider(keys);
+        }
+        let options = WriteOptions {
+            page_checksum: true,
+            zstd_dictionary_size,
+            ..builder.build()
+        };
+        let mut bytes = Vec::new();
+        let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk.clone()], options).unwrap();
+        (bytes, metas)
+    };
+
+    // the files without encrypted columns are unchanged
+    assert_eq!(write(Some(Keys(vec![])), None), write(None, None));
+
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let (bytes, metas) = write(Some(Keys(vec![1, 2])), zstd_dictionary_size);
+        assert_eq!(read_meta(&mut std::io::Cursor::new(&bytes)).unwrap(), metas);
+        assert!(metas[0].pages.iter().all(|page| !page.encrypted));
+        assert!(metas[1].pages.iter().all(|page| page.encrypted));
+        let dictionary = metas[2].dictionary.as_ref().unwrap();
+        assert!(dictionary.pages.iter().all(|page| page.encrypted));
+
+        let read = |column: usize, key: Option<EncryptionKey>| {
+            let meta = &metas[column];
+            let mut reader = std::io::Cursor::new(bytes.as_slice());
+            reader.consume(meta.offset as usize);
+            let reader = NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_zstd_dictionary(meta.zstd_dictionary.clone())
+                .with_checksum_verification(true)
+                .with_decryption_key(column, key)
+                .with_read_options(file_read_options(&bytes));
+            column_iter_to_arrays(vec![reader], vec![], schema.fields[column].clone(), false)
+                .unwrap()
+                .collect::<arrow::error::Result<Vec<_>>>()
+        };
+        let arrays = read(0, None).unwrap();
+        let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+        let array = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(array.as_ref(), chunk.arrays()[0].as_ref());
+        let arrays = read(1, Some(KEY)).unwrap();
+        let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+        let array = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(array.as_ref(), chunk.arrays()[1].as_ref());
+        // the plaintext column needs no key, and a reader with a key rejects it
+        let err = read(0, Some(KEY)).unwrap_err();
+        assert!(err.to_string().contains("is not encrypted"), "{err}");
+        assert!(read(1, None).is_err());
+        assert!(read(1, Some([8; 32])).is_err());
+
+        // the pages are bound to their column and their index in it
+        let reader = std::io::Cursor::new(&bytes[metas[1].offset as usize..]);
+        let err = NativeReader::new(reader, metas[1].pages.clone(), vec![])
+            .with_zstd_dictionary(metas[1].zstd_dictionary.clone())
+            .with_decryption_key(0, Some(KEY))
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to decrypt"), "{err}");
+        let meta = metas[1].slice(1, metas[1].pages.len());
+        let page = |index: usize| {
+            let reader = std::io::Cursor::new(&bytes[meta.offset as usize..]);
+            NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_zstd_dictionary(meta.zstd_dictionary.clone())
+                .with_decryption_key(1, Some(KEY))
+                .with_first_page_index(index)
+                .next()
+                .unwrap()
+        };
+        assert!(page(1).is_ok());
+        assert!(page(0).is_err());
+        let err = merge_files(vec![std::io::Cursor::new(&bytes)], vec![]).unwrap_err();
+        assert!(err.to_string().contains("encrypted column"), "{err}");
+
+        // the values page of a dictionary column follows its key pages
+        let reader = std::io::Cursor::new(&bytes[dictionary.offset as usize..]);
+        let values_page = NativeReader::new(reader, dictionary.pages.clone(), vec![])
+            .with_decryption_key(2, Some(KEY))
+            .with_first_page_index(metas[2].pages.len())
+            .next()
+            .unwrap();
+        assert!(values_page.is_ok());
+
+        let reader = RangePageReader::new(std::io::Cursor::new(bytes.as_slice()), &metas[1])
+            .with_decryption_key(1, Some(KEY));
+        let arrays = column_iter_to_arrays(vec![reader], vec![], schema.fields[1].clone(), false)
+            .unwrap()
+            .collect::<arrow::error::Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(arrays.iter().map(|array| array.len()).sum::<usize>(), size);
+
+        // the pages are not read from their stored bytes
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(metas[1].offset as usize);
+        assert!(read_column_pages(&mut reader, &metas[1]).is_err());
+        assert!(batch_read_array(
+            vec![reader],
+            vec![],
+            schema.fields[1].clone(),
+            false,
+            vec![metas[1].pages.clone()]
+        )
+        .is_err());
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(metas[2].offset as usize);
+        assert!(read_dictionary_column(&mut reader, schema.fields[2].clone(), &metas[2]).is_err());
+    }
+}
+
+#[test]
+fn test_read_row_range() {
+    let size = 5500;
+    let page_size = 1000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_random_string(size, 0.1, size)) as _,
+    ]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            schema.clone(),
+            WriteOptions {
+                max_page_size: Some(page_size),
+                zstd_dictionary_size,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
+
+        let (skip_pages, meta, row_offset) = metas[0].pages_for_row_range(900, 3100);
+        assert_eq!((skip_pages, meta.pages.len(), row_offset), (0, 4, 900));
+        let (skip_pages, meta, row_offset) = metas[0].pages_for_row_range(1500, 1600);
+        assert_eq!((skip_pages, meta.pages.len(), row_offset), (1, 1, 500));
+        assert_eq!(meta, metas[0].slice(1, 2));
+        let (skip_pages, meta, _) = metas[0].pages_for_row_range(2000, 3000);
+        assert_eq!((skip_pages, meta.pages.len()), (2, 1));
+        let (skip_pages, meta, _) = metas[0].pages_for_row_range(6000, 7000);
+        assert_eq!((skip_pages, meta.pages.len()), (6, 0));
+
+        for (start, end) in [
+            (0, 10),
+            (1500, 1600),
+            (900, 3100),
+            (999, 1001),
+            (2000, 3000),
+            (5000, 9999),
+            (2000, 2000),
+            (2500, 2000),
+            (6000, 7000),
+        ] {
+            for (column, meta) in metas.iter().enumerate() {
+                let mut reader = std::io::Cursor::new(bytes.clone());
+                reader.consume(meta.offset as usize);
+                let field = schema.fields[column].clone();
+                let array = read_row_range(&mut reader, field, meta, start, end).unwrap();
+
+                let start = (start as usize).min(size);
+                let end = (end as usize).clamp(start, size);
+                let expected = chunk.arrays()[column].sliced(start, end - start);
+                assert_eq!(array, expected, "{start}..{end}");
+            }
+        }
+    }
+}
+
+#[test]
+fn test_write_multiple_chunks() {
+    let new_chunk = |size: usize| -> Chunk<Box<dyn Array>> {
+        Chunk::new(vec![
+            Box::new(create_random_index(size, 0.1, size)) as _,
+            Box::new(create_random_string(size, 0.1, 100)) as _,
+            Box::new(create_struct(size, 0.2, size)) as _,
+            Box::new(create_list(size, 0.2)) as _,
+        ])
+    };
+    let chunks = [
+        new_chunk(1000),
+        new_chunk(WRITE_PAGE * 2 + 10),
+        new_chunk(0),
+    ];
+    let fields: Vec<Field> = chunks[0]
+        .iter()
+        .enumerate()
+        .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), true))
+        .collect();
+    let num_fields = fields.len();
+    let schema = Schema::from(fields);
+
+    let options = WriteOptions {
+        max_page_size: Some(WRITE_PAGE),
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(&mut bytes, schema.clone(), &chunks, options.clone()).unwrap();
+    let leaves = to_parquet_schema(&schema).unwrap().columns().len();
+    assert_eq!(metas.len(), leaves);
+
+    let projection: Vec<usize> = (0..num_fields).collect();
+    let mut reader = std::io::Cursor::new(bytes.clone());
+    let columns = read_meta_projected(&mut reader, &projection).unwrap();
+    for column in columns {
+        let pages = column.metas.iter().map(|meta| meta.pages.clone()).collect();
+        let readers = column
+            .metas
+            .iter()
+            .map(|meta| {
+                let mut reader = std::io::Cursor::new(bytes.clone());
+                reader.consume(meta.offset as usize);
+                reader
+            })
+            .collect();
+        let is_nested = !is_primitive(column.field.data_type());
+        let array =
+            batch_read_array(readers, column.leaves, column.field, is_nested, pages).unwrap();
+
+        let arrays: Vec<&dyn Array> = chunks
+            .iter()
+            .map(|chunk| chunk.arrays()[column.index].as_ref())
+            .collect();
+        let expected = compute::concatenate::concatenate(&arrays).unwrap();
+        assert_eq!(array, expected);
     }
 
-    let chunk = Chunk::new(vec![Box::new(UInt32Array::from_vec(values)) as _]);
-    test_write_read(chunk);
+    // a chunk is encoded as it is written, a row group holds one
+    let mut writer = NativeWriter::new(Vec::new(), schema.clone(), options);
+    writer.start().unwrap();
+    writer.write(&chunks[0]).unwrap();
+    assert_eq!(writer.metas.len(), leaves);
+    let size = writer.total_size();
+    assert!(size > 8);
+    assert!(writer.write(&chunks[1]).is_err());
+    assert_eq!(writer.metas.len(), leaves);
+    assert_eq!(writer.total_size(), size);
+    writer.finish_row_group().unwrap();
+    writer.write(&chunks[1]).unwrap();
+    assert_eq!(writer.metas.len(), 2 * leaves);
+    assert_eq!(writer.encoding_reports().len(), 2 * leaves);
+
+    let mut writer = NativeWriter::new(Vec::new(), schema, WriteOptions::default());
+    writer.start().unwrap();
+    assert!(writer.write(&Chunk::new(vec![])).is_err());
 }
 
 #[test]
-fn test_bitpacking() {
-    let size = WRITE_PAGE * 5;
-    let chunk = Chunk::new(vec![
-        Box::new(create_random_index(size, 0.1, 8)) as _,
-        Box::new(create_random_index(size, 0.5, 8)) as _,
+fn test_mmap_reader() {
+    let size = 1000;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(UInt8Array::from_vec((0..size).map(|i| i as u8).collect())) as _,
+        Box::new(create_random_index(size, 0.1, size)) as _,
+        Box::new(create_random_double(size, 0.1, size)) as _,
+        Box::new(create_random_string(size, 0.1, 100)) as _,
+        Box::new(create_list(size, 0.2)) as _,
     ]);
-    test_write_read(chunk);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .enumerate()
+            .map(|(i, array)| Field::new(format!("c{i}"), array.data_type().clone(), i > 0))
+            .collect::<Vec<_>>(),
+    );
+
+    for compression in [CommonCompression::None, CommonCompression::Lz4] {
+        let mut file = tempfile::tempfile().unwrap();
+        let mut writer = NativeWriter::new(
+            &mut file,
+            schema.clone(),
+            WriteOptions {
+                default_compression: compression,
+                max_page_size: Some(size),
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+
+        let mut reader = unsafe { MmapReader::try_new(&file) }.unwrap();
+        let projection: Vec<usize> = (0..schema.fields.len()).collect();
+        let columns = read_meta_projected(&mut reader, &projection).unwrap();
+        for column in columns {
+            let pages = column.metas.iter().map(|meta| meta.pages.clone()).collect();
+            let readers = column
+                .metas
+                .iter()
+                .map(|meta| {
+                    let mut reader = reader.clone();
+                    reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+                    reader
+                })
+                .collect();
+            let is_nested = !is_primitive(column.field.data_type());
+            let array =
+                batch_read_array(readers, column.leaves, column.field, is_nested, pages).unwrap();
+            assert_eq!(array.as_ref(), chunk.arrays()[column.index].as_ref());
+
+            if column.index == 0 {
+                // the values of an uncompressed page are not copied
+                let values = array
+                    .as_any()
+                    .downcast_ref::<UInt8Array>()
+                    .unwrap()
+                    .values();
+                let (mmap, _) = reader.mmap().unwrap();
+                let mapped = mmap.as_ptr_range();
+                assert_eq!(
+                    compression == CommonCompression::None,
+                    mapped.contains(&values.as_ptr())
+                );
+            }
+        }
+    }
 }
 
 #[test]
-fn test_deleta_bitpacking() {
-    let size = WRITE_PAGE * 5;
-    let chunk = Chunk::new(vec![
-        Box::new(UInt32Array::from_vec((0..size as u32).collect())) as _,
-        Box::new(Int32Array::from_vec((0..size as i32).collect())) as _,
-    ]);
-    test_write_read(chunk);
+fn test_wide_chunk_shared_readers() {
+    let num_columns = 1000;
+    let arrays: Vec<Box<dyn Array>> = (0..num_columns)
+        .map(|i| Box::new(Int32Array::from_vec(vec![i; 100])) as _)
+        .collect();
+    let chunk = Chunk::new(arrays);
+    let fields: Vec<Field> = (0..num_columns)
+        .map(|i| Field::new(format!("c{i}"), DataType::Int32, false))
+        .collect();
+    let schema = Schema::from(fields);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema.clone(),
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer.write(&chunk).unwrap();
+    writer.finish().unwrap();
+    let metas = writer.metas.clone();
+
+    let bytes: Arc<[u8]> = bytes.into();
+    let readers = shared_native_readers(bytes.clone(), &metas);
+    // every reader shares the same allocation instead of owning a copy of the file
+    assert_eq!(Arc::strong_count(&bytes), num_columns as usize + 1);
+
+    let schema_descriptor = to_parquet_schema(&schema).unwrap();
+    let leaves = schema_descriptor.columns().to_vec();
+    for (((reader, leaf), field), expected) in readers
+        .into_iter()
+        .zip(leaves)
+        .zip(schema.fields.iter())
+        .zip(chunk.arrays())
+    {
+        let mut iter =
+            column_iter_to_arrays(vec![reader], vec![leaf], field.clone(), false).unwrap();
+        let array = iter.next().unwrap().unwrap();
+        assert_eq!(array.as_ref(), expected.as_ref());
+    }
 }
 
 #[test]
-fn test_onevalue() {
-    let size = 10000;
-    let chunk = Chunk::new(vec![
-        Box::new(BooleanArray::from_iter((0..size).map(|_| Some(true)))) as _,
-        Box::new(BooleanArray::from_iter((0..size).map(|_| Some(false)))) as _,
-        Box::new(UInt32Array::from_vec(vec![3; size])) as _,
-        Box::new(create_random_index(size, 0.3, 1)) as _,
-        Box::new(create_random_string(size, 0.4, 1)) as _,
+fn test_borrowed_pages() {
+    let size = WRITE_PAGE * 4;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(create_random_index(size, 0.1, 8)) as _,
+        Box::new(create_random_string(size, 0.2, 8)) as _,
     ]);
-    test_write_read(chunk);
-}
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int32, true),
+        Field::new("s", DataType::Utf8, true),
+    ]);
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            schema.clone(),
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                zstd_dictionary_size,
+                page_checksum: true,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
 
-#[test]
-fn test_struct() {
-    let struct_array = create_struct(1000, 0.2, 1000);
-    let chunk = Chunk::new(vec![Box::new(struct_array) as _]);
-    test_write_read(chunk);
-}
+        let bytes: Arc<[u8]> = bytes.into();
+        let file_range = bytes.as_ptr_range();
+        for meta in metas.iter() {
+            let expected: Vec<(u64, Vec<u8>)> = NativeReader::from_shared(bytes.clone(), meta)
+                .collect::<arrow::error::Result<_>>()
+                .unwrap();
+            let mut reader =
+                NativeReader::from_shared(bytes.clone(), meta).with_checksum_verification(true);
+            let mut pages = vec![];
+            while let Some(page) = reader.next_page() {
+                let (num_values, page) = page.unwrap();
+                // the pages are views of the file unless decompressed
+                let borrowed = matches!(page, Cow::Borrowed(_));
+                assert_eq!(borrowed, meta.zstd_dictionary.is_none());
+                if borrowed {
+                    assert!(file_range.contains(&page.as_ptr()));
+                }
+                pages.push((num_values, page.into_owned()));
+            }
+            assert_eq!(pages, expected);
+        }
 
-#[test]
-fn test_float() {
-    let size = 1000;
-    let chunk = Chunk::new(vec![Box::new(create_random_double(size, 0.5, size)) as _]);
-    test_write_read(chunk);
+        // a reader that doesn't hold the file in memory copies the pages
+        let meta = &metas[0];
+        let mut file = BufReader::new(std::io::Cursor::new(bytes.to_vec()));
+        file.seek(SeekFrom::Start(meta.offset)).unwrap();
+        let mut reader = NativeReader::new(file, meta.pages.clone(), vec![])
+            .with_zstd_dictionary(meta.zstd_dictionary.clone());
+        let page = reader.next_page().unwrap().unwrap();
+        assert!(matches!(page.1, Cow::Owned(_)));
+    }
 }
 
 #[test]
-fn test_list() {
-    let list_array = create_list(1000, 0.2);
-    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
-    test_write_read(chunk);
+fn test_read_page() {
+    let size = WRITE_PAGE * 4;
+    let chunk: Chunk<Box<dyn Array>> =
+        Chunk::new(vec![Box::new(create_random_string(size, 0.2, 8)) as _]);
+    let schema = Schema::from(vec![Field::new("s", DataType::Utf8, true)]);
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            schema.clone(),
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                zstd_dictionary_size,
+                page_checksum: true,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let meta = writer.metas[0].clone();
+
+        let new_reader = || {
+            let mut reader = BufReader::new(std::io::Cursor::new(bytes.clone()));
+            reader.seek(SeekFrom::Start(meta.offset)).unwrap();
+            NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_zstd_dictionary(meta.zstd_dictionary.clone())
+                .with_checksum_verification(true)
+        };
+        let pages: Vec<(u64, Vec<u8>)> = new_reader().collect::<arrow::error::Result<_>>().unwrap();
+        let mut page_start = meta.offset as usize;
+        let raw_pages: Vec<&[u8]> = meta
+            .pages
+            .iter()
+            .map(|page| {
+                page_start += page.length as usize;
+                &bytes[page_start - page.length as usize..page_start]
+            })
+            .collect();
+
+        let mut reader = new_reader();
+        reader.next().unwrap().unwrap();
+        for index in [3, 0, 1, 2, 1] {
+            assert_eq!(reader.read_page(index).unwrap(), pages[index]);
+            assert_eq!(reader.read_raw_page(index).unwrap(), raw_pages[index]);
+        }
+        assert!(reader.read_page(pages.len()).is_err());
+        // the reader goes on from where it was
+        assert_eq!(reader.current_page(), 1);
+        assert_eq!(reader.next().unwrap().unwrap(), pages[1]);
+    }
 }
 
 #[test]
-fn test_map() {
-    let map_array = create_map(1000, 0.2);
-    let chunk = Chunk::new(vec![Box::new(map_array) as _]);
-    test_write_read(chunk);
+fn test_read_column_ffi() {
+    let array = create_random_index(4096, 0.1, 100);
+    let field = Field::new("c", DataType::Int32, true);
+    let schema = Schema::from(vec![field.clone()]);
+
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        schema,
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer
+        .write(&Chunk::new(vec![Box::new(array.clone()) as Box<dyn Array>]))
+        .unwrap();
+    writer.finish().unwrap();
+    let meta = writer.metas[0].clone();
+
+    let mut reader = std::io::Cursor::new(bytes);
+    reader.consume(meta.offset as usize);
+    let (c_array, c_schema) = read_column_ffi(&mut reader, field, meta.pages).unwrap();
+
+    let field = unsafe { ffi::import_field_from_c(&c_schema) }.unwrap();
+    let result = unsafe { ffi::import_array_from_c(c_array, field.data_type) }.unwrap();
+    assert_eq!(result.as_ref(), &array as &dyn Array);
 }
 
 #[test]
-fn test_list_list() {
-    let l1 = create_list(2000, 0.2);
+fn test_read_value_at() {
+    let array = Utf8Array::<i32>::from_iter_values((0..1000).map(|i| format!("value-{i}")));
+    let field = Field::new("c", DataType::Utf8, false);
 
-    let mut offsets = vec![];
-    for i in (0..=1000).step_by(2) {
-        offsets.push(i);
-    }
-    let list_array = ListArray::try_new(
-        DataType::List(Box::new(Field::new("item", l1.data_type().clone(), true))),
-        OffsetsBuffer::try_from(offsets).unwrap(),
-        l1.boxed(),
-        None,
-    )
-    .unwrap();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(vec![field]),
+        WriteOptions {
+            binary_restart_points: true,
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer
+        .write(&Chunk::new(vec![Box::new(array.clone()) as Box<dyn Array>]))
+        .unwrap();
+    writer.finish().unwrap();
+    let meta = writer.metas[0].clone();
+    assert_eq!(meta.pages.len(), 1);
+    let start = meta.offset as usize;
+    let mut page = bytes[start..start + meta.pages[0].length as usize].to_vec();
 
-    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
-    test_write_read(chunk);
+    // page header(17 bytes) + block count(4 bytes), then a restart point
+    // (value offset, lengths position) for each of the 8 blocks and the lengths size
+    let lengths_start = 21 + 8 * 8 + 4;
+    let pos = 21 + 7 * 8 + 4;
+    let block_start = u32::from_le_bytes(page[pos..pos + 4].try_into().unwrap()) as usize;
+    // value 900 is in the last block, clobber the restart points and lengths of the others
+    page[21..21 + 7 * 8].fill(0xff);
+    page[lengths_start..lengths_start + block_start].fill(0xff);
+
+    let value = read_value_at(&page, false, 1000, 900, 1).unwrap();
+    assert_eq!(value, Some(array.value(900).as_bytes()));
+    assert!(read_value_at(&page, false, 1000, 100, 1).is_err());
+
+    test_write_read_with_options(
+        new_test_chunk(),
+        WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            binary_restart_points: true,
+            ..Default::default()
+        },
+    );
 }
 
 #[test]
-fn test_list_struct() {
-    let s1 = create_struct(2000, 0.2, 2000);
+fn test_read_dict_column_with_external_dict() {
+    let dimension = Utf8Array::<i32>::from_slice(["apple", "banana", "cherry", "durian"]);
+    // every page holds all the values in the order of the dimension,
+    // so the page dictionaries are laid out like the dimension
+    let facts = Utf8Array::<i32>::from_iter_values(
+        (0..WRITE_PAGE * 4).map(|i| dimension.value(i % 2 + (i / 3) % 2 * 2)),
+    );
+    let field = Field::new("c", DataType::Utf8, false);
 
-    let mut offsets = vec![];
-    for i in (0..=1000).step_by(2) {
-        offsets.push(i);
-    }
-    let list_array = ListArray::try_new(
-        DataType::List(Box::new(Field::new("item", s1.data_type().clone(), true))),
-        OffsetsBuffer::try_from(offsets).unwrap(),
-        s1.boxed(),
-        None,
-    )
-    .unwrap();
+    let mut bytes = Vec::new();
+    let mut writer = NativeWriter::new(
+        &mut bytes,
+        Schema::from(vec![field.clone()]),
+        WriteOptions {
+            default_compression: CommonCompression::Lz4,
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(2.0f64),
+            forbidden_compressions: vec![
+                Compression::OneValue,
+                Compression::Freq,
+                Compression::Fsst,
+            ],
+            sorted_dictionary: true,
+            ..Default::default()
+        },
+    );
+    writer.start().unwrap();
+    writer
+        .write(&Chunk::new(vec![Box::new(facts.clone()) as Box<dyn Array>]))
+        .unwrap();
+    writer.finish().unwrap();
+    let meta = writer.metas[0].clone();
 
-    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
-    test_write_read(chunk);
+    let mut reader = std::io::Cursor::new(bytes);
+    reader.consume(meta.offset as usize);
+    let result =
+        read_dict_column_with_external_dict(&mut reader, field, meta.pages, &dimension).unwrap();
+    assert_eq!(result.as_ref(), &facts as &dyn Array);
 }
 
 #[test]
-fn test_list_map() {
-    let m1 = create_map(2000, 0.2);
+fn test_read_as_dictionary() {
+    let size = WRITE_PAGE * 4;
+    let mut rng = StdRng::seed_from_u64(42);
+    // the first page has unique values and is not `Dict` encoded
+    let ints = Int64Array::from_iter((0..size).map(|i| match i < WRITE_PAGE {
+        true => Some(i as i64),
+        false => (rng.gen::<f32>() > 0.1).then(|| (1 << 40) + rng.gen_range(0..50i64) * (1 << 33)),
+    }));
+    let strings = Utf8Array::<i32>::from_iter((0..size).map(|i| match i < WRITE_PAGE {
+        true => Some(format!("unique-{i}")),
+        false => (i % 17 != 0).then(|| format!("value-{}", i % 30)),
+    }));
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        ints.boxed(),
+        strings.boxed(),
+        Float64Array::from_vec(vec![1.0; size]).boxed(),
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int64, true),
+        Field::new("s", DataType::Utf8, true),
+        Field::new("f", DataType::Float64, false),
+    ]);
 
-    let mut offsets = vec![];
-    for i in (0..=1000).step_by(2) {
-        offsets.push(i);
-    }
-    let list_array = ListArray::try_new(
-        DataType::List(Box::new(Field::new("item", m1.data_type().clone(), true))),
-        OffsetsBuffer::try_from(offsets).unwrap(),
-        m1.boxed(),
-        None,
-    )
-    .unwrap();
+    for (sorted_dictionary, compress_encodings) in [(false, false), (true, false), (false, true)] {
+        let options = WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            default_compress_ratio: Some(2.0),
+            forbidden_compressions: vec![
+                Compression::OneValue,
+                Compression::Freq,
+                Compression::Fsst,
+                Compression::Rle,
+            ],
+            sorted_dictionary,
+            compress_encodings,
+            ..Default::default()
+        };
+        let mut bytes = Vec::new();
+        let (metas, _) = write_file(&mut bytes, schema.clone(), &[chunk.clone()], options).unwrap();
 
-    let chunk = Chunk::new(vec![Box::new(list_array) as _]);
-    test_write_read(chunk);
+        for (column, meta) in metas.iter().enumerate().take(2) {
+            assert_eq!(meta.dominant_codec, Compression::Dict);
+            let mut reader = std::io::Cursor::new(bytes.as_slice());
+            reader.consume(meta.offset as usize);
+            let field = schema.fields[column].clone();
+            let array = read_as_dictionary(&mut reader, field, meta.pages.clone()).unwrap();
+            let array = array
+                .as_any()
+                .downcast_ref::<DictionaryArray<u32>>()
+                .unwrap();
+            assert_eq!(array.len(), size);
+            // the values of the `Dict` pages are their dictionaries, with a null value
+            assert!(array.values().len() <= WRITE_PAGE + 3 * 51);
+
+            let dense = compute::take::take(array.values().as_ref(), array.keys()).unwrap();
+            assert_eq!(dense.as_ref(), chunk.arrays()[column].as_ref());
+        }
+
+        let meta = &metas[2];
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let field = schema.fields[2].clone();
+        assert!(read_as_dictionary(&mut reader, field, meta.pages.clone()).is_err());
+    }
 }
 
 #[test]
-fn test_struct_list() {
-    let size = 10000;
-    let null_density = 0.2;
-    let dt = DataType::Struct(vec![
-        Field::new("name", DataType::LargeBinary, true),
-        Field::new(
-            "age",
-            DataType::List(Box::new(Field::new("item", DataType::Int32, true))),
-            true,
-        ),
-    ]);
-    let struct_array = StructArray::try_new(
-        dt,
-        vec![
-            Box::new(create_random_string(size, null_density, size)) as _,
-            Box::new(create_list(size, null_density)) as _,
+fn test_max_decode_bytes() {
+    // a few large values repeated by the indices of a `Dict` page
+    let values: Vec<String> = (0..4).map(|i| format!("{i}").repeat(1000)).collect();
+    let array = Utf8Array::<i32>::from_iter_values((0..WRITE_PAGE).map(|i| &values[i % 4]));
+    let field = Field::new("s", DataType::Utf8, false);
+    let options = WriteOptions {
+        max_page_size: Some(WRITE_PAGE),
+        default_compress_ratio: Some(2.0),
+        forbidden_compressions: vec![
+            Compression::OneValue,
+            Compression::Freq,
+            Compression::Fsst,
+            Compression::Rle,
         ],
-        None,
+        ..Default::default()
+    };
+    let mut bytes = Vec::new();
+    let (metas, _) = write_file(
+        &mut bytes,
+        Schema::from(vec![field.clone()]),
+        &[Chunk::new(vec![array.clone().boxed()])],
+        options,
     )
     .unwrap();
-    let chunk = Chunk::new(vec![Box::new(struct_array) as _]);
-    test_write_read(chunk);
+    let meta = &metas[0];
+    assert_eq!(meta.dominant_codec, Compression::Dict);
+    assert!(meta.pages[0].length < 10_000);
+
+    let read = |max_decode_bytes: Option<usize>| {
+        let read_options = ReadOptions {
+            max_decode_bytes,
+            ..file_read_options(&bytes)
+        };
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let iter_result = column_iter_to_arrays(
+            vec![NativeReader::new(reader, meta.pages.clone(), vec![])
+                .with_read_options(read_options)],
+            vec![],
+            field.clone(),
+            false,
+        )
+        .unwrap()
+        .next()
+        .unwrap();
+        let mut reader = std::io::Cursor::new(bytes.as_slice());
+        reader.consume(meta.offset as usize);
+        let batch_result = batch_read_array_with_options(
+            vec![reader],
+            vec![],
+            field.clone(),
+            false,
+            vec![meta.pages.clone()],
+            read_options,
+        );
+        (iter_result, batch_result)
+    };
+
+    for max_decode_bytes in [None, Some(1000 * WRITE_PAGE)] {
+        let (iter_result, batch_result) = read(max_decode_bytes);
+        assert_eq!(iter_result.unwrap().as_ref(), &array as &dyn Array);
+        assert_eq!(batch_result.unwrap().as_ref(), &array as &dyn Array);
+    }
+    let (iter_result, batch_result) = read(Some(100_000));
+    assert!(iter_result
+        .unwrap_err()
+        .to_string()
+        .contains("over the limit of 100000 bytes"));
+    assert!(batch_result.is_err());
+}
+
+#[test]
+fn test_dictionary_array() {
+    let size = WRITE_PAGE * 3 + 7;
+    let mut rng = StdRng::seed_from_u64(42);
+    let strings = Utf8Array::<i32>::from_slice(["apple", "banana", "cherry", "durian"]);
+    let keys: PrimitiveArray<u32> = (0..size)
+        .map(|_| (rng.gen::<f32>() > 0.2).then(|| rng.gen_range(0..4u32)))
+        .collect();
+    let strings = DictionaryArray::try_from_keys(keys, strings.boxed()).unwrap();
+    let numbers = Int64Array::from_vec((0..100).map(|v| v * 1000).collect());
+    let keys = Int8Array::from_vec((0..size).map(|i| (i % 100) as i8).collect());
+    let numbers = DictionaryArray::try_from_keys(keys, numbers.boxed()).unwrap();
+    let arrays: Vec<Box<dyn Array>> = vec![strings.boxed(), numbers.boxed()];
+
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let fields: Vec<Field> = arrays
+            .iter()
+            .map(|array| {
+                Field::new(
+                    "name",
+                    array.data_type().clone(),
+                    array.validity().is_some(),
+                )
+            })
+            .collect();
+        let mut bytes = Vec::new();
+        let mut writer = NativeWriter::new(
+            &mut bytes,
+            Schema::from(fields.clone()),
+            WriteOptions {
+                default_compression: CommonCompression::Zstd,
+                max_page_size: Some(WRITE_PAGE),
+                zstd_dictionary_size,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&Chunk::new(arrays.clone())).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas.clone();
+
+        let mut reader = std::io::Cursor::new(bytes.clone());
+        assert_eq!(read_meta(&mut reader).unwrap(), metas);
+        for ((field, meta), array) in fields.into_iter().zip(metas.iter()).zip(arrays.iter()) {
+            assert_eq!(meta.pages.len(), 4);
+            assert!(meta.dictionary.is_some());
+
+            let mut reader = std::io::Cursor::new(bytes.clone());
+            reader.consume(meta.offset as usize);
+            let result = read_dictionary_column(&mut reader, field, meta).unwrap();
+            assert_eq!(&result, array);
+        }
+    }
 }
 
 fn create_list(size: usize, null_density: f32) -> ListArray<i32> {
@@ -368,6 +4381,50 @@ fn create_random_index(size: usize, null_density: f32, uniq: usize) -> Primitive
         .collect::<PrimitiveArray<i32>>()
 }
 
+fn create_random_fixed_binary(
+    size: usize,
+    null_density: f32,
+    width: usize,
+) -> FixedSizeBinaryArray {
+    let mut rng = StdRng::seed_from_u64(42);
+    let values: Vec<u8> = (0..size * width).map(|_| rng.gen()).collect();
+    let validity = if null_density > 0.0 {
+        let validity: MutableBitmap = (0..size).map(|_| rng.gen::<f32>() > null_density).collect();
+        Some(validity.into())
+    } else {
+        None
+    };
+    FixedSizeBinaryArray::new(DataType::FixedSizeBinary(width), values.into(), validity)
+}
+
+fn create_random_half(size: usize, null_density: f32, uniq: usize) -> PrimitiveArray<f16> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..size)
+        .map(|_| {
+            if rng.gen::<f32>() > null_density {
+                let value = rng.gen_range::<i32, _>(0i32..uniq as i32);
+                Some(f16::from_f32(value as f32 / 8.0))
+            } else {
+                None
+            }
+        })
+        .collect::<PrimitiveArray<f16>>()
+}
+
+fn create_random_decimal(size: usize, null_density: f32, uniq: usize) -> PrimitiveArray<i128> {
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..size)
+        .map(|_| {
+            if rng.gen::<f32>() > null_density {
+                let value = rng.gen_range::<i32, _>(0i32..uniq as i32);
+                Some((value as i128) << 80 | value as i128)
+            } else {
+                None
+            }
+        })
+        .collect::<PrimitiveArray<i128>>()
+}
+
 fn create_random_double(size: usize, null_density: f32, uniq: usize) -> PrimitiveArray<f64> {
     let mut rng = StdRng::seed_from_u64(42);
     (0..size)
@@ -421,6 +4478,9 @@ fn test_write_read(chunk: Chunk<Box<dyn Array>>) {
         CommonCompression::Lz4,
         CommonCompression::Zstd,
         CommonCompression::Snappy,
+        CommonCompression::SnappyFramed,
+        #[cfg(feature = "brotli")]
+        CommonCompression::Brotli,
         CommonCompression::None,
     ];
 
@@ -431,7 +4491,7 @@ fn test_write_read(chunk: Chunk<Box<dyn Array>>) {
                 default_compression: compression,
                 max_page_size: Some(WRITE_PAGE),
                 default_compress_ratio: Some(2.0f64),
-                forbidden_compressions: vec![],
+                ..Default::default()
             },
         );
     }
@@ -475,7 +4535,10 @@ fn test_write_read_with_options(chunk: Chunk<Box<dyn Array>>, options: WriteOpti
             let mut range_bytes = std::io::Cursor::new(bytes.clone());
             range_bytes.consume(curr_meta.offset as usize);
 
-            let native_reader = NativeReader::new(range_bytes, curr_meta.pages.clone(), vec![]);
+            let native_reader = NativeReader::new(range_bytes, curr_meta.pages.clone(), vec![])
+                .with_zstd_dictionary(curr_meta.zstd_dictionary.clone())
+                .with_checksum_verification(true)
+                .with_read_options(file_read_options(&bytes));
             native_readers.push(native_reader);
         }
         let is_nested = !is_primitive(field.data_type());
@@ -508,9 +4571,15 @@ fn test_write_read_with_options(chunk: Chunk<Box<dyn Array>>, options: WriteOpti
         let mut pages: Vec<Vec<PageMeta>> = Vec::with_capacity(n);
         let mut readers = Vec::with_capacity(n);
         for curr_meta in curr_metas.iter() {
-            pages.push(curr_meta.pages.clone());
             let mut reader = std::io::Cursor::new(bytes.clone());
             reader.consume(curr_meta.offset as usize);
+            if curr_meta.zstd_dictionary.is_some() {
+                let (column_bytes, page_metas) = read_column_pages(&mut reader, curr_meta).unwrap();
+                reader = std::io::Cursor::new(column_bytes);
+                pages.push(page_metas);
+            } else {
+                pages.push(curr_meta.pages.clone());
+            }
 
             let buffer_size = curr_meta.total_len().min(8192) as usize;
             let reader = BufReader::with_capacity(buffer_size, reader);
@@ -518,11 +4587,154 @@ fn test_write_read_with_options(chunk: Chunk<Box<dyn Array>>, options: WriteOpti
             readers.push(reader);
         }
         let is_nested = !is_primitive(field.data_type());
-        let batch_result =
-            batch_read_array(readers, curr_leaves, field.clone(), is_nested, pages).unwrap();
+        // the readers of the pages of a zstd dictionary are not of the whole file
+        let batch_result = batch_read_array_with_options(
+            readers,
+            curr_leaves,
+            field.clone(),
+            is_nested,
+            pages,
+            file_read_options(&bytes),
+        )
+        .unwrap();
         batch_results.push(batch_result);
     }
     let batch_result_chunk = Chunk::new(batch_results);
 
     assert_eq!(chunk, batch_result_chunk);
 }
+
+/// The chunk of the files of `read_meta::BASELINE_FILES`.
+fn baseline_chunk() -> Chunk<Box<dyn Array>> {
+    let size = 3000;
+    let list_values = Int32Array::from_iter((0..size * 2).map(|i| (i % 9 != 0).then_some(i % 4)));
+    let offsets: Vec<i32> = (0..=size).map(|i| i * 2).collect();
+    let list = ListArray::<i32>::new(
+        DataType::List(Box::new(Field::new("item", DataType::Int32, true))),
+        offsets.try_into().unwrap(),
+        list_values.boxed(),
+        None,
+    );
+    Chunk::new(vec![
+        Int64Array::from_iter((0..size).map(|i| (i % 7 != 0).then_some(i as i64 * 3))).boxed(),
+        Int32Array::from_vec((0..size).map(|i| i / 100 % 5).collect()).boxed(),
+        Float64Array::from_iter((0..size).map(|i| (i % 11 != 0).then_some((i % 17) as f64 * 0.5)))
+            .boxed(),
+        Utf8Array::<i32>::from_iter(
+            (0..size).map(|i| (i % 13 != 0).then(|| format!("s{}", i % 13))),
+        )
+        .boxed(),
+        BooleanArray::from_iter((0..size).map(|i| Some(i % 3 == 0))).boxed(),
+        list.boxed(),
+    ])
+}
+
+/// Concatenates the arrays of each column of `chunks`.
+fn concatenate_chunks(chunks: &[Chunk<Box<dyn Array>>]) -> Vec<Box<dyn Array>> {
+    (0..chunks[0].arrays().len())
+        .map(|column| {
+            let arrays: Vec<&dyn Array> = chunks
+                .iter()
+                .map(|chunk| chunk.arrays()[column].as_ref())
+                .collect();
+            compute::concatenate::concatenate(&arrays).unwrap()
+        })
+        .collect()
+}
+
+/// The [`ReadOptions`] of the pages of the file `bytes`, with the version of its header.
+fn file_read_options(bytes: &[u8]) -> ReadOptions {
+    ReadOptions {
+        format_version: Some(read_format_version(&mut std::io::Cursor::new(bytes)).unwrap()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_read_format_version_0() {
+    let expected = baseline_chunk();
+    for path in crate::read_meta::BASELINE_FILES {
+        let bytes = std::fs::read(path).unwrap();
+        assert_eq!(
+            read_format_version(&mut std::io::Cursor::new(&bytes)).unwrap(),
+            0
+        );
+
+        let reader = FileReader::try_new(std::io::Cursor::new(&bytes), None).unwrap();
+        let chunks = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(concatenate_chunks(&chunks), expected.arrays(), "{path}");
+
+        let (_, reader) = ChunkReader::try_from_shared(bytes.clone().into(), None, 1200).unwrap();
+        let chunks = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(concatenate_chunks(&chunks), expected.arrays(), "{path}");
+
+        // the columns read with the version of the file
+        let metas = read_meta(&mut std::io::Cursor::new(&bytes)).unwrap();
+        let schema = infer_schema(&mut std::io::Cursor::new(&bytes)).unwrap();
+        let read_options = ReadOptions {
+            format_version: Some(0),
+            ..Default::default()
+        };
+        let leaves = to_parquet_schema(&schema).unwrap().columns().to_vec();
+        for (i, field) in schema.fields.iter().enumerate() {
+            let mut reader = std::io::Cursor::new(bytes.as_slice());
+            reader.consume(metas[i].offset as usize);
+            let is_nested = !is_primitive(field.data_type());
+            let array = batch_read_array_with_options(
+                vec![reader],
+                vec![leaves[i].clone()],
+                field.clone(),
+                is_nested,
+                vec![metas[i].pages.clone()],
+                read_options,
+            )
+            .unwrap();
+            assert_eq!(array.as_ref(), expected.arrays()[i].as_ref(), "{path}");
+
+            // or the version read from the header of the file
+            if !is_nested {
+                let mut reader = std::io::Cursor::new(bytes.as_slice());
+                reader.consume(metas[i].offset as usize);
+                let array =
+                    read_simple(&mut reader, field.clone(), metas[i].pages.clone()).unwrap();
+                assert_eq!(array.as_ref(), expected.arrays()[i].as_ref(), "{path}");
+            }
+        }
+
+        // the pages of a reader without the version are not read
+        let reader = NativeReader::new(
+            std::io::Cursor::new(&bytes[metas[0].offset as usize..]),
+            metas[0].pages.clone(),
+            vec![],
+        );
+        let err = column_iter_to_arrays(vec![reader], vec![], schema.fields[0].clone(), false)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert!(err.to_string().contains("format version"), "{err}");
+
+        let reader = NativeReader::new(
+            std::io::Cursor::new(&bytes[metas[0].offset as usize..]),
+            metas[0].pages.clone(),
+            vec![],
+        )
+        .with_read_options(read_options);
+        let err = stat_simple(reader, schema.fields[0].clone()).unwrap_err();
+        assert!(err.to_string().contains("version 0"), "{err}");
+
+        // their pages can't be copied to a file of the current version
+        let err = merge_files(vec![std::io::Cursor::new(&bytes)], vec![]).unwrap_err();
+        assert!(err.to_string().contains("of version 0"), "{err}");
+    }
+
+    let bytes = std::fs::read(crate::read_meta::BASELINE_FILES[0]).unwrap();
+    let schema = infer_schema(&mut std::io::Cursor::new(&bytes)).unwrap();
+    let mut bytes = vec![];
+    write_file(&mut bytes, schema, &[expected], Default::default()).unwrap();
+    assert_eq!(
+        read_format_version(&mut std::io::Cursor::new(&bytes)).unwrap(),
+        1
+    );
+}
@@ -15,14 +15,29 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::io::{Read, Seek, SeekFrom};
+use std::sync::{Arc, Mutex};
+
 use arrow::{
-    datatypes::{Field, Schema},
+    array::{Array, Int32Array, StructArray, Utf8Array},
+    chunk::Chunk,
+    datatypes::{DataType, Field, Schema},
     error::Result,
 };
+use bytes::Bytes;
 use strawboat::{
-    read::reader::{read_meta, read_meta_async},
-    write::{NativeWriter, WriteOptions},
-    ColumnMeta, CommonCompression,
+    read::{
+        batch_read::batch_read_array,
+        deserialize::column_iter_to_arrays,
+        reader::{
+            infer_row_count, infer_schema, read_bloom_filters, read_catalog_entry, read_meta,
+            read_meta_async, read_row_groups, read_statistics,
+        },
+        ChunkReader, FileReader, RangePageReader, RangeReader,
+    },
+    to_parquet_schema,
+    write::{write_file, NativeWriter, WriteOptions},
+    ColumnMeta, ColumnStatistics, CommonCompression,
 };
 
 use crate::io::{new_test_chunk, WRITE_PAGE};
@@ -70,6 +85,19 @@ fn test_read_meta() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_read_meta_trailing_garbage() {
+    let mut buf = Vec::new();
+    write_data(&mut buf);
+    buf.extend_from_slice(b"garbage");
+
+    let mut reader = std::io::Cursor::new(buf);
+    let err = read_meta(&mut reader).unwrap_err();
+    assert!(err.to_string().contains("missing EOS marker"));
+    let err = infer_schema(&mut reader).unwrap_err();
+    assert!(err.to_string().contains("missing EOS marker"));
+}
+
 #[test]
 fn test_read_meta_async() -> Result<()> {
     async_std::task::block_on(test_read_meta_async_impl())
@@ -95,3 +123,603 @@ async fn test_read_meta_async_impl() -> Result<()> {
     }
     Ok(())
 }
+
+/// Records the position and length of every read.
+struct CountingReader<R> {
+    inner: R,
+    reads: Vec<(u64, usize)>,
+}
+
+impl<R: Read + Seek> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let pos = self.inner.stream_position()?;
+        let size = self.inner.read(buf)?;
+        self.reads.push((pos, size));
+        Ok(size)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+#[test]
+fn test_read_catalog_entry() -> Result<()> {
+    let mut buf = Vec::new();
+    let expected_meta = write_data(&mut buf);
+    let body_end = expected_meta
+        .iter()
+        .map(|meta| meta.offset + meta.total_len())
+        .max()
+        .unwrap();
+    let expected_schema = infer_schema(&mut std::io::Cursor::new(buf.clone()))?;
+
+    let mut reader = CountingReader {
+        inner: std::io::Cursor::new(buf),
+        reads: vec![],
+    };
+    let entry = read_catalog_entry(&mut reader)?;
+
+    assert_eq!(entry.schema, expected_schema);
+    assert_eq!(entry.column_metas, expected_meta);
+    assert_eq!(entry.num_rows, Some(6));
+    // every page is counted once under its encoding
+    for meta in &entry.column_metas {
+        let pages: u64 = meta.codec_counts.iter().map(|(_, pages)| pages).sum();
+        assert_eq!(pages, meta.pages.len() as u64);
+        let max = meta.codec_counts.iter().map(|(_, pages)| *pages).max();
+        assert!(meta
+            .codec_counts
+            .iter()
+            .any(|(codec, pages)| *codec == meta.dominant_codec && Some(*pages) == max));
+    }
+    let histogram = entry.codec_histogram();
+    assert_eq!(
+        histogram.iter().map(|(_, pages)| pages).sum::<u64>(),
+        expected_meta
+            .iter()
+            .map(|meta| meta.pages.len() as u64)
+            .sum::<u64>()
+    );
+
+    assert_eq!(reader.reads.len(), 2);
+    for (pos, _) in reader.reads {
+        assert!(pos >= body_end);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_varint_footer() -> Result<()> {
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(Int32Array::from_slice([1, 2, 3, 4])) as _,
+        Box::new(Utf8Array::<i32>::from_slice(["a", "b", "c", "d"])) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("a", chunk.arrays()[0].data_type().clone(), false),
+        Field::new("b", chunk.arrays()[1].data_type().clone(), false),
+    ]);
+
+    let write = |varint_footer: bool| {
+        let mut buf = vec![];
+        let mut writer = NativeWriter::new(
+            &mut buf,
+            schema.clone(),
+            WriteOptions {
+                default_compression: CommonCompression::Lz4,
+                varint_footer,
+                ..Default::default()
+            },
+        );
+        writer.start().unwrap();
+        writer.write(&chunk).unwrap();
+        writer.finish().unwrap();
+        let metas = writer.metas;
+        // EOS(8 bytes) + meta_size(4 bytes)
+        let tail = &buf[buf.len() - 12..buf.len() - 8];
+        let meta_size = u32::from_le_bytes(tail.try_into().unwrap()) & !(3 << 30);
+        (buf, metas, meta_size)
+    };
+    let (fixed, fixed_metas, fixed_size) = write(false);
+    let (varint, varint_metas, varint_size) = write(true);
+    assert_eq!(fixed_metas, varint_metas);
+    assert!(varint_size * 4 < fixed_size);
+
+    assert_eq!(read_meta(&mut std::io::Cursor::new(fixed))?, fixed_metas);
+    let metas = read_meta(&mut std::io::Cursor::new(varint.clone()))?;
+    assert_eq!(metas, varint_metas);
+    assert_eq!(
+        infer_schema(&mut std::io::Cursor::new(varint.clone()))?,
+        schema
+    );
+
+    let entry = read_catalog_entry(&mut std::io::Cursor::new(varint.clone()))?;
+    assert_eq!(entry.column_metas, metas);
+    assert_eq!(entry.num_rows, Some(4));
+
+    let meta = async_std::task::block_on(read_meta_async(
+        &mut async_std::io::Cursor::new(varint.clone()),
+        Some(varint.len()),
+    ))?;
+    assert_eq!(meta, metas);
+
+    for ((field, meta), expected) in schema.fields.iter().zip(metas).zip(chunk.arrays()) {
+        let mut reader = std::io::Cursor::new(varint.clone());
+        reader.seek(SeekFrom::Start(meta.offset))?;
+        let array = batch_read_array(vec![reader], vec![], field.clone(), false, vec![meta.pages])?;
+        assert_eq!(array.as_ref(), expected.as_ref());
+    }
+    Ok(())
+}
+
+#[test]
+fn test_read_statistics() -> Result<()> {
+    let size = 10_000;
+    let ints = Int32Array::from_iter((0..size).map(|i| (i % 7 != 0).then_some(i % 1000)));
+    let strings = Utf8Array::<i32>::from_iter_values((0..size).map(|i| format!("v{:03}", i % 300)));
+    let nested = StructArray::new(
+        DataType::Struct(vec![Field::new("a", DataType::Int32, false)]),
+        vec![Int32Array::from_vec((0..size).collect()).boxed()],
+        None,
+    );
+    let chunk: Chunk<Box<dyn Array>> =
+        Chunk::new(vec![ints.clone().boxed(), strings.boxed(), nested.boxed()]);
+    let schema = Schema::from(
+        chunk
+            .iter()
+            .map(|array| Field::new("c", array.data_type().clone(), true))
+            .collect::<Vec<_>>(),
+    );
+
+    for varint_footer in [false, true] {
+        let options = WriteOptions {
+            max_page_size: Some(WRITE_PAGE),
+            varint_footer,
+            ..Default::default()
+        };
+        let mut bytes = vec![];
+        write_file(
+            &mut bytes,
+            schema.clone(),
+            &[chunk.clone()],
+            options.clone(),
+        )?;
+        let statistics = read_statistics(&mut std::io::Cursor::new(bytes.as_slice()))?;
+        assert_eq!(
+            statistics[0],
+            ColumnStatistics {
+                min: Some(0i32.to_le_bytes().to_vec()),
+                max: Some(999i32.to_le_bytes().to_vec()),
+                null_count: Some(ints.null_count() as u64),
+                distinct_count_estimate: None,
+            }
+        );
+        assert_eq!(statistics[1].min.as_deref(), Some(b"v000".as_slice()));
+        assert_eq!(statistics[1].max.as_deref(), Some(b"v299".as_slice()));
+        // the nested columns have no min and max
+        assert_eq!(statistics[2].min, None);
+        assert_eq!(statistics[2].null_count, Some(0));
+
+        let mut bytes = vec![];
+        let (metas, _) = write_file(
+            &mut bytes,
+            schema.clone(),
+            &[chunk.clone()],
+            WriteOptions {
+                distinct_count: true,
+                ..options
+            },
+        )?;
+        assert_eq!(
+            read_meta(&mut std::io::Cursor::new(bytes.as_slice()))?,
+            metas
+        );
+        let statistics = read_statistics(&mut std::io::Cursor::new(bytes.as_slice()))?;
+        assert_eq!(statistics[0].min, Some(0i32.to_le_bytes().to_vec()));
+        for (column, expected) in [(0, 1000), (1, 300), (2, size)] {
+            let estimate = statistics[column].distinct_count_estimate.unwrap() as f64;
+            let error = (estimate - expected as f64).abs() / expected as f64;
+            assert!(error < 0.05, "{estimate} distinct values of {expected}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_footer_version() -> Result<()> {
+    let mut buf = Vec::new();
+    write_data(&mut buf);
+    // version(2 bytes) + magic(6 bytes) + schema size(4 bytes) + meta size(4 bytes) + EOS(8 bytes)
+    let version_pos = buf.len() - 24;
+    assert_eq!(&buf[version_pos + 2..version_pos + 8], b"ARROW2");
+
+    // a newer version is rejected
+    let mut newer = buf.clone();
+    let version = u16::from_le_bytes(newer[version_pos..version_pos + 2].try_into().unwrap());
+    assert_eq!(version, 1);
+    newer[version_pos..version_pos + 2].copy_from_slice(&u16::MAX.to_le_bytes());
+    let err = read_meta(&mut std::io::Cursor::new(newer.clone())).unwrap_err();
+    assert!(err.to_string().contains("unsupported strawboat version"));
+    let err = infer_schema(&mut std::io::Cursor::new(newer)).unwrap_err();
+    assert!(err.to_string().contains("unsupported strawboat version"));
+
+    // a corrupted magic is rejected
+    let mut corrupted = buf.clone();
+    corrupted[version_pos + 2] = b'X';
+    let err = read_meta(&mut std::io::Cursor::new(corrupted.clone())).unwrap_err();
+    assert!(err.to_string().contains("missing strawboat magic"));
+    let err = read_catalog_entry(&mut std::io::Cursor::new(corrupted)).unwrap_err();
+    assert!(err.to_string().contains("missing strawboat magic"));
+    Ok(())
+}
+
+/// The files written by strawboat before the footer was versioned, with 3000 rows
+/// in pages of 1000 rows of the columns `i64`, `i32`, `f64`, `utf8`, `bool` and a
+/// `list` of Int32, with and without the compress ratio of 1.2.
+pub(crate) const BASELINE_FILES: [&str; 2] = [
+    "tests/data/baseline_v0.strawboat",
+    "tests/data/baseline_v0_lz4.strawboat",
+];
+
+#[test]
+fn test_footer_version_0() -> Result<()> {
+    for path in BASELINE_FILES {
+        let bytes = std::fs::read(path)?;
+        let metas = read_meta(&mut std::io::Cursor::new(&bytes))?;
+        assert_eq!(metas.len(), 6);
+        // the columns follow the header and each other
+        let mut offset = 8;
+        for (i, meta) in metas.iter().enumerate() {
+            assert_eq!(meta.offset, offset);
+            offset += meta.total_len();
+            // the values of the list are 2 per row
+            let num_values = if i == 5 { 2000 } else { 1000 };
+            assert_eq!(meta.pages.len(), 3);
+            for page in &meta.pages {
+                assert_eq!(page.num_values, num_values);
+                assert_eq!(page.null_count, None);
+                assert_eq!(page.statistics, None);
+            }
+            assert_eq!(meta.null_count, None);
+            assert_eq!(meta.bloom_filter, None);
+        }
+
+        let schema = infer_schema(&mut std::io::Cursor::new(&bytes))?;
+        let names: Vec<&str> = schema.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, ["i64", "i32", "f64", "utf8", "bool", "list"]);
+        let entry = read_catalog_entry(&mut std::io::Cursor::new(&bytes))?;
+        assert_eq!(entry.num_rows, Some(3000));
+        assert_eq!(entry.column_metas, metas);
+        assert_eq!(
+            infer_row_count(&mut std::io::Cursor::new(&bytes))?,
+            Some(3000)
+        );
+        let statistics = read_statistics(&mut std::io::Cursor::new(&bytes))?;
+        assert!(statistics.iter().all(|s| s.null_count.is_none()));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_row_count() -> Result<()> {
+    let mut buf = Vec::new();
+    write_data(&mut buf);
+    assert_eq!(infer_row_count(&mut std::io::Cursor::new(buf))?, Some(6));
+
+    // the rows of a file without non-nested columns
+    let values = Int32Array::from_vec((0..1000).collect()).boxed();
+    let array = StructArray::new(
+        DataType::Struct(vec![Field::new("a", DataType::Int32, false)]),
+        vec![values],
+        None,
+    );
+    let schema = Schema::from(vec![Field::new("s", array.data_type().clone(), false)]);
+    let chunk = Chunk::new(vec![array.boxed()]);
+    for varint_footer in [false, true] {
+        let options = WriteOptions {
+            max_page_size: Some(300),
+            varint_footer,
+            ..Default::default()
+        };
+        let mut buf = vec![];
+        let (metas, _) = write_file(&mut buf, schema.clone(), &[chunk.clone()], options)?;
+        assert_eq!(
+            infer_row_count(&mut std::io::Cursor::new(&buf))?,
+            Some(1000)
+        );
+        let entry = read_catalog_entry(&mut std::io::Cursor::new(&buf))?;
+        assert_eq!(entry.num_rows, Some(1000));
+        assert_eq!(entry.column_metas, metas);
+
+        // files written before the row count was recorded
+        let row_count_size = if varint_footer { 2 } else { 8 };
+        // version(2 bytes) + magic(6 bytes) + schema size(4 bytes) + meta size(4 bytes) + EOS(8 bytes)
+        let version_pos = buf.len() - 24;
+        let mut legacy = buf[..version_pos - row_count_size].to_vec();
+        legacy.extend_from_slice(&buf[version_pos..buf.len() - 12]);
+        let meta_size = u32::from_le_bytes(buf[buf.len() - 12..buf.len() - 8].try_into().unwrap());
+        legacy.extend_from_slice(&(meta_size - row_count_size as u32).to_le_bytes());
+        legacy.extend_from_slice(&buf[buf.len() - 8..]);
+        assert_eq!(read_meta(&mut std::io::Cursor::new(&legacy))?, metas);
+        assert_eq!(infer_row_count(&mut std::io::Cursor::new(&legacy))?, None);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_row_groups() -> Result<()> {
+    let new_chunk = |start: i32, len: i32| {
+        let values = Int32Array::from_vec((start..start + len).collect());
+        let strings =
+            Utf8Array::<i32>::from_iter_values((start..start + len).map(|i| format!("value_{i}")));
+        let nested = StructArray::new(
+            DataType::Struct(vec![Field::new("a", DataType::Int32, false)]),
+            vec![values.clone().boxed()],
+            None,
+        );
+        Chunk::new(vec![values.boxed(), strings.boxed(), nested.boxed()])
+    };
+    let row_group_rows = [100, 30, 250];
+    let mut start = 0;
+    let chunks: Vec<_> = row_group_rows
+        .iter()
+        .map(|len| {
+            let chunk = new_chunk(start, *len);
+            start += len;
+            chunk
+        })
+        .collect();
+    let schema = Schema::from(vec![
+        Field::new("i", DataType::Int32, false),
+        Field::new("s", DataType::Utf8, false),
+        Field::new("n", chunks[0].arrays()[2].data_type().clone(), false),
+    ]);
+
+    let options = WriteOptions {
+        max_page_size: Some(64),
+        ..Default::default()
+    };
+    let mut buf = vec![];
+    let mut writer = NativeWriter::new(&mut buf, schema.clone(), options);
+    writer.start()?;
+    for chunk in &chunks {
+        writer.write(chunk)?;
+        writer.finish_row_group()?;
+    }
+    // a row group must have chunks
+    assert!(writer.finish_row_group().is_err());
+    writer.finish()?;
+    let written = writer.row_groups();
+    assert_eq!(written.len(), 3);
+
+    let row_groups = read_row_groups(&mut std::io::Cursor::new(&buf))?;
+    assert_eq!(row_groups, written);
+    assert_eq!(
+        row_groups.iter().map(|g| g.num_rows).collect::<Vec<_>>(),
+        vec![Some(100), Some(30), Some(250)]
+    );
+    assert_eq!(infer_row_count(&mut std::io::Cursor::new(&buf))?, Some(380));
+    let err = read_meta(&mut std::io::Cursor::new(&buf)).unwrap_err();
+    assert!(err.to_string().contains("3 row groups"));
+
+    // each row group is read on its own
+    let parquet_schema = to_parquet_schema(&schema)?;
+    for (row_group, chunk) in row_groups.iter().zip(chunks.iter()) {
+        assert_eq!(row_group.columns.len(), 3);
+        for (i, field) in schema.fields.iter().enumerate() {
+            let meta = &row_group.columns[i];
+            let mut reader = std::io::Cursor::new(&buf);
+            reader.seek(SeekFrom::Start(meta.offset))?;
+            let array = batch_read_array(
+                vec![reader],
+                vec![parquet_schema.columns()[i].clone()],
+                field.clone(),
+                i == 2,
+                vec![meta.pages.clone()],
+            )?;
+            assert_eq!(array.as_ref(), chunk.arrays()[i].as_ref());
+        }
+    }
+
+    // the readers of the whole file read every row group
+    let expected = new_chunk(0, 380);
+    let concat = |chunks: Vec<Chunk<Box<dyn Array>>>, i: usize| {
+        let arrays: Vec<&dyn Array> = chunks.iter().map(|c| c.arrays()[i].as_ref()).collect();
+        arrow::compute::concatenate::concatenate(&arrays).unwrap()
+    };
+    let file_chunks =
+        FileReader::try_new(std::io::Cursor::new(&buf), None)?.collect::<Result<Vec<_>>>()?;
+    let (_, chunk_reader) = ChunkReader::try_from_shared(buf.clone().into(), Some(&[2, 0]), 64)?;
+    let batches = chunk_reader.collect::<Result<Vec<_>>>()?;
+    for i in 0..3 {
+        assert_eq!(
+            concat(file_chunks.clone(), i).as_ref(),
+            expected.arrays()[i].as_ref()
+        );
+    }
+    assert_eq!(
+        concat(batches.clone(), 0).as_ref(),
+        expected.arrays()[2].as_ref()
+    );
+    assert_eq!(concat(batches, 1).as_ref(), expected.arrays()[0].as_ref());
+
+    // a single row group keeps the footer of the files written before
+    let mut single = vec![];
+    let mut writer = NativeWriter::new(&mut single, schema, WriteOptions::default());
+    writer.start()?;
+    writer.write(&chunks[0])?;
+    writer.finish_row_group()?;
+    writer.finish()?;
+    let version_pos = single.len() - 24;
+    assert_eq!(&single[version_pos..version_pos + 2], &1u16.to_le_bytes());
+    let metas = read_meta(&mut std::io::Cursor::new(&single))?;
+    assert_eq!(
+        read_row_groups(&mut std::io::Cursor::new(&single))?[0].columns,
+        metas
+    );
+    Ok(())
+}
+
+#[test]
+fn test_write_file() -> Result<()> {
+    let chunks: Vec<Chunk<Box<dyn Array>>> = (0..2)
+        .map(|i| {
+            Chunk::new(vec![
+                Box::new(Int32Array::from_slice([i, i + 1, i + 2])) as _,
+                Box::new(Utf8Array::<i32>::from_slice(["a", "b", "c"])) as _,
+            ])
+        })
+        .collect();
+    let schema = Schema::from(vec![
+        Field::new("a", chunks[0].arrays()[0].data_type().clone(), false),
+        Field::new("b", chunks[0].arrays()[1].data_type().clone(), false),
+    ]);
+
+    let mut buf = vec![];
+    let (metas, size) = write_file(&mut buf, schema.clone(), &chunks, WriteOptions::default())?;
+    assert_eq!(size, buf.len() as u64);
+
+    assert_eq!(read_meta(&mut std::io::Cursor::new(buf.clone()))?, metas);
+    assert_eq!(
+        infer_schema(&mut std::io::Cursor::new(buf.clone()))?,
+        schema
+    );
+
+    let expected = [
+        Box::new(Int32Array::from_slice([0, 1, 2, 1, 2, 3])) as Box<dyn Array>,
+        Box::new(Utf8Array::<i32>::from_slice(["a", "b", "c", "a", "b", "c"])) as _,
+    ];
+    for ((field, meta), expected) in schema.fields.iter().zip(metas).zip(expected) {
+        let mut reader = std::io::Cursor::new(buf.clone());
+        reader.seek(SeekFrom::Start(meta.offset))?;
+        let array = batch_read_array(vec![reader], vec![], field.clone(), false, vec![meta.pages])?;
+        assert_eq!(array.as_ref(), expected.as_ref());
+    }
+
+    // a file without chunks can't be written
+    assert!(write_file(vec![], schema, &[], WriteOptions::default()).is_err());
+    Ok(())
+}
+
+/// A file in an object store, read by ranged requests only.
+struct ObjectStore {
+    bytes: Bytes,
+    ranges: Arc<Mutex<Vec<(u64, usize)>>>,
+}
+
+impl RangeReader for ObjectStore {
+    fn file_len(&mut self) -> Result<u64> {
+        Ok(self.bytes.len() as u64)
+    }
+
+    fn read_range(&mut self, offset: u64, len: usize) -> Result<Bytes> {
+        self.ranges.lock().unwrap().push((offset, len));
+        Ok(self.bytes.slice(offset as usize..offset as usize + len))
+    }
+}
+
+#[test]
+fn test_range_reader() -> Result<()> {
+    let size = WRITE_PAGE * 3 + 10;
+    let chunk: Chunk<Box<dyn Array>> = Chunk::new(vec![
+        Box::new(Int32Array::from_vec((0..size as i32).collect())) as _,
+        Box::new(Utf8Array::<i32>::from_iter_values(
+            (0..size).map(|i| format!("value {}", i % 100)),
+        )) as _,
+    ]);
+    let schema = Schema::from(vec![
+        Field::new("a", chunk.arrays()[0].data_type().clone(), false),
+        Field::new("b", chunk.arrays()[1].data_type().clone(), false),
+    ]);
+    for zstd_dictionary_size in [None, Some(1024)] {
+        let mut buf = vec![];
+        let (metas, _) = write_file(
+            &mut buf,
+            schema.clone(),
+            &[chunk.clone()],
+            WriteOptions {
+                max_page_size: Some(WRITE_PAGE),
+                bloom_filter: true,
+                page_checksum: true,
+                zstd_dictionary_size,
+                ..Default::default()
+            },
+        )?;
+        let ranges = Arc::new(Mutex::new(vec![]));
+        let mut store = ObjectStore {
+            bytes: Bytes::from(buf.clone()),
+            ranges: ranges.clone(),
+        };
+
+        // the tail and the footer
+        let mut read_metas = read_meta(&mut store)?;
+        assert_eq!(ranges.lock().unwrap().len(), 2);
+        // then one range per bloom filter
+        read_bloom_filters(&mut store, &mut read_metas)?;
+        assert_eq!(ranges.lock().unwrap().len(), 2 + metas.len());
+        assert_eq!(read_metas, metas);
+        assert_eq!(infer_schema(&mut store)?, schema);
+        ranges.lock().unwrap().clear();
+        let mut entry = read_catalog_entry(&mut store)?;
+        assert_eq!(ranges.lock().unwrap().len(), 2);
+        read_bloom_filters(&mut store, &mut entry.column_metas)?;
+        assert_eq!(entry.column_metas, metas);
+        assert_eq!(entry.schema, schema);
+        // the same ranges as a `Read` + `Seek` reader
+        let mut reader = std::io::Cursor::new(buf);
+        let mut read_metas = read_meta(&mut reader)?;
+        read_bloom_filters(&mut reader, &mut read_metas)?;
+        assert_eq!(read_metas, metas);
+
+        // one range per page
+        ranges.lock().unwrap().clear();
+        let leaves = to_parquet_schema(&schema)?.columns().to_vec();
+        for (i, meta) in metas.iter().enumerate() {
+            let store = ObjectStore {
+                bytes: store.bytes.clone(),
+                ranges: ranges.clone(),
+            };
+            let reader = RangePageReader::new(store, meta).with_checksum_verification(true);
+            let arrays = column_iter_to_arrays(
+                vec![reader],
+                vec![leaves[i].clone()],
+                schema.fields[i].clone(),
+                false,
+            )?
+            .collect::<Result<Vec<_>>>()?;
+            let arrays: Vec<&dyn Array> = arrays.iter().map(|array| array.as_ref()).collect();
+            let array = arrow::compute::concatenate::concatenate(&arrays)?;
+            assert_eq!(array.as_ref(), chunk.arrays()[i].as_ref());
+        }
+        // the version of the file is read from its header with the first page
+        let expected: Vec<(u64, usize)> = metas
+            .iter()
+            .flat_map(|meta| {
+                let pages = meta.pages.iter().scan(meta.offset, |offset, page| {
+                    let range = (*offset, page.length as usize);
+                    *offset += page.length;
+                    Some(range)
+                });
+                std::iter::once((0, 8)).chain(pages)
+            })
+            .collect();
+        assert_eq!(*ranges.lock().unwrap(), expected);
+
+        // skipped pages are not read
+        ranges.lock().unwrap().clear();
+        let store = ObjectStore {
+            bytes: store.bytes.clone(),
+            ranges: ranges.clone(),
+        };
+        let mut reader = RangePageReader::new(store, &metas[0]);
+        let (num_values, _) = reader.nth(2).unwrap()?;
+        assert_eq!(num_values, WRITE_PAGE as u64);
+        assert_eq!(reader.current_page(), 3);
+        assert_eq!(ranges.lock().unwrap()[..], [expected[0], expected[3]]);
+        assert!(reader.nth(1).is_none());
+        assert!(!reader.has_next());
+    }
+    Ok(())
+}